@@ -1,28 +1,90 @@
+use super::loop_detector::{append_final_answer_nudge, LoopDetector};
+use super::utils::{
+    prepare_messages_for_llm, strip_images, tool_result_messages, trim_to_token_budget,
+    TokenCounter,
+};
 use crate::api::agent::core::logging::ConversationLogger;
 use crate::api::agent::core::types::{
     ChatCompletionRequest, ChatCompletionResponse, ChatMessage, MessageContent, MessageRole,
-    ToolCallResult,
+    ToolCall, ToolCallResult, ToolCallTrace, DEFAULT_AGENT_TIMEOUT_SECS,
+    DEFAULT_CONTEXT_TOKEN_BUDGET, DEFAULT_STREAM_FLUSH_CHARS,
 };
 use crate::api::agent::memory::sqlite_memory::SqliteConversationMemory;
+use crate::api::agent::tools::framework::http::DEFAULT_TOOL_TIMEOUT_SECS;
 use crate::api::agent::tools::framework::registry::ToolRegistry;
+use crate::api::metrics::registry as metrics;
 use anyhow::Result;
+use log::{error, info, warn};
 use reqwest::Client;
 use std::sync::Arc;
+use std::time::Duration;
 
 /// Agent loop result
 pub struct AgentLoopResult {
     pub final_message: String,
     pub tool_calls: Vec<ToolCallResult>,
+    /// Arguments, outcome and timing of every tool call made
+    pub tool_traces: Vec<ToolCallTrace>,
+    /// Tool calls the model requested but which were not executed (plan-only mode)
+    pub planned_tool_calls: Vec<ToolCall>,
     pub iterations: usize,
     pub stuck: bool, // True if loop reached max iterations
+    /// True if the loop ran past `agent_timeout_secs`; `iterations` is then 0
+    pub timed_out: bool,
 }
 
+/// Allowed range for per-request `max_iterations` overrides
+pub const MAX_ITERATIONS_RANGE: std::ops::RangeInclusive<usize> = 1..=20;
+
+/// Allowed range for per-request `temperature` overrides
+pub const TEMPERATURE_RANGE: std::ops::RangeInclusive<f32> = 0.0..=2.0;
+
+/// Allowed range for `frequency_penalty` and `presence_penalty`
+pub const PENALTY_RANGE: std::ops::RangeInclusive<f32> = -2.0..=2.0;
+
+/// Most stop sequences OpenAI-compatible servers accept
+pub const MAX_STOP_SEQUENCES: usize = 4;
+
+/// Allowed range for `top_p` (nucleus sampling)
+pub const TOP_P_RANGE: std::ops::RangeInclusive<f32> = 0.0..=1.0;
+
 /// Configuration for agent loop
+#[derive(Debug, Clone)]
 pub struct AgentLoopConfig {
     pub max_iterations: usize,
     pub max_tokens: u32,
     pub temperature: f32,
+    /// Nucleus sampling cutoff; `None` leaves the server default
+    pub top_p: Option<f32>,
+    /// Repetition penalties; `None` leaves the server default
+    pub frequency_penalty: Option<f32>,
+    pub presence_penalty: Option<f32>,
+    /// Stop sequences passed to the server as-is; `None` sends none
+    pub stop: Option<Vec<String>>,
     pub debug_logging: bool,
+    /// Maximum time a single tool execution may take
+    pub tool_timeout_secs: u64,
+    /// Whether assistant tool-call and tool-result messages are stored in conversation memory
+    pub persist_tool_messages: bool,
+    /// Whether the LLM server accepts `role: "tool"` messages; when false tool
+    /// results are sent as user messages to keep user/assistant alternation
+    pub supports_tool_role: bool,
+    /// Whether the model accepts image parts; when false they are replaced with
+    /// a text placeholder before the request is sent
+    pub supports_vision: bool,
+    /// Stop at the first response with tool calls and return them without executing
+    /// anything or storing assistant messages
+    pub plan_only: bool,
+    /// Short id prefixed to log lines so concurrent requests can be told apart
+    pub request_id: String,
+    /// Estimated prompt tokens allowed per request (0 = no limit)
+    pub context_token_budget: usize,
+    /// How long streamed text may be coalesced before it is sent (0 = no buffering)
+    pub stream_flush_ms: u64,
+    /// Pending characters that trigger an early flush of coalesced text
+    pub stream_flush_chars: usize,
+    /// Wall-clock limit for the whole loop (0 = no limit)
+    pub agent_timeout_secs: u64,
 }
 
 impl Default for AgentLoopConfig {
@@ -31,14 +93,211 @@ impl Default for AgentLoopConfig {
             max_iterations: 10, // Maximum tool-call iterations
             max_tokens: 2000,
             temperature: 0.7,
+            top_p: None,
+            frequency_penalty: None,
+            presence_penalty: None,
+            stop: None,
             debug_logging: false,
+            tool_timeout_secs: DEFAULT_TOOL_TIMEOUT_SECS,
+            persist_tool_messages: true,
+            supports_tool_role: false,
+            supports_vision: false,
+            plan_only: false,
+            request_id: String::new(),
+            context_token_budget: DEFAULT_CONTEXT_TOKEN_BUDGET,
+            stream_flush_ms: 0,
+            stream_flush_chars: DEFAULT_STREAM_FLUSH_CHARS,
+            agent_timeout_secs: DEFAULT_AGENT_TIMEOUT_SECS,
         }
     }
 }
 
+impl AgentLoopConfig {
+    /// Apply per-request overrides, keeping the current values for anything not set
+    pub fn with_overrides(
+        mut self,
+        max_iterations: Option<usize>,
+        temperature: Option<f32>,
+        max_tokens: Option<u32>,
+    ) -> Result<Self> {
+        if let Some(max_iterations) = max_iterations {
+            if !MAX_ITERATIONS_RANGE.contains(&max_iterations) {
+                return Err(anyhow::anyhow!(
+                    "max_iterations must be between {} and {}",
+                    MAX_ITERATIONS_RANGE.start(),
+                    MAX_ITERATIONS_RANGE.end()
+                ));
+            }
+            self.max_iterations = max_iterations;
+        }
+
+        if let Some(temperature) = temperature {
+            if !TEMPERATURE_RANGE.contains(&temperature) {
+                return Err(anyhow::anyhow!(
+                    "temperature must be between {:.1} and {:.1}",
+                    TEMPERATURE_RANGE.start(),
+                    TEMPERATURE_RANGE.end()
+                ));
+            }
+            self.temperature = temperature;
+        }
+
+        if let Some(max_tokens) = max_tokens {
+            if max_tokens == 0 {
+                return Err(anyhow::anyhow!("max_tokens must be greater than 0"));
+            }
+            self.max_tokens = max_tokens;
+        }
+
+        Ok(self)
+    }
+
+    /// Apply generation parameters saved for a model, with the same validation
+    /// as per-request overrides
+    pub fn with_generation_defaults(
+        self,
+        temperature: Option<f32>,
+        max_tokens: Option<u32>,
+        top_p: Option<f32>,
+    ) -> Result<Self> {
+        self.with_overrides(None, temperature, max_tokens)?
+            .with_sampling(top_p, None, None)
+    }
+
+    /// Apply per-request sampling overrides, keeping the current values for anything not set
+    pub fn with_sampling(
+        mut self,
+        top_p: Option<f32>,
+        frequency_penalty: Option<f32>,
+        presence_penalty: Option<f32>,
+    ) -> Result<Self> {
+        if let Some(top_p) = top_p {
+            if !TOP_P_RANGE.contains(&top_p) {
+                return Err(anyhow::anyhow!(
+                    "top_p must be between {:.1} and {:.1}",
+                    TOP_P_RANGE.start(),
+                    TOP_P_RANGE.end()
+                ));
+            }
+            self.top_p = Some(top_p);
+        }
+
+        if let Some(frequency_penalty) = frequency_penalty {
+            check_penalty("frequency_penalty", frequency_penalty)?;
+            self.frequency_penalty = Some(frequency_penalty);
+        }
+
+        if let Some(presence_penalty) = presence_penalty {
+            check_penalty("presence_penalty", presence_penalty)?;
+            self.presence_penalty = Some(presence_penalty);
+        }
+
+        Ok(self)
+    }
+
+    /// Set per-request stop sequences: at most `MAX_STOP_SEQUENCES`, none empty
+    pub fn with_stop(mut self, stop: Option<Vec<String>>) -> Result<Self> {
+        if let Some(stop) = stop {
+            if stop.len() > MAX_STOP_SEQUENCES {
+                return Err(anyhow::anyhow!(
+                    "stop accepts at most {} sequences",
+                    MAX_STOP_SEQUENCES
+                ));
+            }
+            if stop.iter().any(|s| s.is_empty()) {
+                return Err(anyhow::anyhow!("stop sequences must not be empty"));
+            }
+            self.stop = Some(stop);
+        }
+        Ok(self)
+    }
+}
+
+/// Answer given when an agent request runs past `agent_timeout_secs`
+pub const AGENT_TIMEOUT_MESSAGE: &str =
+    "Sorry, this request took too long and was stopped. Please try again or ask a narrower question.";
+
+/// Run `future` within the agent's wall-clock limit (0 = no limit).
+/// Returns `None` if time ran out.
+pub async fn with_agent_timeout<F: std::future::Future>(
+    timeout_secs: u64,
+    future: F,
+) -> Option<F::Output> {
+    if timeout_secs == 0 {
+        return Some(future.await);
+    }
+    tokio::time::timeout(Duration::from_secs(timeout_secs), future)
+        .await
+        .ok()
+}
+
 /// Execute agent loop - allows LLM to use tools iteratively until it decides it has enough info
+///
+/// The whole loop is bounded by `config.agent_timeout_secs`. On timeout the
+/// tool messages stored so far are kept and [`AGENT_TIMEOUT_MESSAGE`] is stored
+/// and returned as the answer.
 #[allow(clippy::too_many_arguments)]
 pub async fn execute_agent_loop(
+    client: &Client,
+    llama_url: &str,
+    model_name: String,
+    messages: Vec<ChatMessage>,
+    tools: Vec<crate::api::agent::core::types::Tool>,
+    tool_registry: Arc<ToolRegistry>,
+    sqlite_memory: Arc<SqliteConversationMemory>,
+    conversation_id: String,
+    config: AgentLoopConfig,
+) -> Result<AgentLoopResult> {
+    let timeout_secs = config.agent_timeout_secs;
+    let plan_only = config.plan_only;
+    let request_id = config.request_id.clone();
+
+    let run = run_agent_loop(
+        client,
+        llama_url,
+        model_name,
+        messages,
+        tools,
+        tool_registry,
+        Arc::clone(&sqlite_memory),
+        conversation_id.clone(),
+        config,
+    );
+    if let Some(result) = with_agent_timeout(timeout_secs, run).await {
+        return result;
+    }
+
+    warn!(
+        "[{}] ⏱️ Agent loop timed out after {}s",
+        request_id, timeout_secs
+    );
+    if !plan_only {
+        let timeout_message = ChatMessage {
+            role: MessageRole::Assistant,
+            content: MessageContent::Text(AGENT_TIMEOUT_MESSAGE.to_string()),
+            name: None,
+            tool_calls: None,
+            tool_call_id: None,
+            reasoning_content: None,
+        };
+        sqlite_memory
+            .add_message(&conversation_id, timeout_message)
+            .await?;
+    }
+
+    Ok(AgentLoopResult {
+        final_message: AGENT_TIMEOUT_MESSAGE.to_string(),
+        tool_calls: Vec::new(),
+        tool_traces: Vec::new(),
+        planned_tool_calls: Vec::new(),
+        iterations: 0,
+        stuck: false,
+        timed_out: true,
+    })
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn run_agent_loop(
     client: &Client,
     llama_url: &str,
     model_name: String,
@@ -50,7 +309,12 @@ pub async fn execute_agent_loop(
     config: AgentLoopConfig,
 ) -> Result<AgentLoopResult> {
     let mut tool_results = Vec::new();
+    let mut tool_traces = Vec::new();
     let mut iterations = 0;
+    // Set once the model repeats itself; tools are then withheld until it answers
+    let mut loop_detector = LoopDetector::new();
+    let mut stuck_reason: Option<String> = None;
+    let mut token_counter = TokenCounter::default();
     let logger = ConversationLogger::new(config.debug_logging, &conversation_id);
 
     logger.log("START", "Agent loop started");
@@ -61,13 +325,16 @@ pub async fn execute_agent_loop(
 
     loop {
         iterations += 1;
-        println!(
-            "🔄 Agent loop iteration {}/{}",
-            iterations, config.max_iterations
+        info!(
+            "[{}] 🔄 Agent loop iteration {}/{}",
+            config.request_id, iterations, config.max_iterations
         );
 
         if iterations > config.max_iterations {
-            println!("⚠️ Maximum iterations reached - agent appears stuck");
+            warn!(
+                "[{}] ⚠️ Maximum iterations reached - agent appears stuck",
+                config.request_id
+            );
             // Get the last assistant message or create a default one
             let last_assistant = messages
                 .iter()
@@ -89,56 +356,39 @@ pub async fn execute_agent_loop(
             return Ok(AgentLoopResult {
                 final_message,
                 tool_calls: tool_results,
+                tool_traces,
+                planned_tool_calls: Vec::new(),
                 iterations,
                 stuck: true,
+                timed_out: false,
             });
         }
 
-        // Build request - convert tool messages to user messages to maintain alternation
-        // (the LLM server expects alternating user/assistant and doesn't allow prefill with tool_calls)
-        let mut filtered_messages: Vec<ChatMessage> = Vec::new();
-        let mut tool_results_buffer: Vec<String> = Vec::new();
-
-        for msg in messages.iter() {
-            if matches!(msg.role, MessageRole::Tool) {
-                // Collect tool results to create a user message
-                let tool_name = msg.name.as_deref().unwrap_or("unknown");
-                tool_results_buffer.push(format!("{}: {}", tool_name, msg.content.text()));
-            } else {
-                // If we have buffered tool results, create a user message with them
-                if !tool_results_buffer.is_empty() {
-                    let tool_results_content = tool_results_buffer.join("\n");
-                    filtered_messages.push(ChatMessage {
-                        role: MessageRole::User,
-                        content: MessageContent::Text(format!(
-                            "Tool results:\n{}",
-                            tool_results_content
-                        )),
-                        name: None,
-                        tool_calls: None,
-                        tool_call_id: None,
-                        reasoning_content: None,
-                    });
-                    tool_results_buffer.clear();
-                }
-                filtered_messages.push(msg.clone());
-            }
+        // Build request - tool results are folded into user messages unless the
+        // server supports the `tool` role
+        let context = trim_to_token_budget(&messages, config.context_token_budget, |text| {
+            token_counter.count(text)
+        });
+        if context.len() < messages.len() {
+            info!(
+                "[{}] ✂️ Dropped {} old message(s) to fit the {}-token context budget",
+                config.request_id,
+                messages.len() - context.len(),
+                config.context_token_budget
+            );
         }
-
-        // Handle any remaining tool results at the end
-        if !tool_results_buffer.is_empty() {
-            let tool_results_content = tool_results_buffer.join("\n");
-            filtered_messages.push(ChatMessage {
-                role: MessageRole::User,
-                content: MessageContent::Text(format!("Tool results:\n{}", tool_results_content)),
-                name: None,
-                tool_calls: None,
-                tool_call_id: None,
-                reasoning_content: None,
-            });
+        let context = if config.supports_vision {
+            context
+        } else {
+            strip_images(&context)
+        };
+        let mut filtered_messages = prepare_messages_for_llm(&context, config.supports_tool_role);
+        if let Some(reason) = &stuck_reason {
+            append_final_answer_nudge(&mut filtered_messages, reason);
         }
+        let offer_tools = !tools.is_empty() && stuck_reason.is_none();
 
-        let tool_choice = if !tools.is_empty() {
+        let tool_choice = if offer_tools {
             Some("auto".to_string())
         } else {
             None
@@ -148,23 +398,35 @@ pub async fn execute_agent_loop(
             messages: filtered_messages,
             model: model_name.clone(),
             temperature: Some(config.temperature),
+            top_p: config.top_p,
+            frequency_penalty: config.frequency_penalty,
+            presence_penalty: config.presence_penalty,
             max_tokens: Some(config.max_tokens),
-            tools: if tools.is_empty() {
-                None
-            } else {
+            stop: config.stop.clone(),
+            tools: if offer_tools {
                 Some(tools.clone())
+            } else {
+                None
             },
             tool_choice,
             stream: Some(false),
             stream_options: None,
         };
 
-        println!("📤 Sending request to LLM (iteration {})...", iterations);
+        info!(
+            "[{}] 📤 Sending request to LLM (iteration {})...",
+            config.request_id, iterations
+        );
         logger.log(
             "LOOP ITERATION",
             &format!("Sending request to LLM (iteration {})...", iterations),
         );
+        let llm_started = std::time::Instant::now();
         let response = client.post(llama_url).json(&request).send().await?;
+        metrics::record(|m| {
+            m.llm_request_duration
+                .observe(llm_started.elapsed().as_secs_f64())
+        });
 
         let response_status = response.status();
         let response_text = response.text().await?;
@@ -178,7 +440,10 @@ pub async fn execute_agent_loop(
                 response_text
             ));
         }
-        println!("📥 LLM response received (iteration {})", iterations);
+        info!(
+            "[{}] 📥 LLM response received (iteration {})",
+            config.request_id, iterations
+        );
 
         let completion_response: ChatCompletionResponse = serde_json::from_str(&response_text)
             .map_err(|e| {
@@ -196,20 +461,58 @@ pub async fn execute_agent_loop(
         let choice = completion_response.choices.first().unwrap();
 
         // Check if LLM wants to use tools
-        if let Some(tool_calls) = &choice.message.tool_calls {
-            println!(
-                "🔧 LLM requested {} tool call(s) in iteration {}",
+        // Once stuck, any tool calls the server still parses out are ignored
+        if let Some(tool_calls) = choice
+            .message
+            .tool_calls
+            .as_ref()
+            .filter(|_| stuck_reason.is_none())
+        {
+            info!(
+                "[{}] 🔧 LLM requested {} tool call(s) in iteration {}",
+                config.request_id,
                 tool_calls.len(),
                 iterations
             );
 
+            if config.plan_only {
+                info!(
+                    "[{}] 📝 Plan-only mode: returning tool calls without executing them",
+                    config.request_id
+                );
+                return Ok(AgentLoopResult {
+                    final_message: choice.message.content.text(),
+                    tool_calls: tool_results,
+                    tool_traces,
+                    planned_tool_calls: tool_calls.clone(),
+                    iterations,
+                    stuck: false,
+                    timed_out: false,
+                });
+            }
+
+            // Running the same calls again would only burn iterations
+            if let Some(reason) = loop_detector.check(tool_calls) {
+                warn!(
+                    "[{}] 🔁 Agent is stuck ({}), asking for a final answer without tools",
+                    config.request_id, reason
+                );
+                stuck_reason = Some(reason);
+                continue;
+            }
+
             // Store assistant message with tool calls in SQLite
             let assistant_message = choice.message.clone();
-            if let Err(e) = sqlite_memory
-                .add_message(&conversation_id, assistant_message.clone())
-                .await
-            {
-                println!("⚠️ Failed to store assistant tool call message: {}", e);
+            if config.persist_tool_messages {
+                if let Err(e) = sqlite_memory
+                    .add_message(&conversation_id, assistant_message.clone())
+                    .await
+                {
+                    warn!(
+                        "[{}] ⚠️ Failed to store assistant tool call message: {}",
+                        config.request_id, e
+                    );
+                }
             }
             messages.push(assistant_message.clone());
             logger.log_message(&assistant_message);
@@ -217,17 +520,22 @@ pub async fn execute_agent_loop(
             // Execute all tool calls in parallel
             let mut futures = Vec::new();
             for tool_call in tool_calls {
-                println!(
-                    "   📞 Spawning tool execution: {} with args: {}",
-                    tool_call.function.name, tool_call.function.arguments
+                info!(
+                    "[{}]    📞 Spawning tool execution: {} with args: {}",
+                    config.request_id, tool_call.function.name, tool_call.function.arguments
                 );
 
                 let registry = tool_registry.clone();
                 let call = tool_call.clone();
+                let tool_timeout = Duration::from_secs(config.tool_timeout_secs);
+                let request_id = config.request_id.clone();
 
                 futures.push(tokio::spawn(async move {
-                    let result = registry.execute_tool_call(&call).await;
-                    (call, result)
+                    let started = std::time::Instant::now();
+                    let result = registry
+                        .execute_tool_call_with_timeout(&call, tool_timeout, &request_id)
+                        .await;
+                    (call, result, started.elapsed())
                 }));
             }
 
@@ -239,49 +547,54 @@ pub async fn execute_agent_loop(
 
             for join_result in results {
                 match join_result {
-                    Ok((tool_call, execution_result)) => match execution_result {
-                        Ok(result) => {
-                            println!(
-                                "   ✅ Tool '{}' executed successfully",
-                                tool_call.function.name
-                            );
-                            iteration_tool_results.push((tool_call.clone(), result.clone()));
-                            tool_results.push(result.clone());
-                            logger.log_tool_result(&result);
-                        }
-                        Err(e) => {
-                            println!("   Tool execution error: {}", e);
-                            let error_result = ToolCallResult {
-                                tool_name: tool_call.function.name.clone(),
-                                result: format!("Error: {}", e),
-                            };
-                            iteration_tool_results.push((tool_call, error_result.clone()));
-                            tool_results.push(error_result);
-                        }
-                    },
+                    Ok((tool_call, execution_result, duration)) => {
+                        let (result, success) = match execution_result {
+                            Ok(result) => {
+                                logger.log_tool_result(&result);
+                                (result, true)
+                            }
+                            Err(e) => {
+                                error!("[{}]    Tool execution error: {}", config.request_id, e);
+                                let error_result = ToolCallResult {
+                                    tool_name: tool_call.function.name.clone(),
+                                    result: format!("Error: {}", e),
+                                    tool_call_id: Some(tool_call.id.clone()),
+                                    sources: None,
+                                };
+                                (error_result, false)
+                            }
+                        };
+
+                        tool_traces.push(ToolCallTrace {
+                            tool_call_id: tool_call.id.clone(),
+                            tool_name: tool_call.function.name.clone(),
+                            arguments: tool_call.function.arguments.clone(),
+                            result: result.result.clone(),
+                            success,
+                            duration_ms: duration.as_millis() as u64,
+                        });
+                        iteration_tool_results.push(result.clone());
+                        tool_results.push(result);
+                    }
                     Err(e) => {
-                        println!("   Tool task panic: {}", e);
+                        error!("[{}]    Tool task panic: {}", config.request_id, e);
                         // Handle panic if needed, though unlikely
                     }
                 }
             }
 
             // Add tool results as tool messages and store in SQLite
-            for (tool_call, result) in iteration_tool_results {
-                let tool_message = ChatMessage {
-                    role: MessageRole::Tool,
-                    content: MessageContent::Text(result.result.clone()),
-                    name: Some(tool_call.function.name.clone()),
-                    tool_calls: None,
-                    tool_call_id: Some(tool_call.id.clone()),
-                    reasoning_content: None,
-                };
-
-                if let Err(e) = sqlite_memory
-                    .add_message(&conversation_id, tool_message.clone())
-                    .await
-                {
-                    println!("⚠️ Failed to store tool result message: {}", e);
+            for tool_message in tool_result_messages(tool_calls, &iteration_tool_results) {
+                if config.persist_tool_messages {
+                    if let Err(e) = sqlite_memory
+                        .add_message(&conversation_id, tool_message.clone())
+                        .await
+                    {
+                        warn!(
+                            "[{}] ⚠️ Failed to store tool result message: {}",
+                            config.request_id, e
+                        );
+                    }
                 }
 
                 messages.push(tool_message.clone());
@@ -289,7 +602,10 @@ pub async fn execute_agent_loop(
             }
 
             // Continue loop - LLM will process tool results and decide next action
-            println!("🔄 Continuing loop to process tool results...");
+            info!(
+                "[{}] 🔄 Continuing loop to process tool results...",
+                config.request_id
+            );
             continue;
         } else {
             // No tool calls - LLM has decided it has enough information
@@ -304,9 +620,9 @@ pub async fn execute_agent_loop(
                 choice.message.content.text()
             };
 
-            println!(
-                "✅ LLM provided final answer after {} iterations",
-                iterations
+            info!(
+                "[{}] ✅ LLM provided final answer after {} iterations",
+                config.request_id, iterations
             );
 
             // Store final assistant response in memory
@@ -318,16 +634,274 @@ pub async fn execute_agent_loop(
                 tool_call_id: None,
                 reasoning_content: None,
             };
-            sqlite_memory
-                .add_message(&conversation_id, final_assistant_message)
-                .await?;
+            if !config.plan_only {
+                sqlite_memory
+                    .add_message(&conversation_id, final_assistant_message)
+                    .await?;
+            }
 
             return Ok(AgentLoopResult {
                 final_message,
                 tool_calls: tool_results,
+                tool_traces,
+                planned_tool_calls: Vec::new(),
                 iterations,
                 stuck: false,
+                timed_out: false,
             });
         }
     }
 }
+
+fn check_penalty(name: &str, value: f32) -> Result<()> {
+    if !PENALTY_RANGE.contains(&value) {
+        return Err(anyhow::anyhow!(
+            "{} must be between {:.1} and {:.1}",
+            name,
+            PENALTY_RANGE.start(),
+            PENALTY_RANGE.end()
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::agent::core::streaming::execute_agent_loop_streaming;
+    use crate::api::agent::core::types::AgentStreamEvent;
+    use crate::utils::database::connect_sqlite;
+    use tokio::net::TcpListener;
+    use tokio::sync::mpsc;
+
+    /// Stub LLM server that accepts requests and never answers
+    async fn spawn_stalled_llm() -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let mut open = Vec::new();
+            while let Ok((socket, _)) = listener.accept().await {
+                open.push(socket);
+            }
+        });
+        format!("http://{}/v1/chat/completions", addr)
+    }
+
+    async fn memory_with_conversation() -> (Arc<SqliteConversationMemory>, String) {
+        let memory = SqliteConversationMemory::new(connect_sqlite(":memory:").await.unwrap())
+            .await
+            .unwrap();
+        let id = memory
+            .get_or_create_conversation_id(None, None)
+            .await
+            .unwrap();
+        (Arc::new(memory), id)
+    }
+
+    fn user(text: &str) -> ChatMessage {
+        ChatMessage {
+            role: MessageRole::User,
+            content: MessageContent::Text(text.to_string()),
+            name: None,
+            tool_calls: None,
+            tool_call_id: None,
+            reasoning_content: None,
+        }
+    }
+
+    fn timeout_config() -> AgentLoopConfig {
+        AgentLoopConfig {
+            agent_timeout_secs: 1,
+            ..AgentLoopConfig::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_loop_times_out_on_slow_llm() {
+        let url = spawn_stalled_llm().await;
+        let (memory, id) = memory_with_conversation().await;
+
+        let result = execute_agent_loop(
+            &Client::new(),
+            &url,
+            "stub".to_string(),
+            vec![user("Hello?")],
+            Vec::new(),
+            Arc::new(ToolRegistry::new()),
+            Arc::clone(&memory),
+            id.clone(),
+            timeout_config(),
+        )
+        .await
+        .unwrap();
+
+        assert!(result.timed_out);
+        assert!(!result.stuck);
+        assert_eq!(result.final_message, AGENT_TIMEOUT_MESSAGE);
+        let stored = memory.get_recent_messages(&id, 10).await.unwrap();
+        assert_eq!(stored.last().unwrap().content.text(), AGENT_TIMEOUT_MESSAGE);
+    }
+
+    #[tokio::test]
+    async fn test_streaming_loop_times_out_on_slow_llm() {
+        let url = spawn_stalled_llm().await;
+        let (memory, id) = memory_with_conversation().await;
+        // As saved by an earlier iteration before the LLM stalled
+        memory
+            .save_partial_message(&id, None, "The answer is", None)
+            .await
+            .unwrap();
+        let (tx, mut rx) = mpsc::channel(16);
+        let (_cancel_tx, cancel_rx) = tokio::sync::watch::channel(false);
+
+        execute_agent_loop_streaming(
+            &Client::new(),
+            &url,
+            "stub".to_string(),
+            vec![user("Hello?")],
+            Vec::new(),
+            Arc::new(ToolRegistry::new()),
+            Arc::clone(&memory),
+            id.clone(),
+            timeout_config(),
+            tx,
+            cancel_rx,
+        )
+        .await
+        .unwrap();
+
+        let mut events = Vec::new();
+        while let Some(Ok(event)) = rx.recv().await {
+            events.push(event);
+        }
+        assert!(matches!(
+            &events[events.len() - 2],
+            AgentStreamEvent::TextChunk { text } if text.contains(AGENT_TIMEOUT_MESSAGE)
+        ));
+        assert!(matches!(events.last(), Some(AgentStreamEvent::Done { .. })));
+
+        // The partial answer is kept, followed by the timeout message
+        let stored = memory.get_timestamped_messages(&id, true).await.unwrap();
+        assert_eq!(stored.len(), 2);
+        assert!(!stored[0].partial);
+        assert_eq!(stored[0].message.content.text(), "The answer is");
+        assert_eq!(stored[1].message.content.text(), AGENT_TIMEOUT_MESSAGE);
+    }
+
+    #[tokio::test]
+    async fn test_with_agent_timeout() {
+        assert_eq!(with_agent_timeout(0, async { 7 }).await, Some(7));
+        let slow = tokio::time::sleep(Duration::from_secs(5));
+        assert_eq!(with_agent_timeout(1, slow).await, None);
+    }
+
+    #[test]
+    fn test_with_overrides_falls_back_to_defaults() {
+        let config = AgentLoopConfig::default()
+            .with_overrides(None, None, None)
+            .unwrap();
+        assert_eq!(config.max_iterations, 10);
+        assert_eq!(config.max_tokens, 2000);
+        assert_eq!(config.temperature, 0.7);
+    }
+
+    #[test]
+    fn test_with_overrides_applies_values() {
+        let config = AgentLoopConfig::default()
+            .with_overrides(Some(3), Some(0.0), Some(512))
+            .unwrap();
+        assert_eq!(config.max_iterations, 3);
+        assert_eq!(config.temperature, 0.0);
+        assert_eq!(config.max_tokens, 512);
+    }
+
+    #[test]
+    fn test_with_overrides_rejects_out_of_range() {
+        assert!(AgentLoopConfig::default()
+            .with_overrides(Some(0), None, None)
+            .is_err());
+        assert!(AgentLoopConfig::default()
+            .with_overrides(Some(21), None, None)
+            .is_err());
+        assert!(AgentLoopConfig::default()
+            .with_overrides(None, Some(2.5), None)
+            .is_err());
+        assert!(AgentLoopConfig::default()
+            .with_overrides(None, Some(-0.1), None)
+            .is_err());
+        assert!(AgentLoopConfig::default()
+            .with_overrides(None, None, Some(0))
+            .is_err());
+    }
+
+    #[test]
+    fn test_with_stop() {
+        let config = AgentLoopConfig::default().with_stop(None).unwrap();
+        assert_eq!(config.stop, None);
+
+        let config = AgentLoopConfig::default()
+            .with_stop(Some(vec!["\nUser:".to_string(), "</s>".to_string()]))
+            .unwrap();
+        assert_eq!(
+            config.stop,
+            Some(vec!["\nUser:".to_string(), "</s>".to_string()])
+        );
+
+        let five = (0..5).map(|i| format!("STOP{}", i)).collect();
+        assert!(AgentLoopConfig::default().with_stop(Some(five)).is_err());
+        assert!(AgentLoopConfig::default()
+            .with_stop(Some(vec![String::new()]))
+            .is_err());
+    }
+
+    #[test]
+    fn test_with_sampling() {
+        let config = AgentLoopConfig::default()
+            .with_sampling(Some(0.5), Some(-2.0), Some(1.2))
+            .unwrap();
+        assert_eq!(config.top_p, Some(0.5));
+        assert_eq!(config.frequency_penalty, Some(-2.0));
+        assert_eq!(config.presence_penalty, Some(1.2));
+
+        let unchanged = config.clone().with_sampling(None, None, None).unwrap();
+        assert_eq!(unchanged.top_p, Some(0.5));
+        assert_eq!(unchanged.presence_penalty, Some(1.2));
+
+        // Both ends of 0-1 are allowed
+        for top_p in [0.0, 1.0] {
+            let config = AgentLoopConfig::default()
+                .with_sampling(Some(top_p), None, None)
+                .unwrap();
+            assert_eq!(config.top_p, Some(top_p));
+        }
+        assert!(AgentLoopConfig::default()
+            .with_sampling(Some(-0.1), None, None)
+            .is_err());
+        assert!(AgentLoopConfig::default()
+            .with_sampling(Some(1.1), None, None)
+            .is_err());
+        assert!(AgentLoopConfig::default()
+            .with_sampling(None, Some(2.5), None)
+            .is_err());
+        assert!(AgentLoopConfig::default()
+            .with_sampling(None, None, Some(-2.1))
+            .is_err());
+    }
+
+    #[test]
+    fn test_with_generation_defaults() {
+        let config = AgentLoopConfig::default()
+            .with_generation_defaults(Some(0.3), None, Some(0.9))
+            .unwrap();
+        assert_eq!(config.temperature, 0.3);
+        assert_eq!(config.max_tokens, 2000);
+        assert_eq!(config.top_p, Some(0.9));
+
+        assert!(AgentLoopConfig::default()
+            .with_generation_defaults(None, None, Some(-0.1))
+            .is_err());
+        assert!(AgentLoopConfig::default()
+            .with_generation_defaults(None, None, Some(1.5))
+            .is_err());
+    }
+}
@@ -0,0 +1,127 @@
+use crate::api::agent::core::types::AgentStreamEvent;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio::sync::mpsc::error::{SendTimeoutError, TrySendError};
+
+/// How long an essential event may wait for a full SSE channel before the
+/// client is treated as too slow and disconnected
+pub const SLOW_CLIENT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Events the client can't do without: answer text, tool activity, the final
+/// `Done` and errors. Status updates and reasoning are only progress display.
+pub fn is_essential(event: &AgentStreamEvent) -> bool {
+    !matches!(
+        event,
+        AgentStreamEvent::Status { .. } | AgentStreamEvent::ReasoningChunk { .. }
+    )
+}
+
+/// What happened to an event sent with [`send_with_backpressure`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SendOutcome {
+    Sent,
+    /// Non-essential event skipped because the channel was full
+    Dropped,
+    /// Essential event still didn't fit after the timeout
+    TimedOut,
+    /// The receiver is gone
+    Closed,
+}
+
+/// Send a stream event to a client without letting a slow client stall the
+/// agent loop.
+///
+/// Non-essential events are dropped when the channel is full. Essential events
+/// wait up to `timeout` for room; `TimedOut` tells the caller to disconnect
+/// the client.
+pub async fn send_with_backpressure(
+    tx: &mpsc::Sender<Result<AgentStreamEvent, anyhow::Error>>,
+    event: Result<AgentStreamEvent, anyhow::Error>,
+    timeout: Duration,
+) -> SendOutcome {
+    if matches!(&event, Ok(e) if !is_essential(e)) {
+        return match tx.try_send(event) {
+            Ok(()) => SendOutcome::Sent,
+            Err(TrySendError::Full(_)) => SendOutcome::Dropped,
+            Err(TrySendError::Closed(_)) => SendOutcome::Closed,
+        };
+    }
+
+    match tx.send_timeout(event, timeout).await {
+        Ok(()) => SendOutcome::Sent,
+        Err(SendTimeoutError::Timeout(_)) => SendOutcome::TimedOut,
+        Err(SendTimeoutError::Closed(_)) => SendOutcome::Closed,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn status() -> AgentStreamEvent {
+        AgentStreamEvent::Status {
+            status: "thinking".to_string(),
+            message: None,
+        }
+    }
+
+    fn text(text: &str) -> AgentStreamEvent {
+        AgentStreamEvent::TextChunk {
+            text: text.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_is_essential() {
+        assert!(!is_essential(&status()));
+        assert!(!is_essential(&AgentStreamEvent::ReasoningChunk {
+            text: "hmm".to_string()
+        }));
+        assert!(is_essential(&text("Hello")));
+        assert!(is_essential(&AgentStreamEvent::Error {
+            message: "boom".to_string()
+        }));
+    }
+
+    #[tokio::test]
+    async fn test_never_reading_client_does_not_block_sender() {
+        // The receiver is kept alive but never read
+        let (tx, _rx) = mpsc::channel(1);
+        let timeout = Duration::from_millis(20);
+
+        assert_eq!(
+            send_with_backpressure(&tx, Ok(text("first")), timeout).await,
+            SendOutcome::Sent
+        );
+        // Channel is now full: status updates are skipped straight away...
+        assert_eq!(
+            send_with_backpressure(&tx, Ok(status()), timeout).await,
+            SendOutcome::Dropped
+        );
+        // ...and answer text gives up after the timeout instead of waiting forever
+        assert_eq!(
+            send_with_backpressure(&tx, Ok(text("second")), timeout).await,
+            SendOutcome::TimedOut
+        );
+        assert_eq!(
+            send_with_backpressure(&tx, Err(anyhow::anyhow!("boom")), timeout).await,
+            SendOutcome::TimedOut
+        );
+    }
+
+    #[tokio::test]
+    async fn test_closed_receiver() {
+        let (tx, rx) = mpsc::channel(1);
+        drop(rx);
+        let timeout = Duration::from_millis(20);
+
+        assert_eq!(
+            send_with_backpressure(&tx, Ok(status()), timeout).await,
+            SendOutcome::Closed
+        );
+        assert_eq!(
+            send_with_backpressure(&tx, Ok(text("Hello")), timeout).await,
+            SendOutcome::Closed
+        );
+    }
+}
@@ -1,5 +1,6 @@
 use crate::api::agent::core::types::{ChatMessage, MessageContent, ToolCallResult};
 use chrono::Local;
+use log::warn;
 use std::fs::{self, OpenOptions};
 use std::io::Write;
 use std::path::PathBuf;
@@ -16,7 +17,7 @@ impl ConversationLogger {
         if enabled {
             // Ensure logs directory exists
             if let Err(e) = fs::create_dir_all(&log_path) {
-                println!("⚠️ Failed to create logs directory: {}", e);
+                warn!("⚠️ Failed to create logs directory: {}", e);
             }
         }
 
@@ -40,10 +41,10 @@ impl ConversationLogger {
             .open(&self.log_path)
         {
             if let Err(e) = file.write_all(log_entry.as_bytes()) {
-                println!("⚠️ Failed to write to log file: {}", e);
+                warn!("⚠️ Failed to write to log file: {}", e);
             }
         } else {
-            println!("⚠️ Failed to open log file: {:?}", self.log_path);
+            warn!("⚠️ Failed to open log file: {:?}", self.log_path);
         }
     }
 
@@ -60,7 +61,7 @@ impl ConversationLogger {
             .open(&self.log_path)
         {
             if let Err(e) = file.write_all(content.as_bytes()) {
-                println!("⚠️ Failed to write to log file: {}", e);
+                warn!("⚠️ Failed to write to log file: {}", e);
             }
         }
     }
@@ -0,0 +1,181 @@
+use crate::api::agent::core::types::{ChatMessage, MessageContent, MessageRole, ToolCall};
+
+/// Spots an agent going in circles: asking for exactly the tool calls it made
+/// last iteration, or alternating between two sets of calls (A, B, A, B).
+/// Either way the model already has those results, so running the calls again
+/// only burns iterations.
+#[derive(Default)]
+pub struct LoopDetector {
+    /// Signature of each executed batch of tool calls, oldest first
+    history: Vec<Vec<String>>,
+}
+
+impl LoopDetector {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Check the tool calls the model is asking for. Returns why the loop looks
+    /// stuck, or records the batch as executed and returns `None`.
+    pub fn check(&mut self, tool_calls: &[ToolCall]) -> Option<String> {
+        let batch = batch_signature(tool_calls);
+        let n = self.history.len();
+
+        if n >= 1 && self.history[n - 1] == batch {
+            return Some(format!(
+                "repeated {} with identical arguments",
+                describe(tool_calls)
+            ));
+        }
+        // A, B, A is fine (the model may retry after an error); A, B, A, B is not
+        if n >= 3 && self.history[n - 2] == batch && self.history[n - 3] == self.history[n - 1] {
+            return Some(format!(
+                "oscillating between {} and {}",
+                self.history[n - 1].join(", "),
+                describe(tool_calls)
+            ));
+        }
+
+        self.history.push(batch);
+        None
+    }
+}
+
+/// Ask the model to stop calling tools and answer, once a stuck loop is detected.
+/// Added to the outgoing request only, never stored. Joined onto a trailing
+/// user message (e.g. folded tool results) to keep user/assistant alternation.
+pub fn append_final_answer_nudge(messages: &mut Vec<ChatMessage>, reason: &str) {
+    let nudge = format!(
+        "You are calling tools in a loop ({}). The results you need are already above. \
+         Do not call any more tools; answer the original question now with the information you have.",
+        reason
+    );
+    if let Some(last) = messages.last_mut() {
+        if last.role == MessageRole::User {
+            if let MessageContent::Text(text) = &mut last.content {
+                text.push_str("\n\n");
+                text.push_str(&nudge);
+                return;
+            }
+        }
+    }
+    messages.push(ChatMessage {
+        role: MessageRole::User,
+        content: MessageContent::Text(nudge),
+        name: None,
+        tool_calls: None,
+        tool_call_id: None,
+        reasoning_content: None,
+    });
+}
+
+fn describe(tool_calls: &[ToolCall]) -> String {
+    batch_signature(tool_calls).join(", ")
+}
+
+/// Order-independent signature of a batch; arguments are normalized through
+/// serde so whitespace and key formatting differences don't hide a repeat
+fn batch_signature(tool_calls: &[ToolCall]) -> Vec<String> {
+    let mut calls: Vec<String> = tool_calls
+        .iter()
+        .map(|call| {
+            let arguments = serde_json::from_str::<serde_json::Value>(&call.function.arguments)
+                .map(|v| v.to_string())
+                .unwrap_or_else(|_| call.function.arguments.trim().to_string());
+            format!("{}({})", call.function.name, arguments)
+        })
+        .collect();
+    calls.sort();
+    calls
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::agent::core::types::FunctionCall;
+
+    fn call(name: &str, arguments: &str) -> ToolCall {
+        ToolCall {
+            id: format!("call_{}", name),
+            tool_type: "function".to_string(),
+            function: FunctionCall {
+                name: name.to_string(),
+                arguments: arguments.to_string(),
+            },
+        }
+    }
+
+    #[test]
+    fn test_repeated_identical_call_is_stuck() {
+        let mut detector = LoopDetector::new();
+        assert_eq!(
+            detector.check(&[call("get_weather", r#"{"city": "Paris"}"#)]),
+            None
+        );
+        // Same call, differently formatted arguments
+        let reason = detector
+            .check(&[call("get_weather", r#"{ "city":"Paris" }"#)])
+            .unwrap();
+        assert_eq!(
+            reason,
+            r#"repeated get_weather({"city":"Paris"}) with identical arguments"#
+        );
+    }
+
+    #[test]
+    fn test_changed_arguments_are_not_stuck() {
+        let mut detector = LoopDetector::new();
+        assert_eq!(
+            detector.check(&[call("get_weather", r#"{"city": "Paris"}"#)]),
+            None
+        );
+        assert_eq!(
+            detector.check(&[call("get_weather", r#"{"city": "Rome"}"#)]),
+            None
+        );
+        // A, B, A is still allowed
+        assert_eq!(
+            detector.check(&[call("get_weather", r#"{"city": "Paris"}"#)]),
+            None
+        );
+    }
+
+    #[test]
+    fn test_oscillation_is_stuck() {
+        let mut detector = LoopDetector::new();
+        let a = [call("search", r#"{"q": "rust"}"#)];
+        let b = [call("fetch", r#"{"url": "https://example.com"}"#)];
+        assert_eq!(detector.check(&a), None);
+        assert_eq!(detector.check(&b), None);
+        assert_eq!(detector.check(&a), None);
+        let reason = detector.check(&b).unwrap();
+        assert!(reason.starts_with("oscillating between search("));
+    }
+
+    #[test]
+    fn test_nudge_joins_trailing_user_message() {
+        let tool_results = ChatMessage {
+            role: MessageRole::User,
+            content: MessageContent::Text("Tool results:\nget_weather: 21C".to_string()),
+            name: None,
+            tool_calls: None,
+            tool_call_id: None,
+            reasoning_content: None,
+        };
+        let mut messages = vec![tool_results];
+        append_final_answer_nudge(&mut messages, "repeated get_weather({})");
+        assert_eq!(messages.len(), 1);
+        let text = messages[0].content.text();
+        assert!(text.starts_with("Tool results:"));
+        assert!(text.contains("Do not call any more tools"));
+    }
+
+    #[test]
+    fn test_batches_compare_regardless_of_order() {
+        let mut detector = LoopDetector::new();
+        let first = [call("a", "{}"), call("b", "{}")];
+        let second = [call("b", "{}"), call("a", "{}")];
+        assert_eq!(detector.check(&first), None);
+        assert!(detector.check(&second).is_some());
+    }
+}
@@ -1,5 +1,8 @@
 pub mod agent_loop;
+pub mod backpressure;
 pub mod logging;
+pub mod loop_detector;
+pub mod stream_buffer;
 pub mod streaming;
 pub mod types;
 pub mod utils;
@@ -0,0 +1,96 @@
+use std::time::{Duration, Instant};
+
+/// Coalesces streamed content deltas into fewer, larger `TextChunk` events.
+/// Text is released once `flush_chars` characters are pending or the oldest
+/// pending text is `flush_interval` old. A zero interval disables buffering.
+pub struct TextChunkBuffer {
+    pending: String,
+    pending_chars: usize,
+    pending_since: Option<Instant>,
+    flush_interval: Duration,
+    flush_chars: usize,
+}
+
+impl TextChunkBuffer {
+    pub fn new(flush_ms: u64, flush_chars: usize) -> Self {
+        Self {
+            pending: String::new(),
+            pending_chars: 0,
+            pending_since: None,
+            flush_interval: Duration::from_millis(flush_ms),
+            flush_chars,
+        }
+    }
+
+    /// Whether deltas are buffered at all
+    pub fn is_enabled(&self) -> bool {
+        !self.flush_interval.is_zero()
+    }
+
+    /// Add a delta, returning the text to send now (if any)
+    pub fn push(&mut self, text: &str, now: Instant) -> Option<String> {
+        if !self.is_enabled() {
+            return Some(text.to_string());
+        }
+        self.pending.push_str(text);
+        self.pending_chars += text.chars().count();
+        let since = *self.pending_since.get_or_insert(now);
+
+        if self.pending_chars >= self.flush_chars.max(1)
+            || now.duration_since(since) >= self.flush_interval
+        {
+            return self.flush();
+        }
+        None
+    }
+
+    /// When pending text must be sent even if no more deltas arrive
+    pub fn deadline(&self) -> Option<Instant> {
+        self.pending_since.map(|since| since + self.flush_interval)
+    }
+
+    /// Take all pending text
+    pub fn flush(&mut self) -> Option<String> {
+        self.pending_since = None;
+        self.pending_chars = 0;
+        if self.pending.is_empty() {
+            return None;
+        }
+        Some(std::mem::take(&mut self.pending))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_disabled_buffer_passes_deltas_through() {
+        let mut buffer = TextChunkBuffer::new(0, 64);
+        let now = Instant::now();
+        assert_eq!(buffer.push("Hel", now).as_deref(), Some("Hel"));
+        assert_eq!(buffer.deadline(), None);
+        assert_eq!(buffer.flush(), None);
+    }
+
+    #[test]
+    fn test_buffer_flushes_on_size_and_time() {
+        let start = Instant::now();
+        let mut buffer = TextChunkBuffer::new(50, 8);
+
+        assert_eq!(buffer.push("Hel", start), None);
+        assert_eq!(buffer.deadline(), Some(start + Duration::from_millis(50)));
+        assert_eq!(buffer.push("lo, ", start).as_deref(), None);
+        // Eight characters pending
+        assert_eq!(buffer.push("w", start).as_deref(), Some("Hello, w"));
+        assert_eq!(buffer.deadline(), None);
+
+        assert_eq!(buffer.push("or", start), None);
+        let later = start + Duration::from_millis(60);
+        assert_eq!(buffer.push("ld", later).as_deref(), Some("orld"));
+
+        assert_eq!(buffer.push("!", later), None);
+        assert_eq!(buffer.flush().as_deref(), Some("!"));
+        assert_eq!(buffer.flush(), None);
+    }
+}
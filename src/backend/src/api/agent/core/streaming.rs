@@ -4,20 +4,124 @@ use crate::api::agent::core::types::{
     ToolCallResult,
 };
 use crate::api::agent::memory::sqlite_memory::SqliteConversationMemory;
+use crate::api::agent::service::utils::StreamCleaner;
+use log::{info, warn};
 
 use crate::api::agent::tools::framework::registry::ToolRegistry;
+use crate::api::metrics::registry as metrics;
 use anyhow::Result;
 use reqwest::Client;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tokio::sync::mpsc;
 
-use super::agent_loop::AgentLoopConfig;
-use super::utils::{format_tool_status_message, StatusType};
+use super::agent_loop::{with_agent_timeout, AgentLoopConfig, AGENT_TIMEOUT_MESSAGE};
+use super::loop_detector::{append_final_answer_nudge, LoopDetector};
+use super::stream_buffer::TextChunkBuffer;
+use super::utils::{
+    format_tool_status_message, prepare_messages_for_llm, strip_images, tool_result_messages,
+    trim_to_token_budget, StatusType, TokenCounter,
+};
+
+/// How often the answer streamed so far is saved, so a crash mid-stream keeps it
+const PARTIAL_SAVE_INTERVAL: Duration = Duration::from_secs(3);
+
+/// Store a completed assistant message, replacing its partial row if one was saved
+async fn store_assistant_message(
+    sqlite_memory: &SqliteConversationMemory,
+    conversation_id: &str,
+    partial_id: Option<i64>,
+    message: ChatMessage,
+) -> Result<()> {
+    match partial_id {
+        Some(id) => sqlite_memory.finalize_partial_message(id, message).await,
+        None => sqlite_memory.add_message(conversation_id, message).await,
+    }
+}
 
 /// Execute agent loop with streaming support
 /// Sends events through the provided channel
+///
+/// The whole loop is bounded by `config.agent_timeout_secs`. On timeout the
+/// answer streamed so far is kept (as of its last partial save), the client
+/// gets [`AGENT_TIMEOUT_MESSAGE`] as text followed by `Done`, and the message
+/// is stored in the conversation.
 #[allow(clippy::too_many_arguments)]
 pub async fn execute_agent_loop_streaming(
+    client: &Client,
+    llama_url: &str,
+    model_name: String,
+    messages: Vec<ChatMessage>,
+    tools: Vec<crate::api::agent::core::types::Tool>,
+    tool_registry: Arc<ToolRegistry>,
+    sqlite_memory: Arc<SqliteConversationMemory>,
+    conversation_id: String,
+    config: AgentLoopConfig,
+    tx: mpsc::Sender<Result<AgentStreamEvent, anyhow::Error>>,
+    cancel_rx: tokio::sync::watch::Receiver<bool>,
+) -> Result<()> {
+    let timeout_secs = config.agent_timeout_secs;
+    let request_id = config.request_id.clone();
+
+    let run = run_agent_loop_streaming(
+        client,
+        llama_url,
+        model_name,
+        messages,
+        tools,
+        tool_registry,
+        Arc::clone(&sqlite_memory),
+        conversation_id.clone(),
+        config,
+        tx.clone(),
+        cancel_rx,
+    );
+    if let Some(result) = with_agent_timeout(timeout_secs, run).await {
+        return result;
+    }
+
+    warn!(
+        "[{}] ⏱️ Streaming agent loop timed out after {}s",
+        request_id, timeout_secs
+    );
+    if let Err(e) = sqlite_memory
+        .finish_partial_messages(&conversation_id)
+        .await
+    {
+        warn!("[{}] ⚠️ Failed to keep partial response: {}", request_id, e);
+    }
+    let timeout_message = ChatMessage {
+        role: MessageRole::Assistant,
+        content: MessageContent::Text(AGENT_TIMEOUT_MESSAGE.to_string()),
+        name: None,
+        tool_calls: None,
+        tool_call_id: None,
+        reasoning_content: None,
+    };
+    if let Err(e) = sqlite_memory
+        .add_message(&conversation_id, timeout_message)
+        .await
+    {
+        warn!("[{}] ⚠️ Failed to store timeout message: {}", request_id, e);
+    }
+
+    let _ = tx
+        .send(Ok(AgentStreamEvent::TextChunk {
+            text: format!("\n\n{}", AGENT_TIMEOUT_MESSAGE),
+        }))
+        .await;
+    let _ = tx
+        .send(Ok(AgentStreamEvent::Done {
+            conversation_id: Some(conversation_id),
+            tool_calls: None,
+            usage: None,
+        }))
+        .await;
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn run_agent_loop_streaming(
     client: &Client,
     llama_url: &str,
     model_name: String,
@@ -32,6 +136,10 @@ pub async fn execute_agent_loop_streaming(
 ) -> Result<()> {
     let mut tool_results = Vec::new();
     let mut iterations = 0;
+    // Set once the model repeats itself; tools are then withheld until it answers
+    let mut loop_detector = LoopDetector::new();
+    let mut stuck_reason: Option<String> = None;
+    let mut token_counter = TokenCounter::default();
     let mut total_usage: Option<crate::api::agent::core::types::Usage> = None;
     let logger = ConversationLogger::new(config.debug_logging, &conversation_id);
 
@@ -46,7 +154,10 @@ pub async fn execute_agent_loop_streaming(
 
         // Check for cancellation at start of iteration
         if *cancel_rx.borrow() {
-            println!("⚠️ Cancellation signal received at start of iteration");
+            warn!(
+                "[{}] ⚠️ Cancellation signal received at start of iteration",
+                config.request_id
+            );
             break;
         }
 
@@ -102,51 +213,31 @@ pub async fn execute_agent_loop_streaming(
             break;
         }
 
-        // Build request - convert tool messages to user messages to maintain alternation
-        // (the LLM server expects alternating user/assistant and doesn't allow prefill with tool_calls)
-        let mut filtered_messages: Vec<ChatMessage> = Vec::new();
-        let mut tool_results_buffer: Vec<String> = Vec::new();
-
-        for msg in messages.iter() {
-            if matches!(msg.role, MessageRole::Tool) {
-                // Collect tool results to create a user message
-                let tool_name = msg.name.as_deref().unwrap_or("unknown");
-                tool_results_buffer.push(format!("{}: {}", tool_name, msg.content.text()));
-            } else {
-                // If we have buffered tool results, create a user message with them
-                if !tool_results_buffer.is_empty() {
-                    let tool_results_content = tool_results_buffer.join("\n");
-                    filtered_messages.push(ChatMessage {
-                        role: MessageRole::User,
-                        content: MessageContent::Text(format!(
-                            "Tool results:\n{}",
-                            tool_results_content
-                        )),
-                        name: None,
-                        tool_calls: None,
-                        tool_call_id: None,
-                        reasoning_content: None,
-                    });
-                    tool_results_buffer.clear();
-                }
-                filtered_messages.push(msg.clone());
-            }
+        // Build request - tool results are folded into user messages unless the
+        // server supports the `tool` role
+        let context = trim_to_token_budget(&messages, config.context_token_budget, |text| {
+            token_counter.count(text)
+        });
+        if context.len() < messages.len() {
+            info!(
+                "[{}] ✂️ Dropped {} old message(s) to fit the {}-token context budget",
+                config.request_id,
+                messages.len() - context.len(),
+                config.context_token_budget
+            );
         }
-
-        // Handle any remaining tool results at the end
-        if !tool_results_buffer.is_empty() {
-            let tool_results_content = tool_results_buffer.join("\n");
-            filtered_messages.push(ChatMessage {
-                role: MessageRole::User,
-                content: MessageContent::Text(format!("Tool results:\n{}", tool_results_content)),
-                name: None,
-                tool_calls: None,
-                tool_call_id: None,
-                reasoning_content: None,
-            });
+        let context = if config.supports_vision {
+            context
+        } else {
+            strip_images(&context)
+        };
+        let mut filtered_messages = prepare_messages_for_llm(&context, config.supports_tool_role);
+        if let Some(reason) = &stuck_reason {
+            append_final_answer_nudge(&mut filtered_messages, reason);
         }
+        let offer_tools = !tools.is_empty() && stuck_reason.is_none();
 
-        let tool_choice = if !tools.is_empty() {
+        let tool_choice = if offer_tools {
             Some("auto".to_string())
         } else {
             None
@@ -157,11 +248,15 @@ pub async fn execute_agent_loop_streaming(
             messages: filtered_messages,
             model: model_name.clone(),
             temperature: Some(config.temperature),
+            top_p: config.top_p,
+            frequency_penalty: config.frequency_penalty,
+            presence_penalty: config.presence_penalty,
             max_tokens: Some(config.max_tokens),
-            tools: if tools.is_empty() {
-                None
-            } else {
+            stop: config.stop.clone(),
+            tools: if offer_tools {
                 Some(tools.clone())
+            } else {
+                None
             },
             tool_choice,
             stream: Some(true),
@@ -176,10 +271,17 @@ pub async fn execute_agent_loop_streaming(
             &format!("Sending request to LLM (iteration {})...", iterations),
         );
         logger.log_raw("\n[STREAMING RESPONSE START]\n");
+        let llm_started = std::time::Instant::now();
         let mut response = tokio::select! {
             res = client.post(llama_url).json(&request).send() => {
                 match res {
-                    Ok(r) => r,
+                    Ok(r) => {
+                        metrics::record(|m| {
+                            m.llm_request_duration
+                                .observe(llm_started.elapsed().as_secs_f64())
+                        });
+                        r
+                    }
                     Err(e) => {
                         let _ = tx.send(Ok(AgentStreamEvent::Error {
                             message: format!("Request failed: {}", e),
@@ -189,7 +291,7 @@ pub async fn execute_agent_loop_streaming(
                 }
             }
             _ = cancel_rx.changed() => {
-                println!("⚠️ Cancellation signal received during request setup");
+                warn!("[{}] ⚠️ Cancellation signal received during request setup", config.request_id);
                 break;
             }
         };
@@ -210,16 +312,26 @@ pub async fn execute_agent_loop_streaming(
         let mut accumulated_tool_calls: Vec<crate::api::agent::core::types::ToolCall> = Vec::new();
         let mut final_usage: Option<crate::api::agent::core::types::Usage> = None;
         let mut loop_cancelled = false;
+        let mut text_buffer =
+            TextChunkBuffer::new(config.stream_flush_ms, config.stream_flush_chars);
+        // Strip <think> and tool markers so streamed text matches non-streamed responses
+        let mut cleaner = StreamCleaner::new();
+        // Row holding this iteration's partial answer, once one has been saved
+        let mut partial_id: Option<i64> = None;
+        let mut last_partial_save = Instant::now();
 
         // Process SSE stream
         loop {
+            // Evaluated even while the deadline branch is disabled, hence the fallback
+            let flush_deadline =
+                tokio::time::Instant::from_std(text_buffer.deadline().unwrap_or_else(Instant::now));
             tokio::select! {
                 chunk_option = response.chunk() => {
                     match chunk_option {
                         Ok(Some(chunk)) => {
                             // Eagerly check cancellation
                             if *cancel_rx.borrow() {
-                                println!("⚠️ Cancellation signal received (eager check)");
+                                warn!("[{}] ⚠️ Cancellation signal received (eager check)", config.request_id);
                                 loop_cancelled = true;
                                 break;
                             }
@@ -232,7 +344,8 @@ pub async fn execute_agent_loop_streaming(
                                     }
 
                                     if let Ok(json) = serde_json::from_str::<serde_json::Value>(data) {
-                                        // Extract usage if present
+                                        // Extract usage if present. The usage chunk is cumulative for this
+                                        // response, so keep only the latest rather than summing chunks.
                                         if let Some(usage_val) = json.get("usage") {
                                             if let Ok(usage) = serde_json::from_value(usage_val.clone()) {
                                                 final_usage = Some(usage);
@@ -246,14 +359,35 @@ pub async fn execute_agent_loop_streaming(
                                                 if let Some(delta) = choice.get("delta") {
                                                     // 1. Handle Content Streaming
                                                     if let Some(content) = delta.get("content").and_then(|c| c.as_str()) {
-                                                        if !content.is_empty() {
-                                                            accumulated_content.push_str(content);
-                                                            // Stream text directly to client
-                                                            if tx.send(Ok(AgentStreamEvent::TextChunk { text: content.to_string() })).await.is_err() {
-                                                                // Client disconnected, treat as cancellation
-                                                                loop_cancelled = true;
+                                                        let cleaned = cleaner.push(content);
+                                                        if !cleaned.is_empty() {
+                                                            accumulated_content.push_str(&cleaned);
+                                                            // Stream text to client, coalesced if stream_flush_ms is set
+                                                            if let Some(text) = text_buffer.push(&cleaned, Instant::now()) {
+                                                                if tx.send(Ok(AgentStreamEvent::TextChunk { text })).await.is_err() {
+                                                                    // Client disconnected, treat as cancellation
+                                                                    loop_cancelled = true;
+                                                                }
+                                                            }
+                                                        }
+                                                        logger.log_raw(content);
+
+                                                        if !accumulated_content.is_empty()
+                                                            && last_partial_save.elapsed() >= PARTIAL_SAVE_INTERVAL
+                                                        {
+                                                            last_partial_save = Instant::now();
+                                                            let reasoning = Some(accumulated_reasoning_content.as_str())
+                                                                .filter(|r| !r.is_empty());
+                                                            match sqlite_memory
+                                                                .save_partial_message(&conversation_id, partial_id, &accumulated_content, reasoning)
+                                                                .await
+                                                            {
+                                                                Ok(id) => partial_id = Some(id),
+                                                                Err(e) => warn!(
+                                                                    "[{}] ⚠️ Failed to save partial response: {}",
+                                                                    config.request_id, e
+                                                                ),
                                                             }
-                                                            logger.log_raw(content);
                                                         }
                                                     }
 
@@ -261,11 +395,26 @@ pub async fn execute_agent_loop_streaming(
                                                     if let Some(reasoning) = delta.get("reasoning_content").and_then(|c| c.as_str()) {
                                                         if !reasoning.is_empty() {
                                                             accumulated_reasoning_content.push_str(reasoning);
+                                                            // Keep text and reasoning in the order they were generated
+                                                            if let Some(text) = text_buffer.flush() {
+                                                                if tx.send(Ok(AgentStreamEvent::TextChunk { text })).await.is_err() {
+                                                                    loop_cancelled = true;
+                                                                }
+                                                            }
+                                                            if tx.send(Ok(AgentStreamEvent::ReasoningChunk { text: reasoning.to_string() })).await.is_err() {
+                                                                loop_cancelled = true;
+                                                            }
                                                         }
                                                     }
 
                                                     // 2. Handle Tool Calls Streaming
                                                     if let Some(tool_calls_arr) = delta.get("tool_calls").and_then(|t| t.as_array()) {
+                                                        // Text before a tool call goes out before the call is announced
+                                                        if let Some(text) = text_buffer.flush() {
+                                                            if tx.send(Ok(AgentStreamEvent::TextChunk { text })).await.is_err() {
+                                                                loop_cancelled = true;
+                                                            }
+                                                        }
                                                         for tc in tool_calls_arr {
                                                             let index = tc.get("index").and_then(|i| i.as_u64()).map(|i| i as usize);
 
@@ -324,18 +473,49 @@ pub async fn execute_agent_loop_streaming(
                         }
                     }
                 }
+                _ = tokio::time::sleep_until(flush_deadline), if text_buffer.deadline().is_some() => {
+                    if let Some(text) = text_buffer.flush() {
+                        if tx.send(Ok(AgentStreamEvent::TextChunk { text })).await.is_err() {
+                            loop_cancelled = true;
+                            break;
+                        }
+                    }
+                }
                 _ = cancel_rx.changed() => {
-                    println!("⚠️ Cancellation signal received during streaming");
+                    warn!("[{}] ⚠️ Cancellation signal received during streaming", config.request_id);
                     loop_cancelled = true;
                     break;
                 }
             }
         }
 
+        // A trailing '<' held back as a possible marker is ordinary text after all
+        let held_back = cleaner.finish();
+        if !held_back.is_empty() {
+            accumulated_content.push_str(&held_back);
+            let _ = text_buffer.push(&held_back, Instant::now());
+        }
+
+        // Send whatever is still buffered before tool calls or the final Done event
+        if let Some(text) = text_buffer.flush() {
+            let _ = tx.send(Ok(AgentStreamEvent::TextChunk { text })).await;
+        }
+
+        // Each iteration is a separate LLM request, so add its usage to the total
+        if let Some(usage) = final_usage {
+            match total_usage.as_mut() {
+                Some(total) => total.accumulate(&usage),
+                None => total_usage = Some(usage),
+            }
+        }
+
         // Handle Cancellation - SAVE STATE
         if loop_cancelled {
             if !accumulated_content.is_empty() {
-                println!("💾 Saving partial response due to cancellation...");
+                info!(
+                    "[{}] 💾 Saving partial response due to cancellation...",
+                    config.request_id
+                );
                 let partial_assistant_message = ChatMessage {
                     role: MessageRole::Assistant,
                     content: MessageContent::Text(accumulated_content),
@@ -348,11 +528,18 @@ pub async fn execute_agent_loop_streaming(
                         None
                     },
                 };
-                if let Err(e) = sqlite_memory
-                    .add_message(&conversation_id, partial_assistant_message)
-                    .await
+                if let Err(e) = store_assistant_message(
+                    &sqlite_memory,
+                    &conversation_id,
+                    partial_id,
+                    partial_assistant_message,
+                )
+                .await
                 {
-                    println!("Failed to save partial message: {}", e);
+                    warn!(
+                        "[{}] Failed to save partial message: {}",
+                        config.request_id, e
+                    );
                 }
                 let _ = tx
                     .send(Ok(AgentStreamEvent::Done {
@@ -365,22 +552,34 @@ pub async fn execute_agent_loop_streaming(
             break;
         }
 
-        // Accumulate final usage
-        if let Some(usage) = final_usage {
-            if let Some(ref mut total) = total_usage {
-                total.prompt_tokens = usage.prompt_tokens; // Usually cumulative in last chunk
-                total.completion_tokens = usage.completion_tokens;
-                total.total_tokens = usage.total_tokens;
-            } else {
-                total_usage = Some(usage);
+        // Decide next step: Tool Execution or Final Answer
+        // Running the same calls again would only burn iterations; once stuck,
+        // any tool calls the server still parses out are ignored
+        if !accumulated_tool_calls.is_empty() && stuck_reason.is_none() {
+            if let Some(reason) = loop_detector.check(&accumulated_tool_calls) {
+                warn!(
+                    "[{}] 🔁 Agent is stuck ({}), asking for a final answer without tools",
+                    config.request_id, reason
+                );
+                stuck_reason = Some(reason);
+                // The text of this response isn't kept, so neither is its partial row
+                if let Some(id) = partial_id {
+                    let _ = sqlite_memory.delete_partial_message(id).await;
+                }
+                let _ = tx
+                    .send(Ok(AgentStreamEvent::Status {
+                        status: "thinking".to_string(),
+                        message: Some("Wrapping up with the information gathered...".to_string()),
+                    }))
+                    .await;
+                continue;
             }
-        }
 
-        // Decide next step: Tool Execution or Final Answer
-        if !accumulated_tool_calls.is_empty() {
             // Send tool call events
             let tool_calls_to_process = accumulated_tool_calls.clone();
 
+            // Announce every tool call up front, then run them concurrently
+            let mut pending_calls = Vec::new();
             for tool_call in &tool_calls_to_process {
                 let tool_name = tool_call.function.name.clone();
 
@@ -408,8 +607,6 @@ pub async fn execute_agent_loop_streaming(
                     break;
                 }
 
-                // Send status updates and execute...
-
                 let status_msg = format_tool_status_message(
                     &display_name,
                     tool_metadata.as_ref(),
@@ -422,87 +619,130 @@ pub async fn execute_agent_loop_streaming(
                     }))
                     .await;
 
-                let status_msg = format_tool_status_message(
-                    &display_name,
-                    tool_metadata.as_ref(),
-                    StatusType::Executing,
-                );
-                let _ = tx
-                    .send(Ok(AgentStreamEvent::Status {
-                        status: "tool_executing".to_string(),
-                        message: Some(status_msg),
-                    }))
-                    .await;
+                pending_calls.push((tool_call, tool_metadata, display_name));
+            }
 
-                // Execute tool
-                let tool_exec_start = std::time::Instant::now();
-                match tool_registry.execute_tool_call(tool_call).await {
-                    Ok(result) => {
-                        let duration = tool_exec_start.elapsed();
-                        logger.log_tool_result(&result);
-                        // Send tool result first
-                        let _ = tx
-                            .send(Ok(AgentStreamEvent::ToolResult {
-                                tool_name: tool_call.function.name.clone(),
-                                display_name: Some(display_name.clone()),
-                                success: true,
-                                result: Some(result.result.clone()),
-                            }))
-                            .await;
-                        // Then send completion status
-                        let status_msg = format_tool_status_message(
-                            &display_name,
-                            tool_metadata.as_ref(),
-                            StatusType::Complete(duration),
-                        );
-                        let _ = tx
-                            .send(Ok(AgentStreamEvent::Status {
-                                status: "tool_complete".to_string(),
-                                message: Some(status_msg),
-                            }))
-                            .await;
-                        tool_results.push(result.clone());
+            if loop_cancelled {
+                // None of the tool calls ran, so only the text streamed so far is
+                // kept; without any, the partial row is dropped
+                if accumulated_content.is_empty() {
+                    if let Some(id) = partial_id {
+                        let _ = sqlite_memory.delete_partial_message(id).await;
                     }
-                    Err(e) => {
-                        let duration = tool_exec_start.elapsed();
-                        // Send tool result (error) first
-                        let _ = tx
-                            .send(Ok(AgentStreamEvent::ToolResult {
-                                tool_name: tool_call.function.name.clone(),
-                                display_name: Some(display_name.clone()),
-                                success: false,
-                                result: Some(format!("Error: {}", e)),
-                            }))
-                            .await;
-                        // Then send error status
-                        let status_msg = format_tool_status_message(
-                            &display_name,
-                            tool_metadata.as_ref(),
-                            StatusType::Error(duration),
+                } else {
+                    info!(
+                        "[{}] 💾 Saving partial response due to cancellation...",
+                        config.request_id
+                    );
+                    let partial_assistant_message = ChatMessage {
+                        role: MessageRole::Assistant,
+                        content: MessageContent::Text(accumulated_content),
+                        name: None,
+                        tool_calls: None,
+                        tool_call_id: None,
+                        reasoning_content: if !accumulated_reasoning_content.is_empty() {
+                            Some(accumulated_reasoning_content)
+                        } else {
+                            None
+                        },
+                    };
+                    if let Err(e) = store_assistant_message(
+                        &sqlite_memory,
+                        &conversation_id,
+                        partial_id,
+                        partial_assistant_message,
+                    )
+                    .await
+                    {
+                        warn!(
+                            "[{}] Failed to save partial message: {}",
+                            config.request_id, e
                         );
-                        let _ = tx
-                            .send(Ok(AgentStreamEvent::Status {
-                                status: "tool_error".to_string(),
-                                message: Some(status_msg),
-                            }))
-                            .await;
-                        let error_result = ToolCallResult {
-                            tool_name: tool_call.function.name.clone(),
-                            result: format!("Error: {}", e),
-                        };
-                        logger.log_tool_result(&error_result);
-                        tool_results.push(error_result);
                     }
                 }
+                break;
             }
 
-            if loop_cancelled {
-                // If cancelled during tool calls, we should probably save what we have?
-                // Assistant message with tool calls was not yet added to DB in my previous logic (it was added after loop).
-                // Let's add it now if we have it?
-                // For now, simple break is safer, user can retry.
-                break;
+            // Execute tools concurrently; each streams its own result as soon as it finishes
+            let tool_timeout = Duration::from_secs(config.tool_timeout_secs);
+            let request_id = config.request_id.as_str();
+            let executions =
+                pending_calls
+                    .into_iter()
+                    .map(|(tool_call, tool_metadata, display_name)| {
+                        let tx = tx.clone();
+                        let tool_registry = Arc::clone(&tool_registry);
+                        async move {
+                            let status_msg = format_tool_status_message(
+                                &display_name,
+                                tool_metadata.as_ref(),
+                                StatusType::Executing,
+                            );
+                            let _ = tx
+                                .send(Ok(AgentStreamEvent::Status {
+                                    status: "tool_executing".to_string(),
+                                    message: Some(status_msg),
+                                }))
+                                .await;
+
+                            let tool_exec_start = std::time::Instant::now();
+                            let execution = tool_registry
+                                .execute_tool_call_with_timeout(tool_call, tool_timeout, request_id)
+                                .await;
+                            let duration = tool_exec_start.elapsed();
+
+                            let (result, success, status, status_type) = match execution {
+                                Ok(result) => (
+                                    result,
+                                    true,
+                                    "tool_complete",
+                                    StatusType::Complete(duration),
+                                ),
+                                Err(e) => (
+                                    ToolCallResult {
+                                        tool_name: tool_call.function.name.clone(),
+                                        result: format!("Error: {}", e),
+                                        tool_call_id: Some(tool_call.id.clone()),
+                                        sources: None,
+                                    },
+                                    false,
+                                    "tool_error",
+                                    StatusType::Error(duration),
+                                ),
+                            };
+
+                            // Send tool result first, then the completion/error status
+                            let _ = tx
+                                .send(Ok(AgentStreamEvent::ToolResult {
+                                    tool_name: tool_call.function.name.clone(),
+                                    display_name: Some(display_name.clone()),
+                                    success,
+                                    result: Some(result.result.clone()),
+                                    sources: result.sources.clone(),
+                                }))
+                                .await;
+                            let status_msg = format_tool_status_message(
+                                &display_name,
+                                tool_metadata.as_ref(),
+                                status_type,
+                            );
+                            let _ = tx
+                                .send(Ok(AgentStreamEvent::Status {
+                                    status: status.to_string(),
+                                    message: Some(status_msg),
+                                }))
+                                .await;
+
+                            result
+                        }
+                    });
+
+            // join_all preserves input order, keeping the transcript stable
+            let iteration_results = futures::future::join_all(executions).await;
+            for result in &iteration_results {
+                logger.log_tool_result(result);
             }
+            tool_results.extend(iteration_results.iter().cloned());
 
             // Create and store assistant message with tool calls
             let assistant_message = ChatMessage {
@@ -521,35 +761,39 @@ pub async fn execute_agent_loop_streaming(
                     None
                 },
             };
+            if config.persist_tool_messages {
+                if let Err(e) = store_assistant_message(
+                    &sqlite_memory,
+                    &conversation_id,
+                    partial_id,
+                    assistant_message.clone(),
+                )
+                .await
+                {
+                    warn!(
+                        "[{}] ⚠️ Failed to store assistant tool call message: {}",
+                        config.request_id, e
+                    );
+                }
+            } else if let Some(id) = partial_id {
+                let _ = sqlite_memory.delete_partial_message(id).await;
+            }
             messages.push(assistant_message.clone());
             logger.log_message(&assistant_message);
 
-            // Add tool results as tool messages
-            let tool_calls_to_msg_process = messages
-                .last()
-                .unwrap()
-                .tool_calls
-                .as_ref()
-                .unwrap()
-                .clone();
-            for tool_call in tool_calls_to_msg_process {
-                let result = tool_results
-                    .iter()
-                    .find(|r| r.tool_name == tool_call.function.name)
-                    .cloned()
-                    .unwrap_or_else(|| ToolCallResult {
-                        tool_name: tool_call.function.name.clone(),
-                        result: String::new(),
-                    });
-
-                let tool_message = ChatMessage {
-                    role: MessageRole::Tool,
-                    content: MessageContent::Text(result.result.clone()),
-                    name: Some(tool_call.function.name.clone()),
-                    tool_calls: None,
-                    tool_call_id: Some(tool_call.id.clone()),
-                    reasoning_content: None,
-                };
+            // Add tool results as tool messages, in the same order as the tool calls
+            for tool_message in tool_result_messages(&tool_calls_to_process, &iteration_results) {
+                if config.persist_tool_messages {
+                    if let Err(e) = sqlite_memory
+                        .add_message(&conversation_id, tool_message.clone())
+                        .await
+                    {
+                        warn!(
+                            "[{}] ⚠️ Failed to store tool result message: {}",
+                            config.request_id, e
+                        );
+                    }
+                }
                 messages.push(tool_message.clone());
                 logger.log_message(&tool_message);
             }
@@ -592,9 +836,13 @@ pub async fn execute_agent_loop_streaming(
                     None
                 },
             };
-            if let Err(e) = sqlite_memory
-                .add_message(&conversation_id, final_assistant_message)
-                .await
+            if let Err(e) = store_assistant_message(
+                &sqlite_memory,
+                &conversation_id,
+                partial_id,
+                final_assistant_message,
+            )
+            .await
             {
                 let _ = tx
                     .send(Ok(AgentStreamEvent::Error {
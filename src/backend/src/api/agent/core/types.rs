@@ -1,4 +1,7 @@
+use crate::api::chromadb::client::EmbeddingBackendKind;
+use crate::api::chromadb::types::QueryResponse;
 use serde::{Deserialize, Deserializer, Serialize};
+use std::collections::HashMap;
 
 /// Available tools for the agent
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -13,11 +16,12 @@ pub enum ToolType {
     GitHubPublic,
     GitHubAuthenticated,
     Crypto,
+    ModelNotes,
     // Future tools can be added here
 }
 
 /// Agent configuration
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AgentConfig {
     /// List of enabled tools
     pub enabled_tools: Vec<ToolType>,
@@ -26,6 +30,131 @@ pub struct AgentConfig {
     /// Whether to enable debug logging for agent conversations
     #[serde(default)]
     pub debug_logging: bool,
+    /// How many times tools retry idempotent HTTP requests on 5xx/connection errors
+    #[serde(default = "default_tool_max_retries")]
+    pub tool_max_retries: u32,
+    /// Timeout in seconds for tool HTTP requests and individual tool executions
+    #[serde(default = "default_tool_timeout_secs")]
+    pub tool_timeout_secs: u64,
+    /// How long identical tool call results are cached, in seconds (0 = disabled)
+    #[serde(default)]
+    pub cache_ttl_secs: u64,
+    /// Whether tool calls and results are stored in conversation memory
+    #[serde(default = "default_persist_tool_messages")]
+    pub persist_tool_messages: bool,
+    /// Whether the LLM server accepts `role: "tool"` messages (None = detect from model name)
+    #[serde(default)]
+    pub llm_supports_tool_role: Option<bool>,
+    /// Whether the model accepts image content parts (None = detect from model name)
+    #[serde(default)]
+    pub llm_supports_vision: Option<bool>,
+    /// Estimated prompt tokens allowed per request; older history is dropped
+    /// to fit (0 = no limit)
+    #[serde(default = "default_context_token_budget")]
+    pub context_token_budget: usize,
+    /// Coalesce streamed text for up to this many milliseconds before sending
+    /// it to the client (0 = send every delta as it arrives)
+    #[serde(default)]
+    pub stream_flush_ms: u64,
+    /// Send coalesced text early once this many characters are pending
+    #[serde(default = "default_stream_flush_chars")]
+    pub stream_flush_chars: usize,
+    /// Most concurrent calls per tool id, overriding the tool's own limit (0 = unlimited)
+    #[serde(default)]
+    pub tool_concurrency_limits: HashMap<String, usize>,
+    /// Wall-clock limit in seconds for one agent request, LLM calls and tools
+    /// included (0 = no limit)
+    #[serde(default = "default_agent_timeout_secs")]
+    pub agent_timeout_secs: u64,
+}
+
+/// Long enough for several tool rounds on a slow local model
+pub const DEFAULT_AGENT_TIMEOUT_SECS: u64 = 120;
+
+fn default_agent_timeout_secs() -> u64 {
+    DEFAULT_AGENT_TIMEOUT_SECS
+}
+
+/// Leaves room for the default 2000 completion tokens in an 8k context
+pub const DEFAULT_CONTEXT_TOKEN_BUDGET: usize = 6000;
+
+fn default_context_token_budget() -> usize {
+    DEFAULT_CONTEXT_TOKEN_BUDGET
+}
+
+/// Roughly a line of text, so buffered output still reads as streaming
+pub const DEFAULT_STREAM_FLUSH_CHARS: usize = 64;
+
+fn default_stream_flush_chars() -> usize {
+    DEFAULT_STREAM_FLUSH_CHARS
+}
+
+fn default_tool_max_retries() -> u32 {
+    crate::api::agent::tools::framework::retry::DEFAULT_MAX_RETRIES
+}
+
+fn default_persist_tool_messages() -> bool {
+    true
+}
+
+fn default_tool_timeout_secs() -> u64 {
+    crate::api::agent::tools::framework::http::DEFAULT_TOOL_TIMEOUT_SECS
+}
+
+impl Default for AgentConfig {
+    fn default() -> Self {
+        Self {
+            enabled_tools: Vec::new(),
+            chromadb: None,
+            debug_logging: false,
+            tool_max_retries: default_tool_max_retries(),
+            tool_timeout_secs: default_tool_timeout_secs(),
+            cache_ttl_secs: 0,
+            persist_tool_messages: default_persist_tool_messages(),
+            llm_supports_tool_role: None,
+            llm_supports_vision: None,
+            context_token_budget: default_context_token_budget(),
+            stream_flush_ms: 0,
+            stream_flush_chars: default_stream_flush_chars(),
+            tool_concurrency_limits: HashMap::new(),
+            agent_timeout_secs: default_agent_timeout_secs(),
+        }
+    }
+}
+
+impl AgentConfig {
+    /// Narrow the enabled tools to a per-request subset.
+    /// Every requested tool must be enabled on the server; `None` keeps the server set.
+    pub fn scoped_to(&self, enabled_tools: Option<&[ToolType]>) -> anyhow::Result<Self> {
+        let Some(requested) = enabled_tools else {
+            return Ok(self.clone());
+        };
+
+        for tool in requested {
+            let enabled = match tool {
+                // ChromaDB lives in its own config section rather than enabled_tools
+                ToolType::ChromaDB => self.chromadb.is_some(),
+                _ => self.enabled_tools.contains(tool),
+            };
+            if !enabled {
+                let name = serde_json::to_value(tool)
+                    .ok()
+                    .and_then(|v| v.as_str().map(str::to_string))
+                    .unwrap_or_else(|| format!("{:?}", tool));
+                return Err(anyhow::anyhow!(
+                    "Tool '{}' is not enabled on the server",
+                    name
+                ));
+            }
+        }
+
+        let mut scoped = self.clone();
+        scoped.enabled_tools.retain(|t| requested.contains(t));
+        if !requested.contains(&ToolType::ChromaDB) {
+            scoped.chromadb = None;
+        }
+        Ok(scoped)
+    }
 }
 
 /// ChromaDB tool configuration
@@ -35,6 +164,19 @@ pub struct ChromaDBToolConfig {
     pub collection: String,
     /// Embedding model to use for queries
     pub embedding_model: String,
+    /// Service that generates query embeddings; must match the one used for uploads
+    #[serde(default = "EmbeddingBackendKind::from_env")]
+    pub embedding_backend: EmbeddingBackendKind,
+    /// Results with a normalized similarity (0-1) below this are dropped
+    #[serde(default = "default_min_similarity")]
+    pub min_similarity: f64,
+}
+
+/// Default cut-off, equivalent to a cosine distance of 0.5
+pub const DEFAULT_MIN_SIMILARITY: f64 = 0.75;
+
+fn default_min_similarity() -> f64 {
+    DEFAULT_MIN_SIMILARITY
 }
 
 /// Chat message role
@@ -83,6 +225,16 @@ impl MessageContent {
                 .join(""),
         }
     }
+
+    /// Whether any part is an image
+    pub fn has_images(&self) -> bool {
+        match self {
+            MessageContent::Text(_) => false,
+            MessageContent::Parts(parts) => parts
+                .iter()
+                .any(|p| matches!(p, ContentPart::ImageUrl { .. })),
+        }
+    }
 }
 
 /// Custom deserializer for MessageContent that handles null as empty text
@@ -101,9 +253,24 @@ pub enum ContentPart {
     ImageUrl { image_url: ImageUrl },
 }
 
+/// Image reference in OpenAI format: an http(s) URL or a base64
+/// `data:image/...;base64,` URL
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ImageUrl {
     pub url: String,
+    /// Resolution hint for the model ("low", "high" or "auto")
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+}
+
+impl ImageUrl {
+    /// Accept remote images and inline base64 images only
+    pub fn is_supported(&self) -> bool {
+        let url = self.url.trim();
+        url.starts_with("https://")
+            || url.starts_with("http://")
+            || (url.starts_with("data:image/") && url.contains(";base64,"))
+    }
 }
 
 /// Chat message
@@ -163,7 +330,16 @@ pub struct ChatCompletionRequest {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub temperature: Option<f32>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    pub top_p: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub frequency_penalty: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub presence_penalty: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub max_tokens: Option<u32>,
+    /// Sequences that end generation, forwarded verbatim to the server
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stop: Option<Vec<String>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub tools: Option<Vec<Tool>>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -207,12 +383,57 @@ pub struct Usage {
     pub total_tokens: u32,
 }
 
+impl Usage {
+    /// Add the usage of another LLM request to this running total
+    pub fn accumulate(&mut self, other: &Usage) {
+        self.prompt_tokens = self.prompt_tokens.saturating_add(other.prompt_tokens);
+        self.completion_tokens = self
+            .completion_tokens
+            .saturating_add(other.completion_tokens);
+        self.total_tokens = self.total_tokens.saturating_add(other.total_tokens);
+    }
+}
+
 /// Agent chat request (from frontend)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AgentChatRequest {
     pub message: MessageContent,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub conversation_id: Option<String>,
+    /// Override the maximum number of tool-call iterations (1-20)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_iterations: Option<usize>,
+    /// Override the sampling temperature (0.0-2.0)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f32>,
+    /// Override the maximum tokens per completion
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_tokens: Option<u32>,
+    /// Override nucleus sampling (0.01-1.0)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub top_p: Option<f32>,
+    /// Penalize tokens by how often they already appeared (-2.0-2.0)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub frequency_penalty: Option<f32>,
+    /// Penalize tokens that already appeared at all (-2.0-2.0)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub presence_penalty: Option<f32>,
+    /// Up to 4 stop sequences, forwarded verbatim as the OpenAI `stop` parameter
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stop: Option<Vec<String>>,
+    /// Text prepended to the generated system prompt (e.g. a persona or domain instructions)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub system_prompt_prefix: Option<String>,
+    /// Replaces the generated system prompt entirely; takes precedence over the prefix
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub system_prompt_override: Option<String>,
+    /// Restrict this request to a subset of the server's enabled tools
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub enabled_tools: Option<Vec<ToolType>>,
+    /// Return the tool calls the model would make without executing them or
+    /// storing the exchange (non-streaming endpoint only)
+    #[serde(default)]
+    pub plan_only: bool,
 }
 
 /// Agent chat response
@@ -224,6 +445,124 @@ pub struct AgentChatResponse {
     pub conversation_id: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub tool_calls: Option<Vec<ToolCallResult>>,
+    /// Detailed per-call traces, only included when `verbose=true`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tool_traces: Option<Vec<ToolCallTrace>>,
+    /// Tool calls the model intends to make, only included for `plan_only` requests
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub planned_tool_calls: Option<Vec<ToolCall>>,
+}
+
+/// Query parameters for the non-streaming chat endpoint
+#[derive(Debug, Clone, Deserialize)]
+pub struct AgentChatQuery {
+    /// Include `tool_traces` in the response
+    #[serde(default)]
+    pub verbose: bool,
+}
+
+/// One-shot retrieval-augmented answer request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AgentRagRequest {
+    pub query: String,
+    /// ChromaDB collection to search
+    pub collection: String,
+    /// Number of chunks to retrieve (1-20, default 5)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub n_results: Option<usize>,
+    /// Override the sampling temperature (0.0-2.0)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f32>,
+    /// Override the maximum tokens of the answer
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_tokens: Option<u32>,
+}
+
+/// A retrieved chunk the answer can cite as `[citation]`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SourceChunk {
+    /// 1-based number used for inline citations
+    pub citation: usize,
+    pub id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub filename: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub chunk_index: Option<u64>,
+    pub text: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub distance: Option<f64>,
+    /// Normalized similarity (1 = identical), when the query asked for scores
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub score: Option<f64>,
+}
+
+impl SourceChunk {
+    /// Sources for the first query of a ChromaDB response, numbered from 1.
+    /// `filename` and `chunk_index` come from the metadata written on upload.
+    pub fn from_query_response(response: &QueryResponse) -> Vec<Self> {
+        let Some(ids) = response.ids.first() else {
+            return Vec::new();
+        };
+        let documents = response.documents.as_ref().and_then(|d| d.first());
+        let metadatas = response.metadatas.as_ref().and_then(|m| m.first());
+        let distances = response.distances.as_ref().and_then(|d| d.first());
+        let scores = response.scores.as_ref().and_then(|s| s.first());
+
+        ids.iter()
+            .enumerate()
+            .map(|(i, id)| {
+                let metadata = metadatas.and_then(|m| m.get(i));
+                let field = |key: &str| metadata.and_then(|m| m.get(key));
+                Self {
+                    citation: i + 1,
+                    id: id.clone(),
+                    filename: field("filename")
+                        .and_then(|v| v.as_str())
+                        .map(str::to_string),
+                    // Stored as a string by uploads, but accept numbers too
+                    chunk_index: field("chunk_index").and_then(|v| {
+                        v.as_u64()
+                            .or_else(|| v.as_str().and_then(|s| s.trim().parse().ok()))
+                    }),
+                    text: documents
+                        .and_then(|d| d.get(i))
+                        .cloned()
+                        .unwrap_or_default(),
+                    distance: distances.and_then(|d| d.get(i)).copied(),
+                    score: scores.and_then(|s| s.get(i)).copied(),
+                }
+            })
+            .collect()
+    }
+
+    /// Short label such as `notes.md, chunk 3`
+    pub fn label(&self) -> String {
+        match (&self.filename, self.chunk_index) {
+            (Some(filename), Some(index)) => format!("{}, chunk {}", filename, index),
+            (Some(filename), None) => filename.clone(),
+            (None, Some(index)) => format!("{}, chunk {}", self.id, index),
+            (None, None) => self.id.clone(),
+        }
+    }
+}
+
+/// Grounded answer plus the chunks it was generated from
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AgentRagResponse {
+    pub success: bool,
+    pub answer: String,
+    pub sources: Vec<SourceChunk>,
+}
+
+/// Audit record of a single tool execution
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ToolCallTrace {
+    pub tool_call_id: String,
+    pub tool_name: String,
+    pub arguments: String,
+    pub result: String,
+    pub success: bool,
+    pub duration_ms: u64,
 }
 
 /// Tool call result
@@ -231,12 +570,25 @@ pub struct AgentChatResponse {
 pub struct ToolCallResult {
     pub tool_name: String,
     pub result: String,
+    /// Id of the tool call this result answers
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_call_id: Option<String>,
+    /// Documents the result was built from, for tools that search a knowledge base
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sources: Option<Vec<SourceChunk>>,
 }
 
 pub type ActiveGenerations = std::sync::Arc<
     std::sync::Mutex<std::collections::HashMap<String, tokio::sync::watch::Sender<bool>>>,
 >;
 
+/// Enabled tools update request. Names are kept as strings so unknown
+/// tools can be reported back instead of failing deserialization.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EnabledToolsRequest {
+    pub enabled_tools: Vec<String>,
+}
+
 /// Agent config request
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AgentConfigRequest {
@@ -245,6 +597,28 @@ pub struct AgentConfigRequest {
     pub chromadb: Option<ChromaDBToolConfig>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub debug_logging: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tool_max_retries: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tool_timeout_secs: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cache_ttl_secs: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub persist_tool_messages: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub llm_supports_tool_role: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub llm_supports_vision: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub context_token_budget: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stream_flush_ms: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stream_flush_chars: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tool_concurrency_limits: Option<HashMap<String, usize>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub agent_timeout_secs: Option<u64>,
 }
 
 /// Agent config response
@@ -303,13 +677,20 @@ pub enum AgentStreamEvent {
         display_name: Option<String>,
         success: bool,
         result: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        sources: Option<Vec<SourceChunk>>,
     },
     #[serde(rename = "text_chunk")]
     TextChunk { text: String },
+    /// Model "thinking" streamed separately from the answer (e.g. DeepSeek `reasoning_content`)
+    #[serde(rename = "reasoning_chunk")]
+    ReasoningChunk { text: String },
     #[serde(rename = "done")]
     Done {
         conversation_id: Option<String>,
         tool_calls: Option<Vec<ToolCallResult>>,
+        /// Token usage summed over every LLM request made by the agent loop
+        /// (one per iteration); prompt tokens are counted again on each request
         #[serde(skip_serializing_if = "Option::is_none")]
         usage: Option<Usage>,
     },
@@ -324,6 +705,46 @@ pub struct Conversation {
     pub title: Option<String>,
     pub model: Option<String>,
     pub created_at: i64,
+    /// Unix timestamp of the latest message (or creation time if empty)
+    pub last_updated: i64,
+    pub message_count: i64,
+}
+
+/// A stored message with the Unix timestamp it was saved at
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimestampedMessage {
+    #[serde(flatten)]
+    pub message: ChatMessage,
+    pub created_at: i64,
+    /// Still being streamed, or cut off by a crash before it completed
+    #[serde(default)]
+    pub partial: bool,
+}
+
+/// Query parameters for exporting a conversation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportQuery {
+    /// `markdown` (default) or `json`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub format: Option<String>,
+}
+
+/// A message matching a conversation search
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MessageSearchResult {
+    pub conversation_id: String,
+    pub role: String,
+    /// Excerpt of the message around the match
+    pub snippet: String,
+    pub created_at: i64,
+}
+
+/// Query parameters for searching conversations
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchQuery {
+    pub q: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<usize>,
 }
 
 /// Update conversation request
@@ -331,6 +752,22 @@ pub struct Conversation {
 pub struct UpdateConversationRequest {
     pub title: String,
 }
+
+/// Query parameters for deleting all conversations
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ClearConversationsQuery {
+    /// Must be `true` for the wipe to go ahead
+    #[serde(default)]
+    pub confirm: bool,
+}
+
+/// Query parameters for fetching conversation history
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConversationMessagesQuery {
+    /// Include tool calls and tool results (defaults to true)
+    pub include_tools: Option<bool>,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -371,13 +808,58 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_serialize_multimodal_message() {
+        let message = ChatMessage {
+            role: MessageRole::User,
+            content: MessageContent::Parts(vec![
+                ContentPart::Text {
+                    text: "What is in this image?".to_string(),
+                },
+                ContentPart::ImageUrl {
+                    image_url: ImageUrl {
+                        url: "https://example.com/cat.png".to_string(),
+                        detail: Some("low".to_string()),
+                    },
+                },
+            ]),
+            name: None,
+            tool_calls: None,
+            tool_call_id: None,
+            reasoning_content: None,
+        };
+
+        let json = serde_json::to_value(&message).expect("Failed to serialize message");
+        assert_eq!(
+            json,
+            json!({
+                "role": "user",
+                "content": [
+                    {"type": "text", "text": "What is in this image?"},
+                    {"type": "image_url", "image_url": {"url": "https://example.com/cat.png", "detail": "low"}}
+                ]
+            })
+        );
+
+        let request: AgentChatRequest = serde_json::from_value(json!({
+            "message": [{"type": "image_url", "image_url": {"url": "data:image/png;base64,iVBORw0KGgo="}}]
+        }))
+        .expect("Failed to deserialize multimodal request");
+        assert!(request.message.has_images());
+        assert_eq!(request.message.text(), "");
+    }
+
     #[test]
     fn test_serialize_chat_completion_request_with_usage() {
         let request = ChatCompletionRequest {
             messages: vec![],
             model: "test-model".to_string(),
             temperature: None,
+            top_p: None,
+            frequency_penalty: None,
+            presence_penalty: None,
             max_tokens: None,
+            stop: Some(vec!["</answer>".to_string()]),
             tools: None,
             tool_choice: None,
             stream: Some(true),
@@ -390,5 +872,123 @@ mod tests {
 
         assert_eq!(json["stream"], true);
         assert_eq!(json["stream_options"]["include_usage"], true);
+        assert_eq!(json["stop"], json!(["</answer>"]));
+        // Unset sampling parameters are left out for servers that don't know them
+        assert!(json.get("top_p").is_none());
+        assert!(json.get("frequency_penalty").is_none());
+        assert!(json.get("presence_penalty").is_none());
+    }
+
+    #[test]
+    fn test_serialize_agent_chat_response_omits_traces_by_default() {
+        let response = AgentChatResponse {
+            success: true,
+            message: "Done".to_string(),
+            conversation_id: Some("abc".to_string()),
+            tool_calls: None,
+            tool_traces: None,
+            planned_tool_calls: None,
+        };
+
+        let json = serde_json::to_value(response).expect("Failed to serialize response");
+        assert!(json.get("tool_traces").is_none());
+
+        let verbose = AgentChatResponse {
+            success: true,
+            message: "Done".to_string(),
+            conversation_id: None,
+            tool_calls: None,
+            tool_traces: Some(vec![ToolCallTrace {
+                tool_call_id: "call_1".to_string(),
+                tool_name: "weather_current".to_string(),
+                arguments: r#"{"city":"Warsaw"}"#.to_string(),
+                result: "Sunny".to_string(),
+                success: true,
+                duration_ms: 120,
+            }]),
+            planned_tool_calls: None,
+        };
+
+        let json = serde_json::to_value(verbose).expect("Failed to serialize response");
+        assert_eq!(json["tool_traces"][0]["duration_ms"], 120);
+        assert_eq!(json["tool_traces"][0]["success"], true);
+    }
+
+    #[test]
+    fn test_usage_accumulate() {
+        let mut total = Usage {
+            prompt_tokens: 100,
+            completion_tokens: 20,
+            total_tokens: 120,
+        };
+        total.accumulate(&Usage {
+            prompt_tokens: 150,
+            completion_tokens: 30,
+            total_tokens: 180,
+        });
+
+        assert_eq!(total.prompt_tokens, 250);
+        assert_eq!(total.completion_tokens, 50);
+        assert_eq!(total.total_tokens, 300);
+    }
+
+    #[test]
+    fn test_agent_config_scoped_to() {
+        let config = AgentConfig {
+            enabled_tools: vec![ToolType::Weather, ToolType::Stock],
+            chromadb: Some(ChromaDBToolConfig {
+                collection: "docs".to_string(),
+                embedding_model: "nomic".to_string(),
+                embedding_backend: EmbeddingBackendKind::Ollama,
+                min_similarity: DEFAULT_MIN_SIMILARITY,
+            }),
+            ..AgentConfig::default()
+        };
+
+        let unscoped = config.scoped_to(None).unwrap();
+        assert_eq!(unscoped.enabled_tools, config.enabled_tools);
+
+        let weather_only = config.scoped_to(Some(&[ToolType::Weather])).unwrap();
+        assert_eq!(weather_only.enabled_tools, vec![ToolType::Weather]);
+        assert!(weather_only.chromadb.is_none());
+
+        let with_chromadb = config.scoped_to(Some(&[ToolType::ChromaDB])).unwrap();
+        assert!(with_chromadb.enabled_tools.is_empty());
+        assert!(with_chromadb.chromadb.is_some());
+
+        let err = config
+            .scoped_to(Some(&[ToolType::Weather, ToolType::Crypto]))
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Tool 'crypto' is not enabled on the server"
+        );
+    }
+
+    #[test]
+    fn test_source_chunks_from_query_response() {
+        let mut uploaded = std::collections::HashMap::new();
+        uploaded.insert("filename".to_string(), serde_json::json!("notes.md"));
+        uploaded.insert("chunk_index".to_string(), serde_json::json!("3"));
+
+        let response = QueryResponse {
+            ids: vec![vec!["notes_3".to_string(), "raw_1".to_string()]],
+            distances: Some(vec![vec![0.12, 0.4]]),
+            documents: Some(vec![vec!["First".to_string(), "Second".to_string()]]),
+            metadatas: Some(vec![vec![uploaded, std::collections::HashMap::new()]]),
+            scores: None,
+            distance_metric: None,
+        };
+
+        let sources = SourceChunk::from_query_response(&response);
+        assert_eq!(sources.len(), 2);
+        assert_eq!(sources[0].citation, 1);
+        assert_eq!(sources[0].filename.as_deref(), Some("notes.md"));
+        assert_eq!(sources[0].chunk_index, Some(3));
+        assert_eq!(sources[0].distance, Some(0.12));
+        assert_eq!(sources[0].label(), "notes.md, chunk 3");
+        // Without upload metadata the chunk id is the label
+        assert_eq!(sources[1].label(), "raw_1");
+        assert_eq!(sources[1].text, "Second");
     }
 }
@@ -1,4 +1,11 @@
+use crate::api::agent::core::types::{
+    ChatMessage, ContentPart, MessageContent, MessageRole, ToolCall, ToolCallResult,
+};
 use crate::api::agent::tools::framework::agent_tool::{ToolCategory, ToolMetadata};
+use crate::utils::tokenizer::loaded_tokenizer;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
 
 pub enum StatusType {
     Calling,
@@ -49,3 +56,472 @@ pub fn format_tool_status_message(
         }
     }
 }
+
+/// Model families whose chat templates accept `role: "tool"` messages
+const TOOL_ROLE_MODEL_FAMILIES: &[&str] = &[
+    "qwen2.5",
+    "qwen3",
+    "llama-3.1",
+    "llama-3.2",
+    "llama-3.3",
+    "llama3.1",
+    "llama3.2",
+    "llama3.3",
+    "mistral",
+    "hermes",
+    "functionary",
+    "deepseek",
+    "gpt-oss",
+];
+
+/// Best-effort guess whether the model's chat template handles `tool` messages
+pub fn model_supports_tool_role(model_name: &str) -> bool {
+    let model_name = model_name.to_lowercase();
+    TOOL_ROLE_MODEL_FAMILIES
+        .iter()
+        .any(|family| model_name.contains(family))
+}
+
+/// Model families that accept image content parts
+const VISION_MODEL_FAMILIES: &[&str] = &[
+    "llava",
+    "bakllava",
+    "qwen2-vl",
+    "qwen2.5-vl",
+    "qwen2.5-omni",
+    "minicpm-v",
+    "gemma-3",
+    "gemma3",
+    "pixtral",
+    "llama-3.2-11b-vision",
+    "llama-3.2-90b-vision",
+    "moondream",
+    "smolvlm",
+    "internvl",
+];
+
+/// Best-effort guess whether the model (and its projector) handles images
+pub fn model_supports_vision(model_name: &str) -> bool {
+    let model_name = model_name.to_lowercase();
+    model_name.contains("vision")
+        || VISION_MODEL_FAMILIES
+            .iter()
+            .any(|family| model_name.contains(family))
+}
+
+/// Placeholder left where an image was removed for a text-only model
+const IMAGE_OMITTED: &str = "[image omitted: the current model does not accept images]";
+
+/// Replace image parts with a short placeholder so text-only models still get
+/// the rest of the message (e.g. history from a previous vision model)
+pub fn strip_images(messages: &[ChatMessage]) -> Vec<ChatMessage> {
+    messages
+        .iter()
+        .map(|msg| {
+            let MessageContent::Parts(parts) = &msg.content else {
+                return msg.clone();
+            };
+            if !msg.content.has_images() {
+                return msg.clone();
+            }
+            let text = parts
+                .iter()
+                .map(|p| match p {
+                    ContentPart::Text { text } => text.as_str(),
+                    ContentPart::ImageUrl { .. } => IMAGE_OMITTED,
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+            ChatMessage {
+                content: MessageContent::Text(text),
+                ..msg.clone()
+            }
+        })
+        .collect()
+}
+
+/// Tokens a chat template adds around each message (role markers, separators)
+const MESSAGE_TOKEN_OVERHEAD: usize = 4;
+
+/// Token count of `text` using the shared tokenizer, or a characters / 4
+/// estimate while the tokenizer is still loading (or failed to load)
+pub fn estimate_tokens(text: &str) -> usize {
+    loaded_tokenizer()
+        .and_then(|tokenizer| tokenizer.encode(text, false).ok())
+        .map(|encoding| encoding.len())
+        .unwrap_or_else(|| text.chars().count().div_ceil(4))
+}
+
+/// Remembers `estimate_tokens` results by text, so an agent loop only encodes
+/// the messages added since its previous iteration
+#[derive(Default)]
+pub struct TokenCounter {
+    counts: HashMap<u64, usize>,
+}
+
+impl TokenCounter {
+    pub fn count(&mut self, text: &str) -> usize {
+        let mut hasher = DefaultHasher::new();
+        text.hash(&mut hasher);
+        *self
+            .counts
+            .entry(hasher.finish())
+            .or_insert_with(|| estimate_tokens(text))
+    }
+}
+
+fn message_tokens(message: &ChatMessage, count: &mut impl FnMut(&str) -> usize) -> usize {
+    let tool_call_tokens: usize = message
+        .tool_calls
+        .iter()
+        .flatten()
+        .map(|call| count(&call.function.name) + count(&call.function.arguments))
+        .sum();
+    MESSAGE_TOKEN_OVERHEAD + count(&message.content.text()) + tool_call_tokens
+}
+
+/// Drop the oldest history until the prompt fits in `budget` tokens (0 = no limit).
+///
+/// System messages, the latest user message and everything after it (the
+/// current turn's tool calls) are always kept, so the result can still exceed
+/// the budget. Tool results left without their tool call are dropped with it.
+pub fn trim_to_token_budget(
+    messages: &[ChatMessage],
+    budget: usize,
+    mut count: impl FnMut(&str) -> usize,
+) -> Vec<ChatMessage> {
+    if budget == 0 {
+        return messages.to_vec();
+    }
+
+    let tokens: Vec<usize> = messages
+        .iter()
+        .map(|m| message_tokens(m, &mut count))
+        .collect();
+    let mut total: usize = tokens.iter().sum();
+    let latest_user = messages
+        .iter()
+        .rposition(|m| m.role == MessageRole::User)
+        .unwrap_or(0);
+
+    let mut dropped = vec![false; messages.len()];
+    for (i, message) in messages.iter().enumerate().take(latest_user) {
+        if message.role == MessageRole::System {
+            continue;
+        }
+        // Keep going past the budget only to remove orphaned tool results
+        if total <= budget && message.role != MessageRole::Tool {
+            break;
+        }
+        dropped[i] = true;
+        total -= tokens[i];
+    }
+
+    messages
+        .iter()
+        .zip(dropped)
+        .filter(|(_, dropped)| !dropped)
+        .map(|(m, _)| m.clone())
+        .collect()
+}
+
+/// Build the message list sent to the LLM.
+///
+/// When the server supports the `tool` role, messages are passed through unchanged.
+/// Otherwise consecutive tool results are folded into a single user message
+/// ("Tool results:\n...") since the server expects alternating user/assistant turns.
+pub fn prepare_messages_for_llm(
+    messages: &[ChatMessage],
+    supports_tool_role: bool,
+) -> Vec<ChatMessage> {
+    if supports_tool_role {
+        return messages.to_vec();
+    }
+
+    let mut filtered_messages: Vec<ChatMessage> = Vec::new();
+    let mut tool_results_buffer: Vec<String> = Vec::new();
+
+    for msg in messages {
+        if matches!(msg.role, MessageRole::Tool) {
+            // Collect tool results to create a user message
+            let tool_name = msg.name.as_deref().unwrap_or("unknown");
+            tool_results_buffer.push(format!("{}: {}", tool_name, msg.content.text()));
+        } else {
+            // If we have buffered tool results, create a user message with them
+            flush_tool_results(&mut filtered_messages, &mut tool_results_buffer);
+            filtered_messages.push(msg.clone());
+        }
+    }
+
+    // Handle any remaining tool results at the end
+    flush_tool_results(&mut filtered_messages, &mut tool_results_buffer);
+
+    filtered_messages
+}
+
+fn flush_tool_results(messages: &mut Vec<ChatMessage>, buffer: &mut Vec<String>) {
+    if buffer.is_empty() {
+        return;
+    }
+    messages.push(ChatMessage {
+        role: MessageRole::User,
+        content: MessageContent::Text(format!("Tool results:\n{}", buffer.join("\n"))),
+        name: None,
+        tool_calls: None,
+        tool_call_id: None,
+        reasoning_content: None,
+    });
+    buffer.clear();
+}
+
+/// Build one `tool` message per tool call, pairing each call with the result
+/// carrying its id so repeated calls to the same tool keep their own results
+pub fn tool_result_messages(
+    tool_calls: &[ToolCall],
+    results: &[ToolCallResult],
+) -> Vec<ChatMessage> {
+    tool_calls
+        .iter()
+        .map(|tool_call| {
+            let content = results
+                .iter()
+                .find(|r| r.tool_call_id.as_deref() == Some(tool_call.id.as_str()))
+                .map(|r| r.result.clone())
+                .unwrap_or_else(|| "Error: Tool produced no result".to_string());
+
+            ChatMessage {
+                role: MessageRole::Tool,
+                content: MessageContent::Text(content),
+                name: Some(tool_call.function.name.clone()),
+                tool_calls: None,
+                tool_call_id: Some(tool_call.id.clone()),
+                reasoning_content: None,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::agent::core::types::FunctionCall;
+
+    fn transcript() -> Vec<ChatMessage> {
+        let message = |role, content: &str| ChatMessage {
+            role,
+            content: MessageContent::Text(content.to_string()),
+            name: None,
+            tool_calls: None,
+            tool_call_id: None,
+            reasoning_content: None,
+        };
+        let tool_call = |id: &str, name: &str| ToolCall {
+            id: id.to_string(),
+            tool_type: "function".to_string(),
+            function: FunctionCall {
+                name: name.to_string(),
+                arguments: "{}".to_string(),
+            },
+        };
+        let tool_result = |id: &str, name: &str, content: &str| ChatMessage {
+            name: Some(name.to_string()),
+            tool_call_id: Some(id.to_string()),
+            ..message(MessageRole::Tool, content)
+        };
+
+        vec![
+            message(MessageRole::User, "Weather and BTC price?"),
+            ChatMessage {
+                tool_calls: Some(vec![
+                    tool_call("call_1", "weather_current"),
+                    tool_call("call_2", "crypto_price"),
+                ]),
+                ..message(MessageRole::Assistant, "")
+            },
+            tool_result("call_1", "weather_current", "Sunny"),
+            tool_result("call_2", "crypto_price", "$60,000"),
+        ]
+    }
+
+    #[test]
+    fn test_prepare_messages_with_tool_role() {
+        let prepared = prepare_messages_for_llm(&transcript(), true);
+
+        assert_eq!(prepared.len(), 4);
+        assert_eq!(prepared[2].role, MessageRole::Tool);
+        assert_eq!(prepared[2].tool_call_id.as_deref(), Some("call_1"));
+        assert_eq!(prepared[3].role, MessageRole::Tool);
+        assert_eq!(prepared[3].tool_call_id.as_deref(), Some("call_2"));
+    }
+
+    #[test]
+    fn test_prepare_messages_without_tool_role() {
+        let prepared = prepare_messages_for_llm(&transcript(), false);
+
+        assert_eq!(prepared.len(), 3);
+        assert!(prepared.iter().all(|m| m.role != MessageRole::Tool));
+        assert_eq!(prepared[2].role, MessageRole::User);
+        assert_eq!(
+            prepared[2].content.text(),
+            "Tool results:\nweather_current: Sunny\ncrypto_price: $60,000"
+        );
+    }
+
+    #[test]
+    fn test_trim_to_token_budget_drops_oldest_history() {
+        // One token per word keeps the arithmetic obvious
+        let words = |text: &str| text.split_whitespace().count();
+        let message = |role, content: String| ChatMessage {
+            role,
+            content: MessageContent::Text(content),
+            name: None,
+            tool_calls: None,
+            tool_call_id: None,
+            reasoning_content: None,
+        };
+        let long = |n: usize| vec!["word"; n].join(" ");
+
+        let mut messages = vec![message(MessageRole::System, long(50))];
+        messages.extend(transcript()); // old tool-using turn
+        messages.push(message(MessageRole::Assistant, long(1000)));
+        messages.push(message(MessageRole::User, long(2000)));
+        messages.push(message(MessageRole::Assistant, long(500)));
+        messages.push(message(MessageRole::User, "latest question".to_string()));
+
+        let trimmed = trim_to_token_budget(&messages, 600, words);
+
+        // System prompt and the latest user message survive; history goes
+        let roles: Vec<MessageRole> = trimmed.iter().map(|m| m.role.clone()).collect();
+        assert_eq!(
+            roles,
+            vec![
+                MessageRole::System,
+                MessageRole::Assistant,
+                MessageRole::User
+            ]
+        );
+        assert_eq!(trimmed[1].content.text(), long(500));
+        assert_eq!(trimmed[2].content.text(), "latest question");
+
+        // Never drops below system + latest user, even if that is over budget
+        let minimal = trim_to_token_budget(&messages, 10, words);
+        assert_eq!(minimal.len(), 2);
+        assert_eq!(minimal[0].role, MessageRole::System);
+
+        // A budget that fits everything (or 0) leaves the history alone
+        assert_eq!(trim_to_token_budget(&messages, 100_000, words).len(), 9);
+        assert_eq!(trim_to_token_budget(&messages, 0, words).len(), 9);
+    }
+
+    #[test]
+    fn test_token_counter_reuses_counts() {
+        let mut counter = TokenCounter::default();
+        let first = counter.count("Hello there, how are you?");
+        assert!(first > 0);
+        assert_eq!(counter.count("Hello there, how are you?"), first);
+        assert_eq!(counter.counts.len(), 1);
+        counter.count("Something else");
+        assert_eq!(counter.counts.len(), 2);
+    }
+
+    #[test]
+    fn test_trim_to_token_budget_drops_orphaned_tool_results() {
+        let words = |text: &str| text.split_whitespace().count();
+        let mut messages = transcript();
+        messages.push(ChatMessage {
+            role: MessageRole::User,
+            content: MessageContent::Text("thanks".to_string()),
+            name: None,
+            tool_calls: None,
+            tool_call_id: None,
+            reasoning_content: None,
+        });
+
+        // Dropping the first user message and the assistant tool call is enough
+        // for the budget, but their tool results must go too
+        let trimmed = trim_to_token_budget(&messages, 16, words);
+        assert_eq!(trimmed.len(), 1);
+        assert_eq!(trimmed[0].content.text(), "thanks");
+    }
+
+    #[test]
+    fn test_model_supports_tool_role() {
+        assert!(model_supports_tool_role(
+            "unsloth/Qwen3-30B-A3B-GGUF:Q4_K_M"
+        ));
+        assert!(model_supports_tool_role(
+            "bartowski/Meta-Llama-3.1-8B-Instruct-GGUF"
+        ));
+        assert!(!model_supports_tool_role("google/gemma-2-9b-it-GGUF"));
+    }
+
+    #[test]
+    fn test_model_supports_vision() {
+        assert!(model_supports_vision("ggml-org/gemma-3-4b-it-GGUF"));
+        assert!(model_supports_vision("Qwen/Qwen2.5-VL-7B-Instruct-GGUF"));
+        assert!(model_supports_vision(
+            "unsloth/Llama-3.2-11B-Vision-Instruct"
+        ));
+        assert!(!model_supports_vision("unsloth/Qwen3-30B-A3B-GGUF:Q4_K_M"));
+    }
+
+    #[test]
+    fn test_strip_images_keeps_text_parts() {
+        let message = ChatMessage {
+            role: MessageRole::User,
+            content: MessageContent::Parts(vec![
+                ContentPart::Text {
+                    text: "What is in this picture?".to_string(),
+                },
+                ContentPart::ImageUrl {
+                    image_url: crate::api::agent::core::types::ImageUrl {
+                        url: "data:image/png;base64,iVBORw0KGgo=".to_string(),
+                        detail: None,
+                    },
+                },
+            ]),
+            name: None,
+            tool_calls: None,
+            tool_call_id: None,
+            reasoning_content: None,
+        };
+        let stripped = strip_images(&[message]);
+        assert!(!stripped[0].content.has_images());
+        assert_eq!(
+            stripped[0].content.text(),
+            format!("What is in this picture?\n{}", IMAGE_OMITTED)
+        );
+    }
+
+    #[test]
+    fn test_tool_result_messages_match_by_id() {
+        let call = |id: &str, city: &str| ToolCall {
+            id: id.to_string(),
+            tool_type: "function".to_string(),
+            function: FunctionCall {
+                name: "weather_current".to_string(),
+                arguments: format!(r#"{{"city":"{}"}}"#, city),
+            },
+        };
+        let result = |id: &str, text: &str| ToolCallResult {
+            tool_name: "weather_current".to_string(),
+            result: text.to_string(),
+            tool_call_id: Some(id.to_string()),
+            sources: None,
+        };
+
+        let calls = vec![call("call_1", "Warsaw"), call("call_2", "Berlin")];
+        // Results arrive out of order
+        let results = vec![
+            result("call_2", "Berlin: Rain"),
+            result("call_1", "Warsaw: Sunny"),
+        ];
+
+        let messages = tool_result_messages(&calls, &results);
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[0].tool_call_id.as_deref(), Some("call_1"));
+        assert_eq!(messages[0].content.text(), "Warsaw: Sunny");
+        assert_eq!(messages[1].tool_call_id.as_deref(), Some("call_2"));
+        assert_eq!(messages[1].content.text(), "Berlin: Rain");
+    }
+}
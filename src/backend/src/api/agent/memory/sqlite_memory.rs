@@ -1,57 +1,254 @@
-use crate::api::agent::core::types::{ChatMessage, Conversation, MessageRole, ToolCall};
+use crate::api::agent::core::types::{
+    ChatMessage, ContentPart, Conversation, MessageContent, MessageRole, MessageSearchResult,
+    TimestampedMessage, ToolCall,
+};
+use crate::utils::migrations::{run_migrations, Migration};
 use anyhow::{Context, Result};
-use sqlx::{sqlite::SqliteConnectOptions, Row, SqlitePool};
-use std::path::Path;
+use sqlx::{sqlite::SqliteRow, Row, SqlitePool};
 
-/// SQLite-based conversation storage
-/// Stores all message types including tool calls and results
-pub struct SqliteConversationMemory {
-    pool: SqlitePool,
-}
+/// Conversation schema; append new versions, never edit applied ones
+const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        name: "create conversations and messages",
+        statements: &[
+            "CREATE TABLE IF NOT EXISTS conversations (
+            id TEXT PRIMARY KEY,
+            title TEXT,
+            model TEXT,
+            created_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now'))
+        )",
+            "CREATE TABLE IF NOT EXISTS messages (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            conversation_id TEXT NOT NULL,
+            role TEXT NOT NULL,
+            content TEXT NOT NULL,
+            name TEXT,
+            tool_calls TEXT,
+            tool_call_id TEXT,
+            reasoning_content TEXT,
+            created_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now')),
+            FOREIGN KEY (conversation_id) REFERENCES conversations(id) ON DELETE CASCADE
+        )",
+            "CREATE INDEX IF NOT EXISTS idx_messages_conversation ON messages(conversation_id)",
+        ],
+    },
+    Migration {
+        version: 2,
+        name: "mark partially streamed messages",
+        statements: &["ALTER TABLE messages ADD COLUMN partial INTEGER NOT NULL DEFAULT 0"],
+    },
+];
 
-impl SqliteConversationMemory {
-    /// Create a new SQLite conversation memory store
-    pub async fn new(db_path: impl AsRef<Path>) -> Result<Self> {
-        let db_path = db_path.as_ref();
+/// Maximum length of a title derived from the first user message
+pub const DERIVED_TITLE_MAX_CHARS: usize = 60;
 
-        // Ensure directory exists
-        if let Some(parent) = db_path.parent() {
-            std::fs::create_dir_all(parent).context("Failed to create database directory")?;
+/// Deserialize stored content: JSON array for multipart messages, raw string otherwise
+fn parse_content(content_str: String) -> MessageContent {
+    if content_str.trim().starts_with('[') {
+        match serde_json::from_str::<Vec<ContentPart>>(&content_str) {
+            Ok(parts) => MessageContent::Parts(parts),
+            Err(_) => MessageContent::Text(content_str), // Fallback to raw text if parse fails
         }
+    } else {
+        MessageContent::Text(content_str)
+    }
+}
 
-        // Get absolute path - if file doesn't exist yet, canonicalize parent and join filename
-        let absolute_path = if db_path.exists() {
-            db_path
-                .canonicalize()
-                .context("Failed to canonicalize existing database path")?
-        } else {
-            let parent = db_path.parent().unwrap_or(Path::new("."));
-            let parent_abs = parent
-                .canonicalize()
-                .or_else(|_| std::env::current_dir().map(|d| d.join(parent)))
-                .context("Failed to get absolute path for database directory")?;
-            let filename = db_path
-                .file_name()
-                .and_then(|n| n.to_str())
-                .unwrap_or("conversations.db");
-            parent_abs.join(filename)
-        };
+/// Derive a conversation title from a user message: whitespace collapsed and
+/// truncated to `DERIVED_TITLE_MAX_CHARS`. Returns None for blank messages.
+pub fn derive_title(message: &str) -> Option<String> {
+    let collapsed = message.split_whitespace().collect::<Vec<_>>().join(" ");
+    if collapsed.is_empty() {
+        return None;
+    }
 
-        println!(
-            "💾 Connecting to SQLite database at: {}",
-            absolute_path.display()
-        );
+    if collapsed.chars().count() <= DERIVED_TITLE_MAX_CHARS {
+        return Some(collapsed);
+    }
+
+    let truncated: String = collapsed
+        .chars()
+        .take(DERIVED_TITLE_MAX_CHARS - 1)
+        .collect();
+    Some(format!("{}…", truncated.trim_end()))
+}
+
+/// Maximum number of search results returned by `search_messages`
+pub const MAX_SEARCH_LIMIT: usize = 100;
+
+/// Characters of context kept on each side of a match in LIKE-fallback snippets
+const SNIPPET_CONTEXT_CHARS: usize = 60;
 
-        // Use SqliteConnectOptions with filename directly
-        let options = SqliteConnectOptions::new()
-            .filename(&absolute_path)
-            .create_if_missing(true);
+/// Turn free-form user input into a safe FTS5 query: every whitespace-separated
+/// term is quoted (embedded quotes doubled), so operators and punctuation are
+/// matched literally instead of causing syntax errors. Terms are ANDed together;
+/// pure punctuation terms are dropped since the tokenizer would ignore them anyway.
+fn fts_query(query: &str) -> Option<String> {
+    let terms: Vec<String> = query
+        .split_whitespace()
+        .filter(|term| term.chars().any(char::is_alphanumeric))
+        .map(|term| format!("\"{}\"", term.replace('"', "\"\"")))
+        .collect();
 
-        let pool = SqlitePool::connect_with(options).await.context(format!(
-            "Failed to connect to SQLite database at: {}",
-            absolute_path.display()
-        ))?;
+    if terms.is_empty() {
+        None
+    } else {
+        Some(terms.join(" "))
+    }
+}
+
+/// Escape LIKE wildcards so the query is matched literally (used with `ESCAPE '\'`)
+fn escape_like(query: &str) -> String {
+    query
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
+}
+
+/// Build a snippet around the first case-insensitive occurrence of `query`
+fn make_snippet(content: &str, query: &str) -> String {
+    let chars: Vec<char> = content.chars().collect();
+    let lower: Vec<char> = content.to_lowercase().chars().collect();
+    let needle: Vec<char> = query.to_lowercase().chars().collect();
+
+    // Lowercasing can change char counts for some scripts; fall back to the start
+    let position = if lower.len() == chars.len() && !needle.is_empty() {
+        lower
+            .windows(needle.len())
+            .position(|window| window == needle.as_slice())
+            .unwrap_or(0)
+    } else {
+        0
+    };
+
+    let start = position.saturating_sub(SNIPPET_CONTEXT_CHARS);
+    let end = (position + needle.len() + SNIPPET_CONTEXT_CHARS).min(chars.len());
+
+    let mut snippet = String::new();
+    if start > 0 {
+        snippet.push('…');
+    }
+    snippet.extend(&chars[start..end]);
+    if end < chars.len() {
+        snippet.push('…');
+    }
+    snippet
+}
 
+/// Conversation columns plus activity metadata; callers append WHERE/GROUP BY/ORDER BY
+const CONVERSATION_SUMMARY_SELECT: &str = "SELECT c.id, c.title, c.model, c.created_at,
+        COUNT(m.id) AS message_count,
+        COALESCE(MAX(m.created_at), c.created_at) AS last_updated,
+        (SELECT content FROM messages
+         WHERE conversation_id = c.id AND role = 'user'
+         ORDER BY created_at ASC, id ASC LIMIT 1) AS first_user_message
+     FROM conversations c
+     LEFT JOIN messages m ON m.conversation_id = c.id";
+
+/// Map a `CONVERSATION_SUMMARY_SELECT` row. Conversations still carrying a
+/// placeholder title get one derived from their first user message.
+fn conversation_from_row(row: &SqliteRow) -> Conversation {
+    let stored_title: Option<String> = row.get(1);
+    let first_user_message: Option<String> = row.get(6);
+
+    let title = match stored_title {
+        Some(t) if !is_default_title(&t) => Some(t),
+        stored => first_user_message
+            .and_then(|content| derive_title(&parse_content(content).text()))
+            .or(stored),
+    };
+
+    Conversation {
+        id: row.get(0),
+        title,
+        model: row.get(2),
+        created_at: row.get(3),
+        message_count: row.get(4),
+        last_updated: row.get(5),
+    }
+}
+
+/// Map a `SELECT role, content, name, tool_calls, tool_call_id, created_at,
+/// reasoning_content` row to a message
+fn timestamped_message_from_row(row: &SqliteRow) -> TimestampedMessage {
+    let role_str: String = row.get(0);
+    let role = match role_str.as_str() {
+        "user" => MessageRole::User,
+        "assistant" => MessageRole::Assistant,
+        "system" => MessageRole::System,
+        "tool" => MessageRole::Tool,
+        _ => MessageRole::User, // Default fallback
+    };
+
+    let tool_calls_str: Option<String> = row.get(3);
+    let tool_calls = tool_calls_str
+        .filter(|s| !s.is_empty())
+        .map(|s| serde_json::from_str::<Vec<ToolCall>>(&s).unwrap_or_default());
+
+    TimestampedMessage {
+        message: ChatMessage {
+            role,
+            content: parse_content(row.get(1)),
+            name: row.get(2),
+            tool_calls,
+            tool_call_id: row.get(4),
+            reasoning_content: row.get(6),
+        },
+        created_at: row.get(5),
+        partial: row.get::<i64, _>(7) != 0,
+    }
+}
+
+/// Column values shared by inserts and updates of a message:
+/// role, content (raw string for Text, JSON for Parts) and tool calls JSON
+fn message_columns(message: &ChatMessage) -> Result<(&'static str, String, Option<String>)> {
+    let role_str = match message.role {
+        MessageRole::User => "user",
+        MessageRole::Assistant => "assistant",
+        MessageRole::System => "system",
+        MessageRole::Tool => "tool",
+    };
+
+    let tool_calls_json = if let Some(calls) = &message.tool_calls {
+        Some(serde_json::to_string(calls).context("Failed to serialize tool calls")?)
+    } else {
+        None
+    };
+
+    let content_str = match &message.content {
+        MessageContent::Text(s) => s.clone(),
+        MessageContent::Parts(parts) => serde_json::to_string(parts).unwrap_or_default(),
+    };
+
+    Ok((role_str, content_str, tool_calls_json))
+}
+
+/// A window of a conversation returned by `get_messages_paginated`
+#[derive(Debug, Clone)]
+pub struct MessagePage {
+    /// Messages in chronological order
+    pub messages: Vec<ChatMessage>,
+    /// Cursor for the previous page; `None` when this page reaches the start
+    pub next_before_id: Option<i64>,
+}
+
+/// Whether a title is still the placeholder assigned at creation time
+pub fn is_default_title(title: &str) -> bool {
+    title.starts_with("Chat ") || title == "New Conversation"
+}
+
+/// SQLite-based conversation storage
+/// Stores all message types including tool calls and results
+pub struct SqliteConversationMemory {
+    pool: SqlitePool,
+    /// Whether the FTS5 index is available; search falls back to LIKE otherwise
+    fts_enabled: bool,
+}
+
+impl SqliteConversationMemory {
+    /// Create a new SQLite conversation memory store
+    pub async fn new(pool: SqlitePool) -> Result<Self> {
         // Check if messages table exists and has the new columns
         let table_exists: Option<String> = sqlx::query_scalar(
             "SELECT name FROM sqlite_master WHERE type='table' AND name='messages'",
@@ -77,6 +274,11 @@ impl SqliteConversationMemory {
                     .execute(&pool)
                     .await
                     .context("Failed to drop old messages table")?;
+                // The search index references message ids, so it has to go too
+                sqlx::query("DROP TABLE IF EXISTS messages_fts")
+                    .execute(&pool)
+                    .await
+                    .context("Failed to drop old search index")?;
             }
         }
 
@@ -110,6 +312,10 @@ impl SqliteConversationMemory {
                     .execute(&pool)
                     .await
                     .context("Failed to drop messages table for reset")?;
+                sqlx::query("DROP TABLE IF EXISTS messages_fts")
+                    .execute(&pool)
+                    .await
+                    .context("Failed to drop search index for reset")?;
                 sqlx::query("DROP TABLE conversations")
                     .execute(&pool)
                     .await
@@ -117,44 +323,87 @@ impl SqliteConversationMemory {
             }
         }
 
-        // Create tables
-        sqlx::query(
-            "CREATE TABLE IF NOT EXISTS conversations (
-                id TEXT PRIMARY KEY,
-                title TEXT,
-                model TEXT,
-                created_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now'))
-            )",
+        run_migrations(&pool, "conversations", MIGRATIONS)
+            .await
+            .context("Failed to migrate conversation tables")?;
+
+        // Databases created before reasoning was stored (and before migrations
+        // were tracked) lack the column
+        let has_reasoning: Option<i32> = sqlx::query_scalar(
+            "SELECT 1 FROM pragma_table_info('messages') WHERE name='reasoning_content'",
         )
-        .execute(&pool)
+        .fetch_optional(&pool)
         .await
-        .context("Failed to create conversations table")?;
+        .unwrap_or(None);
 
-        sqlx::query(
-            "CREATE TABLE IF NOT EXISTS messages (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
-                conversation_id TEXT NOT NULL,
-                role TEXT NOT NULL,
-                content TEXT NOT NULL,
-                name TEXT,
-                tool_calls TEXT,
-                tool_call_id TEXT,
-                created_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now')),
-                FOREIGN KEY (conversation_id) REFERENCES conversations(id) ON DELETE CASCADE
-            )",
+        if has_reasoning.is_none() {
+            sqlx::query("ALTER TABLE messages ADD COLUMN reasoning_content TEXT")
+                .execute(&pool)
+                .await
+                .context("Failed to add reasoning_content column")?;
+        }
+
+        let fts_enabled = match Self::init_fts(&pool).await {
+            Ok(()) => true,
+            Err(e) => {
+                println!(
+                    "⚠️  Full-text search unavailable, falling back to LIKE search: {}",
+                    e
+                );
+                false
+            }
+        };
+
+        Ok(Self { pool, fts_enabled })
+    }
+
+    /// Create the FTS5 index over message content and the triggers keeping it in sync
+    async fn init_fts(pool: &SqlitePool) -> Result<()> {
+        let fts_exists: Option<String> = sqlx::query_scalar(
+            "SELECT name FROM sqlite_master WHERE type='table' AND name='messages_fts'",
         )
-        .execute(&pool)
+        .fetch_optional(pool)
         .await
-        .context("Failed to create messages table")?;
+        .context("Failed to check for FTS table")?;
 
         sqlx::query(
-            "CREATE INDEX IF NOT EXISTS idx_messages_conversation ON messages(conversation_id)",
+            "CREATE VIRTUAL TABLE IF NOT EXISTS messages_fts
+             USING fts5(content, content='messages', content_rowid='id')",
         )
-        .execute(&pool)
+        .execute(pool)
         .await
-        .context("Failed to create index")?;
+        .context("Failed to create FTS table")?;
 
-        Ok(Self { pool })
+        let triggers = [
+            "CREATE TRIGGER IF NOT EXISTS messages_fts_insert AFTER INSERT ON messages BEGIN
+                INSERT INTO messages_fts(rowid, content) VALUES (new.id, new.content);
+             END",
+            "CREATE TRIGGER IF NOT EXISTS messages_fts_delete AFTER DELETE ON messages BEGIN
+                INSERT INTO messages_fts(messages_fts, rowid, content)
+                VALUES ('delete', old.id, old.content);
+             END",
+            "CREATE TRIGGER IF NOT EXISTS messages_fts_update AFTER UPDATE ON messages BEGIN
+                INSERT INTO messages_fts(messages_fts, rowid, content)
+                VALUES ('delete', old.id, old.content);
+                INSERT INTO messages_fts(rowid, content) VALUES (new.id, new.content);
+             END",
+        ];
+        for trigger in triggers {
+            sqlx::query(trigger)
+                .execute(pool)
+                .await
+                .context("Failed to create FTS trigger")?;
+        }
+
+        // Index messages stored before the FTS table existed
+        if fts_exists.is_none() {
+            sqlx::query("INSERT INTO messages_fts(messages_fts) VALUES ('rebuild')")
+                .execute(pool)
+                .await
+                .context("Failed to build FTS index")?;
+        }
+
+        Ok(())
     }
 
     /// Get or create a conversation ID
@@ -209,29 +458,11 @@ impl SqliteConversationMemory {
 
     /// Add a message to the conversation
     pub async fn add_message(&self, conversation_id: &str, message: ChatMessage) -> Result<()> {
-        let role_str = match message.role {
-            MessageRole::User => "user",
-            MessageRole::Assistant => "assistant",
-            MessageRole::System => "system",
-            MessageRole::Tool => "tool",
-        };
-
-        let tool_calls_json = if let Some(calls) = &message.tool_calls {
-            Some(serde_json::to_string(calls).context("Failed to serialize tool calls")?)
-        } else {
-            None
-        };
-
-        // Serialize content: Raw string for Text, JSON for Parts
-        use crate::api::agent::core::types::MessageContent;
-        let content_str = match &message.content {
-            MessageContent::Text(s) => s.clone(),
-            MessageContent::Parts(parts) => serde_json::to_string(parts).unwrap_or_default(),
-        };
+        let (role_str, content_str, tool_calls_json) = message_columns(&message)?;
 
         sqlx::query(
-            "INSERT INTO messages (conversation_id, role, content, name, tool_calls, tool_call_id) 
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            "INSERT INTO messages (conversation_id, role, content, name, tool_calls, tool_call_id, reasoning_content) 
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
         )
         .bind(conversation_id)
         .bind(role_str)
@@ -239,6 +470,7 @@ impl SqliteConversationMemory {
         .bind(&message.name)
         .bind(&tool_calls_json)
         .bind(&message.tool_call_id)
+        .bind(&message.reasoning_content)
         .execute(&self.pool)
         .await
         .context("Failed to insert message")?;
@@ -246,65 +478,195 @@ impl SqliteConversationMemory {
         Ok(())
     }
 
-    /// Get all messages for a conversation
-    pub async fn get_messages(&self, conversation_id: &str) -> Result<Vec<ChatMessage>> {
+    /// Save the text streamed so far for an assistant message still being
+    /// generated. Pass `None` the first time to insert a row marked partial,
+    /// then the returned id to overwrite it with newer content.
+    pub async fn save_partial_message(
+        &self,
+        conversation_id: &str,
+        partial_id: Option<i64>,
+        content: &str,
+        reasoning_content: Option<&str>,
+    ) -> Result<i64> {
+        if let Some(id) = partial_id {
+            sqlx::query(
+                "UPDATE messages SET content = ?1, reasoning_content = ?2
+                 WHERE id = ?3 AND partial = 1",
+            )
+            .bind(content)
+            .bind(reasoning_content)
+            .bind(id)
+            .execute(&self.pool)
+            .await
+            .context("Failed to update partial message")?;
+            return Ok(id);
+        }
+
+        let result = sqlx::query(
+            "INSERT INTO messages (conversation_id, role, content, reasoning_content, partial)
+             VALUES (?1, 'assistant', ?2, ?3, 1)",
+        )
+        .bind(conversation_id)
+        .bind(content)
+        .bind(reasoning_content)
+        .execute(&self.pool)
+        .await
+        .context("Failed to insert partial message")?;
+
+        Ok(result.last_insert_rowid())
+    }
+
+    /// Replace a partial message with the complete one and clear its partial mark
+    pub async fn finalize_partial_message(&self, id: i64, message: ChatMessage) -> Result<()> {
+        let (role_str, content_str, tool_calls_json) = message_columns(&message)?;
+
+        sqlx::query(
+            "UPDATE messages SET role = ?1, content = ?2, name = ?3, tool_calls = ?4,
+                 tool_call_id = ?5, reasoning_content = ?6, partial = 0
+             WHERE id = ?7",
+        )
+        .bind(role_str)
+        .bind(&content_str)
+        .bind(&message.name)
+        .bind(&tool_calls_json)
+        .bind(&message.tool_call_id)
+        .bind(&message.reasoning_content)
+        .bind(id)
+        .execute(&self.pool)
+        .await
+        .context("Failed to finalize partial message")?;
+
+        Ok(())
+    }
+
+    /// Keep a conversation's partial messages as they stand, clearing their
+    /// partial mark. Returns how many were kept.
+    pub async fn finish_partial_messages(&self, conversation_id: &str) -> Result<u64> {
+        let result = sqlx::query(
+            "UPDATE messages SET partial = 0 WHERE conversation_id = ?1 AND partial = 1",
+        )
+        .bind(conversation_id)
+        .execute(&self.pool)
+        .await
+        .context("Failed to finish partial messages")?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Remove a partial message that won't be completed
+    pub async fn delete_partial_message(&self, id: i64) -> Result<()> {
+        sqlx::query("DELETE FROM messages WHERE id = ?1 AND partial = 1")
+            .bind(id)
+            .execute(&self.pool)
+            .await
+            .context("Failed to delete partial message")?;
+
+        Ok(())
+    }
+
+    /// Get messages for a conversation, optionally excluding tool traffic.
+    /// When `include_tools` is false, tool results and the assistant messages that
+    /// requested them are skipped, leaving only the user/assistant transcript.
+    pub async fn get_messages_filtered(
+        &self,
+        conversation_id: &str,
+        include_tools: bool,
+    ) -> Result<Vec<ChatMessage>> {
+        Ok(self
+            .get_timestamped_messages(conversation_id, include_tools)
+            .await?
+            .into_iter()
+            .map(|m| m.message)
+            .collect())
+    }
+
+    /// Get messages for a conversation together with the time they were stored
+    pub async fn get_timestamped_messages(
+        &self,
+        conversation_id: &str,
+        include_tools: bool,
+    ) -> Result<Vec<TimestampedMessage>> {
         let rows = sqlx::query(
-            "SELECT role, content, name, tool_calls, tool_call_id FROM messages 
+            "SELECT role, content, name, tool_calls, tool_call_id, created_at, reasoning_content, partial FROM messages 
              WHERE conversation_id = ?1 
-             ORDER BY created_at ASC",
+             AND (?2 OR (role != 'tool' AND tool_calls IS NULL))
+             ORDER BY created_at ASC, id ASC",
         )
         .bind(conversation_id)
+        .bind(include_tools)
         .fetch_all(&self.pool)
         .await
         .context("Failed to fetch messages")?;
 
-        let mut messages = Vec::new();
-        for row in rows {
-            let role_str: String = row.get(0);
-            let content_str: String = row.get(1);
-            let name: Option<String> = row.get(2);
-            let tool_calls_str: Option<String> = row.get(3);
-            let tool_call_id: Option<String> = row.get(4);
-
-            let role = match role_str.as_str() {
-                "user" => MessageRole::User,
-                "assistant" => MessageRole::Assistant,
-                "system" => MessageRole::System,
-                "tool" => MessageRole::Tool,
-                _ => MessageRole::User, // Default fallback
-            };
+        Ok(rows.iter().map(timestamped_message_from_row).collect())
+    }
 
-            use crate::api::agent::core::types::{ContentPart, MessageContent};
-            // Deserialize content
-            let content = if content_str.trim().starts_with('[') {
-                match serde_json::from_str::<Vec<ContentPart>>(&content_str) {
-                    Ok(parts) => MessageContent::Parts(parts),
-                    Err(_) => MessageContent::Text(content_str), // Fallback to raw text if parse fails
-                }
-            } else {
-                MessageContent::Text(content_str)
-            };
+    /// One page of a conversation's complete messages, newest last; rows still
+    /// marked partial are skipped. Pass the returned `next_before_id` as
+    /// `before_id` to fetch the page before it.
+    pub async fn get_messages_paginated(
+        &self,
+        conversation_id: &str,
+        limit: usize,
+        before_id: Option<i64>,
+    ) -> Result<MessagePage> {
+        let limit = limit.max(1);
+        // Fetch one extra row to learn whether older messages remain
+        let rows = sqlx::query(
+            "SELECT role, content, name, tool_calls, tool_call_id, created_at, reasoning_content, partial, id
+             FROM messages
+             WHERE conversation_id = ?1 AND (?2 IS NULL OR id < ?2) AND partial = 0
+             ORDER BY id DESC
+             LIMIT ?3",
+        )
+        .bind(conversation_id)
+        .bind(before_id)
+        .bind(limit as i64 + 1)
+        .fetch_all(&self.pool)
+        .await
+        .context("Failed to fetch message page")?;
 
-            let tool_calls = if let Some(s) = tool_calls_str {
-                if !s.is_empty() {
-                    Some(serde_json::from_str::<Vec<ToolCall>>(&s).unwrap_or_default())
-                } else {
-                    None
-                }
-            } else {
-                None
-            };
+        let has_more = rows.len() > limit;
+        let page = &rows[..rows.len().min(limit)];
+        let next_before_id = if has_more {
+            page.last().map(|row| row.get::<i64, _>(8))
+        } else {
+            None
+        };
 
-            messages.push(ChatMessage {
-                role,
-                content,
-                name,
-                tool_calls,
-                tool_call_id,
-                reasoning_content: None,
-            });
-        }
+        Ok(MessagePage {
+            messages: page
+                .iter()
+                .rev()
+                .map(|row| timestamped_message_from_row(row).message)
+                .collect(),
+            next_before_id,
+        })
+    }
 
+    /// The latest `limit` messages as model context. Stored reasoning is
+    /// dropped; it is not replayed to the model, and neither are partial
+    /// messages from an interrupted stream. The window starts at a user
+    /// message so it never opens with a tool result whose tool call was cut off.
+    pub async fn get_recent_messages(
+        &self,
+        conversation_id: &str,
+        limit: usize,
+    ) -> Result<Vec<ChatMessage>> {
+        let page = self
+            .get_messages_paginated(conversation_id, limit, None)
+            .await?;
+        let mut messages = page.messages;
+        if page.next_before_id.is_some() {
+            let first_user = messages
+                .iter()
+                .position(|m| m.role == MessageRole::User)
+                .unwrap_or(messages.len());
+            messages.drain(..first_user);
+        }
+        for message in &mut messages {
+            message.reasoning_content = None;
+        }
         Ok(messages)
     }
 
@@ -394,29 +756,105 @@ impl SqliteConversationMemory {
         Ok(())
     }
 
-    /// Get all conversations
-    pub async fn get_conversations(&self) -> Result<Vec<Conversation>> {
-        let rows = sqlx::query(
-            "SELECT id, title, model, created_at FROM conversations ORDER BY created_at DESC",
-        )
+    /// List all conversations with activity metadata, most recently active first.
+    /// Conversations still carrying a placeholder title get one derived from
+    /// their first user message.
+    pub async fn list_conversations(&self) -> Result<Vec<Conversation>> {
+        let rows = sqlx::query(&format!(
+            "{} GROUP BY c.id ORDER BY last_updated DESC, c.created_at DESC",
+            CONVERSATION_SUMMARY_SELECT
+        ))
         .fetch_all(&self.pool)
         .await
         .context("Failed to fetch conversations")?;
 
-        let mut conversations = Vec::new();
-        for row in rows {
-            let model: Option<String> = row.get(2);
-            // println!("DEBUG: Fetching conversation: {:?}, model: {:?}", row.get::<String, _>(0), model);
-            conversations.push(Conversation {
-                id: row.get(0),
-                title: row.get(1),
-                model,
-                created_at: row.get(3),
-            });
+        Ok(rows.iter().map(conversation_from_row).collect())
+    }
+
+    /// Get a single conversation with activity metadata
+    pub async fn get_conversation(&self, conversation_id: &str) -> Result<Option<Conversation>> {
+        let row = sqlx::query(&format!(
+            "{} WHERE c.id = ?1 GROUP BY c.id",
+            CONVERSATION_SUMMARY_SELECT
+        ))
+        .bind(conversation_id)
+        .fetch_optional(&self.pool)
+        .await
+        .context("Failed to fetch conversation")?;
+
+        Ok(row.as_ref().map(conversation_from_row))
+    }
+
+    /// Search message content across all conversations, best matches first.
+    /// Uses the FTS5 index when available and a LIKE scan otherwise.
+    pub async fn search_messages(
+        &self,
+        query: &str,
+        limit: usize,
+    ) -> Result<Vec<MessageSearchResult>> {
+        let limit = limit.clamp(1, MAX_SEARCH_LIMIT) as i64;
+
+        if self.fts_enabled {
+            let fts_query = match fts_query(query) {
+                Some(q) => q,
+                None => return Ok(Vec::new()),
+            };
+
+            let rows = sqlx::query(
+                "SELECT m.conversation_id, m.role,
+                        snippet(messages_fts, 0, '**', '**', '…', 16), m.created_at
+                 FROM messages_fts
+                 JOIN messages m ON m.id = messages_fts.rowid
+                 WHERE messages_fts MATCH ?1
+                 ORDER BY rank
+                 LIMIT ?2",
+            )
+            .bind(&fts_query)
+            .bind(limit)
+            .fetch_all(&self.pool)
+            .await
+            .context("Failed to search messages")?;
+
+            return Ok(rows
+                .into_iter()
+                .map(|row| MessageSearchResult {
+                    conversation_id: row.get(0),
+                    role: row.get(1),
+                    snippet: row.get(2),
+                    created_at: row.get(3),
+                })
+                .collect());
+        }
+
+        let query = query.trim();
+        if query.is_empty() {
+            return Ok(Vec::new());
         }
 
-        // println!("DEBUG: Returning {} conversations", conversations.len());
-        Ok(conversations)
+        let rows = sqlx::query(
+            "SELECT conversation_id, role, content, created_at FROM messages
+             WHERE content LIKE ?1 ESCAPE '\\'
+             ORDER BY created_at DESC, id DESC
+             LIMIT ?2",
+        )
+        .bind(format!("%{}%", escape_like(query)))
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .context("Failed to search messages")?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| {
+                let content: String = row.get(2);
+                MessageSearchResult {
+                    conversation_id: row.get(0),
+                    role: row.get(1),
+                    snippet: make_snippet(&content, query),
+                    created_at: row.get(3),
+                }
+            })
+            .collect())
     }
 
     /// Delete a conversation
@@ -432,20 +870,97 @@ impl SqliteConversationMemory {
         Ok(())
     }
 
-    /// Update conversation title
-    pub async fn update_conversation_title(
-        &self,
-        conversation_id: &str,
-        title: &str,
-    ) -> Result<()> {
-        sqlx::query("UPDATE conversations SET title = ?1 WHERE id = ?2")
+    /// Delete every conversation and its messages in a single transaction
+    /// Returns the number of conversations removed
+    pub async fn clear_all(&self) -> Result<u64> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .context("Failed to start transaction")?;
+
+        // Delete messages explicitly rather than relying on CASCADE, so the
+        // FTS delete trigger fires for every row
+        sqlx::query("DELETE FROM messages")
+            .execute(&mut *tx)
+            .await
+            .context("Failed to delete messages")?;
+
+        let result = sqlx::query("DELETE FROM conversations")
+            .execute(&mut *tx)
+            .await
+            .context("Failed to delete conversations")?;
+
+        tx.commit().await.context("Failed to commit transaction")?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Set conversation title
+    /// Returns false if the conversation doesn't exist
+    pub async fn set_title(&self, conversation_id: &str, title: &str) -> Result<bool> {
+        let result = sqlx::query("UPDATE conversations SET title = ?1 WHERE id = ?2")
             .bind(title)
             .bind(conversation_id)
             .execute(&self.pool)
             .await
             .context("Failed to update conversation title")?;
 
-        Ok(())
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Get the first user message of a conversation as plain text
+    async fn first_user_message(&self, conversation_id: &str) -> Result<Option<String>> {
+        let content: Option<String> = sqlx::query_scalar(
+            "SELECT content FROM messages
+             WHERE conversation_id = ?1 AND role = 'user'
+             ORDER BY created_at ASC, id ASC LIMIT 1",
+        )
+        .bind(conversation_id)
+        .fetch_optional(&self.pool)
+        .await
+        .context("Failed to get first user message")?;
+
+        Ok(content.map(|c| parse_content(c).text()))
+    }
+
+    /// Whether the conversation title was generated automatically (placeholder or
+    /// derived from the first user message) rather than chosen by the user or the LLM
+    pub async fn is_auto_title(&self, conversation_id: &str) -> Result<bool> {
+        let title = self.get_title(conversation_id).await?;
+        if is_default_title(&title) {
+            return Ok(true);
+        }
+
+        let derived = self
+            .first_user_message(conversation_id)
+            .await?
+            .and_then(|m| derive_title(&m));
+        Ok(derived.as_deref() == Some(title.as_str()))
+    }
+
+    /// Title a new conversation from its first user message so the UI doesn't show
+    /// placeholder names. Does nothing once the conversation has a real title.
+    /// Returns the new title if one was set.
+    pub async fn auto_title_from_first_message(
+        &self,
+        conversation_id: &str,
+    ) -> Result<Option<String>> {
+        if !is_default_title(&self.get_title(conversation_id).await?) {
+            return Ok(None);
+        }
+
+        let title = match self
+            .first_user_message(conversation_id)
+            .await?
+            .and_then(|m| derive_title(&m))
+        {
+            Some(title) => title,
+            None => return Ok(None),
+        };
+
+        self.set_title(conversation_id, &title).await?;
+        Ok(Some(title))
     }
 
     /// Update conversation model
@@ -476,3 +991,384 @@ impl SqliteConversationMemory {
         Ok(title.unwrap_or_else(|| "New Conversation".to_string()))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::agent::core::types::FunctionCall;
+    use crate::utils::database::connect_sqlite;
+
+    fn message(role: MessageRole, content: &str) -> ChatMessage {
+        ChatMessage {
+            role,
+            content: MessageContent::Text(content.to_string()),
+            name: None,
+            tool_calls: None,
+            tool_call_id: None,
+            reasoning_content: None,
+        }
+    }
+
+    fn temp_db_path() -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("ai_tools_memory_test_{}.db", uuid::Uuid::new_v4()))
+    }
+
+    /// In-memory storage with one empty conversation
+    async fn test_memory() -> (SqliteConversationMemory, String) {
+        let memory = SqliteConversationMemory::new(connect_sqlite(":memory:").await.unwrap())
+            .await
+            .unwrap();
+        let id = memory
+            .get_or_create_conversation_id(None, None)
+            .await
+            .unwrap();
+        (memory, id)
+    }
+
+    #[tokio::test]
+    async fn test_partial_message_survives_restart() {
+        let db_path = temp_db_path();
+        let memory = SqliteConversationMemory::new(connect_sqlite(&db_path).await.unwrap())
+            .await
+            .unwrap();
+        let id = memory
+            .get_or_create_conversation_id(None, None)
+            .await
+            .unwrap();
+        memory
+            .add_message(&id, message(MessageRole::User, "Tell me a story"))
+            .await
+            .unwrap();
+
+        let partial_id = memory
+            .save_partial_message(&id, None, "Once upon", None)
+            .await
+            .unwrap();
+        let same_id = memory
+            .save_partial_message(&id, Some(partial_id), "Once upon a time", Some("plot"))
+            .await
+            .unwrap();
+        assert_eq!(same_id, partial_id);
+
+        // Simulate a crash mid-stream: nothing finalizes the row, the process restarts
+        drop(memory);
+        let memory = SqliteConversationMemory::new(connect_sqlite(&db_path).await.unwrap())
+            .await
+            .unwrap();
+
+        let recovered = memory.get_timestamped_messages(&id, true).await.unwrap();
+        assert_eq!(recovered.len(), 2);
+        assert!(!recovered[0].partial);
+        assert!(recovered[1].partial);
+        assert_eq!(recovered[1].message.role, MessageRole::Assistant);
+        assert_eq!(recovered[1].message.content.text(), "Once upon a time");
+        assert_eq!(
+            recovered[1].message.reasoning_content.as_deref(),
+            Some("plot")
+        );
+
+        // History (above) shows the partial reply, model context leaves it out
+        let context = memory.get_recent_messages(&id, 10).await.unwrap();
+        assert_eq!(context.len(), 1);
+        assert_eq!(context[0].role, MessageRole::User);
+
+        memory
+            .finalize_partial_message(
+                partial_id,
+                message(MessageRole::Assistant, "Once upon a time, the end."),
+            )
+            .await
+            .unwrap();
+        let finalized = memory.get_timestamped_messages(&id, true).await.unwrap();
+        assert_eq!(finalized.len(), 2);
+        assert!(!finalized[1].partial);
+        assert_eq!(
+            finalized[1].message.content.text(),
+            "Once upon a time, the end."
+        );
+        assert_eq!(memory.get_recent_messages(&id, 10).await.unwrap().len(), 2);
+
+        // Finalized rows are no longer touched by partial saves or deletes
+        memory.delete_partial_message(partial_id).await.unwrap();
+        assert_eq!(memory.message_count(&id).await.unwrap(), 2);
+
+        let _ = std::fs::remove_file(&db_path);
+    }
+
+    #[tokio::test]
+    async fn test_get_messages_filtered_tool_messages() {
+        let (memory, id) = test_memory().await;
+
+        memory
+            .add_message(&id, message(MessageRole::User, "weather in Warsaw?"))
+            .await
+            .unwrap();
+        memory
+            .add_message(
+                &id,
+                ChatMessage {
+                    tool_calls: Some(vec![ToolCall {
+                        id: "call_1".to_string(),
+                        tool_type: "function".to_string(),
+                        function: FunctionCall {
+                            name: "weather_current".to_string(),
+                            arguments: r#"{"city":"Warsaw"}"#.to_string(),
+                        },
+                    }]),
+                    ..message(MessageRole::Assistant, "")
+                },
+            )
+            .await
+            .unwrap();
+        memory
+            .add_message(
+                &id,
+                ChatMessage {
+                    name: Some("weather_current".to_string()),
+                    tool_call_id: Some("call_1".to_string()),
+                    ..message(MessageRole::Tool, "Sunny, 21°C")
+                },
+            )
+            .await
+            .unwrap();
+        memory
+            .add_message(&id, message(MessageRole::Assistant, "It's sunny."))
+            .await
+            .unwrap();
+
+        let all = memory.get_messages_filtered(&id, true).await.unwrap();
+        assert_eq!(all.len(), 4);
+        let tool_message = &all[2];
+        assert_eq!(tool_message.role, MessageRole::Tool);
+        assert_eq!(tool_message.tool_call_id.as_deref(), Some("call_1"));
+        assert_eq!(tool_message.name.as_deref(), Some("weather_current"));
+
+        let transcript = memory.get_messages_filtered(&id, false).await.unwrap();
+        assert_eq!(transcript.len(), 2);
+        assert!(transcript.iter().all(|m| m.role != MessageRole::Tool));
+        assert!(transcript.iter().all(|m| m.tool_calls.is_none()));
+    }
+
+    #[tokio::test]
+    async fn test_reasoning_content_persisted_but_not_replayed() {
+        let (memory, id) = test_memory().await;
+
+        memory
+            .add_message(
+                &id,
+                ChatMessage {
+                    reasoning_content: Some("The user wants a greeting.".to_string()),
+                    ..message(MessageRole::Assistant, "Hello!")
+                },
+            )
+            .await
+            .unwrap();
+
+        let stored = memory.get_messages_filtered(&id, true).await.unwrap();
+        assert_eq!(
+            stored[0].reasoning_content.as_deref(),
+            Some("The user wants a greeting.")
+        );
+
+        let context = memory.get_recent_messages(&id, 10).await.unwrap();
+        assert!(context[0].reasoning_content.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_get_messages_paginated() {
+        let (memory, id) = test_memory().await;
+        for i in 0..5 {
+            memory
+                .add_message(&id, message(MessageRole::User, &format!("m{}", i)))
+                .await
+                .unwrap();
+        }
+
+        let texts = |page: &MessagePage| -> Vec<String> {
+            page.messages.iter().map(|m| m.content.text()).collect()
+        };
+
+        let newest = memory.get_messages_paginated(&id, 2, None).await.unwrap();
+        assert_eq!(texts(&newest), vec!["m3", "m4"]);
+        assert!(newest.next_before_id.is_some());
+
+        let middle = memory
+            .get_messages_paginated(&id, 2, newest.next_before_id)
+            .await
+            .unwrap();
+        assert_eq!(texts(&middle), vec!["m1", "m2"]);
+
+        let oldest = memory
+            .get_messages_paginated(&id, 2, middle.next_before_id)
+            .await
+            .unwrap();
+        assert_eq!(texts(&oldest), vec!["m0"]);
+        assert_eq!(oldest.next_before_id, None);
+    }
+
+    #[tokio::test]
+    async fn test_get_recent_messages_starts_at_user_turn() {
+        let (memory, id) = test_memory().await;
+        let history = [
+            message(MessageRole::User, "weather?"),
+            ChatMessage {
+                tool_calls: Some(vec![ToolCall {
+                    id: "call_1".to_string(),
+                    tool_type: "function".to_string(),
+                    function: crate::api::agent::core::types::FunctionCall {
+                        name: "weather_current".to_string(),
+                        arguments: "{}".to_string(),
+                    },
+                }]),
+                ..message(MessageRole::Assistant, "")
+            },
+            ChatMessage {
+                tool_call_id: Some("call_1".to_string()),
+                ..message(MessageRole::Tool, "Sunny")
+            },
+            message(MessageRole::Assistant, "It is sunny."),
+            message(MessageRole::User, "thanks"),
+            message(MessageRole::Assistant, "You're welcome"),
+        ];
+        for m in history {
+            memory.add_message(&id, m).await.unwrap();
+        }
+
+        // A window of 4 would open with the orphaned tool result
+        let window = memory.get_recent_messages(&id, 4).await.unwrap();
+        let roles: Vec<MessageRole> = window.iter().map(|m| m.role.clone()).collect();
+        assert_eq!(roles, vec![MessageRole::User, MessageRole::Assistant]);
+
+        // The whole conversation fits, so nothing is dropped
+        assert_eq!(memory.get_recent_messages(&id, 10).await.unwrap().len(), 6);
+    }
+
+    #[tokio::test]
+    async fn test_auto_title_from_first_message() {
+        let (memory, id) = test_memory().await;
+
+        // No user message yet: nothing to derive from
+        assert_eq!(
+            memory.auto_title_from_first_message(&id).await.unwrap(),
+            None
+        );
+
+        memory
+            .add_message(&id, message(MessageRole::User, "Compare BTC and ETH"))
+            .await
+            .unwrap();
+        assert_eq!(
+            memory.auto_title_from_first_message(&id).await.unwrap(),
+            Some("Compare BTC and ETH".to_string())
+        );
+        assert!(memory.is_auto_title(&id).await.unwrap());
+
+        // A user-chosen title is never overwritten
+        assert!(memory.set_title(&id, "Crypto research").await.unwrap());
+        assert!(!memory.is_auto_title(&id).await.unwrap());
+        assert_eq!(
+            memory.auto_title_from_first_message(&id).await.unwrap(),
+            None
+        );
+        assert_eq!(memory.get_title(&id).await.unwrap(), "Crypto research");
+
+        assert!(!memory.set_title("missing", "Title").await.unwrap());
+    }
+
+    #[test]
+    fn test_fts_query_quotes_terms() {
+        assert_eq!(
+            fts_query("rust async"),
+            Some(r#""rust" "async""#.to_string())
+        );
+        assert_eq!(
+            fts_query(r#"say "hi" OR NOT*"#),
+            Some(r#""say" """hi""" "OR" "NOT*""#.to_string())
+        );
+        assert_eq!(fts_query("   "), None);
+        assert_eq!(fts_query("( * )"), None);
+    }
+
+    #[test]
+    fn test_escape_like() {
+        assert_eq!(escape_like("100%_done\\"), "100\\%\\_done\\\\");
+    }
+
+    #[test]
+    fn test_make_snippet() {
+        let content = format!("{}needle{}", "a".repeat(100), "b".repeat(100));
+        let snippet = make_snippet(&content, "NEEDLE");
+        assert!(snippet.starts_with('…'));
+        assert!(snippet.ends_with('…'));
+        assert!(snippet.contains("needle"));
+
+        assert_eq!(make_snippet("short text", "text"), "short text");
+    }
+
+    #[tokio::test]
+    async fn test_search_messages() {
+        let (memory, id) = test_memory().await;
+
+        memory
+            .add_message(
+                &id,
+                message(MessageRole::User, "How do I bake sourdough bread?"),
+            )
+            .await
+            .unwrap();
+        memory
+            .add_message(
+                &id,
+                message(MessageRole::Assistant, "Start with an active starter."),
+            )
+            .await
+            .unwrap();
+
+        let results = memory.search_messages("sourdough", 10).await.unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].conversation_id, id);
+        assert_eq!(results[0].role, "user");
+        assert!(results[0].snippet.contains("sourdough"));
+
+        // FTS operators and stray quotes must not cause syntax errors
+        assert!(memory
+            .search_messages(r#"starter" OR (*"#, 10)
+            .await
+            .is_ok());
+        assert!(memory
+            .search_messages("croissant", 10)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_clear_all() {
+        let db_path = temp_db_path();
+        let memory = SqliteConversationMemory::new(connect_sqlite(&db_path).await.unwrap())
+            .await
+            .unwrap();
+
+        for content in ["First sourdough chat", "Second sourdough chat"] {
+            let id = memory
+                .get_or_create_conversation_id(None, None)
+                .await
+                .unwrap();
+            memory
+                .add_message(&id, message(MessageRole::User, content))
+                .await
+                .unwrap();
+        }
+
+        assert_eq!(memory.clear_all().await.unwrap(), 2);
+        assert!(memory.list_conversations().await.unwrap().is_empty());
+        assert!(memory
+            .search_messages("sourdough", 10)
+            .await
+            .unwrap()
+            .is_empty());
+        assert_eq!(memory.clear_all().await.unwrap(), 0);
+
+        let _ = std::fs::remove_file(&db_path);
+    }
+}
@@ -1,35 +1,104 @@
 use crate::api::agent::core::agent_loop::{execute_agent_loop, AgentLoopConfig};
+use crate::api::agent::core::backpressure::{
+    send_with_backpressure, SendOutcome, SLOW_CLIENT_TIMEOUT,
+};
 use crate::api::agent::core::streaming::execute_agent_loop_streaming;
 use crate::api::agent::core::types::{
-    ActiveGenerations, AgentChatRequest, AgentChatResponse, AgentConfig, AgentStreamEvent,
-    ChatMessage, MessageContent, MessageRole,
+    ActiveGenerations, AgentChatQuery, AgentChatRequest, AgentChatResponse, AgentConfig,
+    AgentStreamEvent, ChatMessage, ContentPart, MessageContent, MessageRole,
 };
+use crate::api::agent::core::utils::{model_supports_tool_role, model_supports_vision};
 use crate::api::agent::memory::sqlite_memory::SqliteConversationMemory;
 use crate::api::agent::service::naming::attempt_conversation_naming;
-use crate::api::agent::service::utils::clean_response;
+use crate::api::agent::service::utils::{
+    clean_response, compose_system_prompt, new_request_id, REQUEST_ID_HEADER,
+};
 use crate::api::agent::service::websocket::AgentWebSocketState;
 use crate::api::agent::tools::{
     self,
-    framework::{registry::ToolRegistry, selector::ToolSelector},
+    framework::{cache::ToolResultCache, registry::ToolRegistry, selector::ToolSelector},
 };
+use crate::api::error::ApiError;
 use crate::api::llama_server::types::Config;
-use actix_web::{post, web, HttpResponse, Responder, Result as ActixResult};
+use crate::api::metrics::registry as metrics;
+use crate::api::model_notes::ModelNotesStorage;
+use actix_web::{post, web, HttpResponse, Responder, ResponseError, Result as ActixResult};
 use futures::StreamExt;
+use log::{debug, error, info, warn};
 use reqwest::Client;
 use std::sync::{Arc, Mutex};
 use tokio::sync::mpsc;
 
+/// Most stored messages sent to the model as conversation history
+const CONTEXT_HISTORY_MESSAGES: usize = 50;
+
+/// Loop defaults for `model_name`, seeded with any generation params saved for it.
+/// Lookup failures or invalid saved values fall back to the built-in defaults.
+pub(crate) async fn model_loop_defaults(
+    model_notes: &ModelNotesStorage,
+    model_name: &str,
+    request_id: &str,
+) -> AgentLoopConfig {
+    let params = match model_notes.get_generation_params(model_name).await {
+        Ok(Some(params)) => params,
+        Ok(None) => return AgentLoopConfig::default(),
+        Err(e) => {
+            warn!(
+                "[{}] ⚠️ Failed to load generation params for {}: {}",
+                request_id, model_name, e
+            );
+            return AgentLoopConfig::default();
+        }
+    };
+
+    match AgentLoopConfig::default().with_generation_defaults(
+        params.temperature,
+        params.max_tokens,
+        params.top_p,
+    ) {
+        Ok(config) => {
+            info!(
+                "[{}] 🎛️ Using saved generation params for {}",
+                request_id, model_name
+            );
+            config
+        }
+        Err(e) => {
+            warn!(
+                "[{}] ⚠️ Ignoring invalid generation params for {}: {}",
+                request_id, model_name, e
+            );
+            AgentLoopConfig::default()
+        }
+    }
+}
+
 /// Chat completion endpoint
+///
+/// Pass `?verbose=true` to include `tool_traces` (arguments, result, success
+/// and duration of each tool call) in the response.
 #[post("/api/agent/chat")]
+#[allow(clippy::too_many_arguments)]
 pub async fn agent_chat(
     req: web::Json<AgentChatRequest>,
+    query: web::Query<AgentChatQuery>,
     agent_config: web::Data<Arc<Mutex<AgentConfig>>>,
     chroma_address: web::Data<String>,
     _chromadb_config: web::Data<Arc<Mutex<crate::api::chromadb::config::types::ChromaDBConfig>>>,
     llama_config: web::Data<Arc<Mutex<Config>>>,
     sqlite_memory: web::Data<Arc<SqliteConversationMemory>>,
+    tool_cache: web::Data<Arc<ToolResultCache>>,
+    model_notes: web::Data<Arc<ModelNotesStorage>>,
 ) -> ActixResult<HttpResponse> {
-    let config = agent_config.lock().unwrap().clone();
+    let request_id = new_request_id();
+    metrics::record(|m| m.agent_requests.with_label_values(&["chat"]).inc());
+
+    // Server config narrowed to any per-request tool subset
+    let config = agent_config
+        .lock()
+        .unwrap()
+        .scoped_to(req.enabled_tools.as_deref())
+        .map_err(|e| ApiError::BadRequest(e.to_string()))?;
 
     // Get model name from llama_server config
     let model_name = {
@@ -37,35 +106,43 @@ pub async fn agent_chat(
         llama_config_guard.hf_model.clone()
     };
 
-    // Construct Llama URL from config
-    let (llama_host, llama_port) = {
-        let llama_config_guard = llama_config.lock().unwrap();
-        (
-            llama_config_guard
-                .host
-                .clone()
-                .unwrap_or_else(|| "localhost".to_string()),
-            llama_config_guard.port.unwrap_or(8090),
-        )
-    };
+    // Build loop config from agent config, the model's saved generation params
+    // and any per-request overrides (in increasing precedence)
+    let model_defaults = model_loop_defaults(&model_notes, &model_name, &request_id).await;
+    let loop_config = AgentLoopConfig {
+        debug_logging: config.debug_logging,
+        tool_timeout_secs: config.tool_timeout_secs,
+        persist_tool_messages: config.persist_tool_messages,
+        supports_tool_role: config
+            .llm_supports_tool_role
+            .unwrap_or_else(|| model_supports_tool_role(&model_name)),
+        supports_vision: config
+            .llm_supports_vision
+            .unwrap_or_else(|| model_supports_vision(&model_name)),
+        plan_only: req.plan_only,
+        request_id: request_id.clone(),
+        context_token_budget: config.context_token_budget,
+        agent_timeout_secs: config.agent_timeout_secs,
+        ..model_defaults
+    }
+    .with_overrides(req.max_iterations, req.temperature, req.max_tokens)
+    .and_then(|c| c.with_sampling(req.top_p, req.frequency_penalty, req.presence_penalty))
+    .and_then(|c| c.with_stop(req.stop.clone()))
+    .map_err(|e| ApiError::BadRequest(e.to_string()))?;
+    check_image_parts(&req.message, loop_config.supports_vision).map_err(ApiError::BadRequest)?;
 
-    let host_for_url = if llama_host == "0.0.0.0" {
-        "127.0.0.1".to_string()
-    } else {
-        llama_host
-    };
-    let llama_base_url = format!("http://{}:{}", host_for_url, llama_port);
+    // Construct Llama URL from config (or LLM_BASE_URL)
+    let llama_base_url = llama_config.lock().unwrap().llm_base_url();
 
     // Get or create conversation ID from SQLite
-    let conversation_id = sqlite_memory
-        .get_or_create_conversation_id(req.conversation_id.clone(), Some(&model_name))
-        .await
-        .map_err(|e| {
-            actix_web::error::ErrorInternalServerError(format!(
-                "Failed to get conversation ID: {}",
-                e
-            ))
-        })?;
+    let conversation_id = resolve_conversation_id(
+        &sqlite_memory,
+        req.conversation_id.clone(),
+        &model_name,
+        req.plan_only,
+    )
+    .await
+    .map_err(|e| ApiError::Internal(format!("Failed to get conversation ID: {}", e)))?;
 
     // Build tool registry dynamically based on configuration
     let mut tool_registry = ToolRegistry::new();
@@ -74,39 +151,42 @@ pub async fn agent_chat(
     // Register all enabled tools
     let context = tools::RegisterContext {
         chroma_address: Some(chroma_address.as_str()),
+        model_notes: Some(Arc::clone(model_notes.get_ref())),
     };
     tools::register_all(&mut tool_registry, &config, &context);
+    tool_registry.set_cache(Arc::clone(tool_cache.get_ref()), config.cache_ttl_secs);
+    tool_registry.set_concurrency_limits(&config.tool_concurrency_limits);
 
     // Wrap registry in Arc for sharing
     let tool_registry_arc = Arc::new(tool_registry);
 
     // Build tool definitions for OpenAI-compatible API
-    let tools = tool_registry_arc.build_tool_definitions().map_err(|e| {
-        actix_web::error::ErrorInternalServerError(format!(
-            "Failed to build tool definitions: {}",
-            e
-        ))
-    })?;
+    let tools = tool_registry_arc
+        .build_tool_definitions()
+        .map_err(|e| ApiError::Internal(format!("Failed to build tool definitions: {}", e)))?;
 
     // Log tool registry stats and verify registration
     let tool_count = tool_registry_arc.count();
     let all_tool_ids = tool_registry_arc.get_all_tool_ids();
-    println!(
-        "📦 Tool registry: {} tool(s) registered: {:?}",
-        tool_count, all_tool_ids
+    info!(
+        "[{}] 📦 Tool registry: {} tool(s) registered: {:?}",
+        request_id, tool_count, all_tool_ids
     );
 
     // Verify all tools are properly registered and accessible
     for tool_id in &all_tool_ids {
         if !tool_registry_arc.is_registered(tool_id) {
-            println!(
-                "⚠️ Warning: Tool {} marked as registered but not found in registry",
-                tool_id
+            warn!(
+                "[{}] ⚠️ Warning: Tool {} marked as registered but not found in registry",
+                request_id, tool_id
             );
         } else if let Some(tool) = tool_registry_arc.get_tool(tool_id) {
             // Tool exists, verify it's available
             if !tool.is_available() {
-                println!("⚠️ Tool {} is registered but not available", tool_id);
+                warn!(
+                    "[{}] ⚠️ Tool {} is registered but not available",
+                    request_id, tool_id
+                );
             }
         }
     }
@@ -116,7 +196,11 @@ pub async fn agent_chat(
     for tool in &all_tools {
         // Verify tool is available (this uses the is_available method from the trait)
         if !tool.is_available() {
-            println!("⚠️ Tool {} is not available", tool.metadata().name);
+            warn!(
+                "[{}] ⚠️ Tool {} is not available",
+                request_id,
+                tool.metadata().name
+            );
         }
     }
 
@@ -126,19 +210,19 @@ pub async fn agent_chat(
     // Build system prompt using tool selector
     // The prompt already instructs the LLM when NOT to use tools (greetings, small talk, etc.)
     // The LLM will decide which tools to use based on the prompt
-    let system_prompt = tool_selector.build_system_prompt();
+    // The same composed prompt is reused by the stuck-recovery path below
+    let system_prompt = compose_system_prompt(
+        tool_selector.build_system_prompt(),
+        req.system_prompt_prefix.as_deref(),
+        req.system_prompt_override.as_deref(),
+    );
     let system_prompt_clone = system_prompt.clone();
 
     // Get conversation history from SQLite (only user/assistant messages)
     let messages = sqlite_memory
-        .get_messages(&conversation_id)
+        .get_recent_messages(&conversation_id, CONTEXT_HISTORY_MESSAGES)
         .await
-        .map_err(|e| {
-            actix_web::error::ErrorInternalServerError(format!(
-                "Failed to get conversation history: {}",
-                e
-            ))
-        })?;
+        .map_err(|e| ApiError::Internal(format!("Failed to get conversation history: {}", e)))?;
 
     // Always start with fresh system prompt
     let mut messages_with_system = vec![ChatMessage {
@@ -164,16 +248,25 @@ pub async fn agent_chat(
     };
     messages_with_system.push(user_message.clone());
 
-    // Store user message in SQLite
-    sqlite_memory
-        .add_message(&conversation_id, user_message)
-        .await
-        .map_err(|e| {
-            actix_web::error::ErrorInternalServerError(format!(
-                "Failed to store user message: {}",
-                e
-            ))
-        })?;
+    // Plan-only requests leave the conversation untouched
+    if !loop_config.plan_only {
+        // Store user message in SQLite
+        sqlite_memory
+            .add_message(&conversation_id, user_message)
+            .await
+            .map_err(|e| ApiError::Internal(format!("Failed to store user message: {}", e)))?;
+
+        // Give new conversations a readable title right away
+        if let Err(e) = sqlite_memory
+            .auto_title_from_first_message(&conversation_id)
+            .await
+        {
+            warn!(
+                "[{}] ⚠️ Failed to auto-title conversation: {}",
+                request_id, e
+            );
+        }
+    }
 
     let messages = messages_with_system;
 
@@ -190,14 +283,16 @@ pub async fn agent_chat(
         .await
         .unwrap_or(0);
 
-    println!(
-        "📊 Conversation history: {} messages, Tools available: {}",
+    info!(
+        "[{}] 📊 Conversation history: {} messages, Tools available: {}",
+        request_id,
         conversation_msg_count,
         tools.len()
     );
     if !tools.is_empty() {
-        println!(
-            "🔧 Available tools: {:?}",
+        debug!(
+            "[{}] 🔧 Available tools: {:?}",
+            request_id,
             tools.iter().map(|t| &t.function.name).collect::<Vec<_>>()
         );
     }
@@ -206,10 +301,7 @@ pub async fn agent_chat(
     let last_message_id_before_loop = sqlite_memory.get_last_message_id().await.unwrap_or(0);
 
     // Execute agent loop - allows iterative tool use
-    let loop_config = AgentLoopConfig {
-        debug_logging: config.debug_logging,
-        ..AgentLoopConfig::default()
-    };
+    let loop_started = std::time::Instant::now();
     let mut loop_result = execute_agent_loop(
         &client,
         &llama_url,
@@ -219,40 +311,63 @@ pub async fn agent_chat(
         tool_registry_arc.clone(),
         Arc::clone(&sqlite_memory),
         conversation_id.clone(),
-        loop_config,
+        loop_config.clone(),
     )
     .await
     .map_err(|e| {
-        println!("Agent loop error: {}", e);
-        actix_web::error::ErrorInternalServerError(format!("Agent loop failed: {}", e))
+        error!("[{}] Agent loop error: {}", request_id, e);
+        ApiError::Internal(format!("Agent loop failed: {}", e))
     })?;
 
+    if loop_result.timed_out {
+        warn!(
+            "[{}] ⏱️ Agent loop timed out after {}s",
+            request_id, loop_config.agent_timeout_secs
+        );
+    }
+
+    if loop_config.plan_only {
+        return Ok(HttpResponse::Ok()
+            .insert_header((REQUEST_ID_HEADER, request_id))
+            .json(AgentChatResponse {
+                success: true,
+                message: clean_response(&loop_result.final_message),
+                conversation_id: Some(conversation_id),
+                tool_calls: None,
+                tool_traces: None,
+                planned_tool_calls: Some(loop_result.planned_tool_calls),
+            }));
+    }
+
     // If agent got stuck, recover by restarting with clean context
     if loop_result.stuck {
-        println!("🔄 Agent got stuck, attempting rollback and clean context recovery...");
+        warn!(
+            "[{}] 🔄 Agent got stuck, attempting rollback and clean context recovery...",
+            request_id
+        );
 
         // Rollback: delete any messages created during the stuck loop
         if let Err(e) = sqlite_memory
             .delete_messages_after_id(last_message_id_before_loop)
             .await
         {
-            println!("⚠️ Failed to rollback messages after stuck loop: {}", e);
+            error!(
+                "[{}] ⚠️ Failed to rollback messages after stuck loop: {}",
+                request_id, e
+            );
         } else {
-            println!(
-                "✅ Rolled back messages to ID {}",
-                last_message_id_before_loop
+            info!(
+                "[{}] ✅ Rolled back messages to ID {}",
+                request_id, last_message_id_before_loop
             );
         }
 
         // Get clean conversation history from SQLite (only user/assistant messages)
         let clean_messages = sqlite_memory
-            .get_messages(&conversation_id)
+            .get_recent_messages(&conversation_id, CONTEXT_HISTORY_MESSAGES)
             .await
             .map_err(|e| {
-                actix_web::error::ErrorInternalServerError(format!(
-                    "Failed to get clean conversation history: {}",
-                    e
-                ))
+                ApiError::Internal(format!("Failed to get clean conversation history: {}", e))
             })?;
 
         // Build fresh context with system prompt + conversation history
@@ -268,9 +383,13 @@ pub async fn agent_chat(
 
         // Try again with clean context and reduced max iterations
         let recovery_config = AgentLoopConfig {
-            max_iterations: 5, // Reduced for recovery attempt
-            debug_logging: config.debug_logging,
-            ..Default::default()
+            max_iterations: loop_config.max_iterations.min(5), // Reduced for recovery attempt
+            // The time limit covers the whole request, so recovery only gets what is left
+            agent_timeout_secs: match loop_config.agent_timeout_secs {
+                0 => 0,
+                secs => secs.saturating_sub(loop_started.elapsed().as_secs()).max(1),
+            },
+            ..loop_config
         };
 
         loop_result = execute_agent_loop(
@@ -286,12 +405,21 @@ pub async fn agent_chat(
         )
         .await
         .map_err(|e| {
-            println!("Recovery attempt failed: {}", e);
-            actix_web::error::ErrorInternalServerError(format!("Recovery failed: {}", e))
+            error!("[{}] Recovery attempt failed: {}", request_id, e);
+            ApiError::Internal(format!("Recovery failed: {}", e))
         })?;
 
         if loop_result.stuck {
-            println!("⚠️ Recovery attempt also got stuck, returning partial response");
+            warn!(
+                "[{}] ⚠️ Recovery attempt also got stuck, returning partial response",
+                request_id
+            );
+        }
+        if loop_result.timed_out {
+            warn!(
+                "[{}] ⏱️ Recovery attempt ran out of time, returning timeout message",
+                request_id
+            );
         }
     }
 
@@ -307,26 +435,26 @@ pub async fn agent_chat(
     // If conversation has more than 100 messages, clear old messages to prevent bloat
     // Keep the most recent 20 messages for context continuity
     if msg_count > 100 {
-        println!(
-            "🧹 Conversation {} has {} messages, clearing old messages (keeping last 20)",
-            conversation_id, msg_count
+        info!(
+            "[{}] 🧹 Conversation {} has {} messages, clearing old messages (keeping last 20)",
+            request_id, conversation_id, msg_count
         );
         if let Err(e) = sqlite_memory
             .clear_conversation(&conversation_id, Some(20))
             .await
         {
-            println!("⚠️ Failed to clear old messages: {}", e);
+            warn!("[{}] ⚠️ Failed to clear old messages: {}", request_id, e);
         } else {
-            println!(
-                "✅ Cleared old messages from conversation {} (kept last 20)",
-                conversation_id
+            info!(
+                "[{}] ✅ Cleared old messages from conversation {} (kept last 20)",
+                request_id, conversation_id
             );
         }
     }
 
-    println!(
-        "✅ Agent loop completed after {} iterations",
-        loop_result.iterations
+    info!(
+        "[{}] ✅ Agent loop completed after {} iterations",
+        request_id, loop_result.iterations
     );
 
     let sqlite_memory_clone = sqlite_memory.get_ref().clone();
@@ -347,16 +475,24 @@ pub async fn agent_chat(
         .await;
     });
 
-    Ok(HttpResponse::Ok().json(AgentChatResponse {
-        success: true,
-        message: final_message,
-        conversation_id: Some(conversation_id),
-        tool_calls: if loop_result.tool_calls.is_empty() {
-            None
-        } else {
-            Some(loop_result.tool_calls)
-        },
-    }))
+    Ok(HttpResponse::Ok()
+        .insert_header((REQUEST_ID_HEADER, request_id))
+        .json(AgentChatResponse {
+            success: true,
+            message: final_message,
+            conversation_id: Some(conversation_id),
+            tool_calls: if loop_result.tool_calls.is_empty() {
+                None
+            } else {
+                Some(loop_result.tool_calls)
+            },
+            tool_traces: if query.verbose {
+                Some(loop_result.tool_traces)
+            } else {
+                None
+            },
+            planned_tool_calls: None,
+        }))
 }
 
 /// Streaming chat completion endpoint using Server-Sent Events (SSE)
@@ -372,8 +508,25 @@ pub async fn agent_chat_stream(
     sqlite_memory: web::Data<Arc<SqliteConversationMemory>>,
     agent_ws_state: web::Data<Arc<AgentWebSocketState>>,
     active_generations: web::Data<ActiveGenerations>,
+    tool_cache: web::Data<Arc<ToolResultCache>>,
+    model_notes: web::Data<Arc<ModelNotesStorage>>,
 ) -> ActixResult<HttpResponse> {
-    let config = agent_config.lock().unwrap().clone();
+    let request_id = new_request_id();
+    metrics::record(|m| m.agent_requests.with_label_values(&["chat_stream"]).inc());
+
+    if req.plan_only {
+        return Err(ApiError::BadRequest(
+            "plan_only is only supported by /api/agent/chat".to_string(),
+        )
+        .into());
+    }
+
+    // Server config narrowed to any per-request tool subset
+    let config = agent_config
+        .lock()
+        .unwrap()
+        .scoped_to(req.enabled_tools.as_deref())
+        .map_err(|e| ApiError::BadRequest(e.to_string()))?;
 
     // Get model name from llama_server config
     let model_name = {
@@ -381,34 +534,40 @@ pub async fn agent_chat_stream(
         llama_config_guard.hf_model.clone()
     };
 
-    // Construct Llama URL from config
-    let (llama_host, llama_port) = {
-        let llama_config_guard = llama_config.lock().unwrap();
-        (
-            llama_config_guard
-                .host
-                .clone()
-                .unwrap_or_else(|| "localhost".to_string()),
-            llama_config_guard.port.unwrap_or(8090),
-        )
-    };
-    let host_for_url = if llama_host == "0.0.0.0" {
-        "127.0.0.1".to_string()
-    } else {
-        llama_host
-    };
-    let llama_base_url = format!("http://{}:{}", host_for_url, llama_port);
+    // Build loop config from agent config, the model's saved generation params
+    // and any per-request overrides (in increasing precedence)
+    let model_defaults = model_loop_defaults(&model_notes, &model_name, &request_id).await;
+    let loop_config = AgentLoopConfig {
+        debug_logging: config.debug_logging,
+        tool_timeout_secs: config.tool_timeout_secs,
+        persist_tool_messages: config.persist_tool_messages,
+        supports_tool_role: config
+            .llm_supports_tool_role
+            .unwrap_or_else(|| model_supports_tool_role(&model_name)),
+        supports_vision: config
+            .llm_supports_vision
+            .unwrap_or_else(|| model_supports_vision(&model_name)),
+        request_id: request_id.clone(),
+        context_token_budget: config.context_token_budget,
+        agent_timeout_secs: config.agent_timeout_secs,
+        stream_flush_ms: config.stream_flush_ms,
+        stream_flush_chars: config.stream_flush_chars,
+        ..model_defaults
+    }
+    .with_overrides(req.max_iterations, req.temperature, req.max_tokens)
+    .and_then(|c| c.with_sampling(req.top_p, req.frequency_penalty, req.presence_penalty))
+    .and_then(|c| c.with_stop(req.stop.clone()))
+    .map_err(|e| ApiError::BadRequest(e.to_string()))?;
+    check_image_parts(&req.message, loop_config.supports_vision).map_err(ApiError::BadRequest)?;
+
+    // Construct Llama URL from config (or LLM_BASE_URL)
+    let llama_base_url = llama_config.lock().unwrap().llm_base_url();
 
     // Get or create conversation ID
     let conversation_id = sqlite_memory
         .get_or_create_conversation_id(req.conversation_id.clone(), Some(&model_name))
         .await
-        .map_err(|e| {
-            actix_web::error::ErrorInternalServerError(format!(
-                "Failed to get conversation ID: {}",
-                e
-            ))
-        })?;
+        .map_err(|e| ApiError::Internal(format!("Failed to get conversation ID: {}", e)))?;
 
     // Build tool registry (same as non-streaming endpoint)
     let mut tool_registry = ToolRegistry::new();
@@ -416,30 +575,29 @@ pub async fn agent_chat_stream(
     // Register all enabled tools
     let context = tools::RegisterContext {
         chroma_address: Some(chroma_address.as_str()),
+        model_notes: Some(Arc::clone(model_notes.get_ref())),
     };
     tools::register_all(&mut tool_registry, &config, &context);
+    tool_registry.set_cache(Arc::clone(tool_cache.get_ref()), config.cache_ttl_secs);
+    tool_registry.set_concurrency_limits(&config.tool_concurrency_limits);
 
     let tool_registry_arc = Arc::new(tool_registry);
-    let tools = tool_registry_arc.build_tool_definitions().map_err(|e| {
-        actix_web::error::ErrorInternalServerError(format!(
-            "Failed to build tool definitions: {}",
-            e
-        ))
-    })?;
+    let tools = tool_registry_arc
+        .build_tool_definitions()
+        .map_err(|e| ApiError::Internal(format!("Failed to build tool definitions: {}", e)))?;
 
     let tool_selector = ToolSelector::new(Arc::clone(&tool_registry_arc));
-    let system_prompt = tool_selector.build_system_prompt();
+    let system_prompt = compose_system_prompt(
+        tool_selector.build_system_prompt(),
+        req.system_prompt_prefix.as_deref(),
+        req.system_prompt_override.as_deref(),
+    );
 
     // Get conversation history
     let messages = sqlite_memory
-        .get_messages(&conversation_id)
+        .get_recent_messages(&conversation_id, CONTEXT_HISTORY_MESSAGES)
         .await
-        .map_err(|e| {
-            actix_web::error::ErrorInternalServerError(format!(
-                "Failed to get conversation history: {}",
-                e
-            ))
-        })?;
+        .map_err(|e| ApiError::Internal(format!("Failed to get conversation history: {}", e)))?;
 
     let mut messages_with_system = vec![ChatMessage {
         role: MessageRole::System,
@@ -466,12 +624,18 @@ pub async fn agent_chat_stream(
     sqlite_memory
         .add_message(&conversation_id, user_message)
         .await
-        .map_err(|e| {
-            actix_web::error::ErrorInternalServerError(format!(
-                "Failed to store user message: {}",
-                e
-            ))
-        })?;
+        .map_err(|e| ApiError::Internal(format!("Failed to store user message: {}", e)))?;
+
+    // Give new conversations a readable title right away
+    if let Err(e) = sqlite_memory
+        .auto_title_from_first_message(&conversation_id)
+        .await
+    {
+        warn!(
+            "[{}] ⚠️ Failed to auto-title conversation: {}",
+            request_id, e
+        );
+    }
 
     // model_name is already retrieved above
 
@@ -484,10 +648,10 @@ pub async fn agent_chat_stream(
     // Create Cancellation Token using Watch Channel
     let (cancel_tx, cancel_rx) = tokio::sync::watch::channel(false);
 
-    // Register cancellation token
+    // Register cancellation token so POST /api/agent/cancel/{id} can stop this run
     {
         let mut map = active_generations.lock().unwrap();
-        map.insert(conversation_id.clone(), cancel_tx);
+        map.insert(conversation_id.clone(), cancel_tx.clone());
     }
 
     // Clone necessary data for the streaming task
@@ -499,45 +663,49 @@ pub async fn agent_chat_stream(
     let sqlite_memory_clone = sqlite_memory.get_ref().clone();
     let conversation_id_clone = conversation_id.clone();
     let agent_ws_state_clone = agent_ws_state.get_ref().clone();
-    let loop_config = AgentLoopConfig {
-        debug_logging: config.debug_logging,
-        ..AgentLoopConfig::default()
-    };
     let active_generations_clone = active_generations.get_ref().clone();
+    let request_id_clone = request_id.clone();
 
     // Spawn the agent loop in a background task
     actix_rt::spawn(async move {
-        // Create a wrapper sender that broadcasts to both SSE and WebSocket (Bounded)
-        let tx_sse = tx.clone();
+        // Create a wrapper sender that broadcasts to both SSE and WebSocket (Bounded).
+        // The duplicating task owns the only SSE sender, so the SSE stream ends with it.
+        let mut tx_sse = Some(tx);
         let agent_ws_broadcast = agent_ws_state_clone.clone();
+        let ws_conversation_id = conversation_id_clone.clone();
         let (tx_wrapper, mut rx_wrapper) =
             mpsc::channel::<Result<AgentStreamEvent, anyhow::Error>>(100);
 
-        // Spawn task to duplicate events to both SSE and WebSocket
+        // Spawn task to duplicate events to both SSE and WebSocket.
+        // A slow SSE client must not stall the agent loop: status/reasoning events
+        // are dropped when its channel is full, and if answer text or tool events
+        // still can't get through within SLOW_CLIENT_TIMEOUT the SSE client is
+        // disconnected. The run carries on for WebSocket clients and memory.
+        let sse_request_id = request_id_clone.clone();
         actix_rt::spawn(async move {
             while let Some(event_result) = rx_wrapper.recv().await {
                 // Broadcast to WebSocket first (if successful)
                 if let Ok(event) = &event_result {
-                    agent_ws_broadcast.broadcast(event);
+                    agent_ws_broadcast.broadcast(&ws_conversation_id, event);
                 }
-                // Send to SSE (need to handle error case)
-                // This await will block if SSE client is slow, or fail if disconnected
-                match &event_result {
-                    Ok(event) => {
-                        if tx_sse.send(Ok(event.clone())).await.is_err() {
-                            // Client disconnected logic handled by channel drop/backpressure usually,
-                            // but explicit cancel is triggered by API now.
-                            // If network disconnects, we could optionally trigger cancel too?
-                            break;
-                        }
-                    }
-                    Err(e) => {
-                        if tx_sse.send(Err(anyhow::anyhow!("{}", e))).await.is_err() {
-                            break;
-                        }
+                let Some(sse) = &tx_sse else {
+                    continue;
+                };
+                match send_with_backpressure(sse, event_result, SLOW_CLIENT_TIMEOUT).await {
+                    SendOutcome::Sent | SendOutcome::Dropped => {}
+                    SendOutcome::TimedOut => {
+                        warn!(
+                            "[{}] 🐢 SSE client too slow, disconnecting it",
+                            sse_request_id
+                        );
+                        tx_sse = None;
                     }
+                    // Client went away: stop the run, as the agent loop sees the closed channel
+                    SendOutcome::Closed => break,
                 }
             }
+            // Generation is over (or cancelled), there is nothing left to resume
+            agent_ws_broadcast.clear_buffer(&ws_conversation_id);
         });
 
         // Execute streaming loop with cancellation support
@@ -557,24 +725,29 @@ pub async fn agent_chat_stream(
         .await;
 
         if let Err(e) = result {
-            println!("Streaming agent loop error: {}", e);
+            error!("[{}] Streaming agent loop error: {}", request_id_clone, e);
         }
 
-        // Cleanup cancellation token
+        // Cleanup cancellation token, unless a newer generation for the same
+        // conversation has already replaced it
         {
             let mut map = active_generations_clone.lock().unwrap();
-            map.remove(&conversation_id_clone);
+            if map
+                .get(&conversation_id_clone)
+                .is_some_and(|tx| tx.same_channel(&cancel_tx))
+            {
+                map.remove(&conversation_id_clone);
+            }
         }
 
-        // Attempt naming after stream finishes
-        attempt_conversation_naming(
+        // Name the conversation in the background once the exchange is done
+        actix_rt::spawn(attempt_conversation_naming(
             client_clone,
             llama_url_clone,
             model_name_clone,
             sqlite_memory_clone,
             conversation_id_clone,
-        )
-        .await;
+        ));
     });
 
     // Convert events to SSE format
@@ -599,31 +772,169 @@ pub async fn agent_chat_stream(
 
     Ok(HttpResponse::Ok()
         .content_type("text/event-stream")
+        .insert_header((REQUEST_ID_HEADER, request_id))
         .append_header(("Cache-Control", "no-cache"))
         .append_header(("Connection", "keep-alive"))
         .streaming(stream))
 }
 
+/// Reject image parts the model can't use: unsupported URL schemes, or any
+/// image when the model has no vision support
+fn check_image_parts(message: &MessageContent, supports_vision: bool) -> Result<(), String> {
+    let MessageContent::Parts(parts) = message else {
+        return Ok(());
+    };
+    for part in parts {
+        if let ContentPart::ImageUrl { image_url } = part {
+            if !supports_vision {
+                return Err(
+                    "The current model does not accept images (set llm_supports_vision to override)"
+                        .to_string(),
+                );
+            }
+            if !image_url.is_supported() {
+                return Err(
+                    "Images must be http(s) URLs or base64 data:image/...;base64, URLs".to_string(),
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Signal cancellation for an in-flight streaming generation.
+/// Returns whether a generation was registered for the conversation.
+/// Conversation the chat runs in. Plan-only requests never create one: they read
+/// the requested conversation's history as-is, or run against a transient id
+async fn resolve_conversation_id(
+    sqlite_memory: &SqliteConversationMemory,
+    requested: Option<String>,
+    model_name: &str,
+    plan_only: bool,
+) -> anyhow::Result<String> {
+    if plan_only {
+        return Ok(requested.unwrap_or_else(|| uuid::Uuid::new_v4().to_string()));
+    }
+    sqlite_memory
+        .get_or_create_conversation_id(requested, Some(model_name))
+        .await
+}
+
+fn signal_cancellation(active_generations: &ActiveGenerations, conversation_id: &str) -> bool {
+    let map = active_generations.lock().unwrap();
+    match map.get(conversation_id) {
+        Some(tx) => {
+            let _ = tx.send(true); // Send cancellation signal
+            true
+        }
+        None => false,
+    }
+}
+
+/// Stop an in-flight generation for a conversation ("stop generating").
+///
+/// Works for SSE streams too, where the client can't signal the server
+/// other than by dropping the connection.
+#[post("/api/agent/cancel/{conversation_id}")]
+pub async fn cancel_generation(
+    path: web::Path<String>,
+    active_generations: web::Data<ActiveGenerations>,
+) -> impl Responder {
+    cancel_response(&active_generations, &path.into_inner())
+}
+
+/// Legacy alias of `POST /api/agent/cancel/{conversation_id}`
 #[post("/api/agent/chat/{conversation_id}/cancel")]
 pub async fn cancel_agent_generation(
     path: web::Path<String>,
     active_generations: web::Data<ActiveGenerations>,
 ) -> impl Responder {
-    let conversation_id = path.into_inner();
-    println!(
+    cancel_response(&active_generations, &path.into_inner())
+}
+
+fn cancel_response(active_generations: &ActiveGenerations, conversation_id: &str) -> HttpResponse {
+    info!(
         "Received cancellation request for conversation {}",
         conversation_id
     );
 
-    let map = active_generations.lock().unwrap();
-    if let Some(tx) = map.get(&conversation_id) {
-        let _ = tx.send(true); // Send cancellation signal
+    if signal_cancellation(active_generations, conversation_id) {
         HttpResponse::Ok().json(serde_json::json!({"status": "cancelled"}))
     } else {
-        println!(
+        warn!(
             "No active generation found for conversation {}",
             conversation_id
         );
-        HttpResponse::NotFound().json(serde_json::json!({"error": "No active generation found"}))
+        ApiError::NotFound("No active generation found".to_string()).error_response()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::database::connect_sqlite;
+
+    #[tokio::test]
+    async fn test_plan_only_does_not_create_conversation() {
+        let db_path =
+            std::env::temp_dir().join(format!("ai_tools_chat_test_{}.db", uuid::Uuid::new_v4()));
+        let memory = SqliteConversationMemory::new(connect_sqlite(&db_path).await.unwrap())
+            .await
+            .unwrap();
+
+        let id = resolve_conversation_id(&memory, None, "test-model", true)
+            .await
+            .unwrap();
+        assert!(!id.is_empty());
+        assert!(memory.list_conversations().await.unwrap().is_empty());
+
+        resolve_conversation_id(&memory, Some("missing".to_string()), "test-model", true)
+            .await
+            .unwrap();
+        assert!(memory.list_conversations().await.unwrap().is_empty());
+
+        resolve_conversation_id(&memory, None, "test-model", false)
+            .await
+            .unwrap();
+        assert_eq!(memory.list_conversations().await.unwrap().len(), 1);
+
+        let _ = std::fs::remove_file(&db_path);
+    }
+
+    #[test]
+    fn test_signal_cancellation() {
+        let active_generations: ActiveGenerations = Default::default();
+        assert!(!signal_cancellation(&active_generations, "conv-1"));
+
+        let (tx, rx) = tokio::sync::watch::channel(false);
+        active_generations
+            .lock()
+            .unwrap()
+            .insert("conv-1".to_string(), tx);
+
+        assert!(signal_cancellation(&active_generations, "conv-1"));
+        assert!(*rx.borrow());
+    }
+
+    #[test]
+    fn test_check_image_parts() {
+        let request: AgentChatRequest = serde_json::from_value(serde_json::json!({
+            "message": [
+                {"type": "text", "text": "Describe this"},
+                {"type": "image_url", "image_url": {"url": "data:image/jpeg;base64,/9j/4AAQ"}}
+            ]
+        }))
+        .unwrap();
+        assert!(check_image_parts(&request.message, true).is_ok());
+        assert!(check_image_parts(&request.message, false).is_err());
+
+        let local_file = MessageContent::Parts(vec![ContentPart::ImageUrl {
+            image_url: crate::api::agent::core::types::ImageUrl {
+                url: "file:///etc/passwd".to_string(),
+                detail: None,
+            },
+        }]);
+        assert!(check_image_parts(&local_file, true).is_err());
+        assert!(check_image_parts(&MessageContent::Text("hi".to_string()), false).is_ok());
     }
 }
@@ -1,7 +1,10 @@
 use crate::api::agent::core::types::{
-    AgentConfig, AgentConfigRequest, AgentConfigResponse, AgentStatusResponse, ModelCapabilities,
-    ModelPropsResponse, ToolType,
+    AgentConfig, AgentConfigRequest, AgentConfigResponse, AgentStatusResponse, EnabledToolsRequest,
+    ModelCapabilities, ModelPropsResponse, ToolType,
 };
+use crate::api::error::ApiError;
+use crate::api::llama_server::types::Config;
+use crate::api::model_notes::ModelNotesStorage;
 use actix_web::{get, post, web, HttpResponse, Result as ActixResult};
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
@@ -40,17 +43,35 @@ pub async fn post_agent_config(
     let mut config_guard = agent_config.lock().unwrap();
 
     // Validate ChromaDB config if provided
-    if req.chromadb.is_some() {
-        let chromadb_config = req.chromadb.as_ref().unwrap();
+    if let Some(chromadb_config) = req.chromadb.as_ref() {
         if chromadb_config.collection.trim().is_empty()
             || chromadb_config.embedding_model.trim().is_empty()
         {
-            return Ok(HttpResponse::BadRequest().json(AgentConfigResponse {
-                success: false,
-                message: "ChromaDB configuration requires both collection and embedding_model"
-                    .to_string(),
-            }));
+            return Err(ApiError::BadRequest(
+                "ChromaDB configuration requires both collection and embedding_model".to_string(),
+            )
+            .into());
         }
+        if !(0.0..=1.0).contains(&chromadb_config.min_similarity) {
+            return Err(ApiError::BadRequest(
+                "ChromaDB min_similarity must be between 0 and 1".to_string(),
+            )
+            .into());
+        }
+    }
+
+    // Nothing would ever fill a zero-character buffer
+    if req.stream_flush_chars == Some(0) {
+        return Err(
+            ApiError::BadRequest("stream_flush_chars must be greater than 0".to_string()).into(),
+        );
+    }
+
+    // A zero timeout would make every tool request fail immediately
+    if req.tool_timeout_secs == Some(0) {
+        return Err(
+            ApiError::BadRequest("tool_timeout_secs must be greater than 0".to_string()).into(),
+        );
     }
 
     // Remove ChromaDB from enabled_tools if present (it's now a separate config section)
@@ -66,6 +87,59 @@ pub async fn post_agent_config(
         config_guard.debug_logging = debug_logging;
     }
 
+    // Update tool retry count if provided
+    if let Some(tool_max_retries) = req.tool_max_retries {
+        config_guard.tool_max_retries = tool_max_retries;
+    }
+
+    // Update tool timeout if provided
+    if let Some(tool_timeout_secs) = req.tool_timeout_secs {
+        config_guard.tool_timeout_secs = tool_timeout_secs;
+    }
+
+    // Update tool result cache TTL if provided
+    if let Some(cache_ttl_secs) = req.cache_ttl_secs {
+        config_guard.cache_ttl_secs = cache_ttl_secs;
+    }
+
+    // Update tool message persistence if provided
+    if let Some(persist_tool_messages) = req.persist_tool_messages {
+        config_guard.persist_tool_messages = persist_tool_messages;
+    }
+
+    // Update tool role support if provided
+    if let Some(llm_supports_tool_role) = req.llm_supports_tool_role {
+        config_guard.llm_supports_tool_role = Some(llm_supports_tool_role);
+    }
+
+    // Update vision support if provided
+    if let Some(llm_supports_vision) = req.llm_supports_vision {
+        config_guard.llm_supports_vision = Some(llm_supports_vision);
+    }
+
+    // Update prompt token budget if provided
+    if let Some(context_token_budget) = req.context_token_budget {
+        config_guard.context_token_budget = context_token_budget;
+    }
+
+    // Update streamed text coalescing if provided
+    if let Some(stream_flush_ms) = req.stream_flush_ms {
+        config_guard.stream_flush_ms = stream_flush_ms;
+    }
+    if let Some(stream_flush_chars) = req.stream_flush_chars {
+        config_guard.stream_flush_chars = stream_flush_chars;
+    }
+
+    // Replace per-tool concurrency limits if provided
+    if let Some(tool_concurrency_limits) = req.tool_concurrency_limits.clone() {
+        config_guard.tool_concurrency_limits = tool_concurrency_limits;
+    }
+
+    // Update agent request time limit if provided
+    if let Some(agent_timeout_secs) = req.agent_timeout_secs {
+        config_guard.agent_timeout_secs = agent_timeout_secs;
+    }
+
     println!("✅ Agent configuration updated: {:?}", config_guard);
 
     Ok(HttpResponse::Ok().json(AgentConfigResponse {
@@ -74,7 +148,7 @@ pub async fn post_agent_config(
     }))
 }
 
-use crate::api::agent::tools::framework::agent_tool::ToolCategory;
+use crate::api::agent::tools::framework::agent_tool::{AgentTool, ToolCategory};
 
 /// Tool metadata for API responses
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -85,78 +159,224 @@ pub struct ToolInfo {
     pub description: String,
     pub category: ToolCategory,
     pub icon: String, // Material Icon name
+    /// The OpenAI-compatible function definition sent to the model (name, description, parameters)
+    pub function_definition: serde_json::Value,
+}
+
+impl ToolInfo {
+    fn from_tool(tool: &dyn AgentTool) -> Self {
+        let meta = tool.metadata();
+        ToolInfo {
+            id: meta.id.clone(),
+            name: meta.name.clone(),
+            tool_type: meta.tool_type.clone(),
+            description: meta.description.clone(),
+            category: meta.category,
+            icon: meta.category.icon_name().to_string(),
+            function_definition: tool.get_function_definition(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ToolsQuery {
+    /// Only list the tools enabled in the current agent configuration
+    #[serde(default)]
+    pub enabled_only: bool,
 }
 
 /// Get list of all available tools
-/// This returns all tools that are properly configured and available for use
+/// This returns all tools that are properly configured and available for use,
+/// or with `?enabled_only=true` just the ones the agent currently uses
 #[get("/api/agent/tools")]
-pub async fn get_available_tools() -> ActixResult<HttpResponse> {
-    use crate::api::agent::core::types::{ChromaDBToolConfig, ToolType};
+pub async fn get_available_tools(
+    query: web::Query<ToolsQuery>,
+    agent_config: web::Data<AgentConfigHandle>,
+    chroma_address: Option<web::Data<String>>,
+    model_notes: web::Data<Arc<ModelNotesStorage>>,
+) -> ActixResult<HttpResponse> {
+    use crate::api::agent::core::types::{ChromaDBToolConfig, ToolType, DEFAULT_MIN_SIMILARITY};
     use crate::api::agent::tools::{self, framework::registry::ToolRegistry};
+    use crate::api::chromadb::client::EmbeddingBackendKind;
 
-    // Create a temporary registry to discover all available tools
     let mut tool_registry = ToolRegistry::new();
 
-    // Create a configuration that enables ALL known tools
-    // We want to list everything that is available on the system
-    let all_tools_config = AgentConfig {
-        enabled_tools: vec![
-            ToolType::ChromaDB,
-            ToolType::WebsiteCheck,
-            ToolType::Weather,
-            ToolType::Currency,
-            ToolType::Stock,
-            ToolType::GitHubPublic,
-            ToolType::GitHubAuthenticated,
-            ToolType::Crypto,
-        ],
-        // Provide dummy config for ChromaDB so it attempts registration
-        // It will only succeed if the code handles it, but connection check might fail it effectively.
-        chromadb: Some(ChromaDBToolConfig {
-            collection: "metadata_check".to_string(),
-            embedding_model: "metadata_check".to_string(),
-        }),
-        debug_logging: false,
-    };
+    if query.enabled_only {
+        // Same registration the chat handlers do, so this matches what the model sees
+        let config = agent_config.lock().unwrap().clone();
+        let context = tools::RegisterContext {
+            chroma_address: chroma_address.as_ref().map(|a| a.as_str()),
+            model_notes: Some(Arc::clone(model_notes.get_ref())),
+        };
+        tools::register_all(&mut tool_registry, &config, &context);
+    } else {
+        // Create a configuration that enables ALL known tools
+        // We want to list everything that is available on the system
+        let all_tools_config = AgentConfig {
+            enabled_tools: vec![
+                ToolType::ChromaDB,
+                ToolType::WebsiteCheck,
+                ToolType::Weather,
+                ToolType::Currency,
+                ToolType::Stock,
+                ToolType::GitHubPublic,
+                ToolType::GitHubAuthenticated,
+                ToolType::Crypto,
+                ToolType::ModelNotes,
+            ],
+            // Provide dummy config for ChromaDB so it attempts registration
+            // It will only succeed if the code handles it, but connection check might fail it effectively.
+            chromadb: Some(ChromaDBToolConfig {
+                collection: "metadata_check".to_string(),
+                embedding_model: "metadata_check".to_string(),
+                embedding_backend: EmbeddingBackendKind::Ollama,
+                min_similarity: DEFAULT_MIN_SIMILARITY,
+            }),
+            ..AgentConfig::default()
+        };
 
-    // Context with dummy value for ChromaDB address
-    // This allows ChromaDB tool to attempt registration (it might fail if it checks connection)
-    let context = tools::RegisterContext {
-        chroma_address: Some("http://localhost:8000"),
-    };
+        // Context with dummy value for ChromaDB address
+        // This allows ChromaDB tool to attempt registration (it might fail if it checks connection)
+        let context = tools::RegisterContext {
+            chroma_address: Some("http://localhost:8000"),
+            model_notes: Some(Arc::clone(model_notes.get_ref())),
+        };
 
-    // Register all tools
-    // Note: This will only register tools that return true for is_available()
-    // e.g., Weather tool will only appear if API key is set
-    tools::register_all(&mut tool_registry, &all_tools_config, &context);
+        // Register all tools
+        // Note: This will only register tools that return true for is_available()
+        // e.g., Weather tool will only appear if API key is set
+        tools::register_all(&mut tool_registry, &all_tools_config, &context);
+    }
 
     // Extract metadata from registered tools
-    let tools_info: Vec<ToolInfo> = tool_registry
+    let mut tools_info: Vec<ToolInfo> = tool_registry
         .get_all_tools()
         .iter()
-        .map(|tool| {
-            let meta = tool.metadata();
-            ToolInfo {
-                id: meta.id.clone(),
-                name: meta.name.clone(),
-                tool_type: meta.tool_type.clone(),
-                description: meta.description.clone(),
-                category: meta.category,
-                icon: meta.category.icon_name().to_string(),
-            }
-        })
+        .map(|tool| ToolInfo::from_tool(tool.as_ref()))
         .collect();
+    tools_info.sort_by(|a, b| a.id.cmp(&b.id));
 
     Ok(HttpResponse::Ok().json(tools_info))
 }
 
+/// Registered tools of one category
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ToolCategoryGroup {
+    pub category: ToolCategory,
+    pub icon: String,
+    pub tools: Vec<ToolInfo>,
+}
+
+/// Response for `POST /api/agent/config/tools`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EnabledToolsResponse {
+    pub success: bool,
+    pub message: String,
+    pub enabled_tools: Vec<ToolType>,
+    /// Enabled tools that are usable right now, grouped for display
+    pub categories: Vec<ToolCategoryGroup>,
+    /// Enabled tools that were skipped as unavailable (e.g. missing API key)
+    pub unavailable: Vec<ToolInfo>,
+}
+
+/// Parse tool names into `ToolType`s, returning the unknown names on failure
+fn parse_tool_types(names: &[String]) -> Result<Vec<ToolType>, Vec<String>> {
+    let mut tool_types = Vec::new();
+    let mut unknown = Vec::new();
+    for name in names {
+        match serde_json::from_value::<ToolType>(serde_json::Value::String(name.clone())) {
+            Ok(tool_type) if !tool_types.contains(&tool_type) => tool_types.push(tool_type),
+            Ok(_) => {}
+            Err(_) => unknown.push(name.clone()),
+        }
+    }
+    if unknown.is_empty() {
+        Ok(tool_types)
+    } else {
+        Err(unknown)
+    }
+}
+
+/// Group tools by category, in the order categories first appear
+fn group_by_category(tools: Vec<ToolInfo>) -> Vec<ToolCategoryGroup> {
+    let mut groups: Vec<ToolCategoryGroup> = Vec::new();
+    for tool in tools {
+        match groups.iter_mut().find(|g| g.category == tool.category) {
+            Some(group) => group.tools.push(tool),
+            None => groups.push(ToolCategoryGroup {
+                category: tool.category,
+                icon: tool.icon.clone(),
+                tools: vec![tool],
+            }),
+        }
+    }
+    groups
+}
+
+/// Replace the enabled tools at runtime.
+/// Takes effect on the next chat request, since the registry is rebuilt per request.
+#[post("/api/agent/config/tools")]
+pub async fn post_enabled_tools(
+    req: web::Json<EnabledToolsRequest>,
+    agent_config: web::Data<AgentConfigHandle>,
+    chroma_address: Option<web::Data<String>>,
+    model_notes: Option<web::Data<Arc<ModelNotesStorage>>>,
+) -> ActixResult<HttpResponse> {
+    use crate::api::agent::tools::{self, framework::registry::ToolRegistry};
+
+    let mut enabled_tools = match parse_tool_types(&req.enabled_tools) {
+        Ok(tool_types) => tool_types,
+        Err(unknown) => {
+            return Err(
+                ApiError::BadRequest(format!("Unknown tool(s): {}", unknown.join(", "))).into(),
+            );
+        }
+    };
+    // ChromaDB is enabled through the chromadb config section, as in POST /api/agent/config
+    enabled_tools.retain(|t| *t != ToolType::ChromaDB);
+
+    let config = {
+        let mut config_guard = agent_config.lock().unwrap();
+        config_guard.enabled_tools = enabled_tools.clone();
+        config_guard.clone()
+    };
+    println!("✅ Agent enabled tools updated: {:?}", enabled_tools);
+
+    // Build the registry the next chat will get, to report what actually loads
+    let context = tools::RegisterContext {
+        chroma_address: chroma_address.as_ref().map(|a| a.as_str()),
+        model_notes: model_notes.as_ref().map(|m| Arc::clone(m.get_ref())),
+    };
+    let mut registry = ToolRegistry::new();
+    tools::register_all(&mut registry, &config, &context);
+
+    let to_infos = |tools: Vec<Arc<dyn AgentTool>>| {
+        let mut infos: Vec<ToolInfo> = tools
+            .iter()
+            .map(|tool| ToolInfo::from_tool(tool.as_ref()))
+            .collect();
+        infos.sort_by(|a, b| a.id.cmp(&b.id));
+        infos
+    };
+
+    Ok(HttpResponse::Ok().json(EnabledToolsResponse {
+        success: true,
+        message: "Enabled tools updated successfully".to_string(),
+        enabled_tools,
+        categories: group_by_category(to_infos(registry.get_all_tools())),
+        unavailable: to_infos(registry.get_unavailable_tools()),
+    }))
+}
+
 /// Get model capabilities from llama server /props endpoint
 #[get("/api/agent/model-capabilities")]
-pub async fn get_model_capabilities() -> ActixResult<HttpResponse> {
+pub async fn get_model_capabilities(
+    llama_config: web::Data<Arc<Mutex<Config>>>,
+) -> ActixResult<HttpResponse> {
     let client = Client::new();
-    let llama_url = "http://localhost:8080/props";
+    let llama_url = format!("{}/props", llama_config.lock().unwrap().llm_base_url());
 
-    match client.get(llama_url).send().await {
+    match client.get(&llama_url).send().await {
         Ok(response) => {
             if response.status().is_success() {
                 match response.json::<ModelPropsResponse>().await {
@@ -195,3 +415,101 @@ pub async fn get_model_capabilities() -> ActixResult<HttpResponse> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::error::ErrorEnvelope;
+    use crate::utils::database::connect_sqlite;
+    use actix_web::{test, App};
+
+    #[actix_web::test]
+    async fn test_post_enabled_tools_updates_config() {
+        let agent_config: AgentConfigHandle = Arc::new(Mutex::new(AgentConfig::default()));
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(agent_config.clone()))
+                .service(post_enabled_tools),
+        )
+        .await;
+
+        let req = test::TestRequest::post()
+            .uri("/api/agent/config/tools")
+            .set_json(serde_json::json!({
+                "enabled_tools": ["website_check", "git_hub_public", "website_check"]
+            }))
+            .to_request();
+        let resp: EnabledToolsResponse = test::call_and_read_body_json(&app, req).await;
+
+        assert!(resp.success);
+        assert_eq!(
+            resp.enabled_tools,
+            vec![ToolType::WebsiteCheck, ToolType::GitHubPublic]
+        );
+        assert_eq!(
+            agent_config.lock().unwrap().enabled_tools,
+            resp.enabled_tools
+        );
+        let categories: Vec<ToolCategory> = resp.categories.iter().map(|g| g.category).collect();
+        assert!(categories.contains(&ToolCategory::Web));
+        assert!(categories.contains(&ToolCategory::Development));
+    }
+
+    #[actix_web::test]
+    async fn test_post_enabled_tools_rejects_unknown_tools() {
+        let agent_config: AgentConfigHandle = Arc::new(Mutex::new(AgentConfig {
+            enabled_tools: vec![ToolType::Weather],
+            ..AgentConfig::default()
+        }));
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(agent_config.clone()))
+                .service(post_enabled_tools),
+        )
+        .await;
+
+        let req = test::TestRequest::post()
+            .uri("/api/agent/config/tools")
+            .set_json(serde_json::json!({"enabled_tools": ["weather", "teleport"]}))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 400);
+        let body: ErrorEnvelope = test::read_body_json(resp).await;
+        assert_eq!(body.error, "Unknown tool(s): teleport");
+        assert_eq!(body.code, "bad_request");
+
+        // The previous configuration is left untouched
+        assert_eq!(
+            agent_config.lock().unwrap().enabled_tools,
+            vec![ToolType::Weather]
+        );
+    }
+
+    #[actix_web::test]
+    async fn test_enabled_tools_include_function_definitions() {
+        let pool = connect_sqlite(":memory:").await.unwrap();
+        let model_notes = Arc::new(ModelNotesStorage::new(pool).await.unwrap());
+        let agent_config: AgentConfigHandle = Arc::new(Mutex::new(AgentConfig {
+            enabled_tools: vec![ToolType::ModelNotes],
+            ..AgentConfig::default()
+        }));
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(agent_config))
+                .app_data(web::Data::new(model_notes))
+                .service(get_available_tools),
+        )
+        .await;
+
+        let req = test::TestRequest::get()
+            .uri("/api/agent/tools?enabled_only=true")
+            .to_request();
+        let tools: Vec<ToolInfo> = test::call_and_read_body_json(&app, req).await;
+
+        assert_eq!(tools.len(), 1);
+        assert_eq!(tools[0].id, "model_notes");
+        assert_eq!(tools[0].category, ToolCategory::Database);
+        assert_eq!(tools[0].function_definition["name"], "search_model_notes");
+        assert!(tools[0].function_definition["parameters"]["properties"]["query"].is_object());
+    }
+}
@@ -1,19 +1,32 @@
-use crate::api::agent::core::types::UpdateConversationRequest;
+use crate::api::agent::core::types::{
+    ClearConversationsQuery, ConversationMessagesQuery, ExportQuery, SearchQuery,
+    UpdateConversationRequest,
+};
 use crate::api::agent::memory::sqlite_memory::SqliteConversationMemory;
-use actix_web::{delete, get, patch, web, HttpResponse, Result as ActixResult};
+use crate::api::agent::service::export::{export_filename, render_markdown, ExportFormat};
+use crate::api::error::ApiError;
+use crate::auth::api_key::keys_match;
+use actix_web::{
+    delete, get, http::header, patch, web, HttpRequest, HttpResponse, Result as ActixResult,
+};
 use std::sync::Arc;
 
-/// Get all conversations
+/// Maximum length of a user-provided conversation title
+const MAX_TITLE_CHARS: usize = 200;
+
+/// Default number of search results
+const DEFAULT_SEARCH_LIMIT: usize = 20;
+
+/// List all conversations, most recently active first
 #[get("/api/agent/conversations")]
 pub async fn get_conversations(
     sqlite_memory: web::Data<Arc<SqliteConversationMemory>>,
 ) -> ActixResult<HttpResponse> {
-    match sqlite_memory.get_conversations().await {
+    match sqlite_memory.list_conversations().await {
         Ok(conversations) => Ok(HttpResponse::Ok().json(conversations)),
         Err(e) => {
             println!("Failed to fetch conversations: {}", e);
-            Ok(HttpResponse::InternalServerError()
-                .body(format!("Failed to fetch conversations: {}", e)))
+            Err(ApiError::Internal(format!("Failed to fetch conversations: {}", e)).into())
         }
     }
 }
@@ -30,8 +43,62 @@ pub async fn delete_conversation(
         Ok(_) => Ok(HttpResponse::Ok().finish()),
         Err(e) => {
             println!("Failed to delete conversation {}: {}", conversation_id, e);
-            Ok(HttpResponse::InternalServerError()
-                .body(format!("Failed to delete conversation: {}", e)))
+            Err(ApiError::Internal(format!("Failed to delete conversation: {}", e)).into())
+        }
+    }
+}
+
+/// Environment variable holding an optional token required to wipe all conversations
+pub const ADMIN_TOKEN_ENV: &str = "AGENT_ADMIN_TOKEN";
+
+/// Header carrying the admin token
+const ADMIN_TOKEN_HEADER: &str = "X-Admin-Token";
+
+/// Check the safeguards for wiping all conversations, returning the
+/// rejection when they are not met
+fn authorize_clear_all(
+    confirm: bool,
+    admin_token: Option<&str>,
+    provided_token: Option<&str>,
+) -> Result<(), ApiError> {
+    if !confirm {
+        return Err(ApiError::BadRequest(
+            "Deleting all conversations requires the 'confirm=true' query parameter".to_string(),
+        ));
+    }
+
+    match admin_token.filter(|t| !t.is_empty()) {
+        Some(expected) if !provided_token.is_some_and(|t| keys_match(t, expected)) => Err(
+            ApiError::Unauthorized(format!("Missing or invalid {} header", ADMIN_TOKEN_HEADER)),
+        ),
+        _ => Ok(()),
+    }
+}
+
+/// Delete all conversations
+/// Requires `?confirm=true`, plus the `X-Admin-Token` header when `AGENT_ADMIN_TOKEN` is set
+#[delete("/api/agent/conversations")]
+pub async fn clear_all_conversations(
+    req: HttpRequest,
+    query: web::Query<ClearConversationsQuery>,
+    sqlite_memory: web::Data<Arc<SqliteConversationMemory>>,
+) -> ActixResult<HttpResponse> {
+    let admin_token = std::env::var(ADMIN_TOKEN_ENV).ok();
+    let provided_token = req
+        .headers()
+        .get(ADMIN_TOKEN_HEADER)
+        .and_then(|v| v.to_str().ok());
+
+    authorize_clear_all(query.confirm, admin_token.as_deref(), provided_token)?;
+
+    match sqlite_memory.clear_all().await {
+        Ok(deleted) => {
+            println!("🗑️ Deleted all conversations ({})", deleted);
+            Ok(HttpResponse::Ok().json(serde_json::json!({ "deleted": deleted })))
+        }
+        Err(e) => {
+            println!("Failed to delete all conversations: {}", e);
+            Err(ApiError::Internal(format!("Failed to delete conversations: {}", e)).into())
         }
     }
 }
@@ -44,42 +111,170 @@ pub async fn update_conversation_title(
     sqlite_memory: web::Data<Arc<SqliteConversationMemory>>,
 ) -> ActixResult<HttpResponse> {
     let conversation_id = path.into_inner();
+    let title = body.title.trim();
 
-    match sqlite_memory
-        .update_conversation_title(&conversation_id, &body.title)
-        .await
-    {
-        Ok(_) => Ok(HttpResponse::Ok().finish()),
+    if title.is_empty() {
+        return Err(ApiError::BadRequest("Title must not be empty".to_string()).into());
+    }
+    if title.chars().count() > MAX_TITLE_CHARS {
+        return Err(ApiError::BadRequest(format!(
+            "Title must be at most {} characters",
+            MAX_TITLE_CHARS
+        ))
+        .into());
+    }
+
+    match sqlite_memory.set_title(&conversation_id, title).await {
+        Ok(true) => Ok(HttpResponse::Ok().finish()),
+        Ok(false) => {
+            Err(ApiError::NotFound(format!("Conversation {} not found", conversation_id)).into())
+        }
         Err(e) => {
             println!(
                 "Failed to update conversation {} title: {}",
                 conversation_id, e
             );
-            Ok(HttpResponse::InternalServerError()
-                .body(format!("Failed to update conversation title: {}", e)))
+            Err(ApiError::Internal(format!("Failed to update conversation title: {}", e)).into())
         }
     }
 }
 
 /// Get conversation history
+/// Pass `?include_tools=false` to skip tool calls and tool results
 #[get("/api/agent/conversations/{id}/messages")]
 pub async fn get_conversation_history(
     path: web::Path<String>,
+    query: web::Query<ConversationMessagesQuery>,
     sqlite_memory: web::Data<Arc<SqliteConversationMemory>>,
 ) -> ActixResult<HttpResponse> {
     let conversation_id = path.into_inner();
+    let include_tools = query.include_tools.unwrap_or(true);
 
-    match sqlite_memory.get_messages(&conversation_id).await {
+    match sqlite_memory
+        .get_messages_filtered(&conversation_id, include_tools)
+        .await
+    {
         Ok(messages) => Ok(HttpResponse::Ok().json(messages)),
         Err(e) => {
             println!(
                 "Failed to fetch messages for conversation {}: {}",
                 conversation_id, e
             );
-            Ok(
-                HttpResponse::InternalServerError()
-                    .body(format!("Failed to fetch messages: {}", e)),
+            Err(ApiError::Internal(format!("Failed to fetch messages: {}", e)).into())
+        }
+    }
+}
+
+/// Search message content across all conversations
+#[get("/api/agent/search")]
+pub async fn search_conversations(
+    query: web::Query<SearchQuery>,
+    sqlite_memory: web::Data<Arc<SqliteConversationMemory>>,
+) -> ActixResult<HttpResponse> {
+    if query.q.trim().is_empty() {
+        return Err(
+            ApiError::BadRequest("Query parameter 'q' must not be empty".to_string()).into(),
+        );
+    }
+
+    let limit = query.limit.unwrap_or(DEFAULT_SEARCH_LIMIT);
+    match sqlite_memory.search_messages(&query.q, limit).await {
+        Ok(results) => Ok(HttpResponse::Ok().json(results)),
+        Err(e) => {
+            println!("Failed to search conversations for '{}': {}", query.q, e);
+            Err(ApiError::Internal(format!("Failed to search conversations: {}", e)).into())
+        }
+    }
+}
+
+/// Export a conversation as a downloadable markdown transcript or raw JSON
+#[get("/api/agent/conversations/{id}/export")]
+pub async fn export_conversation(
+    path: web::Path<String>,
+    query: web::Query<ExportQuery>,
+    sqlite_memory: web::Data<Arc<SqliteConversationMemory>>,
+) -> ActixResult<HttpResponse> {
+    let conversation_id = path.into_inner();
+
+    let format = match ExportFormat::parse(query.format.as_deref()) {
+        Some(format) => format,
+        None => {
+            return Err(ApiError::BadRequest(
+                "Unsupported export format (expected 'markdown' or 'json')".to_string(),
             )
+            .into())
+        }
+    };
+
+    let conversation = match sqlite_memory.get_conversation(&conversation_id).await {
+        Ok(Some(conversation)) => conversation,
+        Ok(None) => {
+            return Err(
+                ApiError::NotFound(format!("Conversation {} not found", conversation_id)).into(),
+            )
+        }
+        Err(e) => {
+            println!("Failed to fetch conversation {}: {}", conversation_id, e);
+            return Err(ApiError::Internal(format!("Failed to fetch conversation: {}", e)).into());
         }
+    };
+
+    let messages = match sqlite_memory
+        .get_timestamped_messages(&conversation_id, true)
+        .await
+    {
+        Ok(messages) => messages,
+        Err(e) => {
+            println!(
+                "Failed to fetch messages for conversation {}: {}",
+                conversation_id, e
+            );
+            return Err(ApiError::Internal(format!("Failed to fetch messages: {}", e)).into());
+        }
+    };
+
+    let body = match format {
+        ExportFormat::Markdown => render_markdown(&conversation, &messages),
+        ExportFormat::Json => serde_json::to_string_pretty(&serde_json::json!({
+            "conversation": conversation,
+            "messages": messages,
+        }))
+        .unwrap_or_default(),
+    };
+
+    Ok(HttpResponse::Ok()
+        .content_type(format.content_type())
+        .insert_header((
+            header::CONTENT_DISPOSITION,
+            format!(
+                "attachment; filename=\"{}\"",
+                export_filename(&conversation, format)
+            ),
+        ))
+        .body(body))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::http::StatusCode;
+    use actix_web::ResponseError;
+
+    #[test]
+    fn test_authorize_clear_all_requires_confirm() {
+        let err = authorize_clear_all(false, None, None).unwrap_err();
+        assert_eq!(err.status_code(), StatusCode::BAD_REQUEST);
+        assert!(authorize_clear_all(true, None, None).is_ok());
+    }
+
+    #[test]
+    fn test_authorize_clear_all_admin_token() {
+        let err = authorize_clear_all(true, Some("secret"), None).unwrap_err();
+        assert_eq!(err.status_code(), StatusCode::UNAUTHORIZED);
+        let err = authorize_clear_all(true, Some("secret"), Some("wrong")).unwrap_err();
+        assert_eq!(err.status_code(), StatusCode::UNAUTHORIZED);
+        assert!(authorize_clear_all(true, Some("secret"), Some("secret")).is_ok());
+        // An empty token counts as unset
+        assert!(authorize_clear_all(true, Some(""), None).is_ok());
     }
 }
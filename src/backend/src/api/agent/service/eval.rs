@@ -0,0 +1,278 @@
+use crate::api::agent::core::types::AgentConfig;
+use crate::api::agent::memory::sqlite_memory::SqliteConversationMemory;
+use crate::api::agent::testing::runner::{AgentAnswer, AgentHarness};
+use crate::api::agent::testing::storage::check_answer;
+use crate::api::agent::tools::framework::cache::ToolResultCache;
+use crate::api::error::ApiError;
+use crate::api::llama_server::types::Config;
+use crate::api::model_notes::ModelNotesStorage;
+use actix_web::{post, web, HttpResponse};
+use futures::stream::{self, StreamExt};
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+/// Most cases accepted in one eval request
+pub const MAX_EVAL_CASES: usize = 50;
+
+/// Most cases run against the LLM at the same time
+pub const MAX_EVAL_CONCURRENCY: usize = 4;
+
+fn default_expect_no_error() -> bool {
+    true
+}
+
+/// One prompt and what a good answer looks like
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EvalCase {
+    pub prompt: String,
+    /// The answer must contain this text (case-insensitive)
+    #[serde(default)]
+    pub expected_substring: Option<String>,
+    /// Tools the agent must call (by function name)
+    #[serde(default)]
+    pub expected_tools: Vec<String>,
+    /// Fail the case when the agent errors instead of answering. Defaults to true
+    #[serde(default = "default_expect_no_error")]
+    pub expect_no_error: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct EvalRequest {
+    pub cases: Vec<EvalCase>,
+    /// Cases run in parallel (1 to `MAX_EVAL_CONCURRENCY`, default 1 = one after another)
+    #[serde(default)]
+    pub concurrency: Option<usize>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EvalCaseResult {
+    pub index: usize,
+    pub prompt: String,
+    pub passed: bool,
+    pub output: String,
+    pub tool_calls: Vec<String>,
+    pub failures: Vec<String>,
+    /// Set when the agent failed instead of answering
+    pub error: Option<String>,
+    pub duration_ms: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EvalResponse {
+    /// Model the cases were run against
+    pub model: String,
+    pub total: usize,
+    pub passed: usize,
+    pub failed: usize,
+    /// Share of passed cases, 0.0 to 1.0
+    pub score: f64,
+    pub duration_ms: u64,
+    pub results: Vec<EvalCaseResult>,
+}
+
+impl EvalCase {
+    fn validate(&self, index: usize) -> Result<(), String> {
+        if self.prompt.trim().is_empty() {
+            return Err(format!("cases[{}].prompt must not be empty", index));
+        }
+        Ok(())
+    }
+
+    /// Reasons this case failed, given the agent's answer or error
+    fn failures(&self, answer: Result<&AgentAnswer, &str>) -> Vec<String> {
+        match answer {
+            Ok(answer) => check_answer(
+                self.expected_substring.as_deref(),
+                &self.expected_tools,
+                &answer.output,
+                &answer.tool_calls,
+            ),
+            Err(error) if self.expect_no_error => vec![format!("Agent failed: {}", error)],
+            Err(_) => Vec::new(),
+        }
+    }
+}
+
+impl EvalResponse {
+    fn new(model: String, results: Vec<EvalCaseResult>, duration_ms: u64) -> Self {
+        let passed = results.iter().filter(|r| r.passed).count();
+        let total = results.len();
+        Self {
+            model,
+            total,
+            passed,
+            failed: total - passed,
+            score: if total == 0 {
+                0.0
+            } else {
+                passed as f64 / total as f64
+            },
+            duration_ms,
+            results,
+        }
+    }
+}
+
+async fn run_case(harness: &AgentHarness, index: usize, case: EvalCase) -> EvalCaseResult {
+    let started = Instant::now();
+    let answer = harness.ask(&case.prompt).await.map_err(|e| e.to_string());
+    let failures = case.failures(answer.as_ref().map_err(String::as_str));
+    let (output, tool_calls, error) = match answer {
+        Ok(answer) => (answer.output, answer.tool_calls, None),
+        Err(e) => (String::new(), Vec::new(), Some(e)),
+    };
+
+    EvalCaseResult {
+        index,
+        passed: failures.is_empty(),
+        prompt: case.prompt,
+        output,
+        tool_calls,
+        failures,
+        error,
+        duration_ms: started.elapsed().as_millis() as u64,
+    }
+}
+
+/// Run a batch of prompts against the agent and score the answers.
+///
+/// Uses the current agent and llama-server config, so models can be compared
+/// by switching the model and re-running the same batch.
+#[post("/api/agent/eval")]
+#[allow(clippy::too_many_arguments)]
+pub async fn agent_eval(
+    req: web::Json<EvalRequest>,
+    agent_config: web::Data<Arc<Mutex<AgentConfig>>>,
+    llama_config: web::Data<Arc<Mutex<Config>>>,
+    chroma_address: web::Data<String>,
+    sqlite_memory: web::Data<Arc<SqliteConversationMemory>>,
+    tool_cache: web::Data<Arc<ToolResultCache>>,
+    model_notes: web::Data<Arc<ModelNotesStorage>>,
+) -> Result<HttpResponse, ApiError> {
+    let req = req.into_inner();
+    if req.cases.is_empty() || req.cases.len() > MAX_EVAL_CASES {
+        return Err(ApiError::BadRequest(format!(
+            "cases must contain between 1 and {} entries",
+            MAX_EVAL_CASES
+        )));
+    }
+    for (index, case) in req.cases.iter().enumerate() {
+        case.validate(index).map_err(ApiError::BadRequest)?;
+    }
+    let concurrency = req.concurrency.unwrap_or(1);
+    if !(1..=MAX_EVAL_CONCURRENCY).contains(&concurrency) {
+        return Err(ApiError::BadRequest(format!(
+            "concurrency must be between 1 and {}",
+            MAX_EVAL_CONCURRENCY
+        )));
+    }
+
+    let harness = AgentHarness::new(
+        &agent_config,
+        &llama_config,
+        &chroma_address,
+        &sqlite_memory,
+        &tool_cache,
+        &model_notes,
+    );
+    println!(
+        "🧪 Evaluating {} case(s) against {} ({} at a time)",
+        req.cases.len(),
+        harness.model_name,
+        concurrency
+    );
+
+    let started = Instant::now();
+    // `buffered` keeps results in case order while running up to `concurrency` at once
+    let results: Vec<EvalCaseResult> = stream::iter(req.cases.into_iter().enumerate())
+        .map(|(index, case)| run_case(&harness, index, case))
+        .buffered(concurrency)
+        .collect()
+        .await;
+
+    let response = EvalResponse::new(
+        harness.model_name.clone(),
+        results,
+        started.elapsed().as_millis() as u64,
+    );
+    println!(
+        "🧪 Eval finished: {}/{} passed ({:.0}%)",
+        response.passed,
+        response.total,
+        response.score * 100.0
+    );
+    Ok(HttpResponse::Ok().json(response))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn case(json: serde_json::Value) -> EvalCase {
+        serde_json::from_value(json).unwrap()
+    }
+
+    #[test]
+    fn test_case_defaults_to_expecting_no_error() {
+        let case = case(serde_json::json!({ "prompt": "Hi" }));
+        assert!(case.expect_no_error);
+        assert_eq!(
+            case.failures(Err("connection refused")),
+            vec!["Agent failed: connection refused".to_string()]
+        );
+
+        let lenient = EvalCase {
+            expect_no_error: false,
+            ..case
+        };
+        assert!(lenient.failures(Err("connection refused")).is_empty());
+    }
+
+    #[test]
+    fn test_case_checks_text_and_tools() {
+        let case = case(serde_json::json!({
+            "prompt": "Weather in Paris?",
+            "expected_substring": "sunny",
+            "expected_tools": ["weather_current"]
+        }));
+        let answer = |output: &str, tool_calls: &[&str]| AgentAnswer {
+            output: output.to_string(),
+            tool_calls: tool_calls.iter().map(|t| t.to_string()).collect(),
+        };
+        assert!(case
+            .failures(Ok(&answer("Sunny all day", &["weather_current"])))
+            .is_empty());
+        assert_eq!(case.failures(Ok(&answer("Rain", &[]))).len(), 2);
+    }
+
+    #[test]
+    fn test_validate_rejects_blank_prompt() {
+        let blank = case(serde_json::json!({ "prompt": "  " }));
+        assert_eq!(
+            blank.validate(2),
+            Err("cases[2].prompt must not be empty".to_string())
+        );
+    }
+
+    #[test]
+    fn test_response_score() {
+        let result = |passed| EvalCaseResult {
+            index: 0,
+            prompt: String::new(),
+            passed,
+            output: String::new(),
+            tool_calls: Vec::new(),
+            failures: Vec::new(),
+            error: None,
+            duration_ms: 10,
+        };
+        let response = EvalResponse::new(
+            "model".to_string(),
+            vec![result(true), result(false), result(true), result(true)],
+            40,
+        );
+        assert_eq!((response.passed, response.failed), (3, 1));
+        assert!((response.score - 0.75).abs() < f64::EPSILON);
+    }
+}
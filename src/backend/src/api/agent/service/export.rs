@@ -0,0 +1,257 @@
+use crate::api::agent::core::types::{Conversation, MessageRole, TimestampedMessage};
+use std::fmt::Write;
+
+/// Supported conversation export formats
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Markdown,
+    Json,
+}
+
+impl ExportFormat {
+    /// Parse the `format` query parameter, defaulting to markdown
+    pub fn parse(format: Option<&str>) -> Option<Self> {
+        match format.map(|f| f.trim().to_lowercase()).as_deref() {
+            None | Some("") | Some("markdown") | Some("md") => Some(ExportFormat::Markdown),
+            Some("json") => Some(ExportFormat::Json),
+            _ => None,
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Markdown => "md",
+            ExportFormat::Json => "json",
+        }
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            ExportFormat::Markdown => "text/markdown; charset=utf-8",
+            ExportFormat::Json => "application/json",
+        }
+    }
+}
+
+fn format_timestamp(timestamp: i64) -> String {
+    chrono::DateTime::from_timestamp(timestamp, 0)
+        .map(|dt| dt.format("%Y-%m-%d %H:%M:%S UTC").to_string())
+        .unwrap_or_else(|| timestamp.to_string())
+}
+
+/// Pretty-print tool call arguments when they are valid JSON
+fn format_arguments(arguments: &str) -> String {
+    serde_json::from_str::<serde_json::Value>(arguments)
+        .ok()
+        .and_then(|v| serde_json::to_string_pretty(&v).ok())
+        .unwrap_or_else(|| arguments.to_string())
+}
+
+/// Backtick fence longer than any backtick run in `content`, so the block
+/// can't be closed early by the content itself
+fn code_fence(content: &str) -> String {
+    let longest_run = content.split(|c| c != '`').map(str::len).max().unwrap_or(0);
+    "`".repeat((longest_run + 1).max(3))
+}
+
+/// Conversation title or a fallback when it has none
+fn display_title(conversation: &Conversation) -> &str {
+    conversation
+        .title
+        .as_deref()
+        .filter(|t| !t.trim().is_empty())
+        .unwrap_or("Conversation")
+}
+
+/// Render a conversation as a markdown transcript with role headers,
+/// tool-call blocks and timestamps
+pub fn render_markdown(conversation: &Conversation, messages: &[TimestampedMessage]) -> String {
+    let mut out = String::new();
+
+    let _ = writeln!(out, "# {}\n", display_title(conversation));
+    let _ = writeln!(out, "- **Conversation ID:** `{}`", conversation.id);
+    if let Some(model) = &conversation.model {
+        let _ = writeln!(out, "- **Model:** {}", model);
+    }
+    let _ = writeln!(
+        out,
+        "- **Created:** {}",
+        format_timestamp(conversation.created_at)
+    );
+    let _ = writeln!(out, "- **Messages:** {}", messages.len());
+
+    for entry in messages {
+        let message = &entry.message;
+        let timestamp = format_timestamp(entry.created_at);
+        let content = message.content.text();
+
+        out.push_str("\n---\n\n");
+
+        match message.role {
+            MessageRole::User => {
+                let _ = writeln!(out, "### 👤 User · {}\n", timestamp);
+                let _ = writeln!(out, "{}", content.trim());
+            }
+            MessageRole::Assistant => {
+                let _ = writeln!(out, "### 🤖 Assistant · {}\n", timestamp);
+                if !content.trim().is_empty() {
+                    let _ = writeln!(out, "{}", content.trim());
+                }
+                for call in message.tool_calls.iter().flatten() {
+                    let arguments = format_arguments(&call.function.arguments);
+                    let fence = code_fence(&arguments);
+                    let _ = writeln!(
+                        out,
+                        "\n**🔧 Tool call:** `{}`\n\n{}json\n{}\n{}",
+                        call.function.name, fence, arguments, fence
+                    );
+                }
+            }
+            MessageRole::Tool => {
+                let name = message.name.as_deref().unwrap_or("tool");
+                let _ = writeln!(out, "### 🔧 Tool result: `{}` · {}\n", name, timestamp);
+                let fence = code_fence(content.trim());
+                let _ = writeln!(out, "{}\n{}\n{}", fence, content.trim(), fence);
+            }
+            MessageRole::System => {
+                let _ = writeln!(out, "### ⚙️ System · {}\n", timestamp);
+                let _ = writeln!(out, "{}", content.trim());
+            }
+        }
+    }
+
+    out
+}
+
+/// Build a download filename from the conversation title, keeping only
+/// characters that are safe in a `Content-Disposition` header
+pub fn export_filename(conversation: &Conversation, format: ExportFormat) -> String {
+    let sanitized: String = display_title(conversation)
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+
+    let collapsed = sanitized
+        .split('_')
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("_");
+
+    let stem = if collapsed.is_empty() {
+        "conversation".to_string()
+    } else {
+        collapsed.chars().take(80).collect()
+    };
+
+    format!("{}.{}", stem, format.extension())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::agent::core::types::{ChatMessage, FunctionCall, MessageContent, ToolCall};
+
+    fn conversation(title: Option<&str>) -> Conversation {
+        Conversation {
+            id: "abc-123".to_string(),
+            title: title.map(|t| t.to_string()),
+            model: Some("qwen".to_string()),
+            created_at: 1711356300,
+            last_updated: 1711356300,
+            message_count: 3,
+        }
+    }
+
+    fn entry(role: MessageRole, content: &str) -> TimestampedMessage {
+        TimestampedMessage {
+            message: ChatMessage {
+                role,
+                content: MessageContent::Text(content.to_string()),
+                name: None,
+                tool_calls: None,
+                tool_call_id: None,
+                reasoning_content: None,
+            },
+            created_at: 1711356300,
+            partial: false,
+        }
+    }
+
+    #[test]
+    fn test_export_format_parse() {
+        assert_eq!(ExportFormat::parse(None), Some(ExportFormat::Markdown));
+        assert_eq!(ExportFormat::parse(Some("JSON")), Some(ExportFormat::Json));
+        assert_eq!(ExportFormat::parse(Some("pdf")), None);
+    }
+
+    #[test]
+    fn test_render_markdown() {
+        let mut assistant = entry(MessageRole::Assistant, "");
+        assistant.message.tool_calls = Some(vec![ToolCall {
+            id: "call_1".to_string(),
+            tool_type: "function".to_string(),
+            function: FunctionCall {
+                name: "weather_current".to_string(),
+                arguments: r#"{"city":"Warsaw"}"#.to_string(),
+            },
+        }]);
+        let mut tool = entry(MessageRole::Tool, "Sunny, 21°C");
+        tool.message.name = Some("weather_current".to_string());
+
+        let messages = vec![
+            entry(MessageRole::User, "Weather in Warsaw?"),
+            assistant,
+            tool,
+            entry(MessageRole::Assistant, "It's sunny."),
+        ];
+
+        let markdown = render_markdown(&conversation(Some("Warsaw weather")), &messages);
+        assert!(markdown.starts_with("# Warsaw weather\n"));
+        assert!(markdown.contains("### 👤 User · 2024-03-25 08:45:00 UTC"));
+        assert!(markdown.contains("**🔧 Tool call:** `weather_current`"));
+        assert!(markdown.contains("\"city\": \"Warsaw\""));
+        assert!(markdown.contains("### 🔧 Tool result: `weather_current`"));
+        assert!(markdown.contains("It's sunny."));
+    }
+
+    #[test]
+    fn test_render_markdown_fences_tool_output() {
+        let mut tool = entry(
+            MessageRole::Tool,
+            "Example:\n```rust\nfn main() {}\n```\nand ````nested````",
+        );
+        tool.message.name = Some("fetch_docs".to_string());
+
+        let markdown = render_markdown(&conversation(None), &[tool]);
+        assert!(markdown.contains("`````\nExample:\n```rust"));
+        assert!(markdown.contains("````nested````\n`````\n"));
+
+        assert_eq!(code_fence("plain"), "```");
+        assert_eq!(code_fence("a ``` b"), "````");
+    }
+
+    #[test]
+    fn test_export_filename() {
+        assert_eq!(
+            export_filename(
+                &conversation(Some("Trip: Kraków / 2024")),
+                ExportFormat::Markdown
+            ),
+            "Trip_Krak_w_2024.md"
+        );
+        assert_eq!(
+            export_filename(&conversation(None), ExportFormat::Json),
+            "Conversation.json"
+        );
+        assert_eq!(
+            export_filename(&conversation(Some("???")), ExportFormat::Json),
+            "conversation.json"
+        );
+    }
+}
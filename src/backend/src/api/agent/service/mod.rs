@@ -1,6 +1,10 @@
 pub mod chat;
 pub mod config;
 pub mod conversations;
+pub mod eval;
+pub mod export;
 pub mod naming;
+pub mod rag;
 pub mod utils;
+pub mod validate;
 pub mod websocket;
@@ -1,10 +1,19 @@
 use crate::api::agent::core::types::MessageRole;
 use crate::api::agent::memory::sqlite_memory::SqliteConversationMemory;
 use crate::api::agent::service::utils::clean_response;
+use anyhow::Context;
 use reqwest::Client;
 use std::sync::Arc;
 
+/// Smallest number of words requested for a generated title
+const TITLE_MIN_WORDS: usize = 3;
+
+/// Longest generated title kept, in words
+const TITLE_MAX_WORDS: usize = 6;
+
 /// Helper to attempt auto-naming the conversation
+/// Runs after the first exchange, asks the LLM for a short title and falls back
+/// to the first user message when that fails
 pub async fn attempt_conversation_naming(
     client: Client,
     llama_url: String,
@@ -35,9 +44,16 @@ pub async fn attempt_conversation_naming(
         }
     };
 
-    // Check if title is still default "Chat ..." or "New Conversation"
-    // If it doesn't start with "Chat " and isn't "New Conversation", it's likely been renamed by user or previous run.
-    if !current_title.starts_with("Chat ") && current_title != "New Conversation" {
+    // Only replace automatic titles (placeholder or derived from the first message).
+    // Anything else has been renamed by the user or a previous naming run.
+    let is_auto_title = match sqlite_memory.is_auto_title(&conversation_id).await {
+        Ok(auto) => auto,
+        Err(e) => {
+            println!("⚠️ [Naming] Failed to check title: {}", e);
+            return;
+        }
+    };
+    if !is_auto_title {
         println!(
             "ℹ️ [Naming] Skipping naming: conversation already named '{}'",
             current_title
@@ -45,29 +61,15 @@ pub async fn attempt_conversation_naming(
         return;
     }
 
-    // We only want to rename early in the conversation, but allow for some buffer
-    // Lower bound: need at least 2 messages for context
-    // Upper bound: protect against massive context window costs, but relax it significantly (e.g. 50)
     if count < 2 {
         return;
     }
-    if count > 50 {
-        println!(
-            "ℹ️ [Naming] Skipping naming: message count {} too high (limit 50)",
-            count
-        );
-        return;
-    }
-
-    // Delay a bit to let the LLM server finish processing the previous request
-    // Large models might be slow to release resources/slots
-    tokio::time::sleep(tokio::time::Duration::from_millis(3000)).await;
-
-    // Also check if title is still default "Chat ..." or "New Conversation" to avoid overwriting user rename.
-    // Ideally we should check this, but for now we assume if count is low it hasn't been renamed manually yet.
 
     // Get messages to prompt for title
-    let messages = match sqlite_memory.get_messages(&conversation_id).await {
+    let messages = match sqlite_memory
+        .get_messages_filtered(&conversation_id, false)
+        .await
+    {
         Ok(m) => m,
         Err(e) => {
             println!("⚠️ [Naming] Failed to get messages: {}", e);
@@ -75,11 +77,23 @@ pub async fn attempt_conversation_naming(
         }
     };
 
-    if messages.is_empty() {
-        println!("ℹ️ [Naming] Skipping naming: no messages found");
+    // Only name after the first exchange; later turns keep whatever title it produced
+    let user_turns = messages
+        .iter()
+        .filter(|m| m.role == MessageRole::User)
+        .count();
+    if user_turns != 1 || !messages.iter().any(|m| m.role == MessageRole::Assistant) {
+        println!(
+            "ℹ️ [Naming] Skipping naming: not the first exchange ({} user messages)",
+            user_turns
+        );
         return;
     }
 
+    // Delay a bit to let the LLM server finish processing the previous request
+    // Large models might be slow to release resources/slots
+    tokio::time::sleep(tokio::time::Duration::from_millis(3000)).await;
+
     // Construct prompt
     // We use the first user message + assistant response for context
     let context_msgs: Vec<String> = messages
@@ -102,11 +116,54 @@ pub async fn attempt_conversation_naming(
     let context = context_msgs.join("\n");
 
     let prompt = format!(
-        "Please provide a very short, concise title (max 5 words) for the following conversation. The title should summarize the topic. Return ONLY the title text, no quotes, no prefixes.\n\nConversation:\n{}", 
-        context
+        "Please provide a very short, concise title ({}-{} words) for the following conversation. The title should summarize the topic. Return ONLY the title text, no quotes, no prefixes.\n\nConversation:\n{}",
+        TITLE_MIN_WORDS, TITLE_MAX_WORDS, context
     );
 
-    // Call LLM for title
+    println!(
+        "📤 [Naming] Sending request to LLM (model: {})...",
+        model_name
+    );
+
+    match request_title(&client, &llama_url, &model_name, &prompt).await {
+        Ok(title) => {
+            // The user may have renamed the conversation while we were waiting
+            if !sqlite_memory
+                .is_auto_title(&conversation_id)
+                .await
+                .unwrap_or(false)
+            {
+                println!("ℹ️ [Naming] Title changed while naming, keeping it");
+                return;
+            }
+            println!(
+                "📝 Auto-renaming conversation {} to '{}'",
+                conversation_id, title
+            );
+            if let Err(e) = sqlite_memory.set_title(&conversation_id, &title).await {
+                println!("⚠️ [Naming] Failed to store title: {}", e);
+            }
+        }
+        Err(e) => {
+            println!("⚠️ [Naming] {}; falling back to first message title", e);
+            // Make sure the conversation still ends up with a readable title
+            if let Err(e) = sqlite_memory
+                .auto_title_from_first_message(&conversation_id)
+                .await
+            {
+                println!("⚠️ [Naming] Failed to set fallback title: {}", e);
+            }
+        }
+    }
+}
+
+/// Ask the LLM for a title and return it cleaned up
+async fn request_title(
+    client: &Client,
+    llama_url: &str,
+    model_name: &str,
+    prompt: &str,
+) -> anyhow::Result<String> {
     // We use a simple non-streaming request
     let request = serde_json::json!({
         "model": model_name,
@@ -117,52 +174,78 @@ pub async fn attempt_conversation_naming(
         "max_tokens": 1000
     });
 
-    println!(
-        "📤 [Naming] Sending request to LLM (model: {})...",
-        model_name
-    );
-
-    // Fire and forget-ish
-    let res = match client
-        .post(&llama_url)
+    let res = client
+        .post(llama_url)
         .json(&request)
         .timeout(std::time::Duration::from_secs(30))
         .send()
         .await
-    {
-        Ok(r) => r,
-        Err(e) => {
-            println!("⚠️ [Naming] Failed to request title summary: {}", e);
-            return;
-        }
-    };
+        .context("Failed to request title summary")?;
 
     let status = res.status();
     if !status.is_success() {
         let text = res.text().await.unwrap_or_default();
-        println!("⚠️ [Naming] LLM server error (status {}): {}", status, text);
-        return;
+        return Err(anyhow::anyhow!(
+            "LLM server error (status {}): {}",
+            status,
+            text
+        ));
     }
 
-    if let Ok(json) = res.json::<serde_json::Value>().await {
-        if let Some(content) = json["choices"][0]["message"]["content"].as_str() {
-            let title = clean_response(content).replace("\"", "").trim().to_string();
-            if !title.is_empty() {
-                println!(
-                    "📝 Auto-renaming conversation {} to '{}'",
-                    conversation_id, title
-                );
-                let _ = sqlite_memory
-                    .update_conversation_title(&conversation_id, &title)
-                    .await;
-            }
-        } else {
-            println!(
-                "⚠️ [Naming] Unexpected JSON response structure (missing content): {:?}",
-                json
-            );
-        }
+    let json: serde_json::Value = res.json().await.context("Failed to parse JSON response")?;
+    let content = json["choices"][0]["message"]["content"]
+        .as_str()
+        .context("Unexpected JSON response structure (missing content)")?;
+
+    clean_title(content).context("LLM returned an empty title")
+}
+
+/// Normalize an LLM title: drop reasoning, quotes, "Title:" prefixes and
+/// trailing punctuation, and cap it at `TITLE_MAX_WORDS` words
+fn clean_title(raw: &str) -> Option<String> {
+    let cleaned = clean_response(raw);
+    let line = cleaned.lines().map(str::trim).find(|l| !l.is_empty())?;
+
+    let line = line.trim_start_matches(['#', '*', ' ']);
+    let line = match line.split_once(':') {
+        Some((prefix, rest)) if prefix.trim().eq_ignore_ascii_case("title") => rest,
+        _ => line,
+    };
+
+    let title = line
+        .split_whitespace()
+        .take(TITLE_MAX_WORDS)
+        .collect::<Vec<_>>()
+        .join(" ")
+        .trim_matches(|c: char| matches!(c, '"' | '\'' | '*' | '`') || c.is_whitespace())
+        .trim_end_matches(['.', '!', ',', ';', ':'])
+        .to_string();
+
+    if title.is_empty() {
+        None
     } else {
-        println!("⚠️ [Naming] Failed to parse JSON response");
+        Some(title)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clean_title() {
+        assert_eq!(
+            clean_title("\"Sourdough Bread Baking Tips\"").as_deref(),
+            Some("Sourdough Bread Baking Tips")
+        );
+        assert_eq!(
+            clean_title("Title: Weather in Warsaw.\n\nHope this helps!").as_deref(),
+            Some("Weather in Warsaw")
+        );
+        assert_eq!(
+            clean_title("**Planning A Long Weekend Trip To The Mountains**").as_deref(),
+            Some("Planning A Long Weekend Trip To")
+        );
+        assert_eq!(clean_title("  \n\"\"  "), None);
     }
 }
@@ -0,0 +1,231 @@
+use crate::api::agent::core::types::{
+    AgentRagRequest, AgentRagResponse, ChatCompletionRequest, ChatCompletionResponse, ChatMessage,
+    MessageContent, MessageRole, SourceChunk,
+};
+use crate::api::agent::service::chat::model_loop_defaults;
+use crate::api::agent::service::utils::{clean_response, new_request_id, REQUEST_ID_HEADER};
+use crate::api::chromadb::client::{embedding_backend, ChromaDBClient};
+use crate::api::chromadb::config::types::ChromaDBConfig;
+use crate::api::chromadb::types::QueryRequest;
+use crate::api::error::ApiError;
+use crate::api::llama_server::types::Config;
+use crate::api::metrics::registry as metrics;
+use crate::api::model_notes::ModelNotesStorage;
+use actix_web::{post, web, HttpResponse, Result as ActixResult};
+use reqwest::Client;
+use std::sync::{Arc, Mutex};
+
+/// Chunks retrieved when the request doesn't say
+const DEFAULT_RAG_RESULTS: usize = 5;
+
+/// Most chunks put into one prompt
+const MAX_RAG_RESULTS: usize = 20;
+
+/// Answer returned without calling the LLM when the search finds nothing
+const NO_SOURCES_ANSWER: &str = "No relevant documents were found in the collection.";
+
+/// System prompt restricting the model to the retrieved chunks
+fn build_rag_system_prompt(sources: &[SourceChunk]) -> String {
+    let context = sources
+        .iter()
+        .map(|s| format!("[{}] ({})\n{}", s.citation, s.label(), s.text.trim()))
+        .collect::<Vec<_>>()
+        .join("\n\n");
+
+    format!(
+        "Answer the user's question using only the numbered sources below. \
+         Cite every statement with the number of the source it comes from, e.g. [1] or [2][3]. \
+         If the sources do not contain the answer, say so instead of guessing.\n\n\
+         Sources:\n\n{}",
+        context
+    )
+}
+
+/// One-shot retrieval-augmented answer
+///
+/// Searches `collection` with the configured embedding model, puts the top
+/// chunks into the system prompt and asks the LLM for an answer with inline
+/// `[n]` citations. The chunks are returned as `sources` in citation order.
+/// Nothing is stored in conversation memory.
+#[post("/api/agent/rag")]
+pub async fn agent_rag(
+    req: web::Json<AgentRagRequest>,
+    chroma_address: web::Data<String>,
+    chromadb_config: web::Data<Arc<Mutex<ChromaDBConfig>>>,
+    llama_config: web::Data<Arc<Mutex<Config>>>,
+    model_notes: web::Data<Arc<ModelNotesStorage>>,
+) -> ActixResult<HttpResponse> {
+    let request_id = new_request_id();
+    metrics::record(|m| m.agent_requests.with_label_values(&["rag"]).inc());
+    let req = req.into_inner();
+
+    if req.query.trim().is_empty() || req.collection.trim().is_empty() {
+        return Err(ApiError::BadRequest("query and collection are required".to_string()).into());
+    }
+    let n_results = req
+        .n_results
+        .unwrap_or(DEFAULT_RAG_RESULTS)
+        .clamp(1, MAX_RAG_RESULTS);
+
+    let (embedding_model, backend_kind) = {
+        let config = chromadb_config.lock().unwrap();
+        (config.embedding_model.clone(), config.embedding_backend)
+    };
+    if embedding_model.trim().is_empty() {
+        return Err(ApiError::BadRequest(
+            "Embedding model is not configured. Please configure it in ChromaDB settings."
+                .to_string(),
+        )
+        .into());
+    }
+
+    let (model_name, llama_base_url) = {
+        let config = llama_config.lock().unwrap();
+        (config.hf_model.clone(), config.llm_base_url())
+    };
+    let loop_config = model_loop_defaults(&model_notes, &model_name, &request_id)
+        .await
+        .with_overrides(None, req.temperature, req.max_tokens)
+        .map_err(|e| ApiError::BadRequest(e.to_string()))?;
+
+    // Retrieve
+    let embedder = embedding_backend(backend_kind, &embedding_model)
+        .map_err(|e| ApiError::Internal(format!("Embedding backend is not configured: {}", e)))?;
+    let chroma = ChromaDBClient::new(chroma_address.as_str())
+        .map_err(|e| actix_web::error::ErrorServiceUnavailable(e.to_string()))?;
+    let results = chroma
+        .query(
+            QueryRequest {
+                collection: req.collection.clone(),
+                query_texts: vec![req.query.clone()],
+                n_results: Some(n_results),
+                where_clause: None,
+                normalize_scores: false,
+                keyword: None,
+            },
+            embedder.as_ref(),
+        )
+        .await
+        .map_err(|e| {
+            println!("[{}] RAG search failed: {}", request_id, e);
+            ApiError::Internal(format!("ChromaDB search failed: {}", e))
+        })?;
+
+    let sources = SourceChunk::from_query_response(&results);
+    println!(
+        "[{}] 📚 RAG: {} chunk(s) from '{}' for model {}",
+        request_id,
+        sources.len(),
+        req.collection,
+        model_name
+    );
+
+    if sources.is_empty() {
+        return Ok(HttpResponse::Ok()
+            .insert_header((REQUEST_ID_HEADER, request_id))
+            .json(AgentRagResponse {
+                success: true,
+                answer: NO_SOURCES_ANSWER.to_string(),
+                sources,
+            }));
+    }
+
+    // Generate
+    let message = |role, text: String| ChatMessage {
+        role,
+        content: MessageContent::Text(text),
+        name: None,
+        tool_calls: None,
+        tool_call_id: None,
+        reasoning_content: None,
+    };
+    let completion_request = ChatCompletionRequest {
+        messages: vec![
+            message(MessageRole::System, build_rag_system_prompt(&sources)),
+            message(MessageRole::User, req.query.clone()),
+        ],
+        model: model_name,
+        temperature: Some(loop_config.temperature),
+        top_p: loop_config.top_p,
+        frequency_penalty: loop_config.frequency_penalty,
+        presence_penalty: loop_config.presence_penalty,
+        max_tokens: Some(loop_config.max_tokens),
+        stop: loop_config.stop.clone(),
+        tools: None,
+        tool_choice: None,
+        stream: Some(false),
+        stream_options: None,
+    };
+
+    let llama_url = format!("{}/v1/chat/completions", llama_base_url);
+    let llm_started = std::time::Instant::now();
+    let response = Client::new()
+        .post(&llama_url)
+        .json(&completion_request)
+        .send()
+        .await
+        .map_err(|e| ApiError::Upstream(format!("Failed to reach LLM server: {}", e)))?;
+    metrics::record(|m| {
+        m.llm_request_duration
+            .observe(llm_started.elapsed().as_secs_f64())
+    });
+    if !response.status().is_success() {
+        let status = response.status();
+        let text = response.text().await.unwrap_or_default();
+        return Err(
+            ApiError::Upstream(format!("LLM server error (status {}): {}", status, text)).into(),
+        );
+    }
+    let completion: ChatCompletionResponse = response
+        .json()
+        .await
+        .map_err(|e| ApiError::Upstream(format!("Invalid LLM response: {}", e)))?;
+
+    let answer = completion
+        .choices
+        .first()
+        .map(|c| clean_response(&c.message.content.text()))
+        .unwrap_or_default();
+
+    Ok(HttpResponse::Ok()
+        .insert_header((REQUEST_ID_HEADER, request_id))
+        .json(AgentRagResponse {
+            success: true,
+            answer,
+            sources,
+        }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_rag_system_prompt_numbers_sources() {
+        let sources = vec![
+            SourceChunk {
+                citation: 1,
+                id: "guide_0".to_string(),
+                filename: Some("guide.md".to_string()),
+                chunk_index: Some(0),
+                text: "Install with cargo.\n".to_string(),
+                distance: Some(0.1),
+                score: None,
+            },
+            SourceChunk {
+                citation: 2,
+                id: "faq_4".to_string(),
+                filename: None,
+                chunk_index: None,
+                text: "Ports default to 8080.".to_string(),
+                distance: Some(0.3),
+                score: None,
+            },
+        ];
+
+        let prompt = build_rag_system_prompt(&sources);
+        assert!(prompt.contains("[1] (guide.md, chunk 0)\nInstall with cargo."));
+        assert!(prompt.contains("[2] (faq_4)\nPorts default to 8080."));
+        assert!(prompt.contains("e.g. [1]"));
+    }
+}
@@ -1,23 +1,27 @@
+/// Internal markers some models leak into their text output
+const THINK_OPEN: &str = "<think>";
+const THINK_CLOSE: &str = "</think>";
+const INTERNAL_MARKERS: &[&str] = &[
+    THINK_OPEN,
+    THINK_CLOSE,
+    "<|redacted_reasoning|>",
+    "<｜tool▁calls▁begin｜>",
+    "<｜tool▁calls▁end｜>",
+    "<｜tool▁call▁begin｜>",
+    "<｜tool▁call▁end｜>",
+    "<｜tool▁sep｜>",
+    "<｜tool▁outputs▁begin｜>",
+    "<｜tool▁outputs▁end｜>",
+    "<｜tool▁output▁begin｜>",
+    "<｜tool▁output▁end｜>",
+];
+
 /// Clean response text by removing internal reasoning markers and redacted content
 pub fn clean_response(text: &str) -> String {
-    let mut cleaned = text.to_string();
-
-    // Remove redacted reasoning markers
-    cleaned = cleaned.replace("<|redacted_reasoning|>", "");
-    cleaned = cleaned.replace("</think>", "");
-    cleaned = cleaned.replace("<think>", "");
-    cleaned = cleaned.replace("</think>", "");
-
-    // Remove tool call markers
-    cleaned = cleaned.replace("<｜tool▁calls▁begin｜>", "");
-    cleaned = cleaned.replace("<｜tool▁calls▁end｜>", "");
-    cleaned = cleaned.replace("<｜tool▁call▁begin｜>", "");
-    cleaned = cleaned.replace("<｜tool▁call▁end｜>", "");
-    cleaned = cleaned.replace("<｜tool▁sep｜>", "");
-    cleaned = cleaned.replace("<｜tool▁outputs▁begin｜>", "");
-    cleaned = cleaned.replace("<｜tool▁outputs▁end｜>", "");
-    cleaned = cleaned.replace("<｜tool▁output▁begin｜>", "");
-    cleaned = cleaned.replace("<｜tool▁output▁end｜>", "");
+    // Remove <think> blocks and known tool markers; any other < or > is content
+    let mut cleaner = StreamCleaner::new();
+    let mut cleaned = cleaner.push(text);
+    cleaned.push_str(&cleaner.finish());
 
     // Remove common internal reasoning patterns (Thought/Action/Observation format)
     if cleaned.contains("Thought:")
@@ -62,20 +66,200 @@ pub fn clean_response(text: &str) -> String {
         }
     }
 
-    // Remove any remaining HTML-like tags that might be internal markers
-    // Use simple string replacement instead of regex for reliability
-    let mut result = String::new();
-    let mut in_tag = false;
-    for ch in cleaned.chars() {
-        if ch == '<' {
-            in_tag = true;
-        } else if ch == '>' && in_tag {
-            in_tag = false;
-        } else if !in_tag {
-            result.push(ch);
+    cleaned.trim().to_string()
+}
+
+/// Incremental version of the marker stripping in `clean_response`, for streamed text.
+///
+/// Removes `<think>...</think>` blocks and the known markers in `INTERNAL_MARKERS`,
+/// even when one is split across deltas; a `<` that can't start a marker is
+/// passed through untouched. Leading whitespace is skipped. The
+/// Thought/Action/Observation clean-up needs the whole answer, so it only
+/// applies to non-streamed responses.
+#[derive(Default)]
+pub struct StreamCleaner {
+    /// Text that may be the start of a marker, held back until it's decided
+    pending: String,
+    in_think: bool,
+    started: bool,
+}
+
+impl StreamCleaner {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Clean the next delta; returns the text that can be shown now
+    pub fn push(&mut self, delta: &str) -> String {
+        let mut out = String::with_capacity(delta.len());
+        for ch in delta.chars() {
+            self.push_char(ch, &mut out);
         }
+        out
     }
-    cleaned = result;
 
-    cleaned.trim().to_string()
+    /// Release text held back as a possible marker once the stream has ended
+    pub fn finish(&mut self) -> String {
+        let mut out = String::new();
+        let pending = std::mem::take(&mut self.pending);
+        let mut chars = pending.chars();
+        // The first char is a '<' that turned out not to start a marker;
+        // the rest may still hold one
+        if let Some(first) = chars.next() {
+            self.emit(first, &mut out);
+            for ch in chars {
+                self.push_char(ch, &mut out);
+            }
+            out.push_str(&self.finish());
+        }
+        out
+    }
+
+    fn push_char(&mut self, ch: char, out: &mut String) {
+        if self.pending.is_empty() && ch != '<' {
+            self.emit(ch, out);
+            return;
+        }
+        self.pending.push(ch);
+
+        if let Some(marker) = INTERNAL_MARKERS.iter().find(|m| **m == self.pending) {
+            match *marker {
+                THINK_OPEN => self.in_think = true,
+                THINK_CLOSE => self.in_think = false,
+                _ => {}
+            }
+            self.pending.clear();
+        } else if !INTERNAL_MARKERS
+            .iter()
+            .any(|m| m.starts_with(&self.pending))
+        {
+            // Not a marker after all: emit the '<' and rescan the rest
+            let rest = self.pending.split_off(1);
+            let lt = self.pending.pop().unwrap_or('<');
+            self.emit(lt, out);
+            for ch in rest.chars() {
+                self.push_char(ch, out);
+            }
+        }
+    }
+
+    fn emit(&mut self, ch: char, out: &mut String) {
+        if self.in_think || (!self.started && ch.is_whitespace()) {
+            return;
+        }
+        self.started = true;
+        out.push(ch);
+    }
+}
+
+/// Compose the final system prompt from the generated tool prompt and per-request settings.
+///
+/// `system_prompt_override` replaces the generated prompt entirely (including tool
+/// instructions); otherwise `system_prompt_prefix` is prepended to it. Blank values are ignored.
+pub fn compose_system_prompt(
+    generated: String,
+    prefix: Option<&str>,
+    override_prompt: Option<&str>,
+) -> String {
+    if let Some(override_prompt) = override_prompt.filter(|p| !p.trim().is_empty()) {
+        return override_prompt.to_string();
+    }
+
+    match prefix.filter(|p| !p.trim().is_empty()) {
+        Some(prefix) => format!("{}\n\n{}", prefix.trim_end(), generated),
+        None => generated,
+    }
+}
+
+/// Response header carrying the id of the chat request for log correlation
+pub const REQUEST_ID_HEADER: &str = "X-Request-Id";
+
+/// Short random id identifying one chat request in the logs
+pub fn new_request_id() -> String {
+    uuid::Uuid::new_v4().simple().to_string()[..8].to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stream_cleaner_strips_think_tag_split_across_chunks() {
+        let mut cleaner = StreamCleaner::new();
+        let chunks = [
+            "<thi",
+            "nk>Let me ",
+            "add.</th",
+            "ink>\nThe answer",
+            " is 4. Done",
+        ];
+        let streamed: String = chunks.iter().map(|c| cleaner.push(c)).collect();
+        assert_eq!(streamed, "The answer is 4. Done");
+        assert_eq!(streamed, clean_response(&chunks.concat()));
+    }
+
+    #[test]
+    fn test_clean_response_keeps_angle_brackets_in_prose() {
+        assert_eq!(clean_response("1 < 2 and 3 > 2"), "1 < 2 and 3 > 2");
+        assert_eq!(
+            clean_response("<think>compare them</think>Yes: a < b > c, see `Vec<String>`"),
+            "Yes: a < b > c, see `Vec<String>`"
+        );
+        assert_eq!(clean_response("x <thin"), "x <thin");
+    }
+
+    #[test]
+    fn test_stream_cleaner_releases_held_back_text() {
+        let mut cleaner = StreamCleaner::new();
+        assert_eq!(cleaner.push("if a <"), "if a ");
+        assert_eq!(cleaner.push("th"), "");
+        // "<thx" can't be a marker any more
+        assert_eq!(cleaner.push("x"), "<thx");
+        assert_eq!(cleaner.push(" <"), " ");
+        assert_eq!(cleaner.finish(), "<");
+    }
+
+    #[test]
+    fn test_stream_cleaner_strips_tool_markers() {
+        let mut cleaner = StreamCleaner::new();
+        let mut streamed = cleaner.push("Checking<｜tool▁calls");
+        streamed.push_str(&cleaner.push("▁begin｜> now"));
+        assert_eq!(streamed, "Checking now");
+    }
+
+    #[test]
+    fn test_new_request_id() {
+        let id = new_request_id();
+        assert_eq!(id.len(), 8);
+        assert!(id.chars().all(|c| c.is_ascii_hexdigit()));
+        assert_ne!(id, new_request_id());
+    }
+
+    #[test]
+    fn test_compose_system_prompt_default() {
+        assert_eq!(
+            compose_system_prompt("generated".to_string(), None, None),
+            "generated"
+        );
+    }
+
+    #[test]
+    fn test_compose_system_prompt_prefix() {
+        assert_eq!(
+            compose_system_prompt("generated".to_string(), Some("You are a pirate.\n"), None),
+            "You are a pirate.\n\ngenerated"
+        );
+        assert_eq!(
+            compose_system_prompt("generated".to_string(), Some("  "), None),
+            "generated"
+        );
+    }
+
+    #[test]
+    fn test_compose_system_prompt_override_wins() {
+        assert_eq!(
+            compose_system_prompt("generated".to_string(), Some("prefix"), Some("custom")),
+            "custom"
+        );
+    }
 }
@@ -0,0 +1,193 @@
+use crate::api::agent::core::types::ToolType;
+use crate::api::agent::service::config::AgentConfigHandle;
+use crate::api::agent::tools::framework::agent_tool::{AgentTool, ToolCategory};
+use crate::api::agent::tools::{self, framework::registry::ToolRegistry};
+use crate::api::model_notes::ModelNotesStorage;
+use actix_web::{get, web, HttpResponse, Result as ActixResult};
+use futures::future::join_all;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Upper bound for a single tool's connectivity check
+const CONNECTIVITY_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Deserialize)]
+pub struct ValidateToolsQuery {
+    /// Also probe each tool's upstream service
+    #[serde(default)]
+    pub deep: bool,
+}
+
+/// Whether an environment variable a tool needs is set
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EnvVarStatus {
+    pub name: String,
+    pub set: bool,
+}
+
+/// Result of a tool's connectivity check
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConnectivityStatus {
+    pub ok: bool,
+    pub error: Option<String>,
+}
+
+/// Pre-flight report for one enabled tool
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ToolValidation {
+    pub id: String,
+    pub name: String,
+    pub tool_type: ToolType,
+    pub category: ToolCategory,
+    pub available: bool,
+    pub env_vars: Vec<EnvVarStatus>,
+    /// Only present for `deep=true`, and only for tools that have a check
+    pub connectivity: Option<ConnectivityStatus>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ToolValidationResponse {
+    /// True when every enabled tool is available and reachable
+    pub ok: bool,
+    pub deep: bool,
+    pub tools: Vec<ToolValidation>,
+}
+
+async fn validate_tool(tool: Arc<dyn AgentTool>, available: bool, deep: bool) -> ToolValidation {
+    let meta = tool.metadata();
+    let env_vars = tool
+        .required_env_vars()
+        .iter()
+        .map(|name| EnvVarStatus {
+            name: name.to_string(),
+            set: std::env::var(name).is_ok_and(|v| !v.is_empty()),
+        })
+        .collect();
+
+    // Probing an unavailable tool would only repeat what `available` already says
+    let connectivity = if deep && available {
+        match tokio::time::timeout(CONNECTIVITY_TIMEOUT, tool.check_connectivity()).await {
+            Ok(None) => None,
+            Ok(Some(Ok(()))) => Some(ConnectivityStatus {
+                ok: true,
+                error: None,
+            }),
+            Ok(Some(Err(e))) => Some(ConnectivityStatus {
+                ok: false,
+                error: Some(e.to_string()),
+            }),
+            Err(_) => Some(ConnectivityStatus {
+                ok: false,
+                error: Some("Connectivity check timed out".to_string()),
+            }),
+        }
+    } else {
+        None
+    };
+
+    ToolValidation {
+        id: meta.id.clone(),
+        name: meta.name.clone(),
+        tool_type: meta.tool_type.clone(),
+        category: meta.category,
+        available,
+        env_vars,
+        connectivity,
+    }
+}
+
+/// Pre-flight check of the tools enabled in the current agent configuration.
+/// Reports availability and required env vars; `?deep=true` also probes each
+/// tool's upstream service.
+#[get("/api/agent/tools/validate")]
+pub async fn validate_tools(
+    query: web::Query<ValidateToolsQuery>,
+    agent_config: web::Data<AgentConfigHandle>,
+    chroma_address: Option<web::Data<String>>,
+    model_notes: Option<web::Data<Arc<ModelNotesStorage>>>,
+) -> ActixResult<HttpResponse> {
+    let config = agent_config.lock().unwrap().clone();
+
+    let context = tools::RegisterContext {
+        chroma_address: chroma_address.as_ref().map(|a| a.as_str()),
+        model_notes: model_notes.as_ref().map(|m| Arc::clone(m.get_ref())),
+    };
+
+    // Registration is what decides availability, so go through the registry
+    let mut registry = ToolRegistry::new();
+    tools::register_all(&mut registry, &config, &context);
+
+    let checks = registry
+        .get_all_tools()
+        .into_iter()
+        .map(|tool| validate_tool(tool, true, query.deep))
+        .chain(
+            registry
+                .get_unavailable_tools()
+                .into_iter()
+                .map(|tool| validate_tool(tool, false, query.deep)),
+        );
+    let mut results = join_all(checks).await;
+    results.sort_by(|a, b| a.id.cmp(&b.id));
+
+    let ok = results
+        .iter()
+        .all(|t| t.available && t.connectivity.as_ref().is_none_or(|c| c.ok));
+
+    println!(
+        "🩺 Validated {} tool(s) (deep={}): {}",
+        results.len(),
+        query.deep,
+        if ok { "all ok" } else { "problems found" }
+    );
+
+    Ok(HttpResponse::Ok().json(ToolValidationResponse {
+        ok,
+        deep: query.deep,
+        tools: results,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::agent::core::types::AgentConfig;
+    use actix_web::{test, App};
+    use std::sync::Mutex;
+
+    #[actix_web::test]
+    async fn test_validate_tools_reports_enabled_tools() {
+        let agent_config: AgentConfigHandle = Arc::new(Mutex::new(AgentConfig {
+            enabled_tools: vec![ToolType::GitHubPublic, ToolType::Stock],
+            ..AgentConfig::default()
+        }));
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(agent_config))
+                .service(validate_tools),
+        )
+        .await;
+
+        let req = test::TestRequest::get()
+            .uri("/api/agent/tools/validate")
+            .to_request();
+        let resp: ToolValidationResponse = test::call_and_read_body_json(&app, req).await;
+        assert!(!resp.deep);
+
+        let github = resp.tools.iter().find(|t| t.id == "github_public").unwrap();
+        assert!(github.available);
+        assert!(github.env_vars.is_empty());
+        assert!(github.connectivity.is_none());
+
+        // Listed whether or not the key is set, with availability matching the env var
+        let stock = resp
+            .tools
+            .iter()
+            .find(|t| t.tool_type == ToolType::Stock)
+            .unwrap();
+        assert_eq!(stock.env_vars.len(), 1);
+        assert_eq!(stock.env_vars[0].name, "ALPHA_ADVANTAGE_KEY");
+        assert_eq!(stock.available, stock.env_vars[0].set);
+    }
+}
@@ -1,21 +1,57 @@
 use actix_web::{web, web::Payload, Error, HttpRequest, HttpResponse};
 use actix_ws::{Message, Session};
-use std::collections::HashMap;
+use serde::Deserialize;
+use std::collections::{HashMap, VecDeque};
 use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 use tokio::sync::mpsc;
 use uuid::Uuid;
 
 use crate::api::agent::core::types::AgentStreamEvent;
+use crate::api::metrics::registry as metrics;
+
+/// Maximum number of events kept per conversation for replay on resume
+pub const EVENT_BUFFER_CAPACITY: usize = 500;
+
+/// How often the server pings each agent WebSocket client
+pub const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Clients that send nothing (not even a pong) for this long are dropped
+pub const CLIENT_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Whether a connection's heartbeat tick should keep the client or close it.
+/// Each connection only checks its own client: closed once it has been silent
+/// longer than `CLIENT_TIMEOUT` or was removed from the state elsewhere.
+fn heartbeat_expired(
+    state: &AgentWebSocketState,
+    client_id: &str,
+    last_heartbeat: Instant,
+    now: Instant,
+) -> bool {
+    !state.is_connected(client_id) || now.saturating_duration_since(last_heartbeat) > CLIENT_TIMEOUT
+}
+
+/// Messages a client can send over the agent WebSocket
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type")]
+enum ClientMessage {
+    /// Replay buffered events of an in-flight generation after reconnecting
+    #[serde(rename = "resume")]
+    Resume { conversation_id: String },
+}
 
 #[derive(Clone)]
 pub struct AgentWebSocketState {
     pub clients: Arc<Mutex<HashMap<String, mpsc::UnboundedSender<String>>>>,
+    /// Serialized events of in-flight generations, keyed by conversation id
+    pub event_buffers: Arc<Mutex<HashMap<String, VecDeque<String>>>>,
 }
 
 impl AgentWebSocketState {
     pub fn new() -> Self {
         Self {
             clients: Arc::new(Mutex::new(HashMap::new())),
+            event_buffers: Arc::new(Mutex::new(HashMap::new())),
         }
     }
 
@@ -28,15 +64,38 @@ impl AgentWebSocketState {
 
     pub fn remove_client(&self, client_id: &str) {
         let mut clients = self.clients.lock().unwrap();
-        clients.remove(client_id);
-        println!("📡 Agent WebSocket client disconnected: {}", client_id);
+        if clients.remove(client_id).is_some() {
+            println!("📡 Agent WebSocket client disconnected: {}", client_id);
+        }
     }
 
-    pub fn broadcast(&self, event: &AgentStreamEvent) {
-        let clients = self.clients.lock().unwrap();
+    /// Whether `client_id` still receives broadcasts
+    pub fn is_connected(&self, client_id: &str) -> bool {
+        self.clients.lock().unwrap().contains_key(client_id)
+    }
+
+    pub fn broadcast(&self, conversation_id: &str, event: &AgentStreamEvent) {
         // Send event directly as JSON (no wrapper needed, frontend handles AgentStreamEvent directly)
         let message = serde_json::to_string(event).unwrap_or_else(|_| "{}".to_string());
 
+        // Hold the buffer lock while sending so a concurrent resume can't miss
+        // or duplicate this event
+        let mut buffers = self.event_buffers.lock().unwrap();
+        let finished = matches!(
+            event,
+            AgentStreamEvent::Done { .. } | AgentStreamEvent::Error { .. }
+        );
+        if finished {
+            // The generation is over, nothing left to resume
+            buffers.remove(conversation_id);
+        } else {
+            let buffer = buffers.entry(conversation_id.to_string()).or_default();
+            if buffer.len() >= EVENT_BUFFER_CAPACITY {
+                buffer.pop_front();
+            }
+            buffer.push_back(message.clone());
+        }
+
         // Debug: log tool call events
         match event {
             AgentStreamEvent::ToolCall { tool_name, .. } => {
@@ -48,12 +107,37 @@ impl AgentWebSocketState {
             _ => {}
         }
 
+        let clients = self.clients.lock().unwrap();
         for (client_id, tx) in clients.iter() {
             if tx.send(message.clone()).is_err() {
                 println!("⚠️ Failed to send to client {}", client_id);
             }
         }
     }
+
+    /// Send the buffered events of a conversation to a single client.
+    /// Returns how many events were replayed.
+    pub fn replay(&self, client_id: &str, conversation_id: &str) -> usize {
+        let buffers = self.event_buffers.lock().unwrap();
+        let Some(buffer) = buffers.get(conversation_id) else {
+            return 0;
+        };
+
+        let clients = self.clients.lock().unwrap();
+        let Some(tx) = clients.get(client_id) else {
+            return 0;
+        };
+
+        buffer
+            .iter()
+            .take_while(|message| tx.send((*message).clone()).is_ok())
+            .count()
+    }
+
+    /// Drop any buffered events for a conversation
+    pub fn clear_buffer(&self, conversation_id: &str) {
+        self.event_buffers.lock().unwrap().remove(conversation_id);
+    }
 }
 
 impl Default for AgentWebSocketState {
@@ -65,7 +149,7 @@ impl Default for AgentWebSocketState {
 // WebSocket handler for agent streaming
 pub async fn agent_ws(
     state: web::Data<Arc<AgentWebSocketState>>,
-    session: Session,
+    mut session: Session,
     mut msg_stream: actix_ws::MessageStream,
 ) {
     let client_id = Uuid::new_v4().to_string();
@@ -73,6 +157,7 @@ pub async fn agent_ws(
 
     // Add client
     state.add_client(client_id.clone(), tx);
+    metrics::record(|m| m.active_websockets.inc());
 
     // Clone session for sending messages
     let mut session_sender = session.clone();
@@ -86,13 +171,44 @@ pub async fn agent_ws(
         }
     });
 
-    // Handle incoming messages
-    while let Some(Ok(msg)) = msg_stream.recv().await {
-        match msg {
-            Message::Text(_text) => {
-                // Client can send messages (e.g., to request status)
-                // For now, we just ignore client messages
+    let mut heartbeat = tokio::time::interval(HEARTBEAT_INTERVAL);
+    let mut last_heartbeat = Instant::now();
+
+    // Handle incoming messages and ping the client until it leaves or goes quiet
+    loop {
+        let msg = tokio::select! {
+            _ = heartbeat.tick() => {
+                if heartbeat_expired(&state, &client_id, last_heartbeat, Instant::now()) {
+                    println!("💀 Agent WebSocket client {} timed out", client_id);
+                    let _ = session.close(None).await;
+                    break;
+                }
+                if session.ping(b"").await.is_err() {
+                    break;
+                }
+                continue;
             }
+            msg = msg_stream.recv() => match msg {
+                Some(Ok(msg)) => msg,
+                _ => break,
+            },
+        };
+
+        last_heartbeat = Instant::now();
+        match msg {
+            Message::Ping(bytes) if session.pong(&bytes).await.is_err() => break,
+            Message::Text(text) => match serde_json::from_str::<ClientMessage>(&text) {
+                Ok(ClientMessage::Resume { conversation_id }) => {
+                    let replayed = state.replay(&client_id, &conversation_id);
+                    println!(
+                        "📡 Replayed {} events of {} to client {}",
+                        replayed, conversation_id, client_id
+                    );
+                }
+                Err(_) => {
+                    // Ignore anything we don't understand
+                }
+            },
             Message::Close(_) => {
                 break;
             }
@@ -102,6 +218,7 @@ pub async fn agent_ws(
 
     // Remove client on disconnect
     state.remove_client(&client_id);
+    metrics::record(|m| m.active_websockets.dec());
 }
 
 // HTTP handler for agent WebSocket
@@ -115,3 +232,106 @@ pub async fn agent_websocket(
     actix_rt::spawn(agent_ws(state_clone, session, msg_stream));
     Ok(res)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chunk(text: &str) -> AgentStreamEvent {
+        AgentStreamEvent::TextChunk {
+            text: text.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_replay_buffered_events() {
+        let state = AgentWebSocketState::new();
+        state.broadcast("conv-1", &chunk("Hello"));
+        state.broadcast("conv-1", &chunk(" world"));
+        state.broadcast("conv-2", &chunk("Other"));
+
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        state.add_client("late".to_string(), tx);
+
+        assert_eq!(state.replay("late", "conv-1"), 2);
+        assert!(rx.try_recv().unwrap().contains("Hello"));
+        assert!(rx.try_recv().unwrap().contains(" world"));
+        assert!(rx.try_recv().is_err());
+
+        assert_eq!(state.replay("late", "unknown"), 0);
+        assert_eq!(state.replay("missing-client", "conv-1"), 0);
+    }
+
+    #[test]
+    fn test_buffer_is_capped() {
+        let state = AgentWebSocketState::new();
+        for i in 0..EVENT_BUFFER_CAPACITY + 10 {
+            state.broadcast("conv-1", &chunk(&i.to_string()));
+        }
+
+        let buffers = state.event_buffers.lock().unwrap();
+        let buffer = &buffers["conv-1"];
+        assert_eq!(buffer.len(), EVENT_BUFFER_CAPACITY);
+        assert!(buffer.front().unwrap().contains("\"10\""));
+    }
+
+    #[test]
+    fn test_buffer_expires_when_generation_finishes() {
+        let state = AgentWebSocketState::new();
+        state.broadcast("conv-1", &chunk("partial"));
+        state.broadcast(
+            "conv-1",
+            &AgentStreamEvent::Error {
+                message: "boom".to_string(),
+            },
+        );
+
+        assert!(state.event_buffers.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_heartbeat_expires_only_silent_client() {
+        let state = AgentWebSocketState::new();
+        let (quiet_tx, _quiet_rx) = mpsc::unbounded_channel();
+        let (active_tx, mut active_rx) = mpsc::unbounded_channel();
+        state.add_client("quiet".to_string(), quiet_tx);
+        state.add_client("active".to_string(), active_tx);
+
+        let start = Instant::now();
+        assert!(!heartbeat_expired(&state, "quiet", start, start));
+
+        // "active" answered a ping just now, "quiet" hasn't been heard from since start
+        let now = start + CLIENT_TIMEOUT + Duration::from_secs(1);
+        assert!(heartbeat_expired(&state, "quiet", start, now));
+        assert!(!heartbeat_expired(&state, "active", now, now));
+
+        // Checking one connection leaves the other's subscription alone
+        assert!(state.is_connected("quiet"));
+        state.broadcast("conv-1", &chunk("still here"));
+        assert!(active_rx.try_recv().unwrap().contains("still here"));
+    }
+
+    #[test]
+    fn test_heartbeat_expires_client_removed_elsewhere() {
+        let state = AgentWebSocketState::new();
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        state.add_client("evicted".to_string(), tx);
+
+        // Removed by something other than its own connection loop
+        state.remove_client("evicted");
+
+        // Its next tick closes the connection even though it was heard from just now
+        let now = Instant::now();
+        assert!(heartbeat_expired(&state, "evicted", now, now));
+        assert!(rx.try_recv().is_err());
+        assert!(rx.is_closed());
+    }
+
+    #[test]
+    fn test_parse_resume_message() {
+        let message: ClientMessage =
+            serde_json::from_str(r#"{"type":"resume","conversation_id":"conv-1"}"#).unwrap();
+        let ClientMessage::Resume { conversation_id } = message;
+        assert_eq!(conversation_id, "conv-1");
+    }
+}
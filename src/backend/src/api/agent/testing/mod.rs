@@ -1,4 +1,5 @@
 pub mod routes;
+pub mod runner;
 pub mod storage;
 
 #[cfg(test)]
@@ -1,6 +1,17 @@
-use crate::api::agent::testing::storage::TestingStorage;
-use actix_web::{delete, get, post, put, web, HttpResponse, Responder};
-use serde::Deserialize;
+use crate::api::agent::core::types::AgentConfig;
+use crate::api::agent::memory::sqlite_memory::SqliteConversationMemory;
+use crate::api::agent::testing::runner::AgentHarness;
+use crate::api::agent::testing::storage::{TestRun, TestingStorage};
+use crate::api::agent::tools::framework::cache::ToolResultCache;
+use crate::api::error::ApiError;
+use crate::api::llama_server::types::Config;
+use crate::api::model_notes::ModelNotesStorage;
+use actix_web::{delete, get, post, put, web, HttpResponse, Responder, ResponseError};
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex};
+
+/// Runs returned by `GET /questions/{id}/runs` unless `limit` is given
+const DEFAULT_RUNS_LIMIT: usize = 20;
 
 #[derive(Deserialize)]
 pub struct CreateSuiteRequest {
@@ -17,20 +28,54 @@ pub struct UpdateSuiteRequest {
 #[derive(Deserialize)]
 pub struct AddQuestionRequest {
     pub content: String,
+    #[serde(default)]
+    pub expected_substring: Option<String>,
+    #[serde(default)]
+    pub expected_tools: Vec<String>,
 }
 
 #[derive(Deserialize)]
 pub struct UpdateQuestionRequest {
     pub content: String,
+    #[serde(default)]
+    pub expected_substring: Option<String>,
+    #[serde(default)]
+    pub expected_tools: Vec<String>,
+}
+
+#[derive(Deserialize)]
+pub struct RunsQuery {
+    pub limit: Option<usize>,
+}
+
+/// Result of running every question in a suite
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SuiteRunSummary {
+    pub suite_id: String,
+    pub total: usize,
+    pub passed: usize,
+    pub failed: usize,
+    pub runs: Vec<TestRun>,
+}
+
+impl SuiteRunSummary {
+    pub fn new(suite_id: String, runs: Vec<TestRun>) -> Self {
+        let passed = runs.iter().filter(|run| run.passed).count();
+        Self {
+            suite_id,
+            total: runs.len(),
+            passed,
+            failed: runs.len() - passed,
+            runs,
+        }
+    }
 }
 
 #[get("/suites")]
 pub async fn get_suites(storage: web::Data<TestingStorage>) -> impl Responder {
     match storage.get_suites().await {
         Ok(suites) => HttpResponse::Ok().json(suites),
-        Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({
-            "error": e.to_string()
-        })),
+        Err(e) => ApiError::from(e).error_response(),
     }
 }
 
@@ -42,9 +87,7 @@ pub async fn create_suite(
     let req = req.into_inner();
     match storage.create_suite(req.name, req.description).await {
         Ok(suite) => HttpResponse::Ok().json(suite),
-        Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({
-            "error": e.to_string()
-        })),
+        Err(e) => ApiError::from(e).error_response(),
     }
 }
 
@@ -57,9 +100,7 @@ pub async fn update_suite(
     let req = req.into_inner();
     match storage.update_suite(&id, req.name, req.description).await {
         Ok(_) => HttpResponse::Ok().json(serde_json::json!({ "success": true })),
-        Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({
-            "error": e.to_string()
-        })),
+        Err(e) => ApiError::from(e).error_response(),
     }
 }
 
@@ -70,9 +111,7 @@ pub async fn delete_suite(
 ) -> impl Responder {
     match storage.delete_suite(&id).await {
         Ok(_) => HttpResponse::Ok().json(serde_json::json!({ "success": true })),
-        Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({
-            "error": e.to_string()
-        })),
+        Err(e) => ApiError::from(e).error_response(),
     }
 }
 
@@ -83,9 +122,7 @@ pub async fn get_questions(
 ) -> impl Responder {
     match storage.get_questions(&id).await {
         Ok(questions) => HttpResponse::Ok().json(questions),
-        Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({
-            "error": e.to_string()
-        })),
+        Err(e) => ApiError::from(e).error_response(),
     }
 }
 
@@ -95,11 +132,13 @@ pub async fn add_question(
     id: web::Path<String>,
     req: web::Json<AddQuestionRequest>,
 ) -> impl Responder {
-    match storage.add_question(&id, req.into_inner().content).await {
+    let req = req.into_inner();
+    match storage
+        .add_question(&id, req.content, req.expected_substring, req.expected_tools)
+        .await
+    {
         Ok(question) => HttpResponse::Ok().json(question),
-        Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({
-            "error": e.to_string()
-        })),
+        Err(e) => ApiError::from(e).error_response(),
     }
 }
 
@@ -109,11 +148,13 @@ pub async fn update_question(
     id: web::Path<i64>,
     req: web::Json<UpdateQuestionRequest>,
 ) -> impl Responder {
-    match storage.update_question(*id, req.into_inner().content).await {
+    let req = req.into_inner();
+    match storage
+        .update_question(*id, req.content, req.expected_substring, req.expected_tools)
+        .await
+    {
         Ok(_) => HttpResponse::Ok().json(serde_json::json!({ "success": true })),
-        Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({
-            "error": e.to_string()
-        })),
+        Err(e) => ApiError::from(e).error_response(),
     }
 }
 
@@ -124,8 +165,92 @@ pub async fn delete_question(
 ) -> impl Responder {
     match storage.delete_question(*id).await {
         Ok(_) => HttpResponse::Ok().json(serde_json::json!({ "success": true })),
-        Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({
-            "error": e.to_string()
-        })),
+        Err(e) => ApiError::from(e).error_response(),
+    }
+}
+
+/// Run one question against the live agent and record the result
+#[post("/questions/{id}/run")]
+#[allow(clippy::too_many_arguments)]
+pub async fn run_question(
+    storage: web::Data<TestingStorage>,
+    id: web::Path<i64>,
+    agent_config: web::Data<Arc<Mutex<AgentConfig>>>,
+    llama_config: web::Data<Arc<Mutex<Config>>>,
+    chroma_address: web::Data<String>,
+    sqlite_memory: web::Data<Arc<SqliteConversationMemory>>,
+    tool_cache: web::Data<Arc<ToolResultCache>>,
+    model_notes: web::Data<Arc<ModelNotesStorage>>,
+) -> Result<HttpResponse, ApiError> {
+    let question = storage
+        .get_question(*id)
+        .await?
+        .ok_or_else(|| ApiError::NotFound(format!("Question {} not found", id)))?;
+
+    let harness = AgentHarness::new(
+        &agent_config,
+        &llama_config,
+        &chroma_address,
+        &sqlite_memory,
+        &tool_cache,
+        &model_notes,
+    );
+    let run = harness.run(&storage, &question).await?;
+    Ok(HttpResponse::Ok().json(run))
+}
+
+/// Run every question in a suite, one after another, and summarize
+#[post("/suites/{id}/run")]
+#[allow(clippy::too_many_arguments)]
+pub async fn run_suite(
+    storage: web::Data<TestingStorage>,
+    id: web::Path<String>,
+    agent_config: web::Data<Arc<Mutex<AgentConfig>>>,
+    llama_config: web::Data<Arc<Mutex<Config>>>,
+    chroma_address: web::Data<String>,
+    sqlite_memory: web::Data<Arc<SqliteConversationMemory>>,
+    tool_cache: web::Data<Arc<ToolResultCache>>,
+    model_notes: web::Data<Arc<ModelNotesStorage>>,
+) -> Result<HttpResponse, ApiError> {
+    let questions = storage.get_questions(&id).await?;
+    if questions.is_empty() {
+        return Err(ApiError::NotFound(format!(
+            "Suite {} has no questions to run",
+            id
+        )));
+    }
+
+    let harness = AgentHarness::new(
+        &agent_config,
+        &llama_config,
+        &chroma_address,
+        &sqlite_memory,
+        &tool_cache,
+        &model_notes,
+    );
+    let mut runs = Vec::with_capacity(questions.len());
+    for question in &questions {
+        runs.push(harness.run(&storage, question).await?);
+    }
+
+    let summary = SuiteRunSummary::new(id.into_inner(), runs);
+    println!(
+        "🧪 Suite {}: {}/{} passed",
+        summary.suite_id, summary.passed, summary.total
+    );
+    Ok(HttpResponse::Ok().json(summary))
+}
+
+/// Recorded runs of a question, newest first
+#[get("/questions/{id}/runs")]
+pub async fn get_runs(
+    storage: web::Data<TestingStorage>,
+    id: web::Path<i64>,
+    query: web::Query<RunsQuery>,
+) -> impl Responder {
+    let limit = query.limit.unwrap_or(DEFAULT_RUNS_LIMIT);
+    match storage.get_runs(*id, limit).await {
+        Ok(runs) => HttpResponse::Ok().json(runs),
+        Err(e) => ApiError::from(e).error_response(),
     }
 }
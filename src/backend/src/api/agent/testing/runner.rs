@@ -0,0 +1,191 @@
+use crate::api::agent::core::agent_loop::{execute_agent_loop, AgentLoopConfig};
+use crate::api::agent::core::types::{AgentConfig, ChatMessage, MessageContent, MessageRole};
+use crate::api::agent::core::utils::{model_supports_tool_role, model_supports_vision};
+use crate::api::agent::memory::sqlite_memory::SqliteConversationMemory;
+use crate::api::agent::service::chat::model_loop_defaults;
+use crate::api::agent::service::utils::{clean_response, new_request_id};
+use crate::api::agent::testing::storage::{TestQuestion, TestRun, TestingStorage};
+use crate::api::agent::tools::{
+    self,
+    framework::{cache::ToolResultCache, registry::ToolRegistry, selector::ToolSelector},
+};
+use crate::api::llama_server::types::Config;
+use crate::api::model_notes::ModelNotesStorage;
+use anyhow::{Context, Result};
+use reqwest::Client;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+/// What the agent answered to a test prompt
+pub struct AgentAnswer {
+    pub output: String,
+    /// Function names of the tools called, in order
+    pub tool_calls: Vec<String>,
+}
+
+/// Everything needed to put a prompt to the agent the way `/api/agent/chat` does,
+/// snapshotted from app state when a test run starts
+pub struct AgentHarness {
+    pub config: AgentConfig,
+    pub model_name: String,
+    pub llama_base_url: String,
+    pub chroma_address: String,
+    pub sqlite_memory: Arc<SqliteConversationMemory>,
+    pub tool_cache: Arc<ToolResultCache>,
+    pub model_notes: Arc<ModelNotesStorage>,
+}
+
+impl AgentHarness {
+    /// Snapshot of the live agent setup (agent config, loaded model, tools)
+    pub fn new(
+        agent_config: &Arc<Mutex<AgentConfig>>,
+        llama_config: &Arc<Mutex<Config>>,
+        chroma_address: &str,
+        sqlite_memory: &Arc<SqliteConversationMemory>,
+        tool_cache: &Arc<ToolResultCache>,
+        model_notes: &Arc<ModelNotesStorage>,
+    ) -> Self {
+        let (model_name, llama_base_url) = {
+            let llama_config = llama_config.lock().unwrap();
+            (llama_config.hf_model.clone(), llama_config.llm_base_url())
+        };
+        Self {
+            config: agent_config.lock().unwrap().clone(),
+            model_name,
+            llama_base_url,
+            chroma_address: chroma_address.to_string(),
+            sqlite_memory: Arc::clone(sqlite_memory),
+            tool_cache: Arc::clone(tool_cache),
+            model_notes: Arc::clone(model_notes),
+        }
+    }
+
+    /// Ask `question` in a throwaway conversation, check the answer against its
+    /// expectations and record the run. Agent errors are recorded as a failed run.
+    pub async fn run(&self, storage: &TestingStorage, question: &TestQuestion) -> Result<TestRun> {
+        let started = Instant::now();
+        let (output, tool_calls, failures) = match self.ask(&question.content).await {
+            Ok(answer) => {
+                let failures = question.check(&answer.output, &answer.tool_calls);
+                (answer.output, answer.tool_calls, failures)
+            }
+            Err(e) => {
+                let error = format!("Agent failed: {}", e);
+                (error.clone(), Vec::new(), vec![error])
+            }
+        };
+
+        println!(
+            "🧪 Test question {} {}",
+            question.id,
+            if failures.is_empty() {
+                "passed"
+            } else {
+                "failed"
+            }
+        );
+        storage
+            .record_run(
+                question.id,
+                output,
+                tool_calls,
+                failures,
+                started.elapsed().as_millis() as i64,
+            )
+            .await
+    }
+
+    /// Put `prompt` to the agent in a throwaway conversation
+    pub async fn ask(&self, prompt: &str) -> Result<AgentAnswer> {
+        // Test prompts shouldn't show up in the chat history
+        let conversation_id = self
+            .sqlite_memory
+            .get_or_create_conversation_id(None, Some(&self.model_name))
+            .await
+            .context("Failed to create test conversation")?;
+
+        let answer = self.ask_in(prompt, &conversation_id).await;
+
+        if let Err(e) = self
+            .sqlite_memory
+            .delete_conversation(&conversation_id)
+            .await
+        {
+            println!(
+                "⚠️ Failed to delete test conversation {}: {}",
+                conversation_id, e
+            );
+        }
+        answer
+    }
+
+    async fn ask_in(&self, prompt: &str, conversation_id: &str) -> Result<AgentAnswer> {
+        let request_id = new_request_id();
+        let loop_config = AgentLoopConfig {
+            debug_logging: self.config.debug_logging,
+            tool_timeout_secs: self.config.tool_timeout_secs,
+            persist_tool_messages: self.config.persist_tool_messages,
+            supports_tool_role: self
+                .config
+                .llm_supports_tool_role
+                .unwrap_or_else(|| model_supports_tool_role(&self.model_name)),
+            supports_vision: self
+                .config
+                .llm_supports_vision
+                .unwrap_or_else(|| model_supports_vision(&self.model_name)),
+            request_id: request_id.clone(),
+            context_token_budget: self.config.context_token_budget,
+            agent_timeout_secs: self.config.agent_timeout_secs,
+            ..model_loop_defaults(&self.model_notes, &self.model_name, &request_id).await
+        };
+
+        let mut tool_registry = ToolRegistry::new();
+        let context = tools::RegisterContext {
+            chroma_address: Some(self.chroma_address.as_str()),
+            model_notes: Some(Arc::clone(&self.model_notes)),
+        };
+        tools::register_all(&mut tool_registry, &self.config, &context);
+        tool_registry.set_cache(Arc::clone(&self.tool_cache), self.config.cache_ttl_secs);
+        tool_registry.set_concurrency_limits(&self.config.tool_concurrency_limits);
+        let tool_registry = Arc::new(tool_registry);
+
+        let tools = tool_registry
+            .build_tool_definitions()
+            .context("Failed to build tool definitions")?;
+        let system_prompt = ToolSelector::new(Arc::clone(&tool_registry)).build_system_prompt();
+        let message = |role, text: String| ChatMessage {
+            role,
+            content: MessageContent::Text(text),
+            name: None,
+            tool_calls: None,
+            tool_call_id: None,
+            reasoning_content: None,
+        };
+        let messages = vec![
+            message(MessageRole::System, system_prompt),
+            message(MessageRole::User, prompt.to_string()),
+        ];
+
+        let result = execute_agent_loop(
+            &Client::new(),
+            &format!("{}/v1/chat/completions", self.llama_base_url),
+            self.model_name.clone(),
+            messages,
+            tools,
+            tool_registry,
+            Arc::clone(&self.sqlite_memory),
+            conversation_id.to_string(),
+            loop_config,
+        )
+        .await?;
+
+        Ok(AgentAnswer {
+            output: clean_response(&result.final_message),
+            tool_calls: result
+                .tool_calls
+                .into_iter()
+                .map(|call| call.tool_name)
+                .collect(),
+        })
+    }
+}
@@ -1,8 +1,52 @@
+use crate::utils::migrations::{run_migrations, Migration};
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
 use sqlx::{Row, SqlitePool};
 use uuid::Uuid;
 
+/// Testing schema; append new versions, never edit applied ones
+const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        name: "create test_suites and test_questions",
+        statements: &[
+            "CREATE TABLE IF NOT EXISTS test_suites (
+            id TEXT PRIMARY KEY,
+            name TEXT NOT NULL,
+            description TEXT,
+            created_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now'))
+        )",
+            "CREATE TABLE IF NOT EXISTS test_questions (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            suite_id TEXT NOT NULL,
+            content TEXT NOT NULL,
+            created_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now')),
+            FOREIGN KEY (suite_id) REFERENCES test_suites(id) ON DELETE CASCADE
+        )",
+        ],
+    },
+    Migration {
+        version: 2,
+        name: "add test expectations and test_runs",
+        statements: &[
+            "ALTER TABLE test_questions ADD COLUMN expected_substring TEXT",
+            "ALTER TABLE test_questions ADD COLUMN expected_tools TEXT NOT NULL DEFAULT '[]'",
+            "CREATE TABLE IF NOT EXISTS test_runs (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                question_id INTEGER NOT NULL,
+                passed INTEGER NOT NULL,
+                output TEXT NOT NULL,
+                tool_calls TEXT NOT NULL,
+                failures TEXT NOT NULL,
+                duration_ms INTEGER NOT NULL,
+                created_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now')),
+                FOREIGN KEY (question_id) REFERENCES test_questions(id) ON DELETE CASCADE
+            )",
+            "CREATE INDEX IF NOT EXISTS idx_test_runs_question ON test_runs(question_id, id)",
+        ],
+    },
+];
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct TestSuite {
     pub id: String,
@@ -15,10 +59,91 @@ pub struct TestSuite {
 pub struct TestQuestion {
     pub id: i64,
     pub suite_id: String,
+    /// Prompt sent to the agent
     pub content: String,
+    /// The answer must contain this text (case-insensitive)
+    #[serde(default)]
+    pub expected_substring: Option<String>,
+    /// Tools the agent must call (by function name) while answering
+    #[serde(default)]
+    pub expected_tools: Vec<String>,
     pub created_at: i64,
 }
 
+impl TestQuestion {
+    /// Reasons the agent's answer doesn't meet this question's expectations;
+    /// empty when it passes
+    pub fn check(&self, output: &str, tool_calls: &[String]) -> Vec<String> {
+        check_answer(
+            self.expected_substring.as_deref(),
+            &self.expected_tools,
+            output,
+            tool_calls,
+        )
+    }
+}
+
+/// Compare an agent answer with the expected text (case-insensitive) and tools,
+/// returning a reason for each unmet expectation
+pub fn check_answer(
+    expected_substring: Option<&str>,
+    expected_tools: &[String],
+    output: &str,
+    tool_calls: &[String],
+) -> Vec<String> {
+    let mut failures = Vec::new();
+    if let Some(expected) = expected_substring.filter(|e| !e.is_empty()) {
+        if !output.to_lowercase().contains(&expected.to_lowercase()) {
+            failures.push(format!("Answer does not contain '{}'", expected));
+        }
+    }
+    for tool in expected_tools {
+        if !tool_calls.iter().any(|called| called == tool) {
+            failures.push(format!("Tool '{}' was not called", tool));
+        }
+    }
+    failures
+}
+
+/// One recorded run of a test question against the live agent
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TestRun {
+    pub id: i64,
+    pub question_id: i64,
+    pub passed: bool,
+    /// The agent's final answer, or the error when the run failed
+    pub output: String,
+    /// Tools the agent called, in order
+    pub tool_calls: Vec<String>,
+    pub failures: Vec<String>,
+    pub duration_ms: i64,
+    pub created_at: i64,
+}
+
+fn question_from_row(row: &sqlx::sqlite::SqliteRow) -> TestQuestion {
+    TestQuestion {
+        id: row.get(0),
+        suite_id: row.get(1),
+        content: row.get(2),
+        expected_substring: row.get(3),
+        expected_tools: serde_json::from_str(row.get::<&str, _>(4)).unwrap_or_default(),
+        created_at: row.get(5),
+    }
+}
+
+fn run_from_row(row: &sqlx::sqlite::SqliteRow) -> TestRun {
+    TestRun {
+        id: row.get(0),
+        question_id: row.get(1),
+        passed: row.get::<i64, _>(2) != 0,
+        output: row.get(3),
+        tool_calls: serde_json::from_str(row.get::<&str, _>(4)).unwrap_or_default(),
+        failures: serde_json::from_str(row.get::<&str, _>(5)).unwrap_or_default(),
+        duration_ms: row.get(6),
+        created_at: row.get(7),
+    }
+}
+
 #[derive(Clone)]
 pub struct TestingStorage {
     pool: SqlitePool,
@@ -38,30 +163,9 @@ impl TestingStorage {
             .await
             .context("Failed to enable foreign keys")?;
 
-        sqlx::query(
-            "CREATE TABLE IF NOT EXISTS test_suites (
-                id TEXT PRIMARY KEY,
-                name TEXT NOT NULL,
-                description TEXT,
-                created_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now'))
-            )",
-        )
-        .execute(&self.pool)
-        .await
-        .context("Failed to create test_suites table")?;
-
-        sqlx::query(
-            "CREATE TABLE IF NOT EXISTS test_questions (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
-                suite_id TEXT NOT NULL,
-                content TEXT NOT NULL,
-                created_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now')),
-                FOREIGN KEY (suite_id) REFERENCES test_suites(id) ON DELETE CASCADE
-            )",
-        )
-        .execute(&self.pool)
-        .await
-        .context("Failed to create test_questions table")?;
+        run_migrations(&self.pool, "testing", MIGRATIONS)
+            .await
+            .context("Failed to migrate testing tables")?;
 
         Ok(())
     }
@@ -145,34 +249,44 @@ impl TestingStorage {
 
     pub async fn get_questions(&self, suite_id: &str) -> Result<Vec<TestQuestion>> {
         let rows = sqlx::query(
-            "SELECT id, suite_id, content, created_at FROM test_questions WHERE suite_id = ?1 ORDER BY created_at ASC",
+            "SELECT id, suite_id, content, expected_substring, expected_tools, created_at FROM test_questions WHERE suite_id = ?1 ORDER BY created_at ASC, id ASC",
         )
         .bind(suite_id)
         .fetch_all(&self.pool)
         .await
         .context("Failed to fetch test questions")?;
 
-        let questions = rows
-            .into_iter()
-            .map(|row| TestQuestion {
-                id: row.get(0),
-                suite_id: row.get(1),
-                content: row.get(2),
-                created_at: row.get(3),
-            })
-            .collect();
+        Ok(rows.iter().map(question_from_row).collect())
+    }
 
-        Ok(questions)
+    pub async fn get_question(&self, id: i64) -> Result<Option<TestQuestion>> {
+        let row = sqlx::query(
+            "SELECT id, suite_id, content, expected_substring, expected_tools, created_at FROM test_questions WHERE id = ?1",
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await
+        .context("Failed to fetch test question")?;
+
+        Ok(row.as_ref().map(question_from_row))
     }
 
-    pub async fn add_question(&self, suite_id: &str, content: String) -> Result<TestQuestion> {
+    pub async fn add_question(
+        &self,
+        suite_id: &str,
+        content: String,
+        expected_substring: Option<String>,
+        expected_tools: Vec<String>,
+    ) -> Result<TestQuestion> {
         let now = chrono::Utc::now().timestamp();
 
         let id = sqlx::query(
-            "INSERT INTO test_questions (suite_id, content, created_at) VALUES (?1, ?2, ?3) RETURNING id",
+            "INSERT INTO test_questions (suite_id, content, expected_substring, expected_tools, created_at) VALUES (?1, ?2, ?3, ?4, ?5) RETURNING id",
         )
         .bind(suite_id)
         .bind(&content)
+        .bind(&expected_substring)
+        .bind(serde_json::to_string(&expected_tools)?)
         .bind(now)
         .fetch_one(&self.pool)
         .await
@@ -183,17 +297,29 @@ impl TestingStorage {
             id,
             suite_id: suite_id.to_string(),
             content,
+            expected_substring,
+            expected_tools,
             created_at: now,
         })
     }
 
-    pub async fn update_question(&self, id: i64, content: String) -> Result<()> {
-        sqlx::query("UPDATE test_questions SET content = ?1 WHERE id = ?2")
-            .bind(content)
-            .bind(id)
-            .execute(&self.pool)
-            .await
-            .context("Failed to update question")?;
+    pub async fn update_question(
+        &self,
+        id: i64,
+        content: String,
+        expected_substring: Option<String>,
+        expected_tools: Vec<String>,
+    ) -> Result<()> {
+        sqlx::query(
+            "UPDATE test_questions SET content = ?1, expected_substring = ?2, expected_tools = ?3 WHERE id = ?4",
+        )
+        .bind(content)
+        .bind(expected_substring)
+        .bind(serde_json::to_string(&expected_tools)?)
+        .bind(id)
+        .execute(&self.pool)
+        .await
+        .context("Failed to update question")?;
         Ok(())
     }
 
@@ -205,4 +331,59 @@ impl TestingStorage {
             .context("Failed to delete question")?;
         Ok(())
     }
+
+    // --- Runs ---
+
+    /// Record the outcome of running `question_id` against the agent
+    pub async fn record_run(
+        &self,
+        question_id: i64,
+        output: String,
+        tool_calls: Vec<String>,
+        failures: Vec<String>,
+        duration_ms: i64,
+    ) -> Result<TestRun> {
+        let now = chrono::Utc::now().timestamp();
+        let passed = failures.is_empty();
+
+        let id = sqlx::query(
+            "INSERT INTO test_runs (question_id, passed, output, tool_calls, failures, duration_ms, created_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7) RETURNING id",
+        )
+        .bind(question_id)
+        .bind(passed as i64)
+        .bind(&output)
+        .bind(serde_json::to_string(&tool_calls)?)
+        .bind(serde_json::to_string(&failures)?)
+        .bind(duration_ms)
+        .bind(now)
+        .fetch_one(&self.pool)
+        .await
+        .context("Failed to record test run")?
+        .get::<i64, _>(0);
+
+        Ok(TestRun {
+            id,
+            question_id,
+            passed,
+            output,
+            tool_calls,
+            failures,
+            duration_ms,
+            created_at: now,
+        })
+    }
+
+    /// Most recent runs of a question, newest first
+    pub async fn get_runs(&self, question_id: i64, limit: usize) -> Result<Vec<TestRun>> {
+        let rows = sqlx::query(
+            "SELECT id, question_id, passed, output, tool_calls, failures, duration_ms, created_at FROM test_runs WHERE question_id = ?1 ORDER BY id DESC LIMIT ?2",
+        )
+        .bind(question_id)
+        .bind(limit as i64)
+        .fetch_all(&self.pool)
+        .await
+        .context("Failed to fetch test runs")?;
+
+        Ok(rows.iter().map(run_from_row).collect())
+    }
 }
@@ -1,4 +1,5 @@
-use super::storage::TestingStorage;
+use super::routes::SuiteRunSummary;
+use super::storage::{TestQuestion, TestingStorage};
 use sqlx::sqlite::SqlitePoolOptions;
 
 async fn setup_storage() -> TestingStorage {
@@ -76,7 +77,7 @@ async fn test_questions_crud() {
 
     // Add question
     let q1 = storage
-        .add_question(&suite.id, "Question 1".to_string())
+        .add_question(&suite.id, "Question 1".to_string(), None, Vec::new())
         .await
         .expect("Failed to add question");
 
@@ -93,7 +94,12 @@ async fn test_questions_crud() {
 
     // Update question
     storage
-        .update_question(q1.id, "Question 1 Updated".to_string())
+        .update_question(
+            q1.id,
+            "Question 1 Updated".to_string(),
+            Some("paris".to_string()),
+            vec!["weather_current".to_string()],
+        )
         .await
         .expect("Failed to update question");
 
@@ -102,6 +108,8 @@ async fn test_questions_crud() {
         .await
         .expect("Failed to get questions");
     assert_eq!(questions[0].content, "Question 1 Updated");
+    assert_eq!(questions[0].expected_substring.as_deref(), Some("paris"));
+    assert_eq!(questions[0].expected_tools, vec!["weather_current"]);
 
     // Delete question
     storage
@@ -126,7 +134,7 @@ async fn test_cascade_delete() {
         .expect("Failed to create suite");
 
     storage
-        .add_question(&suite.id, "Q1".to_string())
+        .add_question(&suite.id, "Q1".to_string(), None, Vec::new())
         .await
         .expect("Failed to add question");
 
@@ -145,3 +153,90 @@ async fn test_cascade_delete() {
     // For this simple implementation, manual deletion isn't implemented, so we rely on cascade.
     // If cascade fails here due to config, we catch it.
 }
+
+#[test]
+fn test_question_check() {
+    let question = TestQuestion {
+        id: 1,
+        suite_id: "suite".to_string(),
+        content: "What's the weather in Paris?".to_string(),
+        expected_substring: Some("Paris".to_string()),
+        expected_tools: vec!["weather_current".to_string()],
+        created_at: 0,
+    };
+
+    let called = vec!["weather_current".to_string()];
+    assert!(question
+        .check("It is sunny in paris today", &called)
+        .is_empty());
+
+    let failures = question.check("It is sunny", &[]);
+    assert_eq!(
+        failures,
+        vec![
+            "Answer does not contain 'Paris'".to_string(),
+            "Tool 'weather_current' was not called".to_string(),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_record_and_list_runs() {
+    let storage = setup_storage().await;
+    let suite = storage
+        .create_suite("Regression".to_string(), None)
+        .await
+        .expect("Failed to create suite");
+    let question = storage
+        .add_question(
+            &suite.id,
+            "What's the weather in Paris?".to_string(),
+            Some("Paris".to_string()),
+            vec!["weather_current".to_string()],
+        )
+        .await
+        .expect("Failed to add question");
+
+    let stored = storage
+        .get_question(question.id)
+        .await
+        .expect("Failed to get question")
+        .expect("Question should exist");
+    assert_eq!(stored.expected_tools, vec!["weather_current"]);
+
+    let failed = storage
+        .record_run(
+            question.id,
+            "It is sunny".to_string(),
+            Vec::new(),
+            vec!["Answer does not contain 'Paris'".to_string()],
+            120,
+        )
+        .await
+        .expect("Failed to record run");
+    assert!(!failed.passed);
+    let passed = storage
+        .record_run(
+            question.id,
+            "Sunny in Paris".to_string(),
+            vec!["weather_current".to_string()],
+            Vec::new(),
+            80,
+        )
+        .await
+        .expect("Failed to record run");
+    assert!(passed.passed);
+
+    // Newest first
+    let runs = storage
+        .get_runs(question.id, 10)
+        .await
+        .expect("Failed to get runs");
+    assert_eq!(runs.len(), 2);
+    assert_eq!(runs[0].id, passed.id);
+    assert_eq!(runs[0].tool_calls, vec!["weather_current"]);
+    assert_eq!(runs[1].failures, failed.failures);
+
+    let summary = SuiteRunSummary::new(suite.id.clone(), runs);
+    assert_eq!((summary.total, summary.passed, summary.failed), (2, 1, 1));
+}
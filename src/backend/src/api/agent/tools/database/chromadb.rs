@@ -1,8 +1,8 @@
 use crate::api::agent::core::types::ChromaDBToolConfig;
-use crate::api::agent::core::types::{ToolCall, ToolCallResult, ToolType};
+use crate::api::agent::core::types::{SourceChunk, ToolCall, ToolCallResult, ToolType};
 use crate::api::agent::tools::framework::agent_tool::{AgentTool, ToolCategory, ToolMetadata};
-use crate::api::chromadb::client::ChromaDBClient;
-use crate::api::chromadb::types::QueryRequest;
+use crate::api::chromadb::client::{embedding_backend, ChromaDBClient};
+use crate::api::chromadb::types::{QueryRequest, QueryResponse};
 use anyhow::{Context, Result};
 use async_trait::async_trait;
 use serde_json::json;
@@ -36,55 +36,84 @@ impl ChromaDBTool {
     }
 
     /// Execute a ChromaDB search query (internal method)
-    async fn search(&self, query: &str, n_results: Option<usize>) -> Result<String> {
+    async fn search(
+        &self,
+        query: &str,
+        n_results: Option<usize>,
+        min_similarity: f64,
+    ) -> Result<(String, Vec<SourceChunk>)> {
         let query_request = QueryRequest {
             collection: self.config.collection.clone(),
             query_texts: vec![query.to_string()],
             n_results: n_results.or(Some(10)), // Default to 10 for better coverage
             where_clause: None,
+            // Scores are comparable across distance metrics, unlike raw distances
+            normalize_scores: true,
+            keyword: None,
         };
 
-        // Use the configured embedding model
+        // Use the configured embedding model and backend
+        let embedder =
+            embedding_backend(self.config.embedding_backend, &self.config.embedding_model)?;
         let query_response = self
             .client
-            .query(query_request, &self.config.embedding_model)
+            .query(query_request, embedder.as_ref())
             .await
             .context("Failed to execute ChromaDB query")?;
 
-        // Format results: filter by cosine distance (distance <= 0.5 means similarity >= 0.5)
-        // For cosine distance: 0.0 = identical, 1.0 = orthogonal, 2.0 = opposite
-        const MAX_COSINE_DISTANCE: f64 = 0.5; // Equivalent to similarity >= 0.5
-
-        let mut formatted = String::new();
-        if let Some(documents) = &query_response.documents {
-            let mut count = 0;
-            for (i, doc_batch) in documents.iter().enumerate() {
-                for (j, doc) in doc_batch.iter().enumerate() {
-                    // Include if no distance available, or if distance is within threshold
-                    let include = query_response
-                        .distances
-                        .as_ref()
-                        .and_then(|dists| dists.get(i))
-                        .and_then(|batch| batch.get(j))
-                        .map(|&dist| dist <= MAX_COSINE_DISTANCE)
-                        .unwrap_or(true);
-
-                    if include {
-                        count += 1;
-                        formatted.push_str(&format!("=== Document {} ===\n{}\n\n", count, doc));
-                    }
-                }
-            }
+        Ok(format_search_results(&query_response, min_similarity))
+    }
+}
 
-            if count == 0 {
-                formatted.push_str("No relevant documents found (similarity threshold: 0.5).");
-            }
-        } else {
-            formatted.push_str("No documents found in the collection.");
-        }
+/// Format results for the model, each headed by its source so it can be cited,
+/// and return the chunks at or above `min_similarity` renumbered in the same order
+fn format_search_results(
+    query_response: &QueryResponse,
+    min_similarity: f64,
+) -> (String, Vec<SourceChunk>) {
+    let candidates = SourceChunk::from_query_response(query_response);
+    if query_response.documents.is_none() || candidates.is_empty() {
+        return (
+            "No documents found in the collection.".to_string(),
+            Vec::new(),
+        );
+    }
 
-        Ok(formatted)
+    // Include if no score available, or if the score reaches the threshold
+    let candidate_count = candidates.len();
+    let sources: Vec<SourceChunk> = candidates
+        .into_iter()
+        .filter(|s| s.score.is_none_or(|score| score >= min_similarity))
+        .enumerate()
+        .map(|(i, source)| SourceChunk {
+            citation: i + 1,
+            ..source
+        })
+        .collect();
+
+    if sources.is_empty() {
+        return (
+            format!(
+                "No relevant documents found: none of the {} result(s) reached the minimum similarity of {:.2}. \
+                 Tell the user the knowledge base has no relevant information instead of answering from these results.",
+                candidate_count, min_similarity
+            ),
+            sources,
+        );
+    }
+
+    let mut formatted = String::new();
+    for source in &sources {
+        formatted.push_str(&format!(
+            "=== Document {} [source: {}] ===\n{}\n\n",
+            source.citation,
+            source.label(),
+            source.text
+        ));
     }
+    formatted.push_str("Cite documents by their number, e.g. [1], when using them in the answer.");
+
+    (formatted, sources)
 }
 
 #[async_trait]
@@ -104,6 +133,12 @@ impl AgentTool for ChromaDBTool {
                         "type": "string",
                         "description": "The search query to find relevant documents. Use a clear, factual query related to the specific information being requested."
                     },
+                    "min_similarity": {
+                        "type": "number",
+                        "description": "Drop results whose similarity (0-1, 1 = identical) is below this. Defaults to the configured threshold; lower it only when a broad search found nothing.",
+                        "minimum": 0,
+                        "maximum": 1
+                    },
                     "n_results": {
                         "type": "integer",
                         "description": "Number of results to return (default: 5 for technical queries, 3 for general queries, max: 10). Use more results (8-10) for technical topics, frameworks, libraries, or when you need comprehensive information. Use fewer (3-5) for simple factual questions.",
@@ -130,25 +165,44 @@ impl AgentTool for ChromaDBTool {
             .and_then(|v| v.as_u64())
             .map(|n| n as usize);
 
-        let result = self.search(query, n_results).await?;
+        let min_similarity = args
+            .get("min_similarity")
+            .and_then(|v| v.as_f64())
+            .map(|v| v.clamp(0.0, 1.0))
+            .unwrap_or(self.config.min_similarity);
+
+        let (result, sources) = self.search(query, n_results, min_similarity).await?;
 
         Ok(ToolCallResult {
             tool_name: "search_chromadb".to_string(),
             result,
+            tool_call_id: Some(tool_call.id.clone()),
+            sources: Some(sources),
         })
     }
+
+    async fn check_connectivity(&self) -> Option<Result<()>> {
+        match self.client.health_check().await {
+            Ok(true) => Some(Ok(())),
+            Ok(false) => Some(Err(anyhow::anyhow!("ChromaDB is not reachable"))),
+            Err(e) => Some(Err(e)),
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::api::agent::core::types::ChromaDBToolConfig;
+    use crate::api::agent::core::types::{ChromaDBToolConfig, DEFAULT_MIN_SIMILARITY};
+    use crate::api::chromadb::client::EmbeddingBackendKind;
 
     #[test]
     fn test_chromadb_metadata() {
         let config = ChromaDBToolConfig {
             collection: "test_collection".to_string(),
             embedding_model: "all-MiniLM-L6-v2".to_string(),
+            embedding_backend: EmbeddingBackendKind::Ollama,
+            min_similarity: DEFAULT_MIN_SIMILARITY,
         };
         // Use a dummy address, the client creation might fail if it tries to connect immediately
         // But ChromaDBClient::new usually just stores the base URL.
@@ -173,6 +227,8 @@ mod tests {
         let config = ChromaDBToolConfig {
             collection: "test_collection".to_string(),
             embedding_model: "test-model".to_string(),
+            embedding_backend: EmbeddingBackendKind::Ollama,
+            min_similarity: DEFAULT_MIN_SIMILARITY,
         };
         if let Ok(tool) = ChromaDBTool::new("http://localhost:8000", config) {
             let def = tool.get_function_definition();
@@ -180,4 +236,68 @@ mod tests {
             assert!(def["parameters"]["properties"].get("query").is_some());
         }
     }
+
+    #[test]
+    fn test_format_search_results_attributes_sources() {
+        let meta = |filename: &str, chunk: &str| {
+            let mut m = std::collections::HashMap::new();
+            m.insert("filename".to_string(), json!(filename));
+            m.insert("chunk_index".to_string(), json!(chunk));
+            m
+        };
+        let response = QueryResponse {
+            ids: vec![vec!["a".into(), "b".into(), "c".into()]],
+            distances: Some(vec![vec![0.1, 0.9, 0.3]]),
+            scores: Some(vec![vec![0.95, 0.55, 0.85]]),
+            documents: Some(vec![vec![
+                "Bevy uses ECS.".into(),
+                "Off-topic.".into(),
+                "Systems run in parallel.".into(),
+            ]]),
+            metadatas: Some(vec![vec![
+                meta("bevy.md", "0"),
+                meta("misc.md", "7"),
+                meta("bevy.md", "4"),
+            ]]),
+            distance_metric: None,
+        };
+
+        let (text, sources) = format_search_results(&response, DEFAULT_MIN_SIMILARITY);
+        assert!(text.contains("=== Document 1 [source: bevy.md, chunk 0] ===\nBevy uses ECS."));
+        // The distant chunk is dropped and numbering stays contiguous
+        assert!(text.contains("=== Document 2 [source: bevy.md, chunk 4] ==="));
+        assert!(!text.contains("misc.md"));
+
+        let labels: Vec<(usize, String)> =
+            sources.iter().map(|s| (s.citation, s.label())).collect();
+        assert_eq!(
+            labels,
+            vec![
+                (1, "bevy.md, chunk 0".to_string()),
+                (2, "bevy.md, chunk 4".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn test_format_search_results_below_threshold() {
+        let response = QueryResponse {
+            ids: vec![vec!["a".into(), "b".into()]],
+            distances: Some(vec![vec![0.8, 0.9]]),
+            documents: Some(vec![vec!["Weak".into(), "Weaker".into()]]),
+            metadatas: None,
+            scores: Some(vec![vec![0.6, 0.55]]),
+            distance_metric: None,
+        };
+
+        let (text, sources) = format_search_results(&response, 0.75);
+        assert!(sources.is_empty());
+        assert!(text.starts_with("No relevant documents found"));
+        assert!(text.contains("none of the 2 result(s) reached the minimum similarity of 0.75"));
+
+        // A lower threshold lets them through
+        let (_, sources) = format_search_results(&response, 0.5);
+        assert_eq!(sources.len(), 2);
+        assert_eq!(sources[1].score, Some(0.55));
+    }
 }
@@ -1,7 +1,9 @@
 pub mod chromadb;
+pub mod model_notes;
 
-use crate::api::agent::core::types::AgentConfig;
+use crate::api::agent::core::types::{AgentConfig, ToolType};
 use crate::api::agent::tools::database::chromadb::ChromaDBTool;
+use crate::api::agent::tools::database::model_notes::ModelNotesTool;
 use crate::api::agent::tools::framework::registry::ToolRegistry;
 use std::sync::Arc;
 
@@ -24,4 +26,13 @@ pub fn register(
             }
         }
     }
+
+    if config.enabled_tools.contains(&ToolType::ModelNotes) {
+        if let Some(storage) = &context.model_notes {
+            let tool = ModelNotesTool::new(Arc::clone(storage));
+            if let Err(e) = registry.register(Arc::new(tool)) {
+                println!("⚠️ Failed to register model notes tool: {}", e);
+            }
+        }
+    }
 }
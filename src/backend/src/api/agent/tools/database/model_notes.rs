@@ -0,0 +1,213 @@
+use crate::api::agent::core::types::{ToolCall, ToolCallResult, ToolType};
+use crate::api::agent::tools::framework::agent_tool::{AgentTool, ToolCategory, ToolMetadata};
+use crate::api::model_notes::types::ModelNote;
+use crate::api::model_notes::ModelNotesStorage;
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use serde_json::json;
+use std::sync::Arc;
+
+/// Most notes included in one tool result
+const MAX_NOTES: usize = 10;
+
+/// Model notes tool implementation
+/// Lets the agent read the notes, tags and favorites the user saved about models
+pub struct ModelNotesTool {
+    storage: Arc<ModelNotesStorage>,
+    metadata: ToolMetadata,
+}
+
+impl ModelNotesTool {
+    /// Create a new model notes tool reading from `storage`
+    pub fn new(storage: Arc<ModelNotesStorage>) -> Self {
+        Self {
+            storage,
+            metadata: ToolMetadata {
+                id: "model_notes".to_string(),
+                name: "Model Notes".to_string(),
+                description: "Look up the user's saved notes about models".to_string(),
+                category: ToolCategory::Database,
+                tool_type: ToolType::ModelNotes,
+            },
+        }
+    }
+}
+
+/// Format notes for the model, one block per note
+fn format_notes(notes: &[ModelNote], query: Option<&str>, tag: Option<&str>) -> String {
+    if notes.is_empty() {
+        let filter = match (query, tag) {
+            (Some(q), Some(t)) => format!(" matching '{}' with tag '{}'", q, t),
+            (Some(q), None) => format!(" matching '{}'", q),
+            (None, Some(t)) => format!(" with tag '{}'", t),
+            (None, None) => String::new(),
+        };
+        return format!("No model notes found{}.", filter);
+    }
+
+    let mut result = format!("Found {} model note(s):\n", notes.len());
+    for note in notes.iter().take(MAX_NOTES) {
+        let mut flags = Vec::new();
+        if note.is_default {
+            flags.push("default");
+        }
+        if note.is_favorite {
+            flags.push("favorite");
+        }
+
+        result.push_str(&format!(
+            "\n=== {} ({}) ===\n",
+            note.model_name, note.platform
+        ));
+        if !flags.is_empty() {
+            result.push_str(&format!("Flags: {}\n", flags.join(", ")));
+        }
+        if !note.tags.is_empty() {
+            result.push_str(&format!("Tags: {}\n", note.tags.join(", ")));
+        }
+        match note
+            .notes
+            .as_deref()
+            .map(str::trim)
+            .filter(|n| !n.is_empty())
+        {
+            Some(text) => result.push_str(&format!("Notes: {}\n", text)),
+            None => result.push_str("Notes: (none)\n"),
+        }
+    }
+    if notes.len() > MAX_NOTES {
+        result.push_str(&format!(
+            "\n({} more note(s) not shown; narrow the search to see them)\n",
+            notes.len() - MAX_NOTES
+        ));
+    }
+    result
+}
+
+#[async_trait]
+impl AgentTool for ModelNotesTool {
+    fn metadata(&self) -> &ToolMetadata {
+        &self.metadata
+    }
+
+    fn get_function_definition(&self) -> serde_json::Value {
+        json!({
+            "name": "search_model_notes",
+            "description": "Search the notes the user has saved about AI models (model name, platform, tags, favorite/default flags and free-text notes). Use this when the user asks what they noted, tagged or decided about a model, e.g. \"what did I note about llama-3?\" or \"which models did I tag as coding?\". Leave both parameters empty to list all notes.",
+            "parameters": {
+                "type": "object",
+                "properties": {
+                    "query": {
+                        "type": "string",
+                        "description": "Text to look for in model names, notes and tags (case-insensitive), e.g. 'llama-3'"
+                    },
+                    "tag": {
+                        "type": "string",
+                        "description": "Only return notes with exactly this tag, e.g. 'coding'"
+                    }
+                }
+            }
+        })
+    }
+
+    async fn execute(&self, tool_call: &ToolCall) -> Result<ToolCallResult> {
+        let args: serde_json::Value = serde_json::from_str(&tool_call.function.arguments)
+            .context("Failed to parse tool call arguments")?;
+
+        let text_arg = |name: &str| {
+            args.get(name)
+                .and_then(|v| v.as_str())
+                .map(str::trim)
+                .filter(|v| !v.is_empty())
+                .map(str::to_string)
+        };
+        let query = text_arg("query");
+        let tag = text_arg("tag");
+
+        println!("📝 Searching model notes: query={:?} tag={:?}", query, tag);
+        let notes = self
+            .storage
+            .search_notes(query.as_deref(), tag.as_deref())
+            .await
+            .context("Failed to search model notes")?;
+
+        Ok(ToolCallResult {
+            tool_name: "search_model_notes".to_string(),
+            result: format_notes(&notes, query.as_deref(), tag.as_deref()),
+            tool_call_id: Some(tool_call.id.clone()),
+            sources: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::agent::core::types::FunctionCall;
+    use crate::utils::database::connect_sqlite;
+
+    fn note(model_name: &str, tags: &[&str], notes: &str) -> ModelNote {
+        ModelNote {
+            id: None,
+            platform: "llama".to_string(),
+            model_name: model_name.to_string(),
+            model_path: None,
+            is_favorite: false,
+            is_default: false,
+            tags: tags.iter().map(|t| t.to_string()).collect(),
+            notes: Some(notes.to_string()),
+            created_at: None,
+            updated_at: None,
+        }
+    }
+
+    fn call(arguments: serde_json::Value) -> ToolCall {
+        ToolCall {
+            id: "call_1".to_string(),
+            tool_type: "function".to_string(),
+            function: FunctionCall {
+                name: "search_model_notes".to_string(),
+                arguments: arguments.to_string(),
+            },
+        }
+    }
+
+    #[tokio::test]
+    async fn test_model_notes_tool_reads_saved_notes() {
+        let pool = connect_sqlite(":memory:").await.unwrap();
+        let storage = Arc::new(ModelNotesStorage::new(pool).await.unwrap());
+        storage
+            .upsert_note(&note(
+                "meta-llama/Llama-3-8B",
+                &["chat"],
+                "Fast but loses track past 4k tokens",
+            ))
+            .await
+            .unwrap();
+        storage
+            .upsert_note(&note("Qwen/Qwen2.5-Coder", &["coding"], "Best for Rust"))
+            .await
+            .unwrap();
+
+        let tool = ModelNotesTool::new(storage);
+
+        let result = tool
+            .execute(&call(json!({"query": "llama-3"})))
+            .await
+            .unwrap();
+        assert_eq!(result.tool_call_id.as_deref(), Some("call_1"));
+        assert!(result.result.contains("meta-llama/Llama-3-8B (llama)"));
+        assert!(result.result.contains("loses track past 4k tokens"));
+        assert!(!result.result.contains("Qwen"));
+
+        let result = tool.execute(&call(json!({"tag": "coding"}))).await.unwrap();
+        assert!(result.result.contains("Qwen/Qwen2.5-Coder"));
+        assert!(result.result.contains("Tags: coding"));
+
+        let result = tool
+            .execute(&call(json!({"query": "mistral"})))
+            .await
+            .unwrap();
+        assert_eq!(result.result, "No model notes found matching 'mistral'.");
+    }
+}
@@ -1,13 +1,91 @@
 use crate::api::agent::core::types::{ToolCall, ToolCallResult, ToolType};
 use crate::api::agent::tools::framework::agent_tool::{AgentTool, ToolCategory, ToolMetadata};
+use crate::api::agent::tools::framework::http::{self, DEFAULT_TOOL_TIMEOUT_SECS};
+use crate::api::agent::tools::framework::retry::{retry_request, DEFAULT_MAX_RETRIES};
 use anyhow::{Context, Result};
 use async_trait::async_trait;
+use base64::Engine;
 use chrono::Utc;
-use reqwest::{header, Client};
+use reqwest::{header, Client, Response, StatusCode};
 use serde_json::json;
 use std::env;
+use std::fmt::Write;
+
+/// GitHub's maximum page size for list/search endpoints
+const GITHUB_MAX_PER_PAGE: u32 = 100;
+/// Default number of results for repository search and trending
+const DEFAULT_SEARCH_LIMIT: u32 = 5;
+/// Default number of results when listing a user's repositories
+const DEFAULT_USER_REPOS_LIMIT: u32 = 10;
+
+/// Approximate token budget for file contents returned by `get_file`
+const FILE_TOKEN_BUDGET: usize = 4000;
+/// Rough characters-per-token ratio used to turn the token budget into a character cap
+const CHARS_PER_TOKEN: usize = 4;
+
+/// Read the optional `limit` argument, clamped to GitHub's `per_page` range
+fn parse_limit(args: &serde_json::Value, default: u32) -> u32 {
+    args.get("limit")
+        .and_then(|v| v.as_u64())
+        .map(|v| v.clamp(1, GITHUB_MAX_PER_PAGE as u64) as u32)
+        .unwrap_or(default)
+}
+
+/// Warn when fewer than this many requests remain in the current rate-limit window
+const RATE_LIMIT_WARNING_THRESHOLD: u64 = 10;
+
+/// Build a friendly message if the response was rejected because the rate limit is exhausted
+fn rate_limit_message(status: StatusCode, headers: &header::HeaderMap) -> Option<String> {
+    if status != StatusCode::FORBIDDEN && status != StatusCode::TOO_MANY_REQUESTS {
+        return None;
+    }
+
+    let remaining = headers
+        .get("x-ratelimit-remaining")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.parse::<u64>().ok());
+    if remaining != Some(0) {
+        return None;
+    }
+
+    let reset = headers
+        .get("x-ratelimit-reset")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.parse::<i64>().ok())
+        .and_then(|ts| chrono::DateTime::from_timestamp(ts, 0));
+
+    Some(match reset {
+        Some(dt) => format!("GitHub rate limited, resets at {} UTC", dt.format("%H:%M")),
+        None => "GitHub rate limited, try again later".to_string(),
+    })
+}
 
-fn create_github_client(token: &str) -> Client {
+/// Inspect GitHub rate-limit headers on every response.
+/// Logs a warning when the remaining quota is low and fails with a friendly message when it is exhausted.
+fn check_rate_limit(response: &Response) -> Result<()> {
+    let headers = response.headers();
+
+    if let Some(remaining) = headers
+        .get("x-ratelimit-remaining")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.parse::<u64>().ok())
+    {
+        if remaining < RATE_LIMIT_WARNING_THRESHOLD {
+            println!(
+                "⚠️ GitHub API rate limit low: {} request(s) remaining",
+                remaining
+            );
+        }
+    }
+
+    if let Some(message) = rate_limit_message(response.status(), headers) {
+        return Err(anyhow::anyhow!(message));
+    }
+
+    Ok(())
+}
+
+fn create_github_client(token: &str, timeout_secs: u64) -> Client {
     let mut headers = header::HeaderMap::new();
     if !token.is_empty() {
         let mut auth_value = header::HeaderValue::from_str(&format!("Bearer {}", token))
@@ -24,7 +102,7 @@ fn create_github_client(token: &str) -> Client {
         header::HeaderValue::from_static("application/vnd.github.v3+json"),
     );
 
-    Client::builder()
+    http::client_builder(timeout_secs)
         .default_headers(headers)
         .build()
         .expect("Failed to build reqwest client")
@@ -37,6 +115,8 @@ fn create_github_client(token: &str) -> Client {
 pub struct GitHubPublicTool {
     metadata: ToolMetadata,
     client: Client,
+    token: String,
+    max_retries: u32,
 }
 
 impl GitHubPublicTool {
@@ -51,21 +131,46 @@ impl GitHubPublicTool {
                 category: ToolCategory::Development,
                 tool_type: ToolType::GitHubPublic,
             },
-            client: create_github_client(&token),
+            client: create_github_client(&token, DEFAULT_TOOL_TIMEOUT_SECS),
+            token,
+            max_retries: DEFAULT_MAX_RETRIES,
         }
     }
 
-    async fn search_repos(&self, query: &str, sort: Option<&str>) -> Result<serde_json::Value> {
+    /// Set how many times transient request failures are retried
+    pub fn with_max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// Set the HTTP request timeout in seconds
+    pub fn with_timeout(mut self, timeout_secs: u64) -> Self {
+        self.client = create_github_client(&self.token, timeout_secs);
+        self
+    }
+
+    async fn search_repos(
+        &self,
+        query: &str,
+        sort: Option<&str>,
+        limit: u32,
+        page: u32,
+    ) -> Result<serde_json::Value> {
         let url = "https://api.github.com/search/repositories";
         let sort_param = sort.unwrap_or("stars");
-
-        let response = self
-            .client
-            .get(url)
-            .query(&[("q", query), ("sort", sort_param), ("per_page", "5")])
-            .send()
+        let per_page = limit.to_string();
+        let page = page.to_string();
+
+        let request = self.client.get(url).query(&[
+            ("q", query),
+            ("sort", sort_param),
+            ("per_page", &per_page),
+            ("page", &page),
+        ]);
+        let response = retry_request(request, self.max_retries)
             .await
             .context("Failed to search repositories")?;
+        check_rate_limit(&response)?;
 
         if !response.status().is_success() {
             return Err(anyhow::anyhow!("GitHub API error: {}", response.status()));
@@ -81,6 +186,8 @@ impl GitHubPublicTool {
         &self,
         language: Option<&str>,
         timeframe: Option<&str>,
+        limit: u32,
+        page: u32,
     ) -> Result<serde_json::Value> {
         let now = Utc::now();
         let date_filter = match timeframe {
@@ -95,18 +202,27 @@ impl GitHubPublicTool {
             query.push_str(&format!(" language:{}", lang));
         }
 
-        self.search_repos(&query, Some("stars")).await
+        self.search_repos(&query, Some("stars"), limit, page).await
     }
 
-    async fn list_user_repos(&self, username: &str) -> Result<serde_json::Value> {
+    async fn list_user_repos(
+        &self,
+        username: &str,
+        limit: u32,
+        page: u32,
+    ) -> Result<serde_json::Value> {
         let url = format!("https://api.github.com/users/{}/repos", username);
-        let response = self
-            .client
-            .get(&url)
-            .query(&[("sort", "updated"), ("per_page", "10")])
-            .send()
+        let per_page = limit.to_string();
+        let page = page.to_string();
+        let request = self.client.get(&url).query(&[
+            ("sort", "updated"),
+            ("per_page", &per_page),
+            ("page", &page),
+        ]);
+        let response = retry_request(request, self.max_retries)
             .await
             .context("Failed to fetch user repositories")?;
+        check_rate_limit(&response)?;
 
         if !response.status().is_success() {
             return Err(anyhow::anyhow!("GitHub API error: {}", response.status()));
@@ -131,7 +247,8 @@ impl GitHubPublicTool {
             return "No repositories found.".to_string();
         }
 
-        let mut output = String::new();
+        // Pre-size the buffer and write in place so large pages (up to 100 repos) don't reallocate per item
+        let mut output = String::with_capacity(items.len() * 160);
         for item in items {
             let name = item["full_name"].as_str().unwrap_or("unknown");
             let desc = item["description"].as_str().unwrap_or("No description");
@@ -139,10 +256,11 @@ impl GitHubPublicTool {
             let url = item["html_url"].as_str().unwrap_or("");
             let lang = item["language"].as_str().unwrap_or("Unknown");
 
-            output.push_str(&format!(
+            let _ = write!(
+                output,
                 "- **[{}]({})** (⭐ {} | {})\n  {}\n\n",
                 name, url, stars, lang, desc
-            ));
+            );
         }
         output
     }
@@ -182,6 +300,16 @@ impl AgentTool for GitHubPublicTool {
                     "username": {
                         "type": "string",
                         "description": "Target username (required for 'user_repos')."
+                    },
+                    "limit": {
+                        "type": "integer",
+                        "description": "Maximum number of repositories to return (default: 5 for 'search'/'trending', 10 for 'user_repos', max: 100).",
+                        "minimum": 1,
+                        "maximum": 100
+                    },
+                    "page": {
+                        "type": "integer",
+                        "description": "Page number for pagination (default: 1)."
                     }
                 },
                 "required": ["action"]
@@ -197,11 +325,18 @@ impl AgentTool for GitHubPublicTool {
 
         println!("\x1b[36m🐙 GitHub Public Tool executing: {}\x1b[0m", action);
 
+        let page = args
+            .get("page")
+            .and_then(|v| v.as_u64())
+            .unwrap_or(1)
+            .max(1) as u32;
+
         let result = match action {
             "trending" => {
                 let lang = args.get("language").and_then(|v| v.as_str());
                 let timeframe = args.get("timeframe").and_then(|v| v.as_str());
-                let data = self.get_trending(lang, timeframe).await?;
+                let limit = parse_limit(&args, DEFAULT_SEARCH_LIMIT);
+                let data = self.get_trending(lang, timeframe, limit, page).await?;
                 format!(
                     "🔥 **Trending Repositories**\n\n{}",
                     self.format_repo_list(&data)
@@ -212,7 +347,8 @@ impl AgentTool for GitHubPublicTool {
                     .get("query")
                     .and_then(|v| v.as_str())
                     .ok_or_else(|| anyhow::anyhow!("'query' is required for search"))?;
-                let data = self.search_repos(query, None).await?;
+                let limit = parse_limit(&args, DEFAULT_SEARCH_LIMIT);
+                let data = self.search_repos(query, None, limit, page).await?;
                 format!(
                     "🔍 **GitHub Search Results**\n\n{}",
                     self.format_repo_list(&data)
@@ -223,7 +359,8 @@ impl AgentTool for GitHubPublicTool {
                     .get("username")
                     .and_then(|v| v.as_str())
                     .ok_or_else(|| anyhow::anyhow!("'username' is required for user_repos"))?;
-                let data = self.list_user_repos(username).await?;
+                let limit = parse_limit(&args, DEFAULT_USER_REPOS_LIMIT);
+                let data = self.list_user_repos(username, limit, page).await?;
                 format!(
                     "📂 **Repositories for {}**\n\n{}",
                     username,
@@ -236,12 +373,24 @@ impl AgentTool for GitHubPublicTool {
         Ok(ToolCallResult {
             tool_name: "github_public".to_string(),
             result,
+            tool_call_id: Some(tool_call.id.clone()),
+            sources: None,
         })
     }
 
     fn is_available(&self) -> bool {
         true
     }
+
+    fn max_concurrency(&self) -> Option<usize> {
+        // Unauthenticated requests share a small hourly budget per IP
+        Some(2)
+    }
+
+    async fn check_connectivity(&self) -> Option<Result<()>> {
+        // /rate_limit doesn't count against the rate limit
+        Some(http::probe(&self.client, "https://api.github.com/rate_limit").await)
+    }
 }
 
 // ============================================================================================
@@ -252,6 +401,7 @@ pub struct GitHubAuthenticatedTool {
     metadata: ToolMetadata,
     client: Client,
     token: String,
+    max_retries: u32,
 }
 
 impl GitHubAuthenticatedTool {
@@ -265,11 +415,24 @@ impl GitHubAuthenticatedTool {
                 category: ToolCategory::Development,
                 tool_type: ToolType::GitHubAuthenticated,
             },
-            client: create_github_client(&token),
+            client: create_github_client(&token, DEFAULT_TOOL_TIMEOUT_SECS),
             token,
+            max_retries: DEFAULT_MAX_RETRIES,
         }
     }
 
+    /// Set how many times transient request failures are retried
+    pub fn with_max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// Set the HTTP request timeout in seconds
+    pub fn with_timeout(mut self, timeout_secs: u64) -> Self {
+        self.client = create_github_client(&self.token, timeout_secs);
+        self
+    }
+
     async fn check_notifications(&self) -> Result<serde_json::Value> {
         if self.token.is_empty() {
             return Err(anyhow::anyhow!(
@@ -277,13 +440,14 @@ impl GitHubAuthenticatedTool {
             ));
         }
         let url = "https://api.github.com/notifications";
-        let response = self
+        let request = self
             .client
             .get(url)
-            .query(&[("all", "false"), ("per_page", "10")])
-            .send()
+            .query(&[("all", "false"), ("per_page", "10")]);
+        let response = retry_request(request, self.max_retries)
             .await
             .context("Failed to fetch notifications")?;
+        check_rate_limit(&response)?;
 
         if !response.status().is_success() {
             return Err(anyhow::anyhow!("GitHub API error: {}", response.status()));
@@ -301,20 +465,18 @@ impl GitHubAuthenticatedTool {
             ));
         }
         let url = "https://api.github.com/user/repos";
-        let response = self
-            .client
-            .get(url)
-            .query(&[
-                ("sort", "updated"),
-                ("per_page", "100"),
-                ("type", "owner"),
-                ("page", &page.to_string()),
-            ])
-            .send()
+        let request = self.client.get(url).query(&[
+            ("sort", "updated"),
+            ("per_page", "100"),
+            ("type", "owner"),
+            ("page", &page.to_string()),
+        ]);
+        let response = retry_request(request, self.max_retries)
             .await
             .context("Failed to fetch repositories")?;
+        check_rate_limit(&response)?;
 
-        if response.status() == reqwest::StatusCode::FORBIDDEN {
+        if response.status() == StatusCode::FORBIDDEN {
             return Err(anyhow::anyhow!(
                 "Access Forbidden (403). Check that your GITHUB_TOKEN has the 'metadata' or 'contents' scope enabled."
             ));
@@ -335,17 +497,15 @@ impl GitHubAuthenticatedTool {
             ));
         }
         let url = format!("https://api.github.com/orgs/{}/repos", org);
-        let response = self
-            .client
-            .get(&url)
-            .query(&[
-                ("sort", "updated"),
-                ("per_page", "100"),
-                ("page", &page.to_string()),
-            ])
-            .send()
+        let request = self.client.get(&url).query(&[
+            ("sort", "updated"),
+            ("per_page", "100"),
+            ("page", &page.to_string()),
+        ]);
+        let response = retry_request(request, self.max_retries)
             .await
             .context("Failed to fetch organization repositories")?;
+        check_rate_limit(&response)?;
 
         if !response.status().is_success() {
             return Err(anyhow::anyhow!("GitHub API error: {}", response.status()));
@@ -362,15 +522,13 @@ impl GitHubAuthenticatedTool {
             "https://api.github.com/repos/{}/{}/actions/runs",
             owner, repo
         );
-        let response = self
-            .client
-            .get(&url)
-            .query(&[("per_page", "5")])
-            .send()
+        let request = self.client.get(&url).query(&[("per_page", "5")]);
+        let response = retry_request(request, self.max_retries)
             .await
             .context("Failed to fetch workflow runs")?;
+        check_rate_limit(&response)?;
 
-        if response.status() == reqwest::StatusCode::FORBIDDEN {
+        if response.status() == StatusCode::FORBIDDEN {
             return Err(anyhow::anyhow!(
                 "Access Forbidden (403). For Actions, ensure your GITHUB_TOKEN has the 'actions' scope (Read-only)."
             ));
@@ -386,13 +544,15 @@ impl GitHubAuthenticatedTool {
 
     async fn list_issues(&self, owner: &str, repo: &str) -> Result<serde_json::Value> {
         let url = format!("https://api.github.com/repos/{}/{}/issues", owner, repo);
-        let response = self
-            .client
-            .get(&url)
-            .query(&[("state", "open"), ("sort", "updated"), ("per_page", "5")])
-            .send()
+        let request = self.client.get(&url).query(&[
+            ("state", "open"),
+            ("sort", "updated"),
+            ("per_page", "5"),
+        ]);
+        let response = retry_request(request, self.max_retries)
             .await
             .context("Failed to fetch issues")?;
+        check_rate_limit(&response)?;
 
         if !response.status().is_success() {
             return Err(anyhow::anyhow!("GitHub API error: {}", response.status()));
@@ -402,13 +562,11 @@ impl GitHubAuthenticatedTool {
 
     async fn list_events(&self, username: &str) -> Result<serde_json::Value> {
         let url = format!("https://api.github.com/users/{}/events", username);
-        let response = self
-            .client
-            .get(&url)
-            .query(&[("per_page", "5")])
-            .send()
+        let request = self.client.get(&url).query(&[("per_page", "5")]);
+        let response = retry_request(request, self.max_retries)
             .await
             .context("Failed to fetch events")?;
+        check_rate_limit(&response)?;
 
         if !response.status().is_success() {
             return Err(anyhow::anyhow!("GitHub API error: {}", response.status()));
@@ -418,18 +576,16 @@ impl GitHubAuthenticatedTool {
 
     async fn list_pulls(&self, owner: &str, repo: &str) -> Result<serde_json::Value> {
         let url = format!("https://api.github.com/repos/{}/{}/pulls", owner, repo);
-        let response = self
-            .client
-            .get(&url)
-            .query(&[
-                ("state", "open"),
-                ("per_page", "5"),
-                ("sort", "updated"),
-                ("direction", "desc"),
-            ])
-            .send()
+        let request = self.client.get(&url).query(&[
+            ("state", "open"),
+            ("per_page", "5"),
+            ("sort", "updated"),
+            ("direction", "desc"),
+        ]);
+        let response = retry_request(request, self.max_retries)
             .await
             .context("Failed to list pull requests")?;
+        check_rate_limit(&response)?;
 
         if !response.status().is_success() {
             return Err(anyhow::anyhow!("GitHub API error: {}", response.status()));
@@ -444,19 +600,17 @@ impl GitHubAuthenticatedTool {
         page: u32,
     ) -> Result<serde_json::Value> {
         let url = "https://api.github.com/issues";
-        let response = self
-            .client
-            .get(url)
-            .query(&[
-                ("filter", filter),
-                ("state", state),
-                ("sort", "updated"),
-                ("per_page", "100"),
-                ("page", &page.to_string()),
-            ])
-            .send()
+        let request = self.client.get(url).query(&[
+            ("filter", filter),
+            ("state", state),
+            ("sort", "updated"),
+            ("per_page", "100"),
+            ("page", &page.to_string()),
+        ]);
+        let response = retry_request(request, self.max_retries)
             .await
             .context("Failed to fetch authenticated issues")?;
+        check_rate_limit(&response)?;
 
         if !response.status().is_success() {
             return Err(anyhow::anyhow!("GitHub API error: {}", response.status()));
@@ -464,6 +618,41 @@ impl GitHubAuthenticatedTool {
         response.json().await.context("Failed to parse issues")
     }
 
+    async fn get_file(
+        &self,
+        owner: &str,
+        repo: &str,
+        path: &str,
+        git_ref: Option<&str>,
+    ) -> Result<Option<serde_json::Value>> {
+        let url = format!(
+            "https://api.github.com/repos/{}/{}/contents/{}",
+            owner,
+            repo,
+            path.trim_start_matches('/')
+        );
+        let mut request = self.client.get(&url);
+        if let Some(r) = git_ref {
+            request = request.query(&[("ref", r)]);
+        }
+        let response = retry_request(request, self.max_retries)
+            .await
+            .context("Failed to fetch repository contents")?;
+        check_rate_limit(&response)?;
+
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        if !response.status().is_success() {
+            return Err(anyhow::anyhow!("GitHub API error: {}", response.status()));
+        }
+        response
+            .json()
+            .await
+            .map(Some)
+            .context("Failed to parse repository contents")
+    }
+
     // Formatters reused logic could be shared but for now duplication is safer than complexity
     fn format_repo_list(&self, data: &serde_json::Value) -> String {
         let items = if let Some(items) = data.get("items").and_then(|i| i.as_array()) {
@@ -584,6 +773,73 @@ impl GitHubAuthenticatedTool {
         output
     }
 
+    fn format_file(&self, data: &serde_json::Value) -> Result<String> {
+        // Directories come back as an array of entries
+        if let Some(entries) = data.as_array() {
+            if entries.is_empty() {
+                return Ok("Directory is empty.".to_string());
+            }
+            let mut output = String::new();
+            for entry in entries {
+                let name = entry["name"].as_str().unwrap_or("unknown");
+                let icon = match entry["type"].as_str().unwrap_or("") {
+                    "dir" => "📁",
+                    "symlink" => "🔗",
+                    "submodule" => "📦",
+                    _ => "📄",
+                };
+                output.push_str(&format!("- {} {}\n", icon, name));
+            }
+            return Ok(output);
+        }
+
+        let path = data["path"].as_str().unwrap_or("unknown");
+        if data["type"].as_str() != Some("file") {
+            return Ok(format!(
+                "'{}' is a {} and has no readable content.",
+                path,
+                data["type"].as_str().unwrap_or("unknown entry")
+            ));
+        }
+
+        // Files larger than 1MB come back without inline content
+        let encoded = match data["content"].as_str() {
+            Some(c) if !c.is_empty() => c,
+            _ => {
+                return Ok(format!(
+                    "'{}' is too large to be returned inline ({} bytes).",
+                    path,
+                    data["size"].as_u64().unwrap_or(0)
+                ))
+            }
+        };
+
+        // GitHub wraps the base64 payload at 60 characters
+        let cleaned: String = encoded.chars().filter(|c| !c.is_whitespace()).collect();
+        let bytes = base64::engine::general_purpose::STANDARD
+            .decode(cleaned)
+            .context("Failed to decode file content")?;
+        let content = String::from_utf8_lossy(&bytes);
+
+        let max_chars = FILE_TOKEN_BUDGET * CHARS_PER_TOKEN;
+        let total_chars = content.chars().count();
+        let mut output = format!(
+            "```\n{}",
+            content.chars().take(max_chars).collect::<String>()
+        );
+        if !output.ends_with('\n') {
+            output.push('\n');
+        }
+        output.push_str("```\n");
+        if total_chars > max_chars {
+            output.push_str(&format!(
+                "\n_...truncated, showing {} of {} characters_\n",
+                max_chars, total_chars
+            ));
+        }
+        Ok(output)
+    }
+
     fn format_pulls(&self, data: &serde_json::Value) -> String {
         let items = match data.as_array() {
             Some(i) => i,
@@ -614,17 +870,19 @@ impl AgentTool for GitHubAuthenticatedTool {
     fn get_function_definition(&self) -> serde_json::Value {
         json!({
             "name": "github_authenticated",
-            "description": "Access PRIVATE/AUTHENTICATED GitHub features: notifications, your repos, workflow runs, issues, and events. REQUIRED: GITHUB_TOKEN env variable.",
+            "description": "Access PRIVATE/AUTHENTICATED GitHub features: notifications, your repos, workflow runs, issues, events, and reading repository files (get_file). REQUIRED: GITHUB_TOKEN env variable.",
             "parameters": {
                 "type": "object",
                 "properties": {
                     "action": {
                         "type": "string",
-                        "enum": ["notifications", "list_my_repos", "list_org_repos", "actions", "issues", "events", "pulls"],
+                        "enum": ["notifications", "list_my_repos", "list_org_repos", "actions", "issues", "events", "pulls", "get_file"],
                         "description": "The action to perform."
                     },
-                    "owner": { "type": "string", "description": "Repository owner (optional for issues/pulls)." },
-                    "repo": { "type": "string", "description": "Repository name (optional for issues/pulls)." },
+                    "owner": { "type": "string", "description": "Repository owner (optional for issues/pulls, required for get_file)." },
+                    "repo": { "type": "string", "description": "Repository name (optional for issues/pulls, required for get_file)." },
+                    "path": { "type": "string", "description": "File or directory path within the repository (required for get_file, e.g. 'README.md' or 'src')." },
+                    "ref": { "type": "string", "description": "Branch, tag, or commit SHA to read from (optional for get_file, default: repository default branch)." },
                     "org": { "type": "string", "description": "Organization name (required for list_org_repos)." },
                     "username": { "type": "string", "description": "Username for events check." },
                     "page": { "type": "integer", "description": "Page number for pagination (default: 1)." },
@@ -655,6 +913,8 @@ impl AgentTool for GitHubAuthenticatedTool {
             return Ok(ToolCallResult {
                 tool_name: "github_authenticated".to_string(),
                 result: "GITHUB_TOKEN is not set. This tool requires authentication.".to_string(),
+                tool_call_id: Some(tool_call.id.clone()),
+                sources: None,
             });
         }
 
@@ -791,12 +1051,44 @@ impl AgentTool for GitHubAuthenticatedTool {
                     }
                 }
             }
+            "get_file" => {
+                let owner = args.get("owner").and_then(|v| v.as_str()).unwrap_or("");
+                let repo = args.get("repo").and_then(|v| v.as_str()).unwrap_or("");
+                let path = args.get("path").and_then(|v| v.as_str()).unwrap_or("");
+                let git_ref = args.get("ref").and_then(|v| v.as_str());
+                if owner.is_empty() || repo.is_empty() {
+                    return Err(anyhow::anyhow!("Owner and repo required for get_file"));
+                }
+
+                match self.get_file(owner, repo, path, git_ref).await {
+                    Ok(Some(data)) => match self.format_file(&data) {
+                        Ok(content) => format!(
+                            "📄 **{}/{}: {}**\n\n{}",
+                            owner,
+                            repo,
+                            if path.is_empty() { "/" } else { path },
+                            content
+                        ),
+                        Err(e) => format!("Failed: {}", e),
+                    },
+                    Ok(None) => format!(
+                        "'{}' was not found in {}/{}{}. Check the path and ref.",
+                        path,
+                        owner,
+                        repo,
+                        git_ref.map(|r| format!(" at '{}'", r)).unwrap_or_default()
+                    ),
+                    Err(e) => format!("Failed: {}", e),
+                }
+            }
             _ => return Err(anyhow::anyhow!("Unknown action: {}", action)),
         };
 
         Ok(ToolCallResult {
             tool_name: "github_authenticated".to_string(),
             result,
+            tool_call_id: Some(tool_call.id.clone()),
+            sources: None,
         })
     }
 
@@ -804,6 +1096,20 @@ impl AgentTool for GitHubAuthenticatedTool {
         // Only available if token is present
         !self.token.is_empty()
     }
+
+    fn required_env_vars(&self) -> &'static [&'static str] {
+        &["GITHUB_TOKEN"]
+    }
+
+    fn max_concurrency(&self) -> Option<usize> {
+        // GitHub flags bursts of concurrent requests as secondary rate limit abuse
+        Some(2)
+    }
+
+    async fn check_connectivity(&self) -> Option<Result<()>> {
+        // /rate_limit doesn't count against the rate limit, and rejects a bad token
+        Some(http::probe(&self.client, "https://api.github.com/rate_limit").await)
+    }
 }
 
 #[cfg(test)]
@@ -828,6 +1134,65 @@ mod tests {
         assert!(def["parameters"]["properties"].get("action").is_some());
     }
 
+    #[test]
+    fn test_parse_limit() {
+        assert_eq!(parse_limit(&json!({}), DEFAULT_SEARCH_LIMIT), 5);
+        assert_eq!(
+            parse_limit(&json!({ "limit": 25 }), DEFAULT_SEARCH_LIMIT),
+            25
+        );
+        assert_eq!(
+            parse_limit(&json!({ "limit": 500 }), DEFAULT_SEARCH_LIMIT),
+            100
+        );
+        assert_eq!(
+            parse_limit(&json!({ "limit": 0 }), DEFAULT_USER_REPOS_LIMIT),
+            1
+        );
+    }
+
+    #[test]
+    fn test_format_repo_list_large() {
+        let tool = GitHubPublicTool::new();
+        let items: Vec<serde_json::Value> = (0..100)
+            .map(|i| {
+                json!({
+                    "full_name": format!("owner/repo{}", i),
+                    "description": "A repo",
+                    "stargazers_count": i,
+                    "html_url": format!("https://github.com/owner/repo{}", i),
+                    "language": "Rust"
+                })
+            })
+            .collect();
+        let output = tool.format_repo_list(&json!({ "items": items }));
+        assert_eq!(output.matches("- **[").count(), 100);
+        assert!(output.contains("owner/repo99"));
+    }
+
+    #[test]
+    fn test_rate_limit_message() {
+        let mut headers = header::HeaderMap::new();
+        headers.insert("x-ratelimit-remaining", "0".parse().unwrap());
+        headers.insert("x-ratelimit-reset", "1700000000".parse().unwrap());
+
+        let message = rate_limit_message(StatusCode::FORBIDDEN, &headers);
+        assert_eq!(
+            message.as_deref(),
+            Some("GitHub rate limited, resets at 22:13 UTC")
+        );
+
+        // Successful responses are never treated as rate limited
+        assert!(rate_limit_message(StatusCode::OK, &headers).is_none());
+    }
+
+    #[test]
+    fn test_rate_limit_message_ignores_permission_errors() {
+        let mut headers = header::HeaderMap::new();
+        headers.insert("x-ratelimit-remaining", "42".parse().unwrap());
+        assert!(rate_limit_message(StatusCode::FORBIDDEN, &headers).is_none());
+    }
+
     #[test]
     fn test_github_authenticated_metadata() {
         let tool = GitHubAuthenticatedTool::new();
@@ -836,6 +1201,46 @@ mod tests {
         assert_eq!(metadata.tool_type, ToolType::GitHubAuthenticated);
     }
 
+    #[test]
+    fn test_format_file_decodes_content() {
+        let tool = GitHubAuthenticatedTool::new();
+        let data = json!({
+            "type": "file",
+            "path": "README.md",
+            "size": 13,
+            // "# Hello\nWorld" wrapped like GitHub does
+            "content": "IyBIZWxs\nbwpXb3JsZA==\n"
+        });
+        let output = tool.format_file(&data).expect("Should decode file");
+        assert!(output.contains("# Hello\nWorld"));
+        assert!(!output.contains("truncated"));
+    }
+
+    #[test]
+    fn test_format_file_truncates_to_budget() {
+        let tool = GitHubAuthenticatedTool::new();
+        let raw = "a".repeat(FILE_TOKEN_BUDGET * CHARS_PER_TOKEN + 10);
+        let data = json!({
+            "type": "file",
+            "path": "big.txt",
+            "content": base64::engine::general_purpose::STANDARD.encode(&raw)
+        });
+        let output = tool.format_file(&data).expect("Should decode file");
+        assert!(output.contains("truncated"));
+    }
+
+    #[test]
+    fn test_format_file_directory_listing() {
+        let tool = GitHubAuthenticatedTool::new();
+        let data = json!([
+            { "name": "src", "type": "dir" },
+            { "name": "Cargo.toml", "type": "file" }
+        ]);
+        let output = tool.format_file(&data).expect("Should list directory");
+        assert!(output.contains("📁 src"));
+        assert!(output.contains("📄 Cargo.toml"));
+    }
+
     #[test]
     fn test_github_authenticated_availability() {
         let tool = GitHubAuthenticatedTool::new();
@@ -5,9 +5,15 @@ use crate::api::agent::tools::development::github::{GitHubAuthenticatedTool, Git
 use crate::api::agent::tools::framework::registry::ToolRegistry;
 use std::sync::Arc;
 
-pub fn register(registry: &mut ToolRegistry, config: &AgentConfig) {
+pub fn register(
+    registry: &mut ToolRegistry,
+    config: &AgentConfig,
+    _context: &crate::api::agent::tools::RegisterContext,
+) {
     if config.enabled_tools.contains(&ToolType::GitHubPublic) {
-        let tool = GitHubPublicTool::new();
+        let tool = GitHubPublicTool::new()
+            .with_max_retries(config.tool_max_retries)
+            .with_timeout(config.tool_timeout_secs);
         if let Err(e) = registry.register(Arc::new(tool)) {
             println!("⚠️ Failed to register GitHubPublic tool: {}", e);
         }
@@ -17,7 +23,9 @@ pub fn register(registry: &mut ToolRegistry, config: &AgentConfig) {
         .enabled_tools
         .contains(&ToolType::GitHubAuthenticated)
     {
-        let tool = GitHubAuthenticatedTool::new();
+        let tool = GitHubAuthenticatedTool::new()
+            .with_max_retries(config.tool_max_retries)
+            .with_timeout(config.tool_timeout_secs);
         if let Err(e) = registry.register(Arc::new(tool)) {
             println!("⚠️ Failed to register GitHubAuthenticated tool: {}", e);
         }
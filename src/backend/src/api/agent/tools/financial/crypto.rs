@@ -1,5 +1,6 @@
 use crate::api::agent::core::types::{ToolCall, ToolCallResult, ToolType};
 use crate::api::agent::tools::framework::agent_tool::{AgentTool, ToolCategory, ToolMetadata};
+use crate::api::agent::tools::framework::http::{self, DEFAULT_TOOL_TIMEOUT_SECS};
 use anyhow::{Context, Result};
 use async_trait::async_trait;
 use reqwest;
@@ -26,11 +27,17 @@ impl CryptoTool {
                 category: ToolCategory::Financial,
                 tool_type: ToolType::Crypto,
             },
-            client: reqwest::Client::new(),
+            client: http::build_client(DEFAULT_TOOL_TIMEOUT_SECS),
             api_key,
         }
     }
 
+    /// Set the HTTP request timeout in seconds
+    pub fn with_timeout(mut self, timeout_secs: u64) -> Self {
+        self.client = http::build_client(timeout_secs);
+        self
+    }
+
     /// Fetch data from Alpha Vantage API
     async fn fetch_data(
         &self,
@@ -411,10 +418,23 @@ impl AgentTool for CryptoTool {
         Ok(ToolCallResult {
             tool_name: "crypto_data".to_string(),
             result,
+            tool_call_id: Some(tool_call.id.clone()),
+            sources: None,
         })
     }
 
     fn is_available(&self) -> bool {
         self.api_key.is_some()
     }
+
+    fn required_env_vars(&self) -> &'static [&'static str] {
+        &["ALPHA_ADVANTAGE_KEY"]
+    }
+
+    fn max_concurrency(&self) -> Option<usize> {
+        // The free Alpha Vantage tier allows only a few requests per minute
+        Some(1)
+    }
+
+    // No connectivity check: Alpha Vantage's free tier allows 25 requests a day
 }
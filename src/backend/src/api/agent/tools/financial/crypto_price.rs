@@ -0,0 +1,312 @@
+use crate::api::agent::core::types::{ToolCall, ToolCallResult, ToolType};
+use crate::api::agent::tools::framework::agent_tool::{AgentTool, ToolCategory, ToolMetadata};
+use crate::api::agent::tools::framework::http::{self, DEFAULT_TOOL_TIMEOUT_SECS};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use reqwest;
+use serde_json::json;
+
+/// Map common ticker symbols to CoinGecko coin ids.
+/// Anything not listed here is passed through lowercased and treated as a CoinGecko id.
+const SYMBOL_TO_ID: &[(&str, &str)] = &[
+    ("BTC", "bitcoin"),
+    ("ETH", "ethereum"),
+    ("USDT", "tether"),
+    ("BNB", "binancecoin"),
+    ("SOL", "solana"),
+    ("XRP", "ripple"),
+    ("USDC", "usd-coin"),
+    ("ADA", "cardano"),
+    ("DOGE", "dogecoin"),
+    ("TRX", "tron"),
+    ("DOT", "polkadot"),
+    ("MATIC", "matic-network"),
+    ("LTC", "litecoin"),
+    ("AVAX", "avalanche-2"),
+    ("LINK", "chainlink"),
+    ("XLM", "stellar"),
+    ("ATOM", "cosmos"),
+    ("XMR", "monero"),
+];
+
+/// Crypto price tool for fetching spot prices and 24h change from the CoinGecko public API
+pub struct CryptoPriceTool {
+    metadata: ToolMetadata,
+    client: reqwest::Client,
+}
+
+impl CryptoPriceTool {
+    /// Create a new instance of the crypto price tool
+    pub fn new() -> Self {
+        Self {
+            metadata: ToolMetadata {
+                id: "crypto_price".to_string(),
+                name: "Crypto Spot Price".to_string(),
+                description: "Fetch current cryptocurrency spot prices and 24h change via CoinGecko (no API key required)".to_string(),
+                category: ToolCategory::Financial,
+                tool_type: ToolType::Crypto,
+            },
+            client: http::build_client(DEFAULT_TOOL_TIMEOUT_SECS),
+        }
+    }
+
+    /// Set the HTTP request timeout in seconds
+    pub fn with_timeout(mut self, timeout_secs: u64) -> Self {
+        self.client = http::build_client(timeout_secs);
+        self
+    }
+
+    /// Resolve a coin symbol (e.g. 'BTC') or CoinGecko id (e.g. 'bitcoin') to a CoinGecko id
+    fn resolve_coin_id(&self, coin: &str) -> String {
+        let upper = coin.trim().to_uppercase();
+        SYMBOL_TO_ID
+            .iter()
+            .find(|(symbol, _)| *symbol == upper)
+            .map(|(_, id)| id.to_string())
+            .unwrap_or_else(|| coin.trim().to_lowercase())
+    }
+
+    /// Fetch spot price data from CoinGecko
+    async fn fetch_price(&self, coin_id: &str, vs_currency: &str) -> Result<serde_json::Value> {
+        let url = "https://api.coingecko.com/api/v3/simple/price";
+
+        println!(
+            "\x1b[33m🪙 Fetching crypto spot price from CoinGecko: {} in {}\x1b[0m",
+            coin_id, vs_currency
+        );
+
+        let response = self
+            .client
+            .get(url)
+            .header("User-Agent", "ai_tools/1.0")
+            .query(&[
+                ("ids", coin_id),
+                ("vs_currencies", vs_currency),
+                ("include_24hr_change", "true"),
+                ("include_market_cap", "true"),
+                ("include_last_updated_at", "true"),
+            ])
+            .send()
+            .await
+            .context("Failed to request price data from CoinGecko API")?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response.text().await.unwrap_or_default();
+            return Err(anyhow::anyhow!(
+                "CoinGecko API returned error {}: {}",
+                status,
+                error_text
+            ));
+        }
+
+        let data: serde_json::Value = response
+            .json()
+            .await
+            .context("Failed to parse CoinGecko API response")?;
+
+        Ok(data)
+    }
+
+    /// Format the CoinGecko `simple/price` response into a readable string
+    fn format_price_response(
+        &self,
+        data: &serde_json::Value,
+        coin_id: &str,
+        vs_currency: &str,
+    ) -> Result<String> {
+        let coin_data = data
+            .get(coin_id)
+            .and_then(|v| v.as_object())
+            .ok_or_else(|| {
+                anyhow::anyhow!(
+                    "No price data found for '{}'. Use a common symbol (e.g. BTC, ETH) or a CoinGecko coin id (e.g. 'bitcoin').",
+                    coin_id
+                )
+            })?;
+
+        let price = coin_data
+            .get(vs_currency)
+            .and_then(|v| v.as_f64())
+            .ok_or_else(|| {
+                anyhow::anyhow!(
+                    "No price found for '{}' in currency '{}'",
+                    coin_id,
+                    vs_currency
+                )
+            })?;
+
+        let currency_label = vs_currency.to_uppercase();
+        let mut result = format!("🪙 **{} Spot Price**\n\n", coin_id);
+        result.push_str(&format!("💰 **Price: {} {}**\n", price, currency_label));
+
+        if let Some(change) = coin_data
+            .get(&format!("{}_24h_change", vs_currency))
+            .and_then(|v| v.as_f64())
+        {
+            let icon = if change >= 0.0 { "📈" } else { "📉" };
+            result.push_str(&format!("{} 24h Change: {:+.2}%\n", icon, change));
+        }
+
+        if let Some(market_cap) = coin_data
+            .get(&format!("{}_market_cap", vs_currency))
+            .and_then(|v| v.as_f64())
+        {
+            result.push_str(&format!(
+                "🏦 Market Cap: {:.0} {}\n",
+                market_cap, currency_label
+            ));
+        }
+
+        if let Some(updated) = coin_data.get("last_updated_at").and_then(|v| v.as_i64()) {
+            if let Some(dt) = chrono::DateTime::from_timestamp(updated, 0) {
+                result.push_str(&format!(
+                    "🕒 Last Updated: {}\n",
+                    dt.format("%Y-%m-%d %H:%M:%S UTC")
+                ));
+            }
+        }
+
+        Ok(result)
+    }
+}
+
+#[async_trait]
+impl AgentTool for CryptoPriceTool {
+    fn metadata(&self) -> &ToolMetadata {
+        &self.metadata
+    }
+
+    fn get_function_definition(&self) -> serde_json::Value {
+        json!({
+            "name": "crypto_price",
+            "description": "Get the current spot price and 24h price change for a cryptocurrency. Use this for questions like 'what is the price of bitcoin' or 'how much did ETH move today'. For historical crypto data use 'crypto_data' instead.",
+            "parameters": {
+                "type": "object",
+                "properties": {
+                    "symbol": {
+                        "type": "string",
+                        "description": "Coin symbol (e.g. 'BTC', 'ETH', 'SOL') or CoinGecko coin id (e.g. 'bitcoin')."
+                    },
+                    "vs_currency": {
+                        "type": "string",
+                        "description": "Currency to quote the price in (e.g. 'usd', 'eur', 'pln'). Defaults to 'usd'.",
+                        "default": "usd"
+                    }
+                },
+                "required": ["symbol"]
+            }
+        })
+    }
+
+    async fn execute(&self, tool_call: &ToolCall) -> Result<ToolCallResult> {
+        let args: serde_json::Value = serde_json::from_str(&tool_call.function.arguments)
+            .context("Failed to parse crypto price tool arguments")?;
+
+        let symbol = args
+            .get("symbol")
+            .and_then(|v| v.as_str())
+            .filter(|s| !s.trim().is_empty())
+            .context("Missing required 'symbol' parameter")?;
+
+        let vs_currency = args
+            .get("vs_currency")
+            .and_then(|v| v.as_str())
+            .filter(|s| !s.trim().is_empty())
+            .unwrap_or("usd")
+            .trim()
+            .to_lowercase();
+
+        let coin_id = self.resolve_coin_id(symbol);
+
+        println!(
+            "🪙 CryptoPriceTool executing: symbol={}, id={}, vs_currency={}",
+            symbol, coin_id, vs_currency
+        );
+
+        let data = self.fetch_price(&coin_id, &vs_currency).await?;
+        let result = self.format_price_response(&data, &coin_id, &vs_currency)?;
+
+        Ok(ToolCallResult {
+            tool_name: "crypto_price".to_string(),
+            result,
+            tool_call_id: Some(tool_call.id.clone()),
+            sources: None,
+        })
+    }
+
+    fn is_available(&self) -> bool {
+        // CoinGecko's public API does not require a key
+        true
+    }
+
+    async fn check_connectivity(&self) -> Option<Result<()>> {
+        Some(http::probe(&self.client, "https://api.coingecko.com/api/v3/ping").await)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_crypto_price_metadata() {
+        let tool = CryptoPriceTool::new();
+        let metadata = tool.metadata();
+        assert_eq!(metadata.id, "crypto_price");
+        assert_eq!(metadata.category, ToolCategory::Financial);
+        assert_eq!(metadata.tool_type, ToolType::Crypto);
+        assert!(tool.is_available());
+    }
+
+    #[test]
+    fn test_crypto_price_function_definition() {
+        let tool = CryptoPriceTool::new();
+        let def = tool.get_function_definition();
+        assert_eq!(def["name"], "crypto_price");
+        assert!(def["parameters"]["properties"].get("symbol").is_some());
+        assert_eq!(
+            def["parameters"]["properties"]["vs_currency"]["default"],
+            "usd"
+        );
+    }
+
+    #[test]
+    fn test_resolve_coin_id() {
+        let tool = CryptoPriceTool::new();
+        assert_eq!(tool.resolve_coin_id("btc"), "bitcoin");
+        assert_eq!(tool.resolve_coin_id("ETH"), "ethereum");
+        assert_eq!(tool.resolve_coin_id("Shiba-Inu"), "shiba-inu");
+    }
+
+    #[test]
+    fn test_format_price_response() {
+        let tool = CryptoPriceTool::new();
+        let data = json!({
+            "bitcoin": {
+                "usd": 67187.33,
+                "usd_24h_change": -3.6363,
+                "usd_market_cap": 1317802988326.25,
+                "last_updated_at": 1711356300
+            }
+        });
+
+        let result = tool
+            .format_price_response(&data, "bitcoin", "usd")
+            .expect("Formatter should succeed");
+        assert!(result.contains("67187.33 USD"));
+        assert!(result.contains("-3.64%"));
+        assert!(result.contains("📉"));
+        assert!(result.contains("Market Cap"));
+        assert!(result.contains("2024-03-25"));
+    }
+
+    #[test]
+    fn test_format_price_response_unknown_coin() {
+        let tool = CryptoPriceTool::new();
+        let data = json!({});
+        assert!(tool
+            .format_price_response(&data, "notacoin", "usd")
+            .is_err());
+    }
+}
@@ -1,9 +1,40 @@
 use crate::api::agent::core::types::{ToolCall, ToolCallResult, ToolType};
 use crate::api::agent::tools::framework::agent_tool::{AgentTool, ToolCategory, ToolMetadata};
+use crate::api::agent::tools::framework::http::{self, DEFAULT_TOOL_TIMEOUT_SECS};
 use anyhow::{Context, Result};
 use async_trait::async_trait;
 use reqwest;
 use serde_json::json;
+use std::collections::HashMap;
+
+/// Active ISO 4217 currency codes, used to reject typos before calling the API
+const ISO_4217_CODES: &[&str] = &[
+    "AED", "AFN", "ALL", "AMD", "ANG", "AOA", "ARS", "AUD", "AWG", "AZN", "BAM", "BBD", "BDT",
+    "BGN", "BHD", "BIF", "BMD", "BND", "BOB", "BRL", "BSD", "BTN", "BWP", "BYN", "BZD", "CAD",
+    "CDF", "CHF", "CLP", "CNY", "COP", "CRC", "CUP", "CVE", "CZK", "DJF", "DKK", "DOP", "DZD",
+    "EGP", "ERN", "ETB", "EUR", "FJD", "FKP", "GBP", "GEL", "GHS", "GIP", "GMD", "GNF", "GTQ",
+    "GYD", "HKD", "HNL", "HTG", "HUF", "IDR", "ILS", "INR", "IQD", "IRR", "ISK", "JMD", "JOD",
+    "JPY", "KES", "KGS", "KHR", "KMF", "KPW", "KRW", "KWD", "KYD", "KZT", "LAK", "LBP", "LKR",
+    "LRD", "LSL", "LYD", "MAD", "MDL", "MGA", "MKD", "MMK", "MNT", "MOP", "MRU", "MUR", "MVR",
+    "MWK", "MXN", "MYR", "MZN", "NAD", "NGN", "NIO", "NOK", "NPR", "NZD", "OMR", "PAB", "PEN",
+    "PGK", "PHP", "PKR", "PLN", "PYG", "QAR", "RON", "RSD", "RUB", "RWF", "SAR", "SBD", "SCR",
+    "SDG", "SEK", "SGD", "SHP", "SLE", "SOS", "SRD", "SSP", "STN", "SVC", "SYP", "SZL", "THB",
+    "TJS", "TMT", "TND", "TOP", "TRY", "TTD", "TWD", "TZS", "UAH", "UGX", "USD", "UYU", "UZS",
+    "VES", "VND", "VUV", "WST", "XAF", "XCD", "XDR", "XOF", "XPF", "YER", "ZAR", "ZMW", "ZWL",
+];
+
+/// Normalize a currency code and check it against the ISO 4217 list
+fn validate_currency_code(code: &str) -> Result<String> {
+    let normalized = code.trim().to_uppercase();
+    if ISO_4217_CODES.contains(&normalized.as_str()) {
+        Ok(normalized)
+    } else {
+        Err(anyhow::anyhow!(
+            "Unknown currency code '{}'. Use a 3-letter ISO 4217 code such as USD, EUR or GBP",
+            code.trim()
+        ))
+    }
+}
 
 /// Currency tool for fetching exchange rates from NBP (National Bank of Poland)
 pub struct CurrencyTool {
@@ -22,10 +53,16 @@ impl CurrencyTool {
                 category: ToolCategory::Financial,
                 tool_type: ToolType::Currency,
             },
-            client: reqwest::Client::new(),
+            client: http::build_client(DEFAULT_TOOL_TIMEOUT_SECS),
         }
     }
 
+    /// Set the HTTP request timeout in seconds
+    pub fn with_timeout(mut self, timeout_secs: u64) -> Self {
+        self.client = http::build_client(timeout_secs);
+        self
+    }
+
     /// Fetch currency data from NBP API
     async fn fetch_currency_data(
         &self,
@@ -210,6 +247,91 @@ impl CurrencyTool {
             Ok(result)
         }
     }
+
+    /// Mid rates in PLN from an NBP table response, plus PLN itself
+    fn pln_mid_rates(&self, data: &serde_json::Value) -> (HashMap<String, f64>, String) {
+        let table = data.as_array().and_then(|tables| tables.first());
+        let effective_date = table
+            .and_then(|t| t.get("effectiveDate"))
+            .and_then(|d| d.as_str())
+            .unwrap_or("Unknown Date")
+            .to_string();
+
+        let mut rates: HashMap<String, f64> = table
+            .and_then(|t| t.get("rates"))
+            .and_then(|r| r.as_array())
+            .map(|rates| {
+                rates
+                    .iter()
+                    .filter_map(|rate| {
+                        let code = rate.get("code")?.as_str()?;
+                        let mid = rate.get("mid")?.as_f64()?;
+                        Some((code.to_uppercase(), mid))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        rates.insert("PLN".to_string(), 1.0);
+
+        (rates, effective_date)
+    }
+
+    /// Format cross rates from one base currency to several targets, derived from PLN mid rates
+    fn format_multi_conversion(
+        &self,
+        base: &str,
+        targets: &[String],
+        amount: f64,
+        rates: &HashMap<String, f64>,
+        effective_date: &str,
+    ) -> Result<String> {
+        let base_rate = rates.get(base).copied().with_context(|| {
+            format!("NBP does not publish a mid rate for base currency {}", base)
+        })?;
+
+        let mut result = format!(
+            "💱 **{} {} in {} currencies** (NBP mid rates, {})\n",
+            format_amount(amount),
+            base,
+            targets.len(),
+            effective_date
+        );
+        let mut unavailable = Vec::new();
+
+        for target in targets {
+            match rates.get(target) {
+                Some(target_rate) => {
+                    let rate = base_rate / target_rate;
+                    result.push_str(&format!(
+                        "  • {}: **{:.4}** (1 {} = {:.6} {})\n",
+                        target,
+                        amount * rate,
+                        base,
+                        rate,
+                        target
+                    ));
+                }
+                None => unavailable.push(target.as_str()),
+            }
+        }
+
+        if !unavailable.is_empty() {
+            result.push_str(&format!(
+                "⚠️ No NBP mid rate available for: {}\n",
+                unavailable.join(", ")
+            ));
+        }
+
+        Ok(result)
+    }
+}
+
+fn format_amount(amount: f64) -> String {
+    if amount.fract() == 0.0 {
+        format!("{:.0}", amount)
+    } else {
+        format!("{:.2}", amount)
+    }
 }
 
 #[async_trait]
@@ -221,10 +343,29 @@ impl AgentTool for CurrencyTool {
     fn get_function_definition(&self) -> serde_json::Value {
         json!({
             "name": "currency_check",
-            "description": "Check currency exchange rates via NBP (National Bank of Poland). Supports current rates, historical rates, and whole tables. Table 'A' is for mid rates of foreign currencies, 'B' for mid rates of unconvertible currencies, 'C' for bid/ask rates.",
+            "description": "Check currency exchange rates via NBP (National Bank of Poland). Supports current rates, historical rates, and whole tables. Table 'A' is for mid rates of foreign currencies, 'B' for mid rates of unconvertible currencies, 'C' for bid/ask rates. Use action 'convert' with 'base' and 'targets' to convert one currency into several others in a single call.",
             "parameters": {
                 "type": "object",
                 "properties": {
+                    "action": {
+                        "type": "string",
+                        "description": "'rates' for PLN rates (default), 'convert' for cross rates from 'base' to each of 'targets'.",
+                        "enum": ["rates", "convert"],
+                        "default": "rates"
+                    },
+                    "base": {
+                        "type": "string",
+                        "description": "Base currency code for action 'convert' (e.g. 'USD')."
+                    },
+                    "targets": {
+                        "type": "array",
+                        "items": { "type": "string" },
+                        "description": "Target currency codes for action 'convert' (e.g. ['EUR', 'GBP', 'JPY'])."
+                    },
+                    "amount": {
+                        "type": "number",
+                        "description": "Amount of the base currency to convert. Defaults to 1."
+                    },
                     "code": {
                         "type": "string",
                         "description": "3-letter currency code (e.g. 'USD', 'EUR'). If omitted, fetches the whole table."
@@ -253,7 +394,15 @@ impl AgentTool for CurrencyTool {
         let args: serde_json::Value = serde_json::from_str(&tool_call.function.arguments)
             .context("Failed to parse currency tool arguments")?;
 
-        let code = args.get("code").and_then(|v| v.as_str());
+        let action = args
+            .get("action")
+            .and_then(|v| v.as_str())
+            .unwrap_or("rates");
+        let code = args
+            .get("code")
+            .and_then(|v| v.as_str())
+            .map(validate_currency_code)
+            .transpose()?;
         let table = args.get("table").and_then(|v| v.as_str()).unwrap_or("A");
         let date = args.get("date").and_then(|v| v.as_str());
         let last = args.get("last").and_then(|v| v.as_u64());
@@ -267,12 +416,119 @@ impl AgentTool for CurrencyTool {
             }
         }
 
-        let data = self.fetch_currency_data(table, code, date, last).await?;
+        if action == "convert" {
+            let base = validate_currency_code(
+                args.get("base")
+                    .and_then(|v| v.as_str())
+                    .context("Missing required 'base' parameter for action 'convert'")?,
+            )?;
+            let targets = args
+                .get("targets")
+                .and_then(|v| v.as_array())
+                .filter(|targets| !targets.is_empty())
+                .context("Missing required 'targets' list for action 'convert'")?
+                .iter()
+                .map(|t| {
+                    t.as_str()
+                        .context("Target currency codes must be strings")
+                        .and_then(validate_currency_code)
+                })
+                .collect::<Result<Vec<_>>>()?;
+            let amount = args.get("amount").and_then(|v| v.as_f64()).unwrap_or(1.0);
+
+            // One table A request covers every currency NBP publishes mid rates for
+            let data = self.fetch_currency_data("A", None, date, None).await?;
+            let (rates, effective_date) = self.pln_mid_rates(&data);
+            let result =
+                self.format_multi_conversion(&base, &targets, amount, &rates, &effective_date)?;
+
+            return Ok(ToolCallResult {
+                tool_name: "currency_check".to_string(),
+                result,
+                tool_call_id: Some(tool_call.id.clone()),
+                sources: None,
+            });
+        }
+
+        let data = self
+            .fetch_currency_data(table, code.as_deref(), date, last)
+            .await?;
         let result = self.format_currency_response(&data, table)?;
 
         Ok(ToolCallResult {
             tool_name: "currency_check".to_string(),
             result,
+            tool_call_id: Some(tool_call.id.clone()),
+            sources: None,
         })
     }
+
+    async fn check_connectivity(&self) -> Option<Result<()>> {
+        Some(
+            http::probe(
+                &self.client,
+                "https://api.nbp.pl/api/exchangerates/tables/A/?format=json",
+            )
+            .await,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table_a() -> serde_json::Value {
+        json!([{
+            "table": "A",
+            "no": "059/A/NBP/2024",
+            "effectiveDate": "2024-03-25",
+            "rates": [
+                { "currency": "dolar amerykański", "code": "USD", "mid": 4.0 },
+                { "currency": "euro", "code": "EUR", "mid": 4.32 },
+                { "currency": "jen (Japonia)", "code": "JPY", "mid": 0.0264 }
+            ]
+        }])
+    }
+
+    #[test]
+    fn test_validate_currency_code() {
+        assert_eq!(validate_currency_code(" usd ").unwrap(), "USD");
+        let err = validate_currency_code("XYZ").unwrap_err().to_string();
+        assert!(err.contains("Unknown currency code 'XYZ'"));
+    }
+
+    #[test]
+    fn test_format_multi_conversion() {
+        let tool = CurrencyTool::new();
+        let (rates, effective_date) = tool.pln_mid_rates(&table_a());
+        assert_eq!(effective_date, "2024-03-25");
+
+        let targets = vec!["EUR".to_string(), "PLN".to_string(), "JPY".to_string()];
+        let result = tool
+            .format_multi_conversion("USD", &targets, 100.0, &rates, &effective_date)
+            .expect("Formatter should succeed");
+
+        assert!(result.contains("100 USD in 3 currencies"));
+        assert!(result.contains("• EUR: **92.5926** (1 USD = 0.925926 EUR)"));
+        assert!(result.contains("• PLN: **400.0000** (1 USD = 4.000000 PLN)"));
+        assert!(result.contains("• JPY: **15151.5152**"));
+    }
+
+    #[test]
+    fn test_format_multi_conversion_reports_unavailable_targets() {
+        let tool = CurrencyTool::new();
+        let (rates, effective_date) = tool.pln_mid_rates(&table_a());
+        let targets = vec!["EUR".to_string(), "KPW".to_string()];
+
+        let result = tool
+            .format_multi_conversion("PLN", &targets, 1.0, &rates, &effective_date)
+            .expect("Formatter should succeed");
+        assert!(result.contains("• EUR: **0.2315**"));
+        assert!(result.contains("No NBP mid rate available for: KPW"));
+
+        assert!(tool
+            .format_multi_conversion("KPW", &targets, 1.0, &rates, &effective_date)
+            .is_err());
+    }
 }
@@ -1,42 +1,46 @@
 pub mod crypto;
+pub mod crypto_price;
 pub mod currency;
 pub mod stock;
 
 use crate::api::agent::core::types::{AgentConfig, ToolType};
 use crate::api::agent::tools::financial::crypto::CryptoTool;
+use crate::api::agent::tools::financial::crypto_price::CryptoPriceTool;
 use crate::api::agent::tools::financial::currency::CurrencyTool;
 use crate::api::agent::tools::financial::stock::StockTool;
-use crate::api::agent::tools::framework::agent_tool::AgentTool;
 use crate::api::agent::tools::framework::registry::ToolRegistry;
 use std::sync::Arc;
 
-pub fn register(registry: &mut ToolRegistry, config: &AgentConfig) {
+pub fn register(
+    registry: &mut ToolRegistry,
+    config: &AgentConfig,
+    _context: &crate::api::agent::tools::RegisterContext,
+) {
     if config.enabled_tools.contains(&ToolType::Currency) {
-        let tool = CurrencyTool::new();
+        let tool = CurrencyTool::new().with_timeout(config.tool_timeout_secs);
         if let Err(e) = registry.register(Arc::new(tool)) {
             println!("⚠️ Failed to register Currency tool: {}", e);
         }
     }
 
     if config.enabled_tools.contains(&ToolType::Crypto) {
-        let tool = CryptoTool::new();
-        if tool.is_available() {
-            if let Err(e) = registry.register(Arc::new(tool)) {
-                println!("⚠️ Failed to register Crypto tool: {}", e);
-            }
-        } else {
-            println!("⚠️ Crypto tool unavailable: ALPHA_ADVANTAGE_KEY not set");
+        // Spot prices come from CoinGecko, which needs no API key
+        let price_tool = CryptoPriceTool::new().with_timeout(config.tool_timeout_secs);
+        if let Err(e) = registry.register(Arc::new(price_tool)) {
+            println!("⚠️ Failed to register Crypto Price tool: {}", e);
+        }
+
+        // The registry skips (and records) the tool when ALPHA_ADVANTAGE_KEY isn't set
+        let tool = CryptoTool::new().with_timeout(config.tool_timeout_secs);
+        if let Err(e) = registry.register(Arc::new(tool)) {
+            println!("⚠️ Failed to register Crypto tool: {}", e);
         }
     }
 
     if config.enabled_tools.contains(&ToolType::Stock) {
-        let tool = StockTool::new();
-        if tool.is_available() {
-            if let Err(e) = registry.register(Arc::new(tool)) {
-                println!("⚠️ Failed to register Stock tool: {}", e);
-            }
-        } else {
-            println!("⚠️ Stock tool unavailable: ALPHA_ADVANTAGE_KEY not set");
+        let tool = StockTool::new().with_timeout(config.tool_timeout_secs);
+        if let Err(e) = registry.register(Arc::new(tool)) {
+            println!("⚠️ Failed to register Stock tool: {}", e);
         }
     }
 }
@@ -1,11 +1,15 @@
 use crate::api::agent::core::types::{ToolCall, ToolCallResult, ToolType};
 use crate::api::agent::tools::framework::agent_tool::{AgentTool, ToolCategory, ToolMetadata};
+use crate::api::agent::tools::framework::http::{self, DEFAULT_TOOL_TIMEOUT_SECS};
 use anyhow::{Context, Result};
 use async_trait::async_trait;
 use reqwest;
 use serde_json::json;
 use std::env;
 
+/// Trading days shown by the `history` action when no start date is given
+const DEFAULT_HISTORY_DAYS: usize = 30;
+
 /// Stock tool for fetching stock market data from Alpha Vantage API
 pub struct StockTool {
     metadata: ToolMetadata,
@@ -26,11 +30,17 @@ impl StockTool {
                 category: ToolCategory::Financial,
                 tool_type: ToolType::Stock,
             },
-            client: reqwest::Client::new(),
+            client: http::build_client(DEFAULT_TOOL_TIMEOUT_SECS),
             api_key,
         }
     }
 
+    /// Set the HTTP request timeout in seconds
+    pub fn with_timeout(mut self, timeout_secs: u64) -> Self {
+        self.client = http::build_client(timeout_secs);
+        self
+    }
+
     /// Fetch stock data from Alpha Vantage API
     async fn fetch_stock_data(
         &self,
@@ -90,12 +100,7 @@ impl StockTool {
             ));
         }
 
-        if let Some(note) = data.get("Note") {
-            return Err(anyhow::anyhow!(
-                "Alpha Vantage API limit reached: {}",
-                note.as_str().unwrap_or("Rate limit exceeded")
-            ));
-        }
+        check_rate_limit(&data)?;
 
         Ok(data)
     }
@@ -211,6 +216,102 @@ impl StockTool {
             Ok(result)
         }
     }
+
+    /// Format daily history as a compact table with min/max/% change stats.
+    /// Dates are `YYYY-MM-DD`; without a start date the latest `limit` trading days are used.
+    fn format_history_response(
+        &self,
+        data: &serde_json::Value,
+        start_date: Option<&str>,
+        end_date: Option<&str>,
+        limit: usize,
+    ) -> Result<String> {
+        let symbol = data
+            .get("Meta Data")
+            .and_then(|meta| meta.get("2. Symbol"))
+            .and_then(|s| s.as_str())
+            .unwrap_or("Unknown");
+
+        let time_series = data
+            .get("Time Series (Daily)")
+            .and_then(|ts| ts.as_object())
+            .context("No daily time series data found in response")?;
+
+        let field = |values: &serde_json::Value, key: &str| -> Option<f64> {
+            values.get(key)?.as_str()?.parse().ok()
+        };
+
+        // (date, close, volume), oldest first. ISO dates sort lexicographically.
+        let mut entries: Vec<(&str, f64, u64)> = time_series
+            .iter()
+            .filter(|(date, _)| start_date.is_none_or(|start| date.as_str() >= start))
+            .filter(|(date, _)| end_date.is_none_or(|end| date.as_str() <= end))
+            .filter_map(|(date, values)| {
+                let close = field(values, "4. close")?;
+                let volume = field(values, "5. volume").unwrap_or(0.0) as u64;
+                Some((date.as_str(), close, volume))
+            })
+            .collect();
+        entries.sort_by(|a, b| a.0.cmp(b.0));
+
+        if start_date.is_none() && limit > 0 && entries.len() > limit {
+            entries.drain(..entries.len() - limit);
+        }
+
+        let (Some(first), Some(last)) = (entries.first(), entries.last()) else {
+            return Ok(format!(
+                "No daily data for {} in the requested range.",
+                symbol
+            ));
+        };
+
+        let min = entries
+            .iter()
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .unwrap_or(first);
+        let max = entries
+            .iter()
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .unwrap_or(first);
+        let change = if first.1 != 0.0 {
+            (last.1 - first.1) / first.1 * 100.0
+        } else {
+            0.0
+        };
+
+        let mut result = format!(
+            "📊 **{} Daily History ({} → {}, {} trading days)**\n\n",
+            symbol,
+            first.0,
+            last.0,
+            entries.len()
+        );
+        result.push_str(&format!("• Min close: ${:.2} ({})\n", min.1, min.0));
+        result.push_str(&format!("• Max close: ${:.2} ({})\n", max.1, max.0));
+        result.push_str(&format!(
+            "• Change: {:+.2}% (${:.2} → ${:.2})\n\n",
+            change, first.1, last.1
+        ));
+
+        result.push_str("| Date | Close | Volume |\n|---|---|---|\n");
+        for (date, close, volume) in &entries {
+            result.push_str(&format!("| {} | {:.2} | {} |\n", date, close, volume));
+        }
+
+        Ok(result)
+    }
+}
+
+/// Alpha Vantage reports throttling with HTTP 200 and a "Note" (or, on newer
+/// free-tier keys, "Information") field instead of data
+fn check_rate_limit(data: &serde_json::Value) -> Result<()> {
+    if let Some(note) = data.get("Note").or_else(|| data.get("Information")) {
+        return Err(anyhow::anyhow!(
+            "Alpha Vantage rate limit reached (free tier allows only a few requests per minute/day), try again later: {}",
+            note.as_str().unwrap_or("Rate limit exceeded")
+        ));
+    }
+    Ok(())
 }
 
 #[async_trait]
@@ -222,7 +323,7 @@ impl AgentTool for StockTool {
     fn get_function_definition(&self) -> serde_json::Value {
         json!({
             "name": "stock_data",
-            "description": "Fetch stock market data (OHLCV) for a given stock symbol. CHOOSE THE BEST FUNCTION based on the time range requested:\n- **TIME_SERIES_DAILY**: Use for recent data (last few days, last week, up to 2 months).\n- **TIME_SERIES_WEEKLY**: Use for medium-term data (last 2 months to 2 years).\n- **TIME_SERIES_MONTHLY**: Use for long-term data (over 2 years).\n\nExamples:\n- 'last 7 days': TIME_SERIES_DAILY\n- 'last 10 weeks': TIME_SERIES_WEEKLY\n- 'last 5 years': TIME_SERIES_MONTHLY\n\nWhen chart is requested, use this data to generate a json-chart.",
+            "description": "Fetch stock market data (OHLCV) for a given stock symbol. Use action 'history' for daily closes over a date range with min/max/% change stats (e.g. 'how did NVDA do since March?'). For action 'series', CHOOSE THE BEST FUNCTION based on the time range requested:\n- **TIME_SERIES_DAILY**: Use for recent data (last few days, last week, up to 2 months).\n- **TIME_SERIES_WEEKLY**: Use for medium-term data (last 2 months to 2 years).\n- **TIME_SERIES_MONTHLY**: Use for long-term data (over 2 years).\n\nExamples:\n- 'last 7 days': TIME_SERIES_DAILY\n- 'last 10 weeks': TIME_SERIES_WEEKLY\n- 'last 5 years': TIME_SERIES_MONTHLY\n\nWhen chart is requested, use this data to generate a json-chart.",
             "parameters": {
                 "type": "object",
                 "properties": {
@@ -230,6 +331,20 @@ impl AgentTool for StockTool {
                         "type": "string",
                         "description": "Stock ticker symbol (e.g., 'NVDA' for Nvidia, 'AAPL' for Apple)."
                     },
+                    "action": {
+                        "type": "string",
+                        "description": "'series' for raw OHLCV entries, 'history' for a daily close table with summary stats.",
+                        "enum": ["series", "history"],
+                        "default": "series"
+                    },
+                    "start_date": {
+                        "type": "string",
+                        "description": "History start date (YYYY-MM-DD, inclusive). Only used with action 'history'."
+                    },
+                    "end_date": {
+                        "type": "string",
+                        "description": "History end date (YYYY-MM-DD, inclusive). Only used with action 'history'."
+                    },
                     "function": {
                         "type": "string",
                         "description": "Time series function: 'TIME_SERIES_DAILY', 'TIME_SERIES_WEEKLY', 'TIME_SERIES_MONTHLY'.",
@@ -263,6 +378,11 @@ impl AgentTool for StockTool {
             .context("Missing required 'symbol' parameter")?
             .to_uppercase();
 
+        let action = args
+            .get("action")
+            .and_then(|v| v.as_str())
+            .unwrap_or("series");
+
         let function = args
             .get("function")
             .and_then(|v| v.as_str())
@@ -274,6 +394,43 @@ impl AgentTool for StockTool {
             .and_then(|v| v.as_u64())
             .map(|v| v as usize);
 
+        if action == "history" {
+            let start_date = args.get("start_date").and_then(|v| v.as_str());
+            let end_date = args.get("end_date").and_then(|v| v.as_str());
+            for date in [start_date, end_date].into_iter().flatten() {
+                if chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d").is_err() {
+                    return Err(anyhow::anyhow!(
+                        "Invalid date '{}'. Use the YYYY-MM-DD format",
+                        date
+                    ));
+                }
+            }
+
+            let data = self
+                .fetch_stock_data("TIME_SERIES_DAILY", &symbol, outputsize)
+                .await?;
+            let result = self.format_history_response(
+                &data,
+                start_date,
+                end_date,
+                limit.unwrap_or(DEFAULT_HISTORY_DAYS),
+            )?;
+
+            return Ok(ToolCallResult {
+                tool_name: "stock_data".to_string(),
+                result,
+                tool_call_id: Some(tool_call.id.clone()),
+                sources: None,
+            });
+        }
+
+        if action != "series" {
+            return Err(anyhow::anyhow!(
+                "Invalid action '{}'. Must be one of: series, history",
+                action
+            ));
+        }
+
         // Validate function parameter
         let valid_functions = [
             "TIME_SERIES_DAILY",
@@ -293,10 +450,99 @@ impl AgentTool for StockTool {
         Ok(ToolCallResult {
             tool_name: "stock_data".to_string(),
             result,
+            tool_call_id: Some(tool_call.id.clone()),
+            sources: None,
         })
     }
 
     fn is_available(&self) -> bool {
         self.api_key.is_some()
     }
+
+    fn required_env_vars(&self) -> &'static [&'static str] {
+        &["ALPHA_ADVANTAGE_KEY"]
+    }
+
+    fn max_concurrency(&self) -> Option<usize> {
+        // The free Alpha Vantage tier allows only a few requests per minute
+        Some(1)
+    }
+
+    // No connectivity check: Alpha Vantage's free tier allows 25 requests a day
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn daily_series() -> serde_json::Value {
+        json!({
+            "Meta Data": { "2. Symbol": "NVDA" },
+            "Time Series (Daily)": {
+                "2024-03-04": { "4. close": "852.37", "5. volume": "61561600" },
+                "2024-03-05": { "4. close": "859.64", "5. volume": "52063900" },
+                "2024-03-06": { "4. close": "887.00", "5. volume": "58252000" },
+                "2024-03-07": { "4. close": "926.69", "5. volume": "60811900" },
+                "2024-03-08": { "4. close": "875.28", "5. volume": "113299600" }
+            }
+        })
+    }
+
+    #[test]
+    fn test_stock_function_definition_has_history_action() {
+        let tool = StockTool::new();
+        let def = tool.get_function_definition();
+        assert_eq!(def["name"], "stock_data");
+        assert_eq!(
+            def["parameters"]["properties"]["action"]["enum"],
+            json!(["series", "history"])
+        );
+    }
+
+    #[test]
+    fn test_format_history_response_stats() {
+        let tool = StockTool::new();
+        let result = tool
+            .format_history_response(&daily_series(), None, None, 30)
+            .expect("Formatter should succeed");
+
+        assert!(result.contains("NVDA Daily History (2024-03-04 → 2024-03-08, 5 trading days)"));
+        assert!(result.contains("Min close: $852.37 (2024-03-04)"));
+        assert!(result.contains("Max close: $926.69 (2024-03-07)"));
+        assert!(result.contains("Change: +2.69%"));
+        assert!(result.contains("| 2024-03-08 | 875.28 | 113299600 |"));
+    }
+
+    #[test]
+    fn test_format_history_response_range_and_limit() {
+        let tool = StockTool::new();
+        let ranged = tool
+            .format_history_response(&daily_series(), Some("2024-03-05"), Some("2024-03-06"), 30)
+            .expect("Formatter should succeed");
+        assert!(ranged.contains("2024-03-05 → 2024-03-06, 2 trading days"));
+        assert!(!ranged.contains("| 2024-03-07 |"));
+
+        let limited = tool
+            .format_history_response(&daily_series(), None, None, 2)
+            .expect("Formatter should succeed");
+        assert!(limited.contains("2024-03-07 → 2024-03-08, 2 trading days"));
+
+        let empty = tool
+            .format_history_response(&daily_series(), Some("2025-01-01"), None, 30)
+            .expect("Formatter should succeed");
+        assert_eq!(empty, "No daily data for NVDA in the requested range.");
+    }
+
+    #[test]
+    fn test_check_rate_limit() {
+        assert!(check_rate_limit(&daily_series()).is_ok());
+
+        let throttled = json!({ "Note": "Thank you for using Alpha Vantage! Our standard API rate limit is 25 requests per day." });
+        let err = check_rate_limit(&throttled).unwrap_err().to_string();
+        assert!(err.starts_with("Alpha Vantage rate limit reached"));
+        assert!(err.contains("25 requests per day"));
+
+        let information = json!({ "Information": "Please consider upgrading to premium." });
+        assert!(check_rate_limit(&information).is_err());
+    }
 }
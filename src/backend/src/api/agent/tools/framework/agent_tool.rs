@@ -68,4 +68,23 @@ pub trait AgentTool: Send + Sync {
     fn is_available(&self) -> bool {
         true
     }
+
+    /// Environment variables this tool needs before `is_available` returns true
+    fn required_env_vars(&self) -> &'static [&'static str] {
+        &[]
+    }
+
+    /// Most calls of this tool allowed to run at once, to avoid flooding
+    /// rate-limited APIs when the model requests parallel calls (`None` = unlimited).
+    /// `tool_concurrency_limits` in the agent config overrides it per tool id.
+    fn max_concurrency(&self) -> Option<usize> {
+        None
+    }
+
+    /// Cheap reachability probe of the tool's upstream service, used by
+    /// `GET /api/agent/tools/validate?deep=true`. `None` means there is nothing
+    /// worth probing (or probing would spend a metered API quota).
+    async fn check_connectivity(&self) -> Option<Result<()>> {
+        None
+    }
 }
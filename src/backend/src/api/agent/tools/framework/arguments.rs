@@ -0,0 +1,209 @@
+use serde_json::Value;
+
+/// Parse tool call arguments, repairing the mistakes small models commonly make:
+/// markdown code fences, trailing commas, single-quoted strings, empty
+/// arguments and a JSON object double-encoded as a string.
+/// Returns the error from the strict parse when the repaired text still isn't valid.
+pub fn parse_arguments(raw: &str) -> Result<Value, serde_json::Error> {
+    let strict_err = match serde_json::from_str::<Value>(raw) {
+        Ok(value) => return Ok(unwrap_encoded_object(value)),
+        Err(e) => e,
+    };
+
+    let trimmed = strip_code_fences(raw.trim());
+    if trimmed.is_empty() {
+        // Tools without parameters are often called with no arguments at all
+        return Ok(Value::Object(serde_json::Map::new()));
+    }
+
+    let repaired = remove_trailing_commas(&replace_single_quotes(trimmed));
+    serde_json::from_str::<Value>(&repaired)
+        .map(unwrap_encoded_object)
+        .map_err(|_| strict_err)
+}
+
+/// Check arguments against the `parameters` JSON schema of a function definition.
+/// Covers what tools rely on: required fields, property types and enum values.
+/// Returns one message per problem; `null` counts as a missing value.
+pub fn validate_arguments(arguments: &Value, parameters: &Value) -> Result<(), Vec<String>> {
+    let Some(args) = arguments.as_object() else {
+        return Err(vec![format!(
+            "arguments must be a JSON object, got {}",
+            type_name(arguments)
+        )]);
+    };
+    let mut problems = Vec::new();
+
+    if let Some(required) = parameters.get("required").and_then(|r| r.as_array()) {
+        for name in required.iter().filter_map(|n| n.as_str()) {
+            if args.get(name).is_none_or(|v| v.is_null()) {
+                problems.push(format!("missing required parameter '{}'", name));
+            }
+        }
+    }
+
+    if let Some(properties) = parameters.get("properties").and_then(|p| p.as_object()) {
+        for (name, schema) in properties {
+            let Some(value) = args.get(name).filter(|v| !v.is_null()) else {
+                continue;
+            };
+            if let Some(expected) = schema.get("type") {
+                let types: Vec<&str> = match expected {
+                    Value::String(t) => vec![t.as_str()],
+                    Value::Array(ts) => ts.iter().filter_map(|t| t.as_str()).collect(),
+                    _ => Vec::new(),
+                };
+                if !types.is_empty() && !types.iter().any(|t| matches_type(value, t)) {
+                    problems.push(format!(
+                        "parameter '{}' must be of type {}, got {}",
+                        name,
+                        types.join(" or "),
+                        type_name(value)
+                    ));
+                    continue;
+                }
+            }
+            if let Some(allowed) = schema.get("enum").and_then(|e| e.as_array()) {
+                if !allowed.contains(value) {
+                    let allowed: Vec<String> = allowed.iter().map(|v| v.to_string()).collect();
+                    problems.push(format!(
+                        "parameter '{}' must be one of {}, got {}",
+                        name,
+                        allowed.join(", "),
+                        value
+                    ));
+                }
+            }
+        }
+    }
+
+    if problems.is_empty() {
+        Ok(())
+    } else {
+        Err(problems)
+    }
+}
+
+fn matches_type(value: &Value, expected: &str) -> bool {
+    match expected {
+        "string" => value.is_string(),
+        "integer" => {
+            value.is_i64() || value.is_u64() || value.as_f64().is_some_and(|n| n.fract() == 0.0)
+        }
+        "number" => value.is_number(),
+        "boolean" => value.is_boolean(),
+        "array" => value.is_array(),
+        "object" => value.is_object(),
+        "null" => value.is_null(),
+        // Unknown type keywords aren't ours to reject
+        _ => true,
+    }
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+/// `"{\"city\": \"Paris\"}"` -> `{"city": "Paris"}`
+fn unwrap_encoded_object(value: Value) -> Value {
+    if let Value::String(inner) = &value {
+        if let Ok(parsed @ Value::Object(_)) = serde_json::from_str::<Value>(inner) {
+            return parsed;
+        }
+    }
+    value
+}
+
+fn strip_code_fences(text: &str) -> &str {
+    let Some(rest) = text.strip_prefix("```") else {
+        return text;
+    };
+    // Drop the language tag on the opening fence, e.g. ```json
+    let rest = rest
+        .trim_start_matches(|c: char| c.is_ascii_alphanumeric())
+        .trim_start();
+    rest.strip_suffix("```").unwrap_or(rest).trim()
+}
+
+/// Turn `'...'` strings into `"..."`, leaving double-quoted strings alone
+fn replace_single_quotes(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut quote: Option<char> = None;
+    let mut escaped = false;
+
+    for c in text.chars() {
+        match quote {
+            None => match c {
+                '\'' => {
+                    quote = Some('\'');
+                    out.push('"');
+                }
+                '"' => {
+                    quote = Some('"');
+                    out.push(c);
+                }
+                _ => out.push(c),
+            },
+            Some(q) => {
+                if escaped {
+                    escaped = false;
+                    // \' is not a valid JSON escape
+                    if c == '\'' && q == '\'' {
+                        out.pop();
+                    }
+                    out.push(c);
+                } else if c == '\\' {
+                    escaped = true;
+                    out.push(c);
+                } else if c == q {
+                    quote = None;
+                    out.push('"');
+                } else if c == '"' && q == '\'' {
+                    out.push_str("\\\"");
+                } else {
+                    out.push(c);
+                }
+            }
+        }
+    }
+    out
+}
+
+/// Drop commas directly before a closing `}` or `]`, outside of strings
+fn remove_trailing_commas(text: &str) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let mut out = String::with_capacity(text.len());
+    let mut in_string = false;
+    let mut escaped = false;
+
+    for (i, &c) in chars.iter().enumerate() {
+        if in_string {
+            if escaped {
+                escaped = false;
+            } else if c == '\\' {
+                escaped = true;
+            } else if c == '"' {
+                in_string = false;
+            }
+            out.push(c);
+            continue;
+        }
+        if c == '"' {
+            in_string = true;
+        } else if c == ',' {
+            let next = chars[i + 1..].iter().find(|c| !c.is_whitespace());
+            if matches!(next, Some('}') | Some(']')) {
+                continue;
+            }
+        }
+        out.push(c);
+    }
+    out
+}
@@ -0,0 +1,106 @@
+use crate::api::agent::core::types::{ToolCall, ToolCallResult};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Default maximum number of cached tool results
+pub const DEFAULT_CACHE_CAPACITY: usize = 256;
+
+struct CacheEntry {
+    result: ToolCallResult,
+    inserted_at: Instant,
+    last_used: u64,
+}
+
+struct CacheState {
+    entries: HashMap<u64, CacheEntry>,
+    /// Monotonic counter used to track recency for LRU eviction
+    tick: u64,
+}
+
+/// In-memory TTL cache for tool results, bounded in size with LRU eviction.
+///
+/// Shared across requests so repeated identical tool calls (same city, same symbol)
+/// within the TTL window don't hit external APIs again.
+pub struct ToolResultCache {
+    capacity: usize,
+    state: Mutex<CacheState>,
+}
+
+impl ToolResultCache {
+    /// Create a new cache holding at most `capacity` results
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            state: Mutex::new(CacheState {
+                entries: HashMap::new(),
+                tick: 0,
+            }),
+        }
+    }
+
+    /// Cache key for a tool call: hash of the function name and normalized arguments.
+    /// Arguments are re-serialized so key order and whitespace don't cause misses.
+    pub fn key(tool_call: &ToolCall) -> u64 {
+        let arguments = serde_json::from_str::<serde_json::Value>(&tool_call.function.arguments)
+            .map(|v| v.to_string())
+            .unwrap_or_else(|_| tool_call.function.arguments.clone());
+
+        let mut hasher = DefaultHasher::new();
+        tool_call.function.name.hash(&mut hasher);
+        arguments.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// Get a cached result if present and younger than `ttl`
+    pub fn get(&self, tool_call: &ToolCall, ttl: Duration) -> Option<ToolCallResult> {
+        let key = Self::key(tool_call);
+        let mut state = self.state.lock().unwrap();
+        state.tick += 1;
+        let tick = state.tick;
+
+        let expired = match state.entries.get_mut(&key) {
+            Some(entry) if entry.inserted_at.elapsed() < ttl => {
+                entry.last_used = tick;
+                return Some(entry.result.clone());
+            }
+            Some(_) => true,
+            None => false,
+        };
+
+        if expired {
+            state.entries.remove(&key);
+        }
+        None
+    }
+
+    /// Store a result, evicting the least recently used entry when full
+    pub fn insert(&self, tool_call: &ToolCall, result: ToolCallResult) {
+        let key = Self::key(tool_call);
+        let mut state = self.state.lock().unwrap();
+        state.tick += 1;
+        let tick = state.tick;
+
+        if !state.entries.contains_key(&key) && state.entries.len() >= self.capacity {
+            if let Some(lru_key) = state
+                .entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.last_used)
+                .map(|(k, _)| *k)
+            {
+                state.entries.remove(&lru_key);
+            }
+        }
+
+        state.entries.insert(
+            key,
+            CacheEntry {
+                result,
+                inserted_at: Instant::now(),
+                last_used: tick,
+            },
+        );
+    }
+}
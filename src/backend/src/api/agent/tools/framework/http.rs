@@ -0,0 +1,169 @@
+use super::retry::retry_request;
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use reqwest::{Client, ClientBuilder, StatusCode};
+use serde::de::DeserializeOwned;
+use std::time::Duration;
+
+/// Default timeout for a single tool HTTP request
+pub const DEFAULT_TOOL_TIMEOUT_SECS: u64 = 30;
+
+/// Client builder preconfigured with the tool request timeout
+pub fn client_builder(timeout_secs: u64) -> ClientBuilder {
+    Client::builder().timeout(Duration::from_secs(timeout_secs))
+}
+
+/// Build an HTTP client for tools so a hung upstream can't block the agent loop
+pub fn build_client(timeout_secs: u64) -> Client {
+    client_builder(timeout_secs)
+        .build()
+        .expect("Failed to build reqwest client")
+}
+
+/// Status and body of an HTTP response, read in full
+#[derive(Debug, Clone)]
+pub struct ClientResponse {
+    pub status: StatusCode,
+    pub body: String,
+}
+
+impl ClientResponse {
+    pub fn is_success(&self) -> bool {
+        self.status.is_success()
+    }
+
+    /// Parse the body as JSON
+    pub fn json<T: DeserializeOwned>(&self) -> Result<T> {
+        serde_json::from_str(&self.body).context("Response body is not the expected JSON")
+    }
+}
+
+/// HTTP access for tools. Tools hold an `Arc<dyn HttpClient>` so tests can
+/// swap the network for canned responses.
+#[async_trait]
+pub trait HttpClient: Send + Sync {
+    async fn get(&self, url: &str) -> Result<ClientResponse>;
+
+    /// POST `body` as JSON
+    async fn post(&self, url: &str, body: &serde_json::Value) -> Result<ClientResponse>;
+}
+
+async fn read_response(response: reqwest::Response) -> Result<ClientResponse> {
+    let status = response.status();
+    let body = response
+        .text()
+        .await
+        .context("Failed to read response body")?;
+    Ok(ClientResponse { status, body })
+}
+
+/// `HttpClient` for real requests: GETs are retried with `retry_request`,
+/// POSTs are sent once since they may not be idempotent
+pub struct ReqwestHttpClient {
+    client: Client,
+    max_retries: u32,
+}
+
+impl ReqwestHttpClient {
+    pub fn new(timeout_secs: u64, max_retries: u32) -> Self {
+        Self {
+            client: build_client(timeout_secs),
+            max_retries,
+        }
+    }
+}
+
+#[async_trait]
+impl HttpClient for ReqwestHttpClient {
+    async fn get(&self, url: &str) -> Result<ClientResponse> {
+        read_response(retry_request(self.client.get(url), self.max_retries).await?).await
+    }
+
+    async fn post(&self, url: &str, body: &serde_json::Value) -> Result<ClientResponse> {
+        read_response(self.client.post(url).json(body).send().await?).await
+    }
+}
+
+/// A plain client sends every request once
+#[async_trait]
+impl HttpClient for Client {
+    async fn get(&self, url: &str) -> Result<ClientResponse> {
+        read_response(Client::get(self, url).send().await?).await
+    }
+
+    async fn post(&self, url: &str, body: &serde_json::Value) -> Result<ClientResponse> {
+        read_response(Client::post(self, url).json(body).send().await?).await
+    }
+}
+
+/// `HttpClient` for tests: answers each request with the canned response
+/// whose URL fragment it contains, and records the URLs requested
+#[cfg(test)]
+#[derive(Default)]
+pub struct MockHttpClient {
+    responses: Vec<(String, ClientResponse)>,
+    requests: std::sync::Mutex<Vec<String>>,
+}
+
+#[cfg(test)]
+impl MockHttpClient {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Answer requests whose URL contains `url_fragment` with `status` and a JSON body.
+    /// Earlier registrations win when several fragments match.
+    pub fn with_json(mut self, url_fragment: &str, status: u16, body: serde_json::Value) -> Self {
+        self.responses.push((
+            url_fragment.to_string(),
+            ClientResponse {
+                status: StatusCode::from_u16(status).expect("Invalid status code"),
+                body: body.to_string(),
+            },
+        ));
+        self
+    }
+
+    /// URLs requested so far, in order
+    pub fn requests(&self) -> Vec<String> {
+        self.requests.lock().unwrap().clone()
+    }
+
+    fn respond(&self, url: &str) -> Result<ClientResponse> {
+        self.requests.lock().unwrap().push(url.to_string());
+        self.responses
+            .iter()
+            .find(|(fragment, _)| url.contains(fragment.as_str()))
+            .map(|(_, response)| response.clone())
+            .ok_or_else(|| anyhow::anyhow!("No canned response for {}", url))
+    }
+}
+
+#[cfg(test)]
+#[async_trait]
+impl HttpClient for MockHttpClient {
+    async fn get(&self, url: &str) -> Result<ClientResponse> {
+        self.respond(url)
+    }
+
+    async fn post(&self, url: &str, _body: &serde_json::Value) -> Result<ClientResponse> {
+        self.respond(url)
+    }
+}
+
+/// GET `url` and treat any non-error answer as reachable. Rejected
+/// credentials and server errors are reported so a bad API key shows up.
+pub async fn probe(client: &dyn HttpClient, url: &str) -> Result<()> {
+    let response = client
+        .get(url)
+        .await
+        .map_err(|e| anyhow::anyhow!("Request failed: {}", e))?;
+    let status = response.status;
+    if status == reqwest::StatusCode::UNAUTHORIZED || status == reqwest::StatusCode::FORBIDDEN {
+        anyhow::bail!("Credentials rejected ({})", status);
+    }
+    if status.is_server_error() {
+        anyhow::bail!("Upstream error ({})", status);
+    }
+    Ok(())
+}
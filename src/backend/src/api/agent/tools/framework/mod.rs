@@ -1,5 +1,9 @@
 pub mod agent_tool;
+pub mod arguments;
+pub mod cache;
+pub mod http;
 pub mod registry;
+pub mod retry;
 pub mod selector;
 
 #[cfg(test)]
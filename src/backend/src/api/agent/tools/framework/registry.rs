@@ -1,13 +1,25 @@
 use crate::api::agent::core::types::{Tool, ToolCall, ToolCallResult};
 use crate::api::agent::tools::framework::agent_tool::{AgentTool, ToolMetadata};
+use crate::api::agent::tools::framework::arguments::{parse_arguments, validate_arguments};
+use crate::api::agent::tools::framework::cache::ToolResultCache;
+use crate::api::metrics::registry as metrics;
 use anyhow::{Context, Result};
+use log::{debug, info, warn};
 use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Semaphore;
 
 /// Registry for managing all available tools
 pub struct ToolRegistry {
     tools: HashMap<String, Arc<dyn AgentTool>>,
     metadata_map: HashMap<String, ToolMetadata>,
+    /// Enabled tools skipped because `is_available` returned false
+    unavailable: Vec<Arc<dyn AgentTool>>,
+    /// Concurrent call limits by tool id; tools without an entry are unlimited
+    concurrency: HashMap<String, Arc<Semaphore>>,
+    cache: Option<Arc<ToolResultCache>>,
+    cache_ttl: Duration,
 }
 
 impl ToolRegistry {
@@ -16,6 +28,32 @@ impl ToolRegistry {
         Self {
             tools: HashMap::new(),
             metadata_map: HashMap::new(),
+            unavailable: Vec::new(),
+            concurrency: HashMap::new(),
+            cache: None,
+            cache_ttl: Duration::ZERO,
+        }
+    }
+
+    /// Attach a shared result cache. A TTL of 0 leaves caching disabled.
+    pub fn set_cache(&mut self, cache: Arc<ToolResultCache>, ttl_secs: u64) {
+        if ttl_secs == 0 {
+            self.cache = None;
+            return;
+        }
+        self.cache = Some(cache);
+        self.cache_ttl = Duration::from_secs(ttl_secs);
+    }
+
+    /// Override concurrent call limits by tool id (0 = unlimited)
+    pub fn set_concurrency_limits(&mut self, limits: &HashMap<String, usize>) {
+        for (tool_id, &limit) in limits {
+            if limit == 0 {
+                self.concurrency.remove(tool_id);
+            } else {
+                self.concurrency
+                    .insert(tool_id.clone(), Arc::new(Semaphore::new(limit)));
+            }
         }
     }
 
@@ -28,6 +66,7 @@ impl ToolRegistry {
         // Check if tool is available before registering
         if !tool.is_available() {
             println!("⚠️ Tool '{}' is not available, skipping registration", name);
+            self.unavailable.push(tool);
             return Ok(()); // Don't error, just skip unavailable tools
         }
 
@@ -36,6 +75,10 @@ impl ToolRegistry {
             return Err(anyhow::anyhow!("Tool with ID '{}' already registered", id));
         }
 
+        if let Some(limit) = tool.max_concurrency().filter(|&limit| limit > 0) {
+            self.concurrency
+                .insert(id.clone(), Arc::new(Semaphore::new(limit)));
+        }
         self.metadata_map.insert(id.clone(), metadata.clone());
         self.tools.insert(id, Arc::clone(&tool));
 
@@ -72,6 +115,11 @@ impl ToolRegistry {
         self.tools.values().map(Arc::clone).collect()
     }
 
+    /// Get the tools that were enabled but skipped as unavailable
+    pub fn get_unavailable_tools(&self) -> Vec<Arc<dyn AgentTool>> {
+        self.unavailable.iter().map(Arc::clone).collect()
+    }
+
     /// Build OpenAI-compatible tool definitions for all registered tools
     pub fn build_tool_definitions(&self) -> Result<Vec<Tool>> {
         let mut definitions = Vec::new();
@@ -91,11 +139,20 @@ impl ToolRegistry {
         Ok(definitions)
     }
 
-    /// Execute a tool call by function name
-    pub async fn execute_tool_call(&self, tool_call: &ToolCall) -> Result<ToolCallResult> {
-        println!("🔍 Looking for tool: '{}'", tool_call.function.name);
-        println!(
-            "🔍 Available tools in registry: {:?}",
+    /// Execute a tool call by function name. `request_id` tags the log lines
+    /// so they can be matched to the chat request that made the call.
+    pub async fn execute_tool_call(
+        &self,
+        tool_call: &ToolCall,
+        request_id: &str,
+    ) -> Result<ToolCallResult> {
+        debug!(
+            "[{}] 🔍 Looking for tool: '{}'",
+            request_id, tool_call.function.name
+        );
+        debug!(
+            "[{}] 🔍 Available tools in registry: {:?}",
+            request_id,
             self.tools.keys().collect::<Vec<_>>()
         );
 
@@ -115,8 +172,159 @@ impl ToolRegistry {
                 )
             })?;
 
-        println!("✅ Found tool: {}", tool.metadata().name);
-        tool.execute(tool_call).await
+        debug!("[{}] ✅ Found tool: {}", request_id, tool.metadata().name);
+
+        // Repair sloppy JSON so tools can parse arguments strictly; if it can't
+        // be repaired, tell the model what was wrong instead of running the tool
+        let arguments = match parse_arguments(&tool_call.function.arguments) {
+            Ok(arguments) => arguments,
+            Err(e) => {
+                metrics::record(|m| {
+                    m.tool_failures
+                        .with_label_values(&[tool_call.function.name.as_str()])
+                        .inc()
+                });
+                warn!(
+                    "[{}] ⚠️ Invalid JSON arguments for '{}': {}",
+                    request_id, tool_call.function.name, e
+                );
+                let message = format!(
+                    "The arguments for '{}' are not valid JSON ({}). \
+                     Call the tool again with a single JSON object matching its parameters.",
+                    tool_call.function.name, e
+                );
+                return Ok(invalid_arguments_result(tool_call, message, Vec::new()));
+            }
+        };
+
+        // Check required fields, types and enum values against the tool's own schema
+        let function_def = tool.get_function_definition();
+        if let Some(parameters) = function_def.get("parameters") {
+            if let Err(problems) = validate_arguments(&arguments, parameters) {
+                metrics::record(|m| {
+                    m.tool_failures
+                        .with_label_values(&[tool_call.function.name.as_str()])
+                        .inc()
+                });
+                warn!(
+                    "[{}] ⚠️ Arguments for '{}' don't match its schema: {}",
+                    request_id,
+                    tool_call.function.name,
+                    problems.join("; ")
+                );
+                let message = format!(
+                    "The arguments for '{}' don't match its parameters. \
+                     Fix the listed problems and call the tool again.",
+                    tool_call.function.name
+                );
+                return Ok(invalid_arguments_result(tool_call, message, problems));
+            }
+        }
+
+        let repaired_call;
+        let tool_call = if serde_json::from_str::<serde_json::Value>(&tool_call.function.arguments)
+            .is_ok_and(|strict| strict == arguments)
+        {
+            tool_call
+        } else {
+            info!(
+                "[{}] 🔧 Repaired JSON arguments for '{}'",
+                request_id, tool_call.function.name
+            );
+            let mut call = tool_call.clone();
+            call.function.arguments = arguments.to_string();
+            repaired_call = call;
+            &repaired_call
+        };
+
+        if let Some(cache) = &self.cache {
+            if let Some(mut cached) = cache.get(tool_call, self.cache_ttl) {
+                info!(
+                    "[{}] ♻️ Using cached result for '{}'",
+                    request_id, tool_call.function.name
+                );
+                cached.tool_call_id = Some(tool_call.id.clone());
+                return Ok(cached);
+            }
+        }
+
+        // Held until the tool finishes, so at most `limit` calls of it run at once
+        let _permit = match self.concurrency.get(&tool.metadata().id) {
+            Some(semaphore) => {
+                if semaphore.available_permits() == 0 {
+                    info!(
+                        "[{}] ⏳ Waiting for a free slot to run '{}'",
+                        request_id, tool_call.function.name
+                    );
+                }
+                Some(
+                    semaphore
+                        .acquire()
+                        .await
+                        .context("Tool concurrency limit closed")?,
+                )
+            }
+            None => None,
+        };
+
+        metrics::record(|m| {
+            m.tool_calls
+                .with_label_values(&[tool_call.function.name.as_str()])
+                .inc()
+        });
+        let mut result = tool.execute(tool_call).await.inspect_err(|e| {
+            metrics::record(|m| {
+                m.tool_failures
+                    .with_label_values(&[tool_call.function.name.as_str()])
+                    .inc()
+            });
+            warn!(
+                "[{}] ❌ Tool '{}' failed: {}",
+                request_id, tool_call.function.name, e
+            );
+        })?;
+        result.tool_call_id = Some(tool_call.id.clone());
+        info!(
+            "[{}] ✅ Tool '{}' finished",
+            request_id, tool_call.function.name
+        );
+
+        // Only successful results are cached so transient failures can be retried
+        if let Some(cache) = &self.cache {
+            cache.insert(tool_call, result.clone());
+        }
+
+        Ok(result)
+    }
+
+    /// Execute a tool call, failing with an error if it doesn't finish within `timeout`
+    pub async fn execute_tool_call_with_timeout(
+        &self,
+        tool_call: &ToolCall,
+        timeout: Duration,
+        request_id: &str,
+    ) -> Result<ToolCallResult> {
+        match tokio::time::timeout(timeout, self.execute_tool_call(tool_call, request_id)).await {
+            Ok(result) => result,
+            Err(_) => {
+                metrics::record(|m| {
+                    m.tool_failures
+                        .with_label_values(&[tool_call.function.name.as_str()])
+                        .inc()
+                });
+                warn!(
+                    "[{}] ⏱️ Tool '{}' timed out after {}s",
+                    request_id,
+                    tool_call.function.name,
+                    timeout.as_secs()
+                );
+                Err(anyhow::anyhow!(
+                    "Tool '{}' timed out after {} seconds",
+                    tool_call.function.name,
+                    timeout.as_secs()
+                ))
+            }
+        }
     }
 
     /// Check if a tool is registered
@@ -130,6 +338,28 @@ impl ToolRegistry {
     }
 }
 
+/// Tool result telling the model what was wrong with its arguments, so it can retry
+fn invalid_arguments_result(
+    tool_call: &ToolCall,
+    message: String,
+    problems: Vec<String>,
+) -> ToolCallResult {
+    let mut body = serde_json::json!({
+        "error": "invalid_arguments",
+        "message": message,
+        "arguments": tool_call.function.arguments,
+    });
+    if !problems.is_empty() {
+        body["problems"] = serde_json::json!(problems);
+    }
+    ToolCallResult {
+        tool_name: tool_call.function.name.clone(),
+        result: body.to_string(),
+        tool_call_id: Some(tool_call.id.clone()),
+        sources: None,
+    }
+}
+
 impl Default for ToolRegistry {
     fn default() -> Self {
         Self::new()
@@ -0,0 +1,66 @@
+use reqwest::{RequestBuilder, Response};
+use std::time::Duration;
+
+/// Default number of retries for idempotent tool requests
+pub const DEFAULT_MAX_RETRIES: u32 = 2;
+
+/// Delay before the first retry, doubled on every subsequent attempt
+const BASE_DELAY_MS: u64 = 250;
+
+/// Upper bound for a single backoff delay
+const MAX_DELAY_MS: u64 = 5_000;
+
+/// Backoff delay for the given retry attempt (0-based)
+fn backoff_delay(attempt: u32) -> Duration {
+    let delay = BASE_DELAY_MS.saturating_mul(1u64 << attempt.min(16));
+    Duration::from_millis(delay.min(MAX_DELAY_MS))
+}
+
+/// Whether a transport-level error is worth retrying
+fn is_retryable_error(error: &reqwest::Error) -> bool {
+    error.is_connect() || error.is_timeout() || error.is_request()
+}
+
+/// Send an idempotent request, retrying with exponential backoff on 5xx responses and
+/// connection errors. 4xx responses are returned immediately since retrying won't help.
+///
+/// Requests that can't be cloned (streaming bodies) are sent once without retries.
+pub async fn retry_request(request: RequestBuilder, max_retries: u32) -> reqwest::Result<Response> {
+    let mut attempt = 0;
+
+    loop {
+        let current = match request.try_clone() {
+            Some(r) => r,
+            None => return request.send().await,
+        };
+
+        match current.send().await {
+            Ok(response) if response.status().is_server_error() && attempt < max_retries => {
+                let delay = backoff_delay(attempt);
+                println!(
+                    "⚠️ Request returned {}, retrying in {}ms (attempt {}/{})",
+                    response.status(),
+                    delay.as_millis(),
+                    attempt + 1,
+                    max_retries
+                );
+                tokio::time::sleep(delay).await;
+            }
+            Ok(response) => return Ok(response),
+            Err(e) if is_retryable_error(&e) && attempt < max_retries => {
+                let delay = backoff_delay(attempt);
+                println!(
+                    "⚠️ Request failed: {}, retrying in {}ms (attempt {}/{})",
+                    e,
+                    delay.as_millis(),
+                    attempt + 1,
+                    max_retries
+                );
+                tokio::time::sleep(delay).await;
+            }
+            Err(e) => return Err(e),
+        }
+
+        attempt += 1;
+    }
+}
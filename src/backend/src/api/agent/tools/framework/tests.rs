@@ -1,14 +1,27 @@
 use super::agent_tool::{AgentTool, ToolCategory, ToolMetadata};
+use super::arguments::{parse_arguments, validate_arguments};
+use super::cache::ToolResultCache;
+use super::http::{probe, MockHttpClient};
 use super::registry::ToolRegistry;
-use crate::api::agent::core::types::{ToolCall, ToolCallResult, ToolType};
+use super::retry::retry_request;
+use crate::api::agent::core::types::{FunctionCall, ToolCall, ToolCallResult, ToolType};
 use async_trait::async_trait;
 use serde_json::json;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
 
 // Mock Tool Implementation
 struct MockTool {
     available: bool,
     metadata: ToolMetadata,
+    delay: Duration,
+    executions: Arc<AtomicUsize>,
+    concurrency_limit: Option<usize>,
+    in_flight: Arc<AtomicUsize>,
+    peak_in_flight: Arc<AtomicUsize>,
 }
 
 impl MockTool {
@@ -22,8 +35,18 @@ impl MockTool {
                 category: ToolCategory::Utility,
                 tool_type: ToolType::Weather, // Using an existing variant
             },
+            delay: Duration::ZERO,
+            executions: Arc::new(AtomicUsize::new(0)),
+            concurrency_limit: None,
+            in_flight: Arc::new(AtomicUsize::new(0)),
+            peak_in_flight: Arc::new(AtomicUsize::new(0)),
         }
     }
+
+    fn with_delay(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
 }
 
 #[async_trait]
@@ -43,16 +66,27 @@ impl AgentTool for MockTool {
         })
     }
 
-    async fn execute(&self, _tool_call: &ToolCall) -> anyhow::Result<ToolCallResult> {
+    async fn execute(&self, tool_call: &ToolCall) -> anyhow::Result<ToolCallResult> {
+        self.executions.fetch_add(1, Ordering::SeqCst);
+        let running = self.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+        self.peak_in_flight.fetch_max(running, Ordering::SeqCst);
+        tokio::time::sleep(self.delay).await;
+        self.in_flight.fetch_sub(1, Ordering::SeqCst);
         Ok(ToolCallResult {
             tool_name: "mock_tool".to_string(),
             result: "Executed".to_string(),
+            tool_call_id: Some(tool_call.id.clone()),
+            sources: None,
         })
     }
 
     fn is_available(&self) -> bool {
         self.available
     }
+
+    fn max_concurrency(&self) -> Option<usize> {
+        self.concurrency_limit
+    }
 }
 
 #[test]
@@ -102,6 +136,11 @@ fn test_registry_unavailable_tool() {
 
     // Verify it's not in the registry
     assert!(registry.get_tool("tool_unavailable").is_none());
+
+    // ...but it is remembered for tool validation
+    let unavailable = registry.get_unavailable_tools();
+    assert_eq!(unavailable.len(), 1);
+    assert_eq!(unavailable[0].metadata().id, "tool_unavailable");
 }
 
 #[test]
@@ -117,3 +156,450 @@ fn test_get_all_tools() {
     let tools = registry.get_all_tools();
     assert_eq!(tools.len(), 2);
 }
+
+/// Spawn a minimal HTTP server that answers each connection with the next status code in `statuses`
+async fn spawn_status_server(statuses: Vec<u16>) -> (String, Arc<AtomicUsize>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let hits = Arc::new(AtomicUsize::new(0));
+    let hits_clone = hits.clone();
+
+    tokio::spawn(async move {
+        for status in statuses {
+            let Ok((mut socket, _)) = listener.accept().await else {
+                break;
+            };
+            hits_clone.fetch_add(1, Ordering::SeqCst);
+            let mut buf = [0u8; 1024];
+            let _ = socket.read(&mut buf).await;
+            let response = format!(
+                "HTTP/1.1 {} Status\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+                status
+            );
+            let _ = socket.write_all(response.as_bytes()).await;
+        }
+    });
+
+    (format!("http://{}", addr), hits)
+}
+
+#[tokio::test]
+async fn test_retry_request_retries_server_errors() {
+    let (url, hits) = spawn_status_server(vec![503, 200]).await;
+    let client = reqwest::Client::new();
+
+    let response = retry_request(client.get(&url), 2).await.unwrap();
+    assert_eq!(response.status().as_u16(), 200);
+    assert_eq!(hits.load(Ordering::SeqCst), 2);
+}
+
+#[tokio::test]
+async fn test_retry_request_does_not_retry_client_errors() {
+    let (url, hits) = spawn_status_server(vec![404, 200]).await;
+    let client = reqwest::Client::new();
+
+    let response = retry_request(client.get(&url), 2).await.unwrap();
+    assert_eq!(response.status().as_u16(), 404);
+    assert_eq!(hits.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test]
+async fn test_retry_request_gives_up_after_max_retries() {
+    let (url, hits) = spawn_status_server(vec![500, 500, 500]).await;
+    let client = reqwest::Client::new();
+
+    let response = retry_request(client.get(&url), 1).await.unwrap();
+    assert_eq!(response.status().as_u16(), 500);
+    assert_eq!(hits.load(Ordering::SeqCst), 2);
+}
+
+#[tokio::test]
+async fn test_probe_reports_rejected_credentials_and_server_errors() {
+    let (url, _) = spawn_status_server(vec![200, 404, 401, 503]).await;
+    let client = reqwest::Client::new();
+
+    assert!(probe(&client, &url).await.is_ok());
+    // Reachable, even if this path doesn't exist
+    assert!(probe(&client, &url).await.is_ok());
+    let err = probe(&client, &url).await.unwrap_err();
+    assert!(err.to_string().contains("Credentials rejected"));
+    assert!(probe(&client, &url).await.is_err());
+}
+
+#[tokio::test]
+async fn test_probe_with_mock_client() {
+    let client = MockHttpClient::new()
+        .with_json("/ok", 200, json!({}))
+        .with_json("/denied", 403, json!({ "message": "Bad key" }));
+
+    assert!(probe(&client, "https://api.example.com/ok").await.is_ok());
+    let err = probe(&client, "https://api.example.com/denied")
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("Credentials rejected"));
+    // Nothing canned for this URL
+    assert!(probe(&client, "https://api.example.com/other")
+        .await
+        .is_err());
+    assert_eq!(client.requests().len(), 3);
+}
+
+fn mock_tool_call(name: &str) -> ToolCall {
+    tool_call_with_args(name, "{}")
+}
+
+#[tokio::test]
+async fn test_execute_tool_call_with_timeout_completes() {
+    let mut registry = ToolRegistry::new();
+    registry
+        .register(Arc::new(MockTool::new("fast", true)))
+        .expect("Failed to register tool");
+
+    let result = registry
+        .execute_tool_call_with_timeout(
+            &mock_tool_call("mock_function_fast"),
+            Duration::from_secs(1),
+            "test",
+        )
+        .await
+        .expect("Fast tool should complete");
+    assert_eq!(result.result, "Executed");
+}
+
+#[tokio::test]
+async fn test_execute_tool_call_with_timeout_elapses() {
+    let mut registry = ToolRegistry::new();
+    registry
+        .register(Arc::new(
+            MockTool::new("slow", true).with_delay(Duration::from_secs(5)),
+        ))
+        .expect("Failed to register tool");
+
+    let err = registry
+        .execute_tool_call_with_timeout(
+            &mock_tool_call("mock_function_slow"),
+            Duration::from_millis(50),
+            "test",
+        )
+        .await
+        .expect_err("Slow tool should time out");
+    assert!(err.to_string().contains("timed out"));
+}
+
+fn tool_call_with_args(name: &str, arguments: &str) -> ToolCall {
+    ToolCall {
+        id: "call_1".to_string(),
+        tool_type: "function".to_string(),
+        function: FunctionCall {
+            name: name.to_string(),
+            arguments: arguments.to_string(),
+        },
+    }
+}
+
+fn cached_result(result: &str) -> ToolCallResult {
+    ToolCallResult {
+        tool_name: "mock_tool".to_string(),
+        result: result.to_string(),
+        tool_call_id: None,
+        sources: None,
+    }
+}
+
+#[test]
+fn test_cache_key_ignores_argument_formatting() {
+    let a = tool_call_with_args("weather", r#"{"city": "Warsaw", "units": "metric"}"#);
+    let b = tool_call_with_args("weather", r#"{"units":"metric","city":"Warsaw"}"#);
+    let c = tool_call_with_args("forecast", r#"{"city": "Warsaw", "units": "metric"}"#);
+    assert_eq!(ToolResultCache::key(&a), ToolResultCache::key(&b));
+    assert_ne!(ToolResultCache::key(&a), ToolResultCache::key(&c));
+}
+
+#[test]
+fn test_cache_respects_ttl() {
+    let cache = ToolResultCache::new(4);
+    let call = tool_call_with_args("weather", r#"{"city":"Warsaw"}"#);
+    cache.insert(&call, cached_result("sunny"));
+
+    assert_eq!(
+        cache.get(&call, Duration::from_secs(60)).unwrap().result,
+        "sunny"
+    );
+    assert!(cache.get(&call, Duration::ZERO).is_none());
+    // Expired entries are dropped on lookup
+    assert!(cache.get(&call, Duration::from_secs(60)).is_none());
+}
+
+#[test]
+fn test_cache_evicts_least_recently_used() {
+    let cache = ToolResultCache::new(2);
+    let ttl = Duration::from_secs(60);
+    let first = tool_call_with_args("weather", r#"{"city":"Warsaw"}"#);
+    let second = tool_call_with_args("weather", r#"{"city":"Berlin"}"#);
+    let third = tool_call_with_args("weather", r#"{"city":"Paris"}"#);
+
+    cache.insert(&first, cached_result("first"));
+    cache.insert(&second, cached_result("second"));
+    // Touch `first` so `second` becomes the least recently used entry
+    assert!(cache.get(&first, ttl).is_some());
+    cache.insert(&third, cached_result("third"));
+
+    assert!(cache.get(&first, ttl).is_some());
+    assert!(cache.get(&second, ttl).is_none());
+    assert!(cache.get(&third, ttl).is_some());
+}
+
+#[tokio::test]
+async fn test_registry_uses_cache_for_repeated_calls() {
+    let tool = MockTool::new("cached", true);
+    let executions = Arc::clone(&tool.executions);
+
+    let mut registry = ToolRegistry::new();
+    registry
+        .register(Arc::new(tool))
+        .expect("Failed to register tool");
+    registry.set_cache(Arc::new(ToolResultCache::new(8)), 60);
+
+    let call = mock_tool_call("mock_function_cached");
+    registry.execute_tool_call(&call, "test").await.unwrap();
+    registry.execute_tool_call(&call, "test").await.unwrap();
+
+    assert_eq!(executions.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test]
+async fn test_registry_cache_disabled_with_zero_ttl() {
+    let tool = MockTool::new("uncached", true);
+    let executions = Arc::clone(&tool.executions);
+
+    let mut registry = ToolRegistry::new();
+    registry
+        .register(Arc::new(tool))
+        .expect("Failed to register tool");
+    registry.set_cache(Arc::new(ToolResultCache::new(8)), 0);
+
+    let call = mock_tool_call("mock_function_uncached");
+    registry.execute_tool_call(&call, "test").await.unwrap();
+    registry.execute_tool_call(&call, "test").await.unwrap();
+
+    assert_eq!(executions.load(Ordering::SeqCst), 2);
+}
+
+#[tokio::test]
+async fn test_registry_tags_results_with_tool_call_id() {
+    let mut registry = ToolRegistry::new();
+    registry
+        .register(Arc::new(MockTool::new("tagged", true)))
+        .expect("Failed to register tool");
+    registry.set_cache(Arc::new(ToolResultCache::new(8)), 60);
+
+    let first = ToolCall {
+        id: "call_1".to_string(),
+        ..mock_tool_call("mock_function_tagged")
+    };
+    let second = ToolCall {
+        id: "call_2".to_string(),
+        ..mock_tool_call("mock_function_tagged")
+    };
+
+    let first_result = registry.execute_tool_call(&first, "test").await.unwrap();
+    // Same arguments, so this one is served from the cache
+    let second_result = registry.execute_tool_call(&second, "test").await.unwrap();
+
+    assert_eq!(first_result.tool_call_id.as_deref(), Some("call_1"));
+    assert_eq!(second_result.tool_call_id.as_deref(), Some("call_2"));
+}
+
+#[test]
+fn test_parse_arguments_repairs_common_malformations() {
+    let expected = json!({"city": "Paris", "units": ["metric"]});
+    let malformed = [
+        r#"{"city": "Paris", "units": ["metric"]}"#,
+        "```json\n{\"city\": \"Paris\", \"units\": [\"metric\"]}\n```",
+        r#"{"city": "Paris", "units": ["metric",],}"#,
+        r#"{'city': 'Paris', 'units': ['metric']}"#,
+        r#""{\"city\": \"Paris\", \"units\": [\"metric\"]}""#,
+    ];
+    for raw in malformed {
+        assert_eq!(parse_arguments(raw).unwrap(), expected, "input: {}", raw);
+    }
+
+    // Quotes and commas inside strings are left alone
+    assert_eq!(
+        parse_arguments(r#"{'query': 'it\'s "quoted", ok',}"#).unwrap(),
+        json!({"query": "it's \"quoted\", ok"})
+    );
+    assert_eq!(parse_arguments("  ").unwrap(), json!({}));
+    assert!(parse_arguments("{city: Paris").is_err());
+}
+
+#[tokio::test]
+async fn test_registry_repairs_or_reports_invalid_arguments() {
+    let tool = MockTool::new("args", true);
+    let executions = Arc::clone(&tool.executions);
+    let mut registry = ToolRegistry::new();
+    registry.register(Arc::new(tool)).unwrap();
+
+    let repaired = tool_call_with_args("mock_function_args", r#"{"a": 1,}"#);
+    let result = registry.execute_tool_call(&repaired, "test").await.unwrap();
+    assert_eq!(result.result, "Executed");
+    assert_eq!(executions.load(Ordering::SeqCst), 1);
+
+    // Unrepairable arguments come back as a result the model can act on
+    let broken = tool_call_with_args("mock_function_args", "{a: ");
+    let result = registry.execute_tool_call(&broken, "test").await.unwrap();
+    let body: serde_json::Value = serde_json::from_str(&result.result).unwrap();
+    assert_eq!(body["error"], "invalid_arguments");
+    assert_eq!(body["arguments"], "{a: ");
+    assert_eq!(result.tool_call_id.as_deref(), Some("call_1"));
+    assert_eq!(executions.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn test_validate_arguments_against_schema() {
+    let parameters = json!({
+        "type": "object",
+        "properties": {
+            "city": {"type": "string"},
+            "days": {"type": "integer"},
+            "units": {"type": "string", "enum": ["metric", "imperial"]}
+        },
+        "required": ["city"]
+    });
+
+    assert!(validate_arguments(&json!({"city": "Paris"}), &parameters).is_ok());
+    assert!(validate_arguments(
+        &json!({"city": "Paris", "days": 3, "units": "metric", "extra": true}),
+        &parameters
+    )
+    .is_ok());
+
+    let problems =
+        validate_arguments(&json!({"days": "3", "units": "kelvin"}), &parameters).unwrap_err();
+    assert_eq!(
+        problems,
+        vec![
+            "missing required parameter 'city'".to_string(),
+            "parameter 'days' must be of type integer, got string".to_string(),
+            "parameter 'units' must be one of \"metric\", \"imperial\", got \"kelvin\"".to_string(),
+        ]
+    );
+
+    // null is treated as missing
+    assert!(validate_arguments(&json!({"city": null}), &parameters).is_err());
+    assert!(validate_arguments(&json!(["Paris"]), &parameters).is_err());
+}
+
+/// Tool with a required parameter, for schema validation through the registry
+struct RequiredParamTool {
+    metadata: ToolMetadata,
+    executions: Arc<AtomicUsize>,
+}
+
+#[async_trait]
+impl AgentTool for RequiredParamTool {
+    fn metadata(&self) -> &ToolMetadata {
+        &self.metadata
+    }
+
+    fn get_function_definition(&self) -> serde_json::Value {
+        json!({
+            "name": "lookup",
+            "description": "Look something up",
+            "parameters": {
+                "type": "object",
+                "properties": {"query": {"type": "string"}},
+                "required": ["query"]
+            }
+        })
+    }
+
+    async fn execute(&self, tool_call: &ToolCall) -> anyhow::Result<ToolCallResult> {
+        self.executions.fetch_add(1, Ordering::SeqCst);
+        Ok(ToolCallResult {
+            tool_name: "lookup".to_string(),
+            result: "Found".to_string(),
+            tool_call_id: Some(tool_call.id.clone()),
+            sources: None,
+        })
+    }
+}
+
+#[tokio::test]
+async fn test_registry_rejects_missing_required_argument() {
+    let executions = Arc::new(AtomicUsize::new(0));
+    let mut registry = ToolRegistry::new();
+    registry
+        .register(Arc::new(RequiredParamTool {
+            metadata: ToolMetadata {
+                id: "lookup".to_string(),
+                name: "Lookup".to_string(),
+                description: "Look something up".to_string(),
+                category: ToolCategory::Search,
+                tool_type: ToolType::WebsiteCheck,
+            },
+            executions: Arc::clone(&executions),
+        }))
+        .unwrap();
+
+    let result = registry
+        .execute_tool_call(&tool_call_with_args("lookup", "{}"), "test")
+        .await
+        .unwrap();
+    let body: serde_json::Value = serde_json::from_str(&result.result).unwrap();
+    assert_eq!(body["error"], "invalid_arguments");
+    assert_eq!(body["problems"][0], "missing required parameter 'query'");
+    assert_eq!(executions.load(Ordering::SeqCst), 0);
+
+    let result = registry
+        .execute_tool_call(
+            &tool_call_with_args("lookup", r#"{"query": "rust"}"#),
+            "test",
+        )
+        .await
+        .unwrap();
+    assert_eq!(result.result, "Found");
+    assert_eq!(executions.load(Ordering::SeqCst), 1);
+}
+
+/// Run `calls` concurrent calls of a 20ms mock tool and return the most that overlapped
+async fn peak_concurrent_calls(
+    limit: Option<usize>,
+    overrides: &[(&str, usize)],
+    calls: usize,
+) -> usize {
+    let mut tool = MockTool::new("limited", true).with_delay(Duration::from_millis(20));
+    tool.concurrency_limit = limit;
+    let peak = Arc::clone(&tool.peak_in_flight);
+
+    let mut registry = ToolRegistry::new();
+    registry.register(Arc::new(tool)).unwrap();
+    registry.set_concurrency_limits(
+        &overrides
+            .iter()
+            .map(|(id, limit)| (id.to_string(), *limit))
+            .collect(),
+    );
+
+    let call = mock_tool_call("mock_function_limited");
+    let runs = (0..calls).map(|_| registry.execute_tool_call(&call, "test"));
+    for result in futures::future::join_all(runs).await {
+        assert_eq!(result.unwrap().result, "Executed");
+    }
+    peak.load(Ordering::SeqCst)
+}
+
+#[tokio::test]
+async fn test_registry_limits_concurrent_calls_per_tool() {
+    assert_eq!(peak_concurrent_calls(None, &[], 4).await, 4);
+    assert_eq!(peak_concurrent_calls(Some(2), &[], 4).await, 2);
+    // Config overrides win over the tool's own limit; 0 lifts it
+    assert_eq!(
+        peak_concurrent_calls(Some(2), &[("limited", 1)], 4).await,
+        1
+    );
+    assert_eq!(
+        peak_concurrent_calls(Some(2), &[("limited", 0)], 4).await,
+        4
+    );
+}
@@ -7,19 +7,25 @@ pub mod web;
 
 use crate::api::agent::core::types::AgentConfig;
 use crate::api::agent::tools::framework::registry::ToolRegistry;
+use crate::api::model_notes::ModelNotesStorage;
+use std::sync::Arc;
 
-/// Context for tool registration containing dependencies that aren't in AgentConfig
+/// Shared services tools may depend on, beyond what's in AgentConfig.
+/// Every field is optional; a tool whose dependency is missing is skipped.
+#[derive(Default)]
 pub struct RegisterContext<'a> {
     pub chroma_address: Option<&'a str>,
+    /// Saved model notes, read by the model notes tool
+    pub model_notes: Option<Arc<ModelNotesStorage>>,
 }
 
 /// Register all enabled tools given the configuration
 pub fn register_all(registry: &mut ToolRegistry, config: &AgentConfig, context: &RegisterContext) {
     database::register(registry, config, context);
-    development::register(registry, config);
-    financial::register(registry, config);
-    utility::register(registry, config);
-    web::register(registry, config);
+    development::register(registry, config, context);
+    financial::register(registry, config, context);
+    utility::register(registry, config, context);
+    web::register(registry, config, context);
 }
 
 #[cfg(test)]
@@ -35,9 +41,7 @@ mod tests {
         // Enable a few tools
         config.enabled_tools = vec![ToolType::GitHubPublic, ToolType::Weather];
 
-        let context = RegisterContext {
-            chroma_address: None,
-        };
+        let context = RegisterContext::default();
 
         register_all(&mut registry, &config, &context);
 
@@ -47,4 +51,23 @@ mod tests {
             "GitHubPublic failed to register"
         );
     }
+
+    #[test]
+    fn test_register_crypto_price_without_api_key() {
+        let mut registry = ToolRegistry::new();
+        let config = AgentConfig {
+            enabled_tools: vec![ToolType::Crypto],
+            ..AgentConfig::default()
+        };
+
+        let context = RegisterContext::default();
+
+        register_all(&mut registry, &config, &context);
+
+        // CoinGecko spot prices need no key, so this is always registered
+        assert!(
+            registry.get_tool("crypto_price").is_some(),
+            "Crypto price tool failed to register"
+        );
+    }
 }
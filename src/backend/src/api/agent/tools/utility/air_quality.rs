@@ -0,0 +1,329 @@
+use crate::api::agent::core::types::{ToolCall, ToolCallResult, ToolType};
+use crate::api::agent::tools::framework::agent_tool::{AgentTool, ToolCategory, ToolMetadata};
+use crate::api::agent::tools::framework::http::{self, DEFAULT_TOOL_TIMEOUT_SECS};
+use crate::api::agent::tools::framework::retry::{retry_request, DEFAULT_MAX_RETRIES};
+use crate::api::agent::tools::utility::weather::validate_coordinates;
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use reqwest;
+use serde_json::json;
+use std::env;
+use url::Url;
+
+/// Air quality tool backed by the OpenWeatherMap Air Pollution API
+pub struct AirQualityTool {
+    metadata: ToolMetadata,
+    client: reqwest::Client,
+    api_key: String,
+    max_retries: u32,
+}
+
+/// Human-readable label for OpenWeatherMap's 1-5 air quality index
+fn aqi_label(aqi: u64) -> &'static str {
+    match aqi {
+        1 => "Good",
+        2 => "Fair",
+        3 => "Moderate",
+        4 => "Poor",
+        5 => "Very Poor",
+        _ => "Unknown",
+    }
+}
+
+impl AirQualityTool {
+    /// Create a new instance of the air quality tool
+    pub fn new() -> Self {
+        let api_key = env::var("OPENWEATHER_API_KEY").unwrap_or_default();
+
+        Self {
+            metadata: ToolMetadata {
+                id: "weather_air_quality".to_string(),
+                name: "Air Quality".to_string(),
+                description: "Get current air quality (AQI, PM2.5, PM10, O3, NO2) for a location via OpenWeatherMap".to_string(),
+                category: ToolCategory::Utility,
+                tool_type: ToolType::Weather,
+            },
+            client: http::build_client(DEFAULT_TOOL_TIMEOUT_SECS),
+            api_key,
+            max_retries: DEFAULT_MAX_RETRIES,
+        }
+    }
+
+    /// Set how many times transient request failures are retried
+    pub fn with_max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// Set the HTTP request timeout in seconds
+    pub fn with_timeout(mut self, timeout_secs: u64) -> Self {
+        self.client = http::build_client(timeout_secs);
+        self
+    }
+
+    /// GET an OpenWeatherMap URL and parse the JSON body
+    async fn get_json(&self, url: Url, what: &str) -> Result<serde_json::Value> {
+        let response = retry_request(self.client.get(url.as_str()), self.max_retries)
+            .await
+            .with_context(|| format!("Failed to request {} from OpenWeatherMap", what))?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response.text().await.unwrap_or_default();
+            return Err(anyhow::anyhow!(
+                "{} API returned error {}: {}",
+                what,
+                status,
+                error_text
+            ));
+        }
+
+        response
+            .json()
+            .await
+            .with_context(|| format!("Failed to parse {} response", what))
+    }
+
+    /// Resolve a city to coordinates and a display name via the Geocoding API
+    async fn geocode(&self, city: &str, country: Option<&str>) -> Result<(f64, f64, String)> {
+        let mut query = city.to_string();
+        if let Some(country_val) = country.filter(|c| !c.is_empty()) {
+            query.push_str(&format!(",{}", country_val));
+        }
+
+        let mut url = Url::parse("https://api.openweathermap.org/geo/1.0/direct")
+            .context("Failed to parse base URL")?;
+        url.query_pairs_mut()
+            .append_pair("q", &query)
+            .append_pair("limit", "1")
+            .append_pair("appid", &self.api_key);
+
+        let data = self.get_json(url, "Geocoding").await?;
+        let place = data
+            .as_array()
+            .and_then(|places| places.first())
+            .with_context(|| format!("Location '{}' not found", query))?;
+
+        let lat = place["lat"]
+            .as_f64()
+            .context("Missing latitude in geocoding response")?;
+        let lon = place["lon"]
+            .as_f64()
+            .context("Missing longitude in geocoding response")?;
+        let name = match (place["name"].as_str(), place["country"].as_str()) {
+            (Some(name), Some(country)) => format!("{}, {}", name, country),
+            (Some(name), None) => name.to_string(),
+            _ => query,
+        };
+
+        Ok((lat, lon, name))
+    }
+
+    /// Fetch current air pollution data for coordinates
+    async fn fetch_air_quality(&self, lat: f64, lon: f64) -> Result<serde_json::Value> {
+        let mut url = Url::parse("https://api.openweathermap.org/data/2.5/air_pollution")
+            .context("Failed to parse base URL")?;
+        url.query_pairs_mut()
+            .append_pair("lat", &lat.to_string())
+            .append_pair("lon", &lon.to_string())
+            .append_pair("appid", &self.api_key);
+
+        println!("\x1b[33m🌫️ Fetching air quality data...\x1b[0m");
+
+        self.get_json(url, "Air pollution").await
+    }
+
+    /// Format air pollution data into a readable response string
+    fn format_air_quality_response(
+        &self,
+        data: &serde_json::Value,
+        location: &str,
+    ) -> Result<String> {
+        let entry = data["list"]
+            .as_array()
+            .and_then(|list| list.first())
+            .ok_or_else(|| anyhow::anyhow!("Missing 'list' in air pollution response"))?;
+
+        let aqi = entry["main"]["aqi"]
+            .as_u64()
+            .ok_or_else(|| anyhow::anyhow!("Missing AQI in air pollution response"))?;
+        let components = &entry["components"];
+        let component = |key: &str| {
+            components[key]
+                .as_f64()
+                .map(|v| format!("{:.1} µg/m³", v))
+                .unwrap_or_else(|| "N/A".to_string())
+        };
+
+        let mut result = format!("🌫️ **Air Quality in {}**\n\n", location);
+        result.push_str(&format!(
+            "**AQI:** {} ({}) on a 1 (Good) to 5 (Very Poor) scale\n",
+            aqi,
+            aqi_label(aqi)
+        ));
+        result.push_str(&format!(
+            "**PM2.5:** {} | **PM10:** {}\n",
+            component("pm2_5"),
+            component("pm10")
+        ));
+        result.push_str(&format!(
+            "**O₃:** {} | **NO₂:** {}\n",
+            component("o3"),
+            component("no2")
+        ));
+
+        Ok(result)
+    }
+}
+
+#[async_trait]
+impl AgentTool for AirQualityTool {
+    fn metadata(&self) -> &ToolMetadata {
+        &self.metadata
+    }
+
+    fn get_function_definition(&self) -> serde_json::Value {
+        json!({
+            "name": "air_quality",
+            "description": "Get current air quality for a location: AQI (1-5) with a quality label plus PM2.5, PM10, O3 and NO2 concentrations. Use when the user asks about air quality, smog or pollution. If you know the coordinates, provide them directly to skip geocoding.",
+            "parameters": {
+                "type": "object",
+                "properties": {
+                    "city": {
+                        "type": "string",
+                        "description": "Name of the city. Required if latitude/longitude are not provided."
+                    },
+                    "country": {
+                        "type": "string",
+                        "description": "Optional country code (2-letter) for disambiguation when using city name"
+                    },
+                    "latitude": {
+                        "type": "number",
+                        "description": "Latitude coordinate (-90 to 90)."
+                    },
+                    "longitude": {
+                        "type": "number",
+                        "description": "Longitude coordinate (-180 to 180)."
+                    }
+                },
+                "required": []
+            }
+        })
+    }
+
+    async fn execute(&self, tool_call: &ToolCall) -> Result<ToolCallResult> {
+        let args: serde_json::Value = serde_json::from_str(&tool_call.function.arguments)
+            .context("Failed to parse air quality tool arguments")?;
+
+        let city = args.get("city").and_then(|v| v.as_str());
+        let country = args.get("country").and_then(|v| v.as_str());
+        let lat = args.get("latitude").and_then(|v| v.as_f64());
+        let lon = args.get("longitude").and_then(|v| v.as_f64());
+
+        validate_coordinates(lat, lon)?;
+
+        let (lat, lon, location) = match (lat, lon, city) {
+            (Some(lat), Some(lon), _) => (lat, lon, format!("{:.4}, {:.4}", lat, lon)),
+            (_, _, Some(city_name)) => self.geocode(city_name, country).await?,
+            _ => {
+                return Err(anyhow::anyhow!(
+                    "Either 'city' or both 'latitude' and 'longitude' must be provided"
+                ))
+            }
+        };
+
+        let data = self.fetch_air_quality(lat, lon).await?;
+        let result = self.format_air_quality_response(&data, &location)?;
+
+        println!("\x1b[32m✅ Air quality data retrieved successfully\x1b[0m");
+
+        Ok(ToolCallResult {
+            tool_name: "air_quality".to_string(),
+            result,
+            tool_call_id: Some(tool_call.id.clone()),
+            sources: None,
+        })
+    }
+
+    fn is_available(&self) -> bool {
+        !self.api_key.is_empty()
+    }
+
+    fn required_env_vars(&self) -> &'static [&'static str] {
+        &["OPENWEATHER_API_KEY"]
+    }
+
+    async fn check_connectivity(&self) -> Option<Result<()>> {
+        // A one-result geocoding lookup checks both reachability and the key
+        let url = format!(
+            "https://api.openweathermap.org/geo/1.0/direct?q=London&limit=1&appid={}",
+            self.api_key
+        );
+        Some(http::probe(&self.client, &url).await)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_air_quality_metadata() {
+        let tool = AirQualityTool::new();
+        let metadata = tool.metadata();
+        assert_eq!(metadata.id, "weather_air_quality");
+        assert_eq!(metadata.category, ToolCategory::Utility);
+        assert_eq!(metadata.tool_type, ToolType::Weather);
+        assert_eq!(tool.get_function_definition()["name"], "air_quality");
+    }
+
+    #[test]
+    fn test_aqi_label() {
+        assert_eq!(aqi_label(1), "Good");
+        assert_eq!(aqi_label(2), "Fair");
+        assert_eq!(aqi_label(3), "Moderate");
+        assert_eq!(aqi_label(4), "Poor");
+        assert_eq!(aqi_label(5), "Very Poor");
+        assert_eq!(aqi_label(0), "Unknown");
+        assert_eq!(aqi_label(6), "Unknown");
+    }
+
+    #[test]
+    fn test_format_air_quality_response() {
+        let tool = AirQualityTool::new();
+        let data = json!({
+            "coord": { "lon": 21.0118, "lat": 52.2298 },
+            "list": [{
+                "main": { "aqi": 3 },
+                "components": {
+                    "co": 283.72,
+                    "no2": 19.19,
+                    "o3": 68.66,
+                    "pm2_5": 27.45,
+                    "pm10": 33.1
+                },
+                "dt": 1711356300
+            }]
+        });
+
+        let result = tool
+            .format_air_quality_response(&data, "Warsaw, PL")
+            .expect("Formatter should succeed");
+        assert!(result.contains("**Air Quality in Warsaw, PL**"));
+        assert!(result.contains("**AQI:** 3 (Moderate)"));
+        assert!(result.contains("**PM2.5:** 27.4 µg/m³ | **PM10:** 33.1 µg/m³"));
+        assert!(result.contains("**O₃:** 68.7 µg/m³ | **NO₂:** 19.2 µg/m³"));
+
+        assert!(tool
+            .format_air_quality_response(&json!({}), "Warsaw, PL")
+            .is_err());
+    }
+
+    #[test]
+    fn test_air_quality_availability() {
+        let tool = AirQualityTool::new();
+        if std::env::var("OPENWEATHER_API_KEY").is_err() {
+            assert!(!tool.is_available());
+        }
+    }
+}
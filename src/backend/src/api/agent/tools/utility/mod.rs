@@ -1,20 +1,37 @@
+pub mod air_quality;
 pub mod weather;
 
 use crate::api::agent::core::types::{AgentConfig, ToolType};
 use crate::api::agent::tools::framework::registry::ToolRegistry;
+use crate::api::agent::tools::utility::air_quality::AirQualityTool;
 use crate::api::agent::tools::utility::weather::{ForecastTool, WeatherTool};
 use std::sync::Arc;
 
-pub fn register(registry: &mut ToolRegistry, config: &AgentConfig) {
+pub fn register(
+    registry: &mut ToolRegistry,
+    config: &AgentConfig,
+    _context: &crate::api::agent::tools::RegisterContext,
+) {
     if config.enabled_tools.contains(&ToolType::Weather) {
-        let weather_tool = WeatherTool::new();
+        let weather_tool = WeatherTool::new()
+            .with_max_retries(config.tool_max_retries)
+            .with_timeout(config.tool_timeout_secs);
         if let Err(e) = registry.register(Arc::new(weather_tool)) {
             println!("⚠️ Failed to register Weather tool: {}", e);
         }
 
-        let forecast_tool = ForecastTool::new();
+        let forecast_tool = ForecastTool::new()
+            .with_max_retries(config.tool_max_retries)
+            .with_timeout(config.tool_timeout_secs);
         if let Err(e) = registry.register(Arc::new(forecast_tool)) {
             println!("⚠️ Failed to register Forecast tool: {}", e);
         }
+
+        let air_quality_tool = AirQualityTool::new()
+            .with_max_retries(config.tool_max_retries)
+            .with_timeout(config.tool_timeout_secs);
+        if let Err(e) = registry.register(Arc::new(air_quality_tool)) {
+            println!("⚠️ Failed to register Air Quality tool: {}", e);
+        }
     }
 }
@@ -1,18 +1,101 @@
 use crate::api::agent::core::types::{ToolCall, ToolCallResult, ToolType};
 use crate::api::agent::tools::framework::agent_tool::{AgentTool, ToolCategory, ToolMetadata};
+use crate::api::agent::tools::framework::http::{
+    self, HttpClient, ReqwestHttpClient, DEFAULT_TOOL_TIMEOUT_SECS,
+};
+use crate::api::agent::tools::framework::retry::DEFAULT_MAX_RETRIES;
 use anyhow::{Context, Result};
 use async_trait::async_trait;
 use chrono::Utc;
-use reqwest;
 use serde_json::json;
 use std::env;
+use std::sync::Arc;
 use url::Url;
 
+/// Check that latitude/longitude, when provided, are within valid ranges
+pub(crate) fn validate_coordinates(lat: Option<f64>, lon: Option<f64>) -> Result<()> {
+    if let Some(lat_val) = lat {
+        if !(-90.0..=90.0).contains(&lat_val) {
+            return Err(anyhow::anyhow!("Latitude must be between -90 and 90"));
+        }
+    }
+    if let Some(lon_val) = lon {
+        if !(-180.0..=180.0).contains(&lon_val) {
+            return Err(anyhow::anyhow!("Longitude must be between -180 and 180"));
+        }
+    }
+    Ok(())
+}
+
+/// Maximum number of geocoding candidates requested for a bare city name
+const GEOCODE_CANDIDATE_LIMIT: u32 = 5;
+
+/// Outcome of resolving a bare city name through the Geocoding API
+#[derive(Debug, PartialEq)]
+enum GeocodeResolution {
+    /// Exactly one distinct location matched
+    Found { lat: f64, lon: f64 },
+    /// Several locations matched; carries a message listing the candidates
+    Ambiguous(String),
+}
+
+/// Pick a location from `/geo/1.0/direct` results. Entries sharing the same
+/// name, state and country are treated as one place.
+fn resolve_geocode_matches(city: &str, matches: &[serde_json::Value]) -> Result<GeocodeResolution> {
+    let mut candidates: Vec<(String, f64, f64)> = Vec::new();
+
+    for place in matches {
+        let (Some(lat), Some(lon)) = (place["lat"].as_f64(), place["lon"].as_f64()) else {
+            continue;
+        };
+        let label = [
+            place["name"].as_str(),
+            place["state"].as_str(),
+            place["country"].as_str(),
+        ]
+        .into_iter()
+        .flatten()
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join(", ");
+
+        if !candidates.iter().any(|(existing, _, _)| existing == &label) {
+            candidates.push((label, lat, lon));
+        }
+    }
+
+    match candidates.as_slice() {
+        [] => Err(anyhow::anyhow!("Location '{}' not found", city)),
+        [(_, lat, lon)] => Ok(GeocodeResolution::Found {
+            lat: *lat,
+            lon: *lon,
+        }),
+        _ => {
+            let mut message = format!(
+                "📍 Multiple locations match '{}'. Ask the user which one they mean, or call weather_current again with a state/country or coordinates:\n\n",
+                city
+            );
+            for (i, (label, lat, lon)) in candidates.iter().enumerate() {
+                message.push_str(&format!(
+                    "{}. {} (Lat: {:.4}, Lon: {:.4})\n",
+                    i + 1,
+                    label,
+                    lat,
+                    lon
+                ));
+            }
+            Ok(GeocodeResolution::Ambiguous(message))
+        }
+    }
+}
+
 /// Weather tool for fetching current weather data
 pub struct WeatherTool {
     metadata: ToolMetadata,
-    client: reqwest::Client,
+    http: Arc<dyn HttpClient>,
     api_key: String,
+    timeout_secs: u64,
+    max_retries: u32,
 }
 
 impl WeatherTool {
@@ -28,9 +111,66 @@ impl WeatherTool {
                 category: ToolCategory::Utility,
                 tool_type: ToolType::Weather,
             },
-            client: reqwest::Client::new(),
+            http: Arc::new(ReqwestHttpClient::new(
+                DEFAULT_TOOL_TIMEOUT_SECS,
+                DEFAULT_MAX_RETRIES,
+            )),
             api_key,
+            timeout_secs: DEFAULT_TOOL_TIMEOUT_SECS,
+            max_retries: DEFAULT_MAX_RETRIES,
+        }
+    }
+
+    /// Set how many times transient request failures are retried
+    pub fn with_max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self.http = Arc::new(ReqwestHttpClient::new(self.timeout_secs, max_retries));
+        self
+    }
+
+    /// Set the HTTP request timeout in seconds
+    pub fn with_timeout(mut self, timeout_secs: u64) -> Self {
+        self.timeout_secs = timeout_secs;
+        self.http = Arc::new(ReqwestHttpClient::new(timeout_secs, self.max_retries));
+        self
+    }
+
+    /// Send requests through `http` instead of the network (replaced again by
+    /// later `with_timeout`/`with_max_retries` calls)
+    #[cfg(test)]
+    pub fn with_http_client(mut self, http: Arc<dyn HttpClient>) -> Self {
+        self.http = http;
+        self
+    }
+
+    /// Look up candidate locations for a city name via the Geocoding API
+    async fn geocode_city(&self, city: &str) -> Result<Vec<serde_json::Value>> {
+        let mut url = Url::parse("https://api.openweathermap.org/geo/1.0/direct")
+            .context("Failed to parse base URL")?;
+        url.query_pairs_mut()
+            .append_pair("q", city)
+            .append_pair("limit", &GEOCODE_CANDIDATE_LIMIT.to_string())
+            .append_pair("appid", &self.api_key);
+
+        let response = self
+            .http
+            .get(url.as_str())
+            .await
+            .context("Failed to request geocoding data from OpenWeatherMap")?;
+
+        if !response.is_success() {
+            return Err(anyhow::anyhow!(
+                "Geocoding API returned error {}: {}",
+                response.status,
+                response.body
+            ));
         }
+
+        let matches: Vec<serde_json::Value> = response
+            .json()
+            .context("Failed to parse geocoding response")?;
+
+        Ok(matches)
     }
 
     /// Fetch weather data using city name or coordinates
@@ -81,26 +221,22 @@ impl WeatherTool {
         println!("\x1b[33m🌤️ Fetching weather data...\x1b[0m");
 
         let weather_response = self
-            .client
+            .http
             .get(&weather_url)
-            .send()
             .await
             .context("Failed to request weather data from OpenWeatherMap")?;
 
         // Check HTTP status
-        if !weather_response.status().is_success() {
-            let status = weather_response.status();
-            let error_text = weather_response.text().await.unwrap_or_default();
+        if !weather_response.is_success() {
             return Err(anyhow::anyhow!(
                 "Weather API returned error {}: {}",
-                status,
-                error_text
+                weather_response.status,
+                weather_response.body
             ));
         }
 
         let weather_data: serde_json::Value = weather_response
             .json()
-            .await
             .context("Failed to parse weather response")?;
 
         Ok(weather_data)
@@ -300,16 +436,7 @@ impl AgentTool for WeatherTool {
         let lon = args.get("longitude").and_then(|v| v.as_f64());
 
         // Validate coordinates if provided
-        if let Some(lat_val) = lat {
-            if !(-90.0..=90.0).contains(&lat_val) {
-                return Err(anyhow::anyhow!("Latitude must be between -90 and 90"));
-            }
-        }
-        if let Some(lon_val) = lon {
-            if !(-180.0..=180.0).contains(&lon_val) {
-                return Err(anyhow::anyhow!("Longitude must be between -180 and 180"));
-            }
-        }
+        validate_coordinates(lat, lon)?;
 
         // Log what we're doing
         if let (Some(lat_val), Some(lon_val)) = (lat, lon) {
@@ -337,6 +464,34 @@ impl AgentTool for WeatherTool {
             ));
         }
 
+        // A bare city name may match several places, so resolve it first
+        // instead of letting OpenWeatherMap pick one arbitrarily
+        let has_qualifier = [state, country]
+            .into_iter()
+            .flatten()
+            .any(|v| !v.is_empty());
+        let (lat, lon) = match city {
+            Some(city_name) if (lat.is_none() || lon.is_none()) && !has_qualifier => {
+                let matches = self.geocode_city(city_name).await?;
+                match resolve_geocode_matches(city_name, &matches)? {
+                    GeocodeResolution::Found { lat, lon } => (Some(lat), Some(lon)),
+                    GeocodeResolution::Ambiguous(message) => {
+                        println!(
+                            "\x1b[33m📍 Ambiguous location '{}', asking for clarification\x1b[0m",
+                            city_name
+                        );
+                        return Ok(ToolCallResult {
+                            tool_name: "weather_current".to_string(),
+                            result: message,
+                            tool_call_id: Some(tool_call.id.clone()),
+                            sources: None,
+                        });
+                    }
+                }
+            }
+            _ => (lat, lon),
+        };
+
         // Fetch weather data
         let weather_data = self
             .fetch_weather_data(city, state, country, lat, lon, units)
@@ -350,6 +505,8 @@ impl AgentTool for WeatherTool {
         Ok(ToolCallResult {
             tool_name: "weather_current".to_string(),
             result,
+            tool_call_id: Some(tool_call.id.clone()),
+            sources: None,
         })
     }
 
@@ -357,6 +514,19 @@ impl AgentTool for WeatherTool {
         // Check if API key is set
         !self.api_key.is_empty()
     }
+
+    fn required_env_vars(&self) -> &'static [&'static str] {
+        &["OPENWEATHER_API_KEY"]
+    }
+
+    async fn check_connectivity(&self) -> Option<Result<()>> {
+        // A one-result geocoding lookup checks both reachability and the key
+        let url = format!(
+            "https://api.openweathermap.org/geo/1.0/direct?q=London&limit=1&appid={}",
+            self.api_key
+        );
+        Some(http::probe(self.http.as_ref(), &url).await)
+    }
 }
 
 // Helper trait for string formatting
@@ -391,8 +561,10 @@ impl StringExt for str {
 /// Weather tool for fetching 5-day forecast data
 pub struct ForecastTool {
     metadata: ToolMetadata,
-    client: reqwest::Client,
+    http: Arc<dyn HttpClient>,
     api_key: String,
+    timeout_secs: u64,
+    max_retries: u32,
 }
 
 impl ForecastTool {
@@ -408,11 +580,38 @@ impl ForecastTool {
                 category: ToolCategory::Utility,
                 tool_type: ToolType::Weather,
             },
-            client: reqwest::Client::new(),
+            http: Arc::new(ReqwestHttpClient::new(
+                DEFAULT_TOOL_TIMEOUT_SECS,
+                DEFAULT_MAX_RETRIES,
+            )),
             api_key,
+            timeout_secs: DEFAULT_TOOL_TIMEOUT_SECS,
+            max_retries: DEFAULT_MAX_RETRIES,
         }
     }
 
+    /// Set how many times transient request failures are retried
+    pub fn with_max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self.http = Arc::new(ReqwestHttpClient::new(self.timeout_secs, max_retries));
+        self
+    }
+
+    /// Set the HTTP request timeout in seconds
+    pub fn with_timeout(mut self, timeout_secs: u64) -> Self {
+        self.timeout_secs = timeout_secs;
+        self.http = Arc::new(ReqwestHttpClient::new(timeout_secs, self.max_retries));
+        self
+    }
+
+    /// Send requests through `http` instead of the network (replaced again by
+    /// later `with_timeout`/`with_max_retries` calls)
+    #[cfg(test)]
+    pub fn with_http_client(mut self, http: Arc<dyn HttpClient>) -> Self {
+        self.http = http;
+        self
+    }
+
     /// Fetch forecast data
     async fn fetch_forecast_data(
         &self,
@@ -458,25 +657,21 @@ impl ForecastTool {
         println!("\x1b[33m🗓️ Fetching 5-day forecast data...\x1b[0m");
 
         let response = self
-            .client
+            .http
             .get(&forecast_url)
-            .send()
             .await
             .context("Failed to request forecast data")?;
 
-        if !response.status().is_success() {
-            let status = response.status();
-            let error_text = response.text().await.unwrap_or_default();
+        if !response.is_success() {
             return Err(anyhow::anyhow!(
                 "Forecast API returned error {}: {}",
-                status,
-                error_text
+                response.status,
+                response.body
             ));
         }
 
         let data: serde_json::Value = response
             .json()
-            .await
             .context("Failed to parse forecast response")?;
 
         Ok(data)
@@ -733,17 +928,45 @@ impl AgentTool for ForecastTool {
         Ok(ToolCallResult {
             tool_name: "weather_forecast".to_string(),
             result,
+            tool_call_id: Some(tool_call.id.clone()),
+            sources: None,
         })
     }
 
     fn is_available(&self) -> bool {
         !self.api_key.is_empty()
     }
+
+    fn required_env_vars(&self) -> &'static [&'static str] {
+        &["OPENWEATHER_API_KEY"]
+    }
+
+    async fn check_connectivity(&self) -> Option<Result<()>> {
+        // A one-result geocoding lookup checks both reachability and the key
+        let url = format!(
+            "https://api.openweathermap.org/geo/1.0/direct?q=London&limit=1&appid={}",
+            self.api_key
+        );
+        Some(http::probe(self.http.as_ref(), &url).await)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::api::agent::core::types::FunctionCall;
+    use crate::api::agent::tools::framework::http::MockHttpClient;
+
+    fn weather_call(arguments: serde_json::Value) -> ToolCall {
+        ToolCall {
+            id: "call_1".to_string(),
+            tool_type: "function".to_string(),
+            function: FunctionCall {
+                name: "weather_current".to_string(),
+                arguments: arguments.to_string(),
+            },
+        }
+    }
 
     #[test]
     fn test_weather_metadata() {
@@ -779,4 +1002,295 @@ mod tests {
             assert!(!tool.is_available());
         }
     }
+
+    /// Trimmed OpenWeatherMap `/data/2.5/weather` response for Warsaw (units=metric)
+    fn warsaw_metric_fixture() -> serde_json::Value {
+        json!({
+            "coord": { "lon": 21.0118, "lat": 52.2298 },
+            "weather": [
+                { "id": 803, "main": "Clouds", "description": "broken clouds", "icon": "04d" }
+            ],
+            "main": {
+                "temp": 18.34,
+                "feels_like": 17.61,
+                "pressure": 1015,
+                "humidity": 58
+            },
+            "visibility": 10000,
+            "wind": { "speed": 4.12, "deg": 250 },
+            "clouds": { "all": 75 },
+            "name": "Warsaw"
+        })
+    }
+
+    /// Trimmed OpenWeatherMap response for New York (units=imperial)
+    fn new_york_imperial_fixture() -> serde_json::Value {
+        json!({
+            "coord": { "lon": -74.006, "lat": 40.7143 },
+            "weather": [
+                { "id": 500, "main": "Rain", "description": "LIGHT RAIN", "icon": "10n" }
+            ],
+            "main": {
+                "temp": 61.5,
+                "feels_like": 60.8,
+                "pressure": 1009,
+                "humidity": 81
+            },
+            "visibility": 8047,
+            "wind": { "speed": 11.5, "deg": 40 },
+            "clouds": { "all": 100 },
+            "name": "New York"
+        })
+    }
+
+    #[tokio::test]
+    async fn test_fetch_and_format_weather_by_coordinates() {
+        let mock = Arc::new(MockHttpClient::new().with_json(
+            "/data/2.5/weather",
+            200,
+            warsaw_metric_fixture(),
+        ));
+        let tool = WeatherTool::new().with_http_client(mock.clone());
+
+        let data = tool
+            .fetch_weather_data(None, None, None, Some(52.2298), Some(21.0118), "metric")
+            .await
+            .unwrap();
+        let result = tool.format_weather_response(&data, "metric").unwrap();
+
+        assert!(result.starts_with("🌡️ **Current Weather in Warsaw**"));
+        assert!(result.contains("**Temperature:** 18.3°C (Feels like 17.6°C)\n"));
+        let requests = mock.requests();
+        assert_eq!(requests.len(), 1);
+        assert!(requests[0].contains("lat=52.2298&lon=21.0118&units=metric"));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_weather_data_reports_api_error() {
+        let mock = MockHttpClient::new().with_json(
+            "/data/2.5/weather",
+            401,
+            json!({ "cod": 401, "message": "Invalid API key" }),
+        );
+        let tool = WeatherTool::new().with_http_client(Arc::new(mock));
+
+        let err = tool
+            .fetch_weather_data(Some("Warsaw"), None, Some("PL"), None, None, "metric")
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("Weather API returned error 401"));
+        assert!(err.to_string().contains("Invalid API key"));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_and_format_forecast_for_date() {
+        let mock = Arc::new(MockHttpClient::new().with_json(
+            "/data/2.5/forecast",
+            200,
+            json!({
+                "city": { "name": "Warsaw" },
+                "list": [
+                    {
+                        "dt_txt": "2024-05-01 12:00:00",
+                        "main": { "temp": 16.4 },
+                        "weather": [{ "description": "light rain", "icon": "10d" }],
+                        "pop": 0.6
+                    },
+                    {
+                        "dt_txt": "2024-05-02 12:00:00",
+                        "main": { "temp": 19.0 },
+                        "weather": [{ "description": "clear sky", "icon": "01d" }],
+                        "pop": 0.0
+                    }
+                ]
+            }),
+        ));
+        let tool = ForecastTool::new().with_http_client(mock.clone());
+
+        let data = tool
+            .fetch_forecast_data(Some("Warsaw"), None, Some("PL"), None, None, "metric")
+            .await
+            .unwrap();
+        let result = tool
+            .format_forecast_response(&data, Some("2024-05-01"), "metric")
+            .unwrap();
+
+        assert!(result.starts_with("🗓️ **5-Day Weather Forecast for Warsaw**"));
+        assert!(result.contains("- `12:00`: 16.4°C, Light Rain, ☔ 60%"));
+        assert!(!result.contains("Clear Sky"));
+        let requests = mock.requests();
+        assert_eq!(requests.len(), 1);
+        assert!(requests[0].contains("q=Warsaw%2CPL&units=metric"));
+    }
+
+    #[tokio::test]
+    async fn test_execute_resolves_bare_city_through_geocoding() {
+        let mock = Arc::new(
+            MockHttpClient::new()
+                .with_json(
+                    "/geo/1.0/direct",
+                    200,
+                    json!([{ "name": "Warsaw", "country": "PL", "lat": 52.2298, "lon": 21.0118 }]),
+                )
+                .with_json("/data/2.5/weather", 200, warsaw_metric_fixture()),
+        );
+        let tool = WeatherTool::new().with_http_client(mock.clone());
+
+        let result = tool
+            .execute(&weather_call(json!({ "city": "Warsaw" })))
+            .await
+            .unwrap();
+
+        assert_eq!(result.tool_call_id.as_deref(), Some("call_1"));
+        assert!(result.result.contains("**Conditions:** Broken Clouds"));
+        let requests = mock.requests();
+        assert_eq!(requests.len(), 2);
+        assert!(requests[0].contains("q=Warsaw"));
+        assert!(requests[1].contains("lat=52.2298"));
+    }
+
+    #[tokio::test]
+    async fn test_execute_asks_which_city_when_ambiguous() {
+        let mock = Arc::new(MockHttpClient::new().with_json(
+            "/geo/1.0/direct",
+            200,
+            json!([
+                { "name": "Springfield", "state": "Illinois", "country": "US", "lat": 39.80, "lon": -89.64 },
+                { "name": "Springfield", "state": "Missouri", "country": "US", "lat": 37.21, "lon": -93.29 }
+            ]),
+        ));
+        let tool = WeatherTool::new().with_http_client(mock.clone());
+
+        let result = tool
+            .execute(&weather_call(json!({ "city": "Springfield" })))
+            .await
+            .unwrap();
+
+        assert!(result
+            .result
+            .contains("Multiple locations match 'Springfield'"));
+        // No weather is fetched until the user picks one
+        assert_eq!(mock.requests().len(), 1);
+    }
+
+    #[test]
+    fn test_format_weather_response_metric() {
+        let tool = WeatherTool::new();
+        let result = tool
+            .format_weather_response(&warsaw_metric_fixture(), "metric")
+            .expect("Formatter should succeed");
+
+        assert!(
+            result.starts_with("🌡️ **Current Weather in Warsaw** (Lat: 52.2298, Lon: 21.0118)\n\n")
+        );
+        assert!(result.contains("**Temperature:** 18.3°C (Feels like 17.6°C)\n"));
+        assert!(result.contains("**Conditions:** Broken Clouds\n"));
+        assert!(
+            result.contains("**Humidity:** 58% | **Pressure:** 1015 hPa | **Wind:** 4.1 m/s (W)\n")
+        );
+        assert!(result.contains("**Visibility:** 10.0 km\n"));
+        assert!(result.contains("**Cloudiness:** 75%\n"));
+        assert!(result.contains("https://openweathermap.org/img/wn/04d@2x.png"));
+    }
+
+    #[test]
+    fn test_format_weather_response_imperial() {
+        let tool = WeatherTool::new();
+        let result = tool
+            .format_weather_response(&new_york_imperial_fixture(), "imperial")
+            .expect("Formatter should succeed");
+
+        assert!(result.contains("**Current Weather in New York** (Lat: 40.7143, Lon: -74.0060)"));
+        assert!(result.contains("**Temperature:** 61.5°F (Feels like 60.8°F)\n"));
+        assert!(result.contains("**Conditions:** Light Rain\n"));
+        assert!(result.contains("**Wind:** 11.5 mph (NE)\n"));
+        assert!(result.contains("**Visibility:** 8.0 km\n"));
+    }
+
+    #[test]
+    fn test_format_weather_response_optional_fields() {
+        let tool = WeatherTool::new();
+        let mut data = warsaw_metric_fixture();
+        let fields = data.as_object_mut().unwrap();
+        fields.remove("visibility");
+        fields.remove("clouds");
+        fields.insert("wind".to_string(), json!({ "speed": 2.0 }));
+
+        let result = tool
+            .format_weather_response(&data, "standard")
+            .expect("Formatter should succeed");
+
+        assert!(result.contains("**Temperature:** 18.3°K"));
+        assert!(result.contains("**Wind:** 2.0 m/s\n"));
+        assert!(!result.contains("Visibility"));
+        assert!(!result.contains("Cloudiness"));
+    }
+
+    #[test]
+    fn test_format_weather_response_missing_main() {
+        let tool = WeatherTool::new();
+        let mut data = warsaw_metric_fixture();
+        data.as_object_mut().unwrap().remove("main");
+
+        let err = tool.format_weather_response(&data, "metric").unwrap_err();
+        assert_eq!(err.to_string(), "Missing 'main' field in weather response");
+    }
+
+    #[test]
+    fn test_degrees_to_direction_boundaries() {
+        let tool = WeatherTool::new();
+        assert_eq!(tool.degrees_to_direction(0), "N");
+        assert_eq!(tool.degrees_to_direction(22), "N");
+        assert_eq!(tool.degrees_to_direction(23), "NE");
+        assert_eq!(tool.degrees_to_direction(112), "E");
+        assert_eq!(tool.degrees_to_direction(113), "SE");
+        assert_eq!(tool.degrees_to_direction(202), "S");
+        assert_eq!(tool.degrees_to_direction(247), "SW");
+        assert_eq!(tool.degrees_to_direction(292), "W");
+        assert_eq!(tool.degrees_to_direction(337), "NW");
+        assert_eq!(tool.degrees_to_direction(338), "N");
+        assert_eq!(tool.degrees_to_direction(360), "N");
+    }
+
+    #[test]
+    fn test_resolve_geocode_no_matches() {
+        let err = resolve_geocode_matches("Atlantis", &[]).unwrap_err();
+        assert_eq!(err.to_string(), "Location 'Atlantis' not found");
+    }
+
+    #[test]
+    fn test_resolve_geocode_single_match() {
+        let matches = vec![
+            json!({ "name": "Warsaw", "lat": 52.2298, "lon": 21.0118, "country": "PL", "state": "Masovian Voivodeship" }),
+            // Duplicate entry for the same place collapses into one candidate
+            json!({ "name": "Warsaw", "lat": 52.2297, "lon": 21.0122, "country": "PL", "state": "Masovian Voivodeship" }),
+        ];
+
+        assert_eq!(
+            resolve_geocode_matches("Warsaw", &matches).unwrap(),
+            GeocodeResolution::Found {
+                lat: 52.2298,
+                lon: 21.0118
+            }
+        );
+    }
+
+    #[test]
+    fn test_resolve_geocode_multiple_matches() {
+        let matches = vec![
+            json!({ "name": "Springfield", "lat": 39.799, "lon": -89.644, "country": "US", "state": "Illinois" }),
+            json!({ "name": "Springfield", "lat": 37.2153, "lon": -93.2982, "country": "US", "state": "Missouri" }),
+            json!({ "name": "Springfield", "lat": -43.3333, "lon": 171.9333, "country": "NZ" }),
+        ];
+
+        let GeocodeResolution::Ambiguous(message) =
+            resolve_geocode_matches("Springfield", &matches).unwrap()
+        else {
+            panic!("Expected an ambiguous resolution");
+        };
+        assert!(message.contains("Multiple locations match 'Springfield'"));
+        assert!(message.contains("1. Springfield, Illinois, US (Lat: 39.7990, Lon: -89.6440)"));
+        assert!(message.contains("2. Springfield, Missouri, US"));
+        assert!(message.contains("3. Springfield, NZ (Lat: -43.3333, Lon: 171.9333)"));
+    }
 }
@@ -5,9 +5,13 @@ use crate::api::agent::tools::framework::registry::ToolRegistry;
 use crate::api::agent::tools::web::website_check::WebsiteCheckTool;
 use std::sync::Arc;
 
-pub fn register(registry: &mut ToolRegistry, config: &AgentConfig) {
+pub fn register(
+    registry: &mut ToolRegistry,
+    config: &AgentConfig,
+    _context: &crate::api::agent::tools::RegisterContext,
+) {
     if config.enabled_tools.contains(&ToolType::WebsiteCheck) {
-        let tool = WebsiteCheckTool::new();
+        let tool = WebsiteCheckTool::new().with_timeout(config.tool_timeout_secs);
         if let Err(e) = registry.register(Arc::new(tool)) {
             println!("⚠️ Failed to register Website Check tool: {}", e);
         }
@@ -1,5 +1,6 @@
 use crate::api::agent::core::types::{ToolCall, ToolCallResult, ToolType};
 use crate::api::agent::tools::framework::agent_tool::{AgentTool, ToolCategory, ToolMetadata};
+use crate::api::agent::tools::framework::http::{self, DEFAULT_TOOL_TIMEOUT_SECS};
 use crate::markdown_utils::convert::{convert_html_to_markdown, ConversionConfig};
 use anyhow::{Context, Result};
 use async_trait::async_trait;
@@ -10,6 +11,7 @@ use url::Url;
 /// Converts a URL to markdown and provides it to the LLM for analysis
 pub struct WebsiteCheckTool {
     metadata: ToolMetadata,
+    client: reqwest::Client,
 }
 
 impl WebsiteCheckTool {
@@ -23,16 +25,28 @@ impl WebsiteCheckTool {
                 category: ToolCategory::Web,
                 tool_type: ToolType::WebsiteCheck,
             },
+            client: http::build_client(DEFAULT_TOOL_TIMEOUT_SECS),
         }
     }
 
+    /// Set the HTTP request timeout in seconds
+    pub fn with_timeout(mut self, timeout_secs: u64) -> Self {
+        self.client = http::build_client(timeout_secs);
+        self
+    }
+
     /// Fetch URL and convert to markdown (internal method)
     async fn check_website(&self, url: &str) -> Result<String> {
         // Validate URL format
         Url::parse(url).context("Invalid URL format")?;
 
         // Fetch HTML from the URL
-        let response = reqwest::get(url).await.context("Failed to fetch URL")?;
+        let response = self
+            .client
+            .get(url)
+            .send()
+            .await
+            .context("Failed to fetch URL")?;
 
         if !response.status().is_success() {
             return Err(anyhow::anyhow!(
@@ -127,6 +141,8 @@ impl AgentTool for WebsiteCheckTool {
         Ok(ToolCallResult {
             tool_name: "check_website".to_string(),
             result,
+            tool_call_id: Some(tool_call.id.clone()),
+            sources: None,
         })
     }
 }
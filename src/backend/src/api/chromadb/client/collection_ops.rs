@@ -5,6 +5,7 @@
 use crate::api::chromadb::types::Collection;
 use anyhow::{Context, Result};
 use chroma::ChromaHttpClient;
+use log::{info, warn};
 use std::collections::HashMap;
 
 use super::metadata::{chromadb_metadata_to_hashmap, to_chromadb_metadata};
@@ -45,7 +46,7 @@ pub async fn create_collection(
     name: &str,
     metadata: Option<HashMap<String, String>>,
 ) -> Result<Collection> {
-    println!(
+    info!(
         "🔧 ChromaDBClient::create_collection called with name: '{}', metadata: {:?}",
         name, metadata
     );
@@ -55,7 +56,7 @@ pub async fn create_collection(
     // Log distance metric if set
     if let Some(ref meta) = metadata_map {
         if let Some(space) = meta.get("hnsw:space") {
-            println!(
+            info!(
                 "📊 Distance metric configured: {} (via hnsw:space)",
                 match space {
                     chroma::types::MetadataValue::Str(s) => s.as_str(),
@@ -65,7 +66,7 @@ pub async fn create_collection(
         }
     }
 
-    println!(
+    info!(
         "🔧 Calling chroma client.create_collection with name: '{}', metadata_map: {:?}",
         name, metadata_map
     );
@@ -80,19 +81,19 @@ pub async fn create_collection(
             )
         })?;
 
-    println!(
+    info!(
         "✅ ChromaDB collection created successfully: {}",
         collection.name()
     );
 
     // Verify the collection's metadata to check if distance metric was set
     if let Some(collection_metadata) = collection.metadata() {
-        println!(
+        info!(
             "📋 Collection metadata after creation: {:?}",
             collection_metadata
         );
         if let Some(space) = collection_metadata.get("hnsw:space") {
-            println!(
+            info!(
                 "✅ Distance metric confirmed in collection: {}",
                 match space {
                     chroma::types::MetadataValue::Str(s) => s.as_str(),
@@ -100,8 +101,8 @@ pub async fn create_collection(
                 }
             );
         } else {
-            println!("⚠️ WARNING: Distance metric (hnsw:space) not found in collection metadata!");
-            println!("   The collection may be using default L2 distance instead of cosine.");
+            warn!("⚠️ WARNING: Distance metric (hnsw:space) not found in collection metadata!");
+            warn!("   The collection may be using default L2 distance instead of cosine.");
         }
     }
 
@@ -2,13 +2,84 @@
 //!
 //! This module handles adding documents to ChromaDB collections with embedding generation.
 
+use crate::api::chromadb::schema::MetadataSchema;
 use crate::api::chromadb::types::AddDocumentsRequest;
+use crate::api::metrics::registry as metrics;
 use anyhow::{Context, Result};
-use chroma::types::Metadata;
+use chroma::types::{Metadata, MetadataValue};
 use chroma::ChromaHttpClient;
+use log::{info, warn};
+use std::collections::HashMap;
+use std::fmt;
 
-use super::metadata::vec_to_chromadb_metadata;
-use super::ollama::{OllamaConfig, OllamaManager};
+use super::embedding::EmbeddingBackend;
+use super::metadata::{chromadb_metadata_to_hashmap, vec_to_chromadb_metadata};
+
+/// Collection metadata key recording the embedding dimension of stored documents
+pub const EMBEDDING_DIMENSION_KEY: &str = "embedding_dimension";
+
+/// Returned by `add_documents` when new embeddings don't match the dimension
+/// recorded for the collection
+#[derive(Debug, Clone, PartialEq)]
+pub struct EmbeddingDimensionError {
+    pub expected: usize,
+    pub actual: usize,
+}
+
+impl fmt::Display for EmbeddingDimensionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "collection expects dim {} but got {}; re-create the collection or use the original model",
+            self.expected, self.actual
+        )
+    }
+}
+
+impl std::error::Error for EmbeddingDimensionError {}
+
+/// Returned by `add_documents` when the target collection can't be loaded
+#[derive(Debug, Clone, PartialEq)]
+pub struct CollectionNotFoundError {
+    pub name: String,
+}
+
+impl fmt::Display for CollectionNotFoundError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Collection '{}' not found", self.name)
+    }
+}
+
+impl std::error::Error for CollectionNotFoundError {}
+
+/// Check embeddings against the dimension recorded in collection metadata.
+/// Returns the dimension to record when the collection has none yet.
+pub(super) fn check_embedding_dimension(
+    collection_metadata: &HashMap<String, String>,
+    embeddings: &[Vec<f32>],
+) -> Result<Option<usize>> {
+    let Some(actual) = embeddings.first().map(Vec::len) else {
+        return Ok(None);
+    };
+    if let Some(other) = embeddings.iter().map(Vec::len).find(|&len| len != actual) {
+        return Err(anyhow::anyhow!(
+            "Embedding backend returned vectors of different dimensions ({} and {})",
+            actual,
+            other
+        ));
+    }
+
+    match collection_metadata
+        .get(EMBEDDING_DIMENSION_KEY)
+        .and_then(|v| v.parse::<usize>().ok())
+    {
+        Some(expected) if expected != actual => {
+            Err(EmbeddingDimensionError { expected, actual }.into())
+        }
+        Some(_) => Ok(None),
+        None => Ok(Some(actual)),
+    }
+}
 
 /// Normalize embeddings to unit length for cosine similarity
 /// This ensures embeddings are on the unit sphere, which is required for proper cosine distance calculation
@@ -24,45 +95,56 @@ fn normalize_embeddings(embeddings: &mut [Vec<f32>]) {
 }
 
 /// Add documents to a collection with automatic embedding generation
+///
+/// If the collection has a metadata schema, every document's metadata is checked
+/// first and a [`crate::api::chromadb::schema::MetadataSchemaError`] is returned
+/// on mismatch. The embedding dimension is recorded on the first insert and later
+/// inserts with a different dimension fail with [`EmbeddingDimensionError`].
 pub async fn add_documents(
     client: &ChromaHttpClient,
     request: AddDocumentsRequest,
-    embedding_model: &str,
+    embedder: &dyn EmbeddingBackend,
 ) -> Result<()> {
-    let collection = client
+    let embedding_model = embedder.model();
+    let mut collection = client
         .get_collection(&request.collection)
         .await
-        .context("Collection not found")?;
+        .with_context(|| CollectionNotFoundError {
+            name: request.collection.clone(),
+        })?;
+
+    let collection_metadata = collection
+        .metadata()
+        .as_ref()
+        .map(chromadb_metadata_to_hashmap)
+        .unwrap_or_default();
+    if let Some(schema) = MetadataSchema::from_collection_metadata(&collection_metadata)
+        .map_err(anyhow::Error::msg)?
+    {
+        schema.validate(request.documents.len(), request.metadatas.as_deref())?;
+    }
 
     // Convert metadatas to ChromaDB format
     let metadatas: Option<Vec<Option<Metadata>>> = request.metadatas.map(vec_to_chromadb_metadata);
 
-    println!(
-        "🔧 Generating embeddings for {} documents using Ollama embedding model '{}'",
+    info!(
+        "🔧 Generating embeddings for {} documents using embedding model '{}'",
         request.documents.len(),
         embedding_model
     );
 
-    // Generate embeddings using Ollama with configured model
-    let config = OllamaConfig {
-        model: embedding_model.to_string(),
-        ..Default::default()
-    };
-    let ollama_manager = OllamaManager::new(config);
     let document_refs: Vec<&str> = request.documents.iter().map(|s| s.as_str()).collect();
-    let mut embeddings = ollama_manager
-        .generate_embeddings_with_server(&document_refs)
-        .await
-        .with_context(|| {
-            format!(
-                "Failed to generate embeddings from documents using model '{}'",
-                embedding_model
-            )
-        })?;
+    metrics::record(|m| m.embedding_calls.inc());
+    let mut embeddings = embedder.embed(&document_refs).await.with_context(|| {
+        format!(
+            "Failed to generate embeddings from documents using model '{}'",
+            embedding_model
+        )
+    })?;
 
     // Log embedding dimension for debugging
     if let Some(first_embedding) = embeddings.first() {
-        println!(
+        info!(
             "📐 Document embedding dimension: {} (using model '{}')",
             first_embedding.len(),
             embedding_model
@@ -76,12 +158,14 @@ pub async fn add_documents(
     // Verify normalization
     if let Some(first_embedding) = embeddings.first() {
         let norm: f32 = first_embedding.iter().map(|x| x * x).sum::<f32>().sqrt();
-        println!(
+        info!(
             "📊 Document embedding norm after normalization: {:.4} (expected: ~1.0)",
             norm
         );
     }
 
+    let dimension_to_record = check_embedding_dimension(&collection_metadata, &embeddings)?;
+
     // Convert documents to Option<Vec<Option<String>>>
     let documents: Option<Vec<Option<String>>> =
         Some(request.documents.into_iter().map(Some).collect());
@@ -98,14 +182,74 @@ pub async fn add_documents(
         .await
         .context("Failed to add documents to ChromaDB")?;
 
+    if let Some(dimension) = dimension_to_record {
+        // Modify replaces the metadata, so send the existing keys along
+        let mut metadata = collection.metadata().clone().unwrap_or_default();
+        metadata.insert(
+            EMBEDDING_DIMENSION_KEY.to_string(),
+            MetadataValue::Int(dimension as i64),
+        );
+        match collection.modify(None::<&str>, Some(metadata)).await {
+            Ok(_) => info!(
+                "📐 Recorded embedding dimension {} for collection '{}'",
+                dimension, request.collection
+            ),
+            Err(e) => warn!(
+                "⚠️ Failed to record embedding dimension for collection '{}': {}",
+                request.collection, e
+            ),
+        }
+    }
+
     Ok(())
 }
 
 #[cfg(test)]
 mod tests {
+    use super::*;
+
     #[test]
     fn test_function_exists() {
         // Verify the function is defined
         assert!(true);
     }
+
+    #[test]
+    fn test_check_embedding_dimension_records_first_insert() {
+        let embeddings = vec![vec![0.0; 768], vec![0.0; 768]];
+        let dimension = check_embedding_dimension(&HashMap::new(), &embeddings).unwrap();
+        assert_eq!(dimension, Some(768));
+    }
+
+    #[test]
+    fn test_check_embedding_dimension_rejects_mismatch() {
+        let mut metadata = HashMap::new();
+        metadata.insert(EMBEDDING_DIMENSION_KEY.to_string(), "768".to_string());
+
+        // Matching dimension: nothing new to record
+        let same = check_embedding_dimension(&metadata, &[vec![0.0; 768]]).unwrap();
+        assert_eq!(same, None);
+
+        // A different model (e.g. mxbai-embed-large) produces 1024 dimensions
+        let err = check_embedding_dimension(&metadata, &[vec![0.0; 1024]]).unwrap_err();
+        let mismatch = err.downcast_ref::<EmbeddingDimensionError>().unwrap();
+        assert_eq!(
+            mismatch,
+            &EmbeddingDimensionError {
+                expected: 768,
+                actual: 1024
+            }
+        );
+        assert_eq!(
+            err.to_string(),
+            "collection expects dim 768 but got 1024; re-create the collection or use the original model"
+        );
+    }
+
+    #[test]
+    fn test_check_embedding_dimension_rejects_mixed_vectors() {
+        let embeddings = vec![vec![0.0; 768], vec![0.0; 384]];
+        let err = check_embedding_dimension(&HashMap::new(), &embeddings).unwrap_err();
+        assert!(err.downcast_ref::<EmbeddingDimensionError>().is_none());
+    }
 }
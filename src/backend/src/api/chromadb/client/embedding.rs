@@ -0,0 +1,309 @@
+//! Embedding backends
+//!
+//! Documents and queries are embedded through an [`EmbeddingBackend`]: a local
+//! Ollama server (the default) or any OpenAI-compatible `/embeddings` endpoint.
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use log::{info, warn};
+use reqwest::header::{HeaderMap, HeaderValue, AUTHORIZATION};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+use super::ollama::{OllamaConfig, OllamaManager};
+use crate::api::agent::tools::framework::http::HttpClient;
+
+/// Selects the default backend (`ollama` or `openai`)
+pub const EMBEDDING_BACKEND_ENV: &str = "EMBEDDING_BACKEND";
+/// Base URL of the OpenAI-compatible API, e.g. `https://api.openai.com/v1`
+pub const EMBEDDING_API_BASE_ENV: &str = "EMBEDDING_API_BASE";
+/// Optional bearer token for the OpenAI-compatible API
+pub const EMBEDDING_API_KEY_ENV: &str = "EMBEDDING_API_KEY";
+
+/// Which service generates embeddings
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EmbeddingBackendKind {
+    #[default]
+    Ollama,
+    #[serde(rename = "openai")]
+    OpenAi,
+}
+
+impl EmbeddingBackendKind {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "ollama" => Some(Self::Ollama),
+            "openai" => Some(Self::OpenAi),
+            _ => None,
+        }
+    }
+
+    /// Backend named by `EMBEDDING_BACKEND`, falling back to Ollama
+    pub fn from_env() -> Self {
+        std::env::var(EMBEDDING_BACKEND_ENV)
+            .ok()
+            .and_then(|v| Self::parse(&v))
+            .unwrap_or_default()
+    }
+}
+
+/// Turns texts into embedding vectors, one per input in the same order
+#[async_trait]
+pub trait EmbeddingBackend: Send + Sync {
+    /// Model used for embeddings (shown in logs and errors)
+    fn model(&self) -> &str;
+
+    async fn embed(&self, texts: &[&str]) -> Result<Vec<Vec<f32>>>;
+}
+
+/// Embeddings from a local Ollama server, started on demand
+pub struct OllamaEmbedding {
+    model: String,
+}
+
+impl OllamaEmbedding {
+    pub fn new(model: &str) -> Self {
+        Self {
+            model: model.to_string(),
+        }
+    }
+}
+
+#[async_trait]
+impl EmbeddingBackend for OllamaEmbedding {
+    fn model(&self) -> &str {
+        &self.model
+    }
+
+    async fn embed(&self, texts: &[&str]) -> Result<Vec<Vec<f32>>> {
+        let manager = OllamaManager::new(OllamaConfig {
+            model: self.model.clone(),
+            ..Default::default()
+        });
+        manager.generate_embeddings_with_server(texts).await
+    }
+}
+
+/// Embeddings from an OpenAI-compatible `POST {base}/embeddings` endpoint
+pub struct OpenAiCompatEmbedding {
+    http: Arc<dyn HttpClient>,
+    base_url: String,
+    model: String,
+}
+
+#[derive(Deserialize)]
+struct EmbeddingsResponse {
+    data: Vec<EmbeddingData>,
+}
+
+#[derive(Deserialize)]
+struct EmbeddingData {
+    index: usize,
+    embedding: Vec<f32>,
+}
+
+impl OpenAiCompatEmbedding {
+    pub fn new(base_url: &str, api_key: Option<String>, model: &str) -> Self {
+        let api_key = api_key.filter(|k| !k.trim().is_empty());
+        Self {
+            http: Arc::new(authorized_client(api_key.as_deref())),
+            base_url: base_url.trim_end_matches('/').to_string(),
+            model: model.to_string(),
+        }
+    }
+
+    #[cfg(test)]
+    pub fn with_http_client(mut self, http: Arc<dyn HttpClient>) -> Self {
+        self.http = http;
+        self
+    }
+
+    /// Build from `EMBEDDING_API_BASE` and `EMBEDDING_API_KEY`
+    pub fn from_env(model: &str) -> Result<Self> {
+        let base_url = std::env::var(EMBEDDING_API_BASE_ENV)
+            .ok()
+            .filter(|v| !v.trim().is_empty())
+            .with_context(|| {
+                format!(
+                    "{} must be set to use the openai embedding backend",
+                    EMBEDDING_API_BASE_ENV
+                )
+            })?;
+        Ok(Self::new(
+            &base_url,
+            std::env::var(EMBEDDING_API_KEY_ENV).ok(),
+            model,
+        ))
+    }
+}
+
+/// Client sending `api_key` as a bearer token with every request
+fn authorized_client(api_key: Option<&str>) -> reqwest::Client {
+    let mut headers = HeaderMap::new();
+    if let Some(key) = api_key {
+        match HeaderValue::from_str(&format!("Bearer {}", key)) {
+            Ok(mut value) => {
+                value.set_sensitive(true);
+                headers.insert(AUTHORIZATION, value);
+            }
+            Err(_) => warn!(
+                "⚠️ {} is not a valid header value, sending requests without it",
+                EMBEDDING_API_KEY_ENV
+            ),
+        }
+    }
+    reqwest::Client::builder()
+        .default_headers(headers)
+        .build()
+        .expect("Failed to build reqwest client")
+}
+
+/// Order embeddings by their `index` and check one came back per input
+fn parse_embeddings_response(body: &str, expected: usize) -> Result<Vec<Vec<f32>>> {
+    let mut response: EmbeddingsResponse =
+        serde_json::from_str(body).context("Invalid embeddings response")?;
+    response.data.sort_by_key(|d| d.index);
+
+    if response.data.len() != expected {
+        return Err(anyhow::anyhow!(
+            "Embeddings response has {} vector(s) for {} input(s)",
+            response.data.len(),
+            expected
+        ));
+    }
+
+    Ok(response.data.into_iter().map(|d| d.embedding).collect())
+}
+
+#[async_trait]
+impl EmbeddingBackend for OpenAiCompatEmbedding {
+    fn model(&self) -> &str {
+        &self.model
+    }
+
+    async fn embed(&self, texts: &[&str]) -> Result<Vec<Vec<f32>>> {
+        if texts.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let url = format!("{}/embeddings", self.base_url);
+        info!(
+            "🔧 Requesting {} embedding(s) from {} with model '{}'",
+            texts.len(),
+            url,
+            self.model
+        );
+
+        let body = serde_json::json!({
+            "model": self.model,
+            "input": texts,
+        });
+        let response = self
+            .http
+            .post(&url, &body)
+            .await
+            .with_context(|| format!("Failed to reach embedding API at {}", url))?;
+        if !response.is_success() {
+            return Err(anyhow::anyhow!(
+                "Embedding API returned {}: {}",
+                response.status,
+                response.body.chars().take(500).collect::<String>()
+            ));
+        }
+
+        parse_embeddings_response(&response.body, texts.len())
+    }
+}
+
+/// Build the configured backend for `model`
+pub fn embedding_backend(
+    kind: EmbeddingBackendKind,
+    model: &str,
+) -> Result<Box<dyn EmbeddingBackend>> {
+    Ok(match kind {
+        EmbeddingBackendKind::Ollama => Box::new(OllamaEmbedding::new(model)),
+        EmbeddingBackendKind::OpenAi => Box::new(OpenAiCompatEmbedding::from_env(model)?),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::agent::tools::framework::http::MockHttpClient;
+
+    #[test]
+    fn test_parse_backend_kind() {
+        assert_eq!(
+            EmbeddingBackendKind::parse("OpenAI"),
+            Some(EmbeddingBackendKind::OpenAi)
+        );
+        assert_eq!(
+            EmbeddingBackendKind::parse("ollama"),
+            Some(EmbeddingBackendKind::Ollama)
+        );
+        assert_eq!(EmbeddingBackendKind::parse("cohere"), None);
+        assert_eq!(
+            serde_json::to_value(EmbeddingBackendKind::OpenAi).unwrap(),
+            "openai"
+        );
+    }
+
+    #[test]
+    fn test_parse_embeddings_response_orders_by_index() {
+        let body = r#"{"data": [
+            {"object": "embedding", "index": 1, "embedding": [0.0, 1.0]},
+            {"object": "embedding", "index": 0, "embedding": [1.0, 0.0]}
+        ], "model": "text-embedding-3-small"}"#;
+
+        let embeddings = parse_embeddings_response(body, 2).unwrap();
+        assert_eq!(embeddings, vec![vec![1.0, 0.0], vec![0.0, 1.0]]);
+    }
+
+    #[test]
+    fn test_parse_embeddings_response_rejects_count_mismatch() {
+        let body = r#"{"data": [{"index": 0, "embedding": [1.0]}]}"#;
+        assert!(parse_embeddings_response(body, 2).is_err());
+        assert!(parse_embeddings_response("{}", 1).is_err());
+    }
+
+    #[test]
+    fn test_openai_compat_trims_base_url() {
+        let backend =
+            OpenAiCompatEmbedding::new("http://localhost:1234/v1/", Some(String::new()), "m");
+        assert_eq!(backend.base_url, "http://localhost:1234/v1");
+        assert_eq!(backend.model(), "m");
+    }
+
+    #[tokio::test]
+    async fn test_openai_compat_embed() {
+        let mock = Arc::new(MockHttpClient::new().with_json(
+            "/v1/embeddings",
+            200,
+            serde_json::json!({"data": [
+                {"index": 1, "embedding": [0.0, 1.0]},
+                {"index": 0, "embedding": [1.0, 0.0]}
+            ]}),
+        ));
+        let backend = OpenAiCompatEmbedding::new("http://localhost:1234/v1", None, "m")
+            .with_http_client(mock.clone());
+
+        let embeddings = backend.embed(&["a", "b"]).await.unwrap();
+        assert_eq!(embeddings, vec![vec![1.0, 0.0], vec![0.0, 1.0]]);
+        assert_eq!(mock.requests(), vec!["http://localhost:1234/v1/embeddings"]);
+    }
+
+    #[tokio::test]
+    async fn test_openai_compat_embed_error_status() {
+        let mock = MockHttpClient::new().with_json(
+            "/embeddings",
+            401,
+            serde_json::json!({"error": "invalid api key"}),
+        );
+        let backend = OpenAiCompatEmbedding::new("http://localhost:1234/v1", None, "m")
+            .with_http_client(Arc::new(mock));
+
+        let err = backend.embed(&["a"]).await.unwrap_err().to_string();
+        assert!(err.starts_with("Embedding API returned 401"));
+    }
+}
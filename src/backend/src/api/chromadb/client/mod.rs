@@ -4,6 +4,7 @@
 //!
 //! This module is organized by concern:
 //! - `ollama.rs`: Ollama server management and embedding generation
+//! - `embedding.rs`: Embedding backends (Ollama or an OpenAI-compatible API)
 //! - `metadata.rs`: Metadata format conversion utilities
 //! - `collection_ops.rs`: Collection operations (list, create, get, delete)
 //! - `document_ops.rs`: Document operations (add with embeddings)
@@ -11,6 +12,7 @@
 
 mod collection_ops;
 mod document_ops;
+mod embedding;
 mod metadata;
 mod ollama;
 mod query_ops;
@@ -22,16 +24,19 @@ mod tests;
 // Re-export for external use
 pub use collection_ops::*;
 pub use document_ops::*;
+pub use embedding::{embedding_backend, EmbeddingBackend, EmbeddingBackendKind};
+pub use ollama::OllamaManager;
 pub use query_ops::*;
 
 use crate::api::chromadb::types::{AddDocumentsRequest, Collection, QueryRequest, QueryResponse};
 use anyhow::{Context, Result};
 use chroma::ChromaHttpClient;
+use log::{debug, warn};
 
 /// Main ChromaDB client
 ///
 /// This client provides a clean interface to ChromaDB operations,
-/// with automatic embedding generation using Ollama.
+/// with automatic embedding generation through an [`EmbeddingBackend`].
 pub struct ChromaDBClient {
     client: ChromaHttpClient,
 }
@@ -71,11 +76,11 @@ impl ChromaDBClient {
         // This will fail if the server is not ready or not accessible
         match self.client.list_collections(10, None).await {
             Ok(_) => {
-                println!("✅ ChromaDB health check: Connected");
+                debug!("✅ ChromaDB health check: Connected");
                 Ok(true)
             }
             Err(e) => {
-                println!("⚠️ ChromaDB health check failed: {}", e);
+                warn!("⚠️ ChromaDB health check failed: {}", e);
                 Ok(false)
             }
         }
@@ -109,13 +114,17 @@ impl ChromaDBClient {
     pub async fn add_documents(
         &self,
         request: AddDocumentsRequest,
-        embedding_model: &str,
+        embedder: &dyn EmbeddingBackend,
     ) -> Result<()> {
-        add_documents(&self.client, request, embedding_model).await
+        add_documents(&self.client, request, embedder).await
     }
 
     /// Query a collection with embedding-based search
-    pub async fn query(&self, request: QueryRequest, query_model: &str) -> Result<QueryResponse> {
-        query_collection(&self.client, request, query_model).await
+    pub async fn query(
+        &self,
+        request: QueryRequest,
+        embedder: &dyn EmbeddingBackend,
+    ) -> Result<QueryResponse> {
+        query_collection(&self.client, request, embedder).await
     }
 }
@@ -5,7 +5,9 @@
 
 use anyhow::{Context, Result};
 use chroma::embed::{ollama::OllamaEmbeddingFunction, EmbeddingFunction};
-use std::process::{Command, Stdio};
+use log::{debug, info, warn};
+use std::process::{Child, Command, Stdio};
+use std::sync::Mutex;
 use tokio::time::{sleep, Duration};
 
 /// Configuration for Ollama embedding generation
@@ -29,6 +31,9 @@ impl Default for OllamaConfig {
     }
 }
 
+/// Ollama server started by us, kept here so it can also be killed on shutdown
+static SPAWNED_SERVER: Mutex<Option<Child>> = Mutex::new(None);
+
 /// Manages Ollama server lifecycle and embedding generation
 pub struct OllamaManager {
     config: OllamaConfig,
@@ -40,21 +45,21 @@ impl OllamaManager {
     }
 
     /// Start Ollama server and wait for it to be ready
-    /// Returns Ok(Some(process)) if we started it, Ok(None) if it was already running
-    pub async fn start_server(&self) -> Result<Option<std::process::Child>> {
+    /// Returns Ok(true) if we started it, Ok(false) if it was already running
+    pub async fn start_server(&self) -> Result<bool> {
         // First check if Ollama is already running
         if tokio::net::TcpStream::connect(format!("127.0.0.1:{}", self.config.port))
             .await
             .is_ok()
         {
-            println!(
+            info!(
                 "✅ Ollama server is already running on port {}",
                 self.config.port
             );
-            return Ok(None);
+            return Ok(false);
         }
 
-        println!("🚀 Starting Ollama server for embedding generation...");
+        info!("🚀 Starting Ollama server for embedding generation...");
 
         let process = tokio::task::spawn_blocking(|| {
             Command::new("ollama")
@@ -67,20 +72,25 @@ impl OllamaManager {
         .context("Failed to spawn blocking task for Ollama")?
         .context("Failed to spawn Ollama server. Make sure 'ollama' is installed and in PATH.")?;
 
+        *SPAWNED_SERVER.lock().unwrap() = Some(process);
+
         // Wait for Ollama to be ready
-        self.wait_for_server().await?;
+        if let Err(e) = self.wait_for_server().await {
+            self.stop_server(true).await;
+            return Err(e);
+        }
 
-        println!("✅ Ollama server is ready");
+        info!("✅ Ollama server is ready");
 
         // Give Ollama a moment to fully initialize after port is open
         sleep(Duration::from_millis(500)).await;
 
-        Ok(Some(process))
+        Ok(true)
     }
 
     /// Wait for Ollama server to be ready by checking if port is accessible
     async fn wait_for_server(&self) -> Result<()> {
-        println!("⏳ Waiting for Ollama server to be ready...");
+        info!("⏳ Waiting for Ollama server to be ready...");
 
         let mut retries = self.config.max_retries;
         while retries > 0 {
@@ -106,7 +116,7 @@ impl OllamaManager {
     /// 2. If not, pull it and wait for completion
     /// 3. Verify the model is available before returning
     pub async fn ensure_model_available(&self) -> Result<()> {
-        println!(
+        debug!(
             "🔍 Checking if model '{}' is available...",
             self.config.model
         );
@@ -142,12 +152,12 @@ impl OllamaManager {
         .map_err(|e| anyhow::anyhow!("Failed to check model availability: {}", e))?;
 
         if is_available {
-            println!("✅ Model '{}' is already available", self.config.model);
+            info!("✅ Model '{}' is already available", self.config.model);
             return Ok(());
         }
 
         // Model not available, pull it
-        println!(
+        info!(
             "📥 Pulling model '{}' (this may take a while)...",
             self.config.model
         );
@@ -162,10 +172,10 @@ impl OllamaManager {
             Ok(output) => {
                 if output.status.success() {
                     let stdout = String::from_utf8_lossy(&output.stdout);
-                    println!("✅ Model '{}' pulled successfully", self.config.model);
+                    info!("✅ Model '{}' pulled successfully", self.config.model);
                     // Print pull output for visibility
                     if !stdout.trim().is_empty() {
-                        println!("📋 Pull output: {}", stdout);
+                        info!("📋 Pull output: {}", stdout);
                     }
                 } else {
                     let stderr = String::from_utf8_lossy(&output.stderr);
@@ -174,13 +184,13 @@ impl OllamaManager {
                         "Failed to pull model '{}'. stderr: {}, stdout: {}",
                         self.config.model, stderr, stdout
                     );
-                    println!("{}", error_msg);
+                    warn!("{}", error_msg);
                     return Err(anyhow::anyhow!(error_msg));
                 }
             }
             Err(e) => {
                 let error_msg = format!("Failed to execute ollama pull: {}", e);
-                println!("{}", error_msg);
+                warn!("{}", error_msg);
                 return Err(anyhow::anyhow!(error_msg));
             }
         }
@@ -219,7 +229,7 @@ impl OllamaManager {
             ));
         }
 
-        println!(
+        info!(
             "✅ Model '{}' is now available and verified",
             self.config.model
         );
@@ -227,10 +237,16 @@ impl OllamaManager {
     }
 
     /// Stop the Ollama server process
-    /// Only stops if we started it (process is Some)
-    pub async fn stop_server(&self, process: Option<std::process::Child>) {
+    /// Only stops if we started it
+    pub async fn stop_server(&self, started: bool) {
+        let process = if started {
+            SPAWNED_SERVER.lock().unwrap().take()
+        } else {
+            None
+        };
+
         if let Some(mut process) = process {
-            println!("🛑 Stopping Ollama server...");
+            info!("🛑 Stopping Ollama server...");
 
             let kill_result = tokio::task::spawn_blocking(move || {
                 let _ = process.kill();
@@ -240,30 +256,35 @@ impl OllamaManager {
 
             match kill_result {
                 Ok(Ok(_)) => {
-                    println!("✅ Ollama server stopped successfully");
+                    info!("✅ Ollama server stopped successfully");
                 }
                 Ok(Err(e)) => {
-                    println!("⚠️ Warning: Failed to stop Ollama server: {}", e);
+                    warn!("⚠️ Warning: Failed to stop Ollama server: {}", e);
                 }
                 Err(e) => {
-                    println!("⚠️ Warning: Failed to wait for Ollama kill task: {}", e);
+                    warn!("⚠️ Warning: Failed to wait for Ollama kill task: {}", e);
                 }
             }
         } else {
-            println!("ℹ️  Ollama server was already running, not stopping it");
+            info!("ℹ️  Ollama server was already running, not stopping it");
         }
     }
 
+    /// Kill an Ollama server we started that is still running (used on shutdown)
+    pub fn kill_spawned_server() {
+        crate::utils::process::kill_child(&SPAWNED_SERVER, "Ollama server");
+    }
+
     /// Generate embeddings for the given texts
     pub async fn generate_embeddings(&self, texts: &[&str]) -> Result<Vec<Vec<f32>>> {
-        println!(
+        info!(
             "🔧 Initializing Ollama embedding function with model '{}' at {}:{}...",
             self.config.model, self.config.host, self.config.port
         );
 
         // Verify the model name matches what we expect (check for common issues)
         if self.config.model.contains(":latest") {
-            println!(
+            info!(
                 "ℹ️  Model name includes ':latest' tag: '{}'",
                 self.config.model
             );
@@ -274,7 +295,7 @@ impl OllamaManager {
         // Try to initialize the embedding function with detailed error handling
         let embedding_fn = match OllamaEmbeddingFunction::new(&endpoint, &self.config.model).await {
             Ok(fn_) => {
-                println!("✅ Ollama embedding function initialized successfully");
+                info!("✅ Ollama embedding function initialized successfully");
                 fn_
             }
             Err(e) => {
@@ -287,12 +308,12 @@ impl OllamaManager {
                     4. Check that Ollama is accessible at {}:{}",
                     self.config.model, endpoint, e, self.config.model, self.config.model, self.config.host, self.config.port
                 );
-                println!("{}", error_msg);
+                warn!("{}", error_msg);
                 return Err(anyhow::anyhow!(error_msg));
             }
         };
 
-        println!("📝 Generating embeddings for {} text(s)...", texts.len());
+        info!("📝 Generating embeddings for {} text(s)...", texts.len());
 
         let embeddings = match embedding_fn.embed_strs(texts).await {
             Ok(embeds) => embeds,
@@ -305,13 +326,13 @@ impl OllamaManager {
                     3. There's a network issue connecting to Ollama",
                     self.config.model, e, self.config.model
                 );
-                println!("{}", error_msg);
+                warn!("{}", error_msg);
                 return Err(anyhow::anyhow!(error_msg));
             }
         };
 
         let embedding_dim = embeddings.first().map(|e| e.len()).unwrap_or(0);
-        println!(
+        info!(
             "✅ Generated {} embeddings using model '{}' (dimension: {})",
             embeddings.len(),
             self.config.model,
@@ -320,14 +341,14 @@ impl OllamaManager {
 
         // Log expected dimensions for common models to help debug mismatches
         match embedding_dim {
-            384 => println!(
+            384 => info!(
                 "ℹ️  Dimension 384 typically indicates: chroma/all-minilm-l6-v2-f32 or similar"
             ),
-            768 => println!("ℹ️  Dimension 768 typically indicates: nomic-embed-text"),
+            768 => info!("ℹ️  Dimension 768 typically indicates: nomic-embed-text"),
             1024 => {
-                println!("ℹ️  Dimension 1024 typically indicates: mxbai-embed-large or similar")
+                info!("ℹ️  Dimension 1024 typically indicates: mxbai-embed-large or similar")
             }
-            _ => println!(
+            _ => info!(
                 "ℹ️  Dimension {} - verify this matches your model's expected output",
                 embedding_dim
             ),
@@ -338,16 +359,16 @@ impl OllamaManager {
 
     /// Complete workflow: start server, ensure model, generate embeddings, stop server
     pub async fn generate_embeddings_with_server(&self, texts: &[&str]) -> Result<Vec<Vec<f32>>> {
-        println!(
+        info!(
             "🚀 Starting embedding generation workflow for model '{}'",
             self.config.model
         );
 
-        let process = match self.start_server().await {
-            Ok(Some(p)) => Some(p),
-            Ok(None) => {
-                println!("ℹ️  Using existing Ollama server instance");
-                None
+        let started = match self.start_server().await {
+            Ok(true) => true,
+            Ok(false) => {
+                info!("ℹ️  Using existing Ollama server instance");
+                false
             }
             Err(e) => {
                 return Err(anyhow::anyhow!(
@@ -359,7 +380,7 @@ impl OllamaManager {
 
         // Ensure model is available (will pull if needed)
         if let Err(e) = self.ensure_model_available().await {
-            self.stop_server(process).await;
+            self.stop_server(started).await;
             return Err(anyhow::anyhow!(
                 "Failed to ensure model '{}' is available: {}",
                 self.config.model,
@@ -371,11 +392,11 @@ impl OllamaManager {
         let result = self.generate_embeddings(texts).await;
 
         // Always stop the server if we started it, even if embedding generation failed
-        self.stop_server(process).await;
+        self.stop_server(started).await;
 
         match &result {
-            Ok(_) => println!("✅ Embedding generation workflow completed successfully"),
-            Err(e) => println!("Embedding generation workflow failed: {}", e),
+            Ok(_) => info!("✅ Embedding generation workflow completed successfully"),
+            Err(e) => warn!("Embedding generation workflow failed: {}", e),
         }
 
         result
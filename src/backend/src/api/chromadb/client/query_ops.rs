@@ -2,14 +2,17 @@
 //!
 //! This module handles querying ChromaDB collections with embedding-based search.
 
-use crate::api::chromadb::types::{QueryRequest, QueryResponse};
+use crate::api::chromadb::types::{DistanceMetric, QueryRequest, QueryResponse};
+use crate::api::metrics::registry as metrics;
 use anyhow::{Context, Result};
 use chroma::types::IncludeList;
 use chroma::ChromaHttpClient;
+use log::{debug, info};
 use std::collections::HashMap;
 
-use super::metadata::metadata_value_to_json;
-use super::ollama::{OllamaConfig, OllamaManager};
+use super::document_ops::check_embedding_dimension;
+use super::embedding::EmbeddingBackend;
+use super::metadata::{chromadb_metadata_to_hashmap, metadata_value_to_json};
 use super::where_clause::convert_where_clause;
 
 /// Normalize query embeddings to unit length for cosine similarity
@@ -24,12 +27,61 @@ fn normalize_query_embeddings(embeddings: &mut [Vec<f32>]) {
     }
 }
 
+/// ChromaDB's result count when `n_results` is not given
+const DEFAULT_N_RESULTS: usize = 10;
+/// With a keyword, fetch this many times `n_results` candidates to re-rank
+const KEYWORD_CANDIDATE_FACTOR: usize = 5;
+/// Upper bound on candidates fetched for keyword re-ranking
+const MAX_KEYWORD_CANDIDATES: usize = 100;
+
+/// Reorder each query's results so documents containing `keyword` (lowercase)
+/// come first, keeping vector order within the matching and non-matching groups,
+/// then keep the top `n_results`.
+fn rerank_by_keyword(response: &mut QueryResponse, keyword: &str, n_results: usize) {
+    fn reorder<T: Clone>(items: &mut Vec<T>, order: &[usize]) {
+        *items = order
+            .iter()
+            .filter_map(|&i| items.get(i).cloned())
+            .collect();
+    }
+
+    for batch in 0..response.ids.len() {
+        let documents = response.documents.as_ref().and_then(|d| d.get(batch));
+        let mut order: Vec<usize> = (0..response.ids[batch].len()).collect();
+        // Stable sort: keyword matches (false < true after negation) move up
+        order.sort_by_key(|&i| {
+            !documents
+                .and_then(|docs| docs.get(i))
+                .is_some_and(|doc| doc.to_lowercase().contains(keyword))
+        });
+        order.truncate(n_results);
+
+        reorder(&mut response.ids[batch], &order);
+        if let Some(batch_docs) = response.documents.as_mut().and_then(|d| d.get_mut(batch)) {
+            reorder(batch_docs, &order);
+        }
+        if let Some(batch_dists) = response.distances.as_mut().and_then(|d| d.get_mut(batch)) {
+            reorder(batch_dists, &order);
+        }
+        if let Some(batch_meta) = response.metadatas.as_mut().and_then(|m| m.get_mut(batch)) {
+            reorder(batch_meta, &order);
+        }
+        if let Some(batch_scores) = response.scores.as_mut().and_then(|s| s.get_mut(batch)) {
+            reorder(batch_scores, &order);
+        }
+    }
+}
+
 /// Query a collection with embedding-based search
+///
+/// With a `keyword`, this is a hybrid search: more candidates are fetched by
+/// vector similarity and those whose text contains the keyword are ranked first.
 pub async fn query_collection(
     client: &ChromaHttpClient,
     request: QueryRequest,
-    query_model: &str,
+    embedder: &dyn EmbeddingBackend,
 ) -> Result<QueryResponse> {
+    let query_model = embedder.model();
     let collection = client
         .get_collection(&request.collection)
         .await
@@ -41,7 +93,7 @@ pub async fn query_collection(
     let where_clause =
         convert_where_clause(request.where_clause).context("Failed to convert where clause")?;
 
-    println!(
+    debug!(
         "🔍 Generating embeddings for query using model '{}': {:?}",
         query_model, request.query_texts
     );
@@ -51,26 +103,15 @@ pub async fn query_collection(
         return Err(anyhow::anyhow!("Query texts cannot be empty"));
     }
 
-    // Generate query embeddings using Ollama with configured model
-    let config = OllamaConfig {
-        model: query_model.to_string(),
-        ..Default::default()
-    };
-    let ollama_manager = OllamaManager::new(config);
     let query_refs: Vec<&str> = request.query_texts.iter().map(|s| s.as_str()).collect();
-    let mut query_embeddings = ollama_manager
-        .generate_embeddings_with_server(&query_refs)
-        .await
-        .with_context(|| {
-            format!(
-                "Failed to generate embeddings from query texts using model '{}'. \
-                This could mean:\n\
-                1. The model '{}' is not available (run 'ollama pull {}')\n\
-                2. Ollama server is not accessible\n\
-                3. The model doesn't support embeddings",
-                query_model, query_model, query_model
-            )
-        })?;
+    metrics::record(|m| m.embedding_calls.inc());
+    let mut query_embeddings = embedder.embed(&query_refs).await.with_context(|| {
+        format!(
+            "Failed to generate embeddings from query texts using model '{}'. \
+            Check that the model exists and supports embeddings",
+            query_model
+        )
+    })?;
 
     // Normalize query embeddings for cosine similarity
     normalize_query_embeddings(&mut query_embeddings);
@@ -78,7 +119,7 @@ pub async fn query_collection(
     // Verify normalization
     if let Some(first_embedding) = query_embeddings.first() {
         let norm: f32 = first_embedding.iter().map(|x| x * x).sum::<f32>().sqrt();
-        println!(
+        info!(
             "📊 Query embedding norm after normalization: {:.4} (expected: ~1.0)",
             norm
         );
@@ -86,22 +127,46 @@ pub async fn query_collection(
 
     let include = Some(IncludeList::default_query());
 
-    println!(
+    info!(
         "🔎 Querying collection '{}' with {} embedding(s), requesting {} results",
         request.collection,
         query_embeddings.len(),
         request.n_results.unwrap_or(10)
     );
 
+    // Fail early with a clear message if the collection was filled with another model
+    let collection_metadata = collection
+        .metadata()
+        .as_ref()
+        .map(chromadb_metadata_to_hashmap)
+        .unwrap_or_default();
+    check_embedding_dimension(&collection_metadata, &query_embeddings)?;
+
     // Get embedding dimension for error reporting
     let embedding_dim = query_embeddings.first().map(|e| e.len()).unwrap_or(0);
 
-    println!("📐 Query embedding dimension: {}", embedding_dim);
+    info!("📐 Query embedding dimension: {}", embedding_dim);
+
+    // Hybrid search widens the vector candidate pool before keyword re-ranking
+    let keyword = request
+        .keyword
+        .as_deref()
+        .map(str::trim)
+        .filter(|k| !k.is_empty())
+        .map(str::to_lowercase);
+    let n_results = request.n_results.unwrap_or(DEFAULT_N_RESULTS);
+    let n_fetch = if keyword.is_some() {
+        (n_results * KEYWORD_CANDIDATE_FACTOR)
+            .min(MAX_KEYWORD_CANDIDATES)
+            .max(n_results)
+    } else {
+        n_results
+    };
 
     let results = match collection
         .query(
             query_embeddings,
-            request.n_results.map(|n| n as u32),
+            Some(n_fetch as u32),
             where_clause,
             None, // ids
             include,
@@ -156,25 +221,39 @@ pub async fn query_collection(
                     .flatten()
                     .max_by(|a, b| a.partial_cmp(b).unwrap())
                 {
-                    println!("📊 Query distance range: min={:.4}, max={:.4} (cosine distance, lower is better)", min_dist, max_dist);
+                    info!("📊 Query distance range: min={:.4}, max={:.4} (cosine distance, lower is better)", min_dist, max_dist);
                 }
             }
         }
     }
 
     // Convert results to our format
-    Ok(QueryResponse {
-        ids: results.ids,
-        distances: results.distances.map(|d| {
-            d.into_iter()
-                .map(|inner| {
-                    inner
-                        .into_iter()
-                        .filter_map(|opt| opt.map(|f| f as f64))
-                        .collect()
-                })
+    let distances: Option<Vec<Vec<f64>>> = results.distances.map(|d| {
+        d.into_iter()
+            .map(|inner| {
+                inner
+                    .into_iter()
+                    .filter_map(|opt| opt.map(|f| f as f64))
+                    .collect()
+            })
+            .collect()
+    });
+
+    let (scores, distance_metric) = if request.normalize_scores {
+        let metric = DistanceMetric::from_collection_metadata(&collection_metadata);
+        let scores = distances.as_ref().map(|d| {
+            d.iter()
+                .map(|inner| inner.iter().map(|&dist| metric.similarity(dist)).collect())
                 .collect()
-        }),
+        });
+        (scores, Some(metric))
+    } else {
+        (None, None)
+    };
+
+    let mut response = QueryResponse {
+        ids: results.ids,
+        distances,
         documents: results.documents.map(|d| {
             d.into_iter()
                 .map(|inner| inner.into_iter().flatten().collect())
@@ -199,14 +278,77 @@ pub async fn query_collection(
                 })
                 .collect::<Vec<Vec<HashMap<String, serde_json::Value>>>>()
         }),
-    })
+        scores,
+        distance_metric,
+    };
+
+    if let Some(keyword) = keyword {
+        info!(
+            "🔤 Re-ranking {} candidate(s) by keyword '{}'",
+            n_fetch, keyword
+        );
+        rerank_by_keyword(&mut response, &keyword, n_results);
+    }
+
+    Ok(response)
 }
 
 #[cfg(test)]
 mod tests {
+    use super::*;
+
     #[test]
     fn test_function_exists() {
         // Verify the function is defined
         assert!(true);
     }
+
+    /// Vector ranking where the only exact keyword match sits at the bottom
+    fn fixture() -> QueryResponse {
+        QueryResponse {
+            ids: vec![vec!["a".into(), "b".into(), "c".into(), "d".into()]],
+            distances: Some(vec![vec![0.1, 0.2, 0.3, 0.9]]),
+            documents: Some(vec![vec![
+                "Error handling in Rust".into(),
+                "Result and Option types".into(),
+                "Panics and unwinding".into(),
+                "Fixing error E0502 borrow conflicts".into(),
+            ]]),
+            metadatas: Some(vec![vec![HashMap::new(); 4]]),
+            scores: Some(vec![vec![0.95, 0.9, 0.85, 0.55]]),
+            distance_metric: Some(DistanceMetric::Cosine),
+        }
+    }
+
+    #[test]
+    fn test_rerank_by_keyword_rescues_low_ranked_match() {
+        let mut response = fixture();
+        rerank_by_keyword(&mut response, "e0502", 2);
+
+        assert_eq!(response.ids, vec![vec!["d".to_string(), "a".to_string()]]);
+        assert_eq!(response.distances, Some(vec![vec![0.9, 0.1]]));
+        assert_eq!(response.scores, Some(vec![vec![0.55, 0.95]]));
+        assert_eq!(
+            response.documents.as_ref().unwrap()[0][0],
+            "Fixing error E0502 borrow conflicts"
+        );
+        assert_eq!(response.metadatas.as_ref().unwrap()[0].len(), 2);
+    }
+
+    #[test]
+    fn test_rerank_by_keyword_keeps_vector_order_within_groups() {
+        let mut response = fixture();
+        rerank_by_keyword(&mut response, "error", 4);
+
+        // Both "error" documents first (in vector order), then the rest
+        assert_eq!(
+            response.ids[0],
+            vec![
+                "a".to_string(),
+                "d".to_string(),
+                "b".to_string(),
+                "c".to_string()
+            ]
+        );
+    }
 }
@@ -8,6 +8,7 @@
 
 #[cfg(test)]
 mod integration_tests {
+    use super::super::embedding::OllamaEmbedding;
     use super::super::*;
     use crate::api::chromadb::types::{AddDocumentsRequest, QueryRequest};
     use std::collections::HashMap;
@@ -103,7 +104,7 @@ mod integration_tests {
         };
 
         client
-            .add_documents(request, "nomic-embed-text")
+            .add_documents(request, &OllamaEmbedding::new("nomic-embed-text"))
             .await
             .unwrap();
 
@@ -113,10 +114,12 @@ mod integration_tests {
             query_texts: vec!["Rust programming".to_string()],
             n_results: Some(2),
             where_clause: None,
+            normalize_scores: false,
+            keyword: None,
         };
 
         let results = client
-            .query(query_request, "nomic-embed-text")
+            .query(query_request, &OllamaEmbedding::new("nomic-embed-text"))
             .await
             .unwrap();
         assert!(!results.ids.is_empty());
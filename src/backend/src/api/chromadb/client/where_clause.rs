@@ -16,6 +16,7 @@
 
 use anyhow::Result;
 use chroma::types::Where;
+use log::warn;
 use serde_json::Value;
 use std::collections::HashMap;
 
@@ -84,14 +85,14 @@ pub fn convert_where_clause(where_clause: Option<HashMap<String, Value>>) -> Res
         // This would require either:
         // 1. Serializing to JSON and deserializing to Where (if it implements Deserialize)
         // 2. Manually constructing the Where type based on the chroma crate's API
-        println!("⚠️ Complex where clause with operators detected. Where clause filtering is not yet fully implemented.");
+        warn!("⚠️ Complex where clause with operators detected. Where clause filtering is not yet fully implemented.");
         return Ok(None);
     }
 
     // For simple equality cases, we can attempt conversion
     // However, the chroma crate's Where type structure is not easily constructible
     // without knowing its internal structure. For now, we return None and log.
-    println!("⚠️ Where clause conversion is not fully implemented. Simple equality filters may work in future versions.");
+    warn!("⚠️ Where clause conversion is not fully implemented. Simple equality filters may work in future versions.");
     Ok(None)
 }
 
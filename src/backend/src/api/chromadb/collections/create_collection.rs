@@ -1,6 +1,8 @@
 use crate::api::chromadb::client::ChromaDBClient;
+use crate::api::chromadb::schema::{MetadataSchema, SCHEMA_METADATA_KEY};
 use crate::api::chromadb::types::{ChromaDBResponse, Collection, DistanceMetric};
 use actix_web::{post, web, HttpResponse, Result as ActixResult};
+use log::{error, info, warn};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
@@ -10,6 +12,9 @@ pub struct CreateCollectionRequest {
     pub metadata: Option<HashMap<String, String>>,
     #[serde(default)]
     pub distance_metric: Option<DistanceMetric>,
+    /// Required keys and value types enforced when documents are added
+    #[serde(default)]
+    pub metadata_schema: Option<MetadataSchema>,
 }
 
 #[post("/api/chromadb/collections")]
@@ -17,7 +22,7 @@ pub async fn create_collection(
     req: web::Json<CreateCollectionRequest>,
     chroma_address: web::Data<String>,
 ) -> ActixResult<HttpResponse> {
-    println!(
+    info!(
         "📝 Creating collection request: name={}, metadata={:?}, distance_metric={:?}",
         req.name, req.metadata, req.distance_metric
     );
@@ -78,16 +83,27 @@ pub async fn create_collection(
 
     // If name was sanitized, log it
     if sanitized_name != collection_name {
-        println!(
+        warn!(
             "⚠️ Collection name sanitized: '{}' -> '{}'",
             collection_name, sanitized_name
         );
     }
 
+    if let Some(Err(e)) = req.metadata_schema.as_ref().map(MetadataSchema::check) {
+        return Ok(
+            HttpResponse::BadRequest().json(ChromaDBResponse::<Collection> {
+                success: false,
+                data: None,
+                error: Some(e),
+                message: None,
+            }),
+        );
+    }
+
     let client = match ChromaDBClient::new(chroma_address.as_str()) {
         Ok(c) => c,
         Err(e) => {
-            println!("Failed to create ChromaDB client: {}", e);
+            warn!("Failed to create ChromaDB client: {}", e);
             return Ok(
                 HttpResponse::ServiceUnavailable().json(ChromaDBResponse::<Collection> {
                     success: false,
@@ -99,7 +115,7 @@ pub async fn create_collection(
         }
     };
 
-    println!(
+    info!(
         "✅ ChromaDB client created, attempting to create collection: {} (sanitized: {})",
         collection_name, sanitized_name
     );
@@ -114,7 +130,14 @@ pub async fn create_collection(
             DistanceMetric::Ip => "ip",
         };
         metadata.insert("hnsw:space".to_string(), metric_str.to_string());
-        println!("🔧 Setting distance metric to: {}", metric_str);
+        info!("🔧 Setting distance metric to: {}", metric_str);
+    }
+    if let Some(schema) = &req.metadata_schema {
+        metadata.insert(SCHEMA_METADATA_KEY.to_string(), schema.to_metadata_value());
+        info!(
+            "🔧 Setting metadata schema with {} field(s)",
+            schema.fields.len()
+        );
     }
 
     match client
@@ -122,7 +145,7 @@ pub async fn create_collection(
         .await
     {
         Ok(collection) => {
-            println!("✅ Collection created successfully: {}", collection.name);
+            info!("✅ Collection created successfully: {}", collection.name);
             Ok(HttpResponse::Ok().json(ChromaDBResponse {
                 success: true,
                 data: Some(collection),
@@ -131,8 +154,8 @@ pub async fn create_collection(
             }))
         }
         Err(e) => {
-            println!("Failed to create collection '{}': {}", req.name, e);
-            println!("   Error details: {:?}", e);
+            warn!("Failed to create collection '{}': {}", req.name, e);
+            error!("   Error details: {:?}", e);
             Ok(
                 HttpResponse::InternalServerError().json(ChromaDBResponse::<Collection> {
                     success: false,
@@ -166,6 +189,7 @@ mod tests {
                 name: "   ".to_string(), // Empty after trim
                 metadata: None,
                 distance_metric: None,
+                metadata_schema: None,
             })
             .to_request();
 
@@ -190,6 +214,7 @@ mod tests {
                 name: long_name,
                 metadata: None,
                 distance_metric: None,
+                metadata_schema: None,
             })
             .to_request();
 
@@ -213,6 +238,7 @@ mod tests {
                 name: "test collection name".to_string(),
                 metadata: None,
                 distance_metric: None,
+                metadata_schema: None,
             })
             .to_request();
 
@@ -240,6 +266,7 @@ mod tests {
                 name: "test_collection".to_string(),
                 metadata: Some(metadata),
                 distance_metric: None,
+                metadata_schema: None,
             })
             .to_request();
 
@@ -247,4 +274,31 @@ mod tests {
         // Should not be 400 (bad request)
         assert_ne!(resp.status().as_u16(), 400);
     }
+
+    #[actix_web::test]
+    async fn test_create_collection_rejects_empty_schema_field() {
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new("http://localhost:8000".to_string()))
+                .service(create_collection),
+        )
+        .await;
+
+        let schema: MetadataSchema = serde_json::from_value(serde_json::json!({
+            "fields": { " ": { "type": "string", "required": true } }
+        }))
+        .unwrap();
+        let req = test::TestRequest::post()
+            .uri("/api/chromadb/collections")
+            .set_json(&CreateCollectionRequest {
+                name: "test_collection".to_string(),
+                metadata: None,
+                distance_metric: None,
+                metadata_schema: Some(schema),
+            })
+            .to_request();
+
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status().as_u16(), 400);
+    }
 }
@@ -1,6 +1,7 @@
 use crate::api::chromadb::client::ChromaDBClient;
 use crate::api::chromadb::types::ChromaDBResponse;
 use actix_web::{delete, web, HttpResponse, Result as ActixResult};
+use log::warn;
 
 #[delete("/api/chromadb/collections/{name}")]
 pub async fn delete_collection(
@@ -11,7 +12,7 @@ pub async fn delete_collection(
     let client = match ChromaDBClient::new(chroma_address.as_str()) {
         Ok(c) => c,
         Err(e) => {
-            println!("Failed to create ChromaDB client: {}", e);
+            warn!("Failed to create ChromaDB client: {}", e);
             return Ok(
                 HttpResponse::ServiceUnavailable().json(ChromaDBResponse::<()> {
                     success: false,
@@ -31,7 +32,7 @@ pub async fn delete_collection(
             message: Some(format!("Collection {} deleted successfully", name)),
         })),
         Err(e) => {
-            println!("Failed to delete collection: {}", e);
+            warn!("Failed to delete collection: {}", e);
             Ok(
                 HttpResponse::InternalServerError().json(ChromaDBResponse::<()> {
                     success: false,
@@ -1,6 +1,7 @@
 use crate::api::chromadb::client::ChromaDBClient;
 use crate::api::chromadb::types::{ChromaDBResponse, Collection};
 use actix_web::{get, web, HttpResponse, Result as ActixResult};
+use log::warn;
 
 #[get("/api/chromadb/collections/{name}")]
 pub async fn get_collection(
@@ -11,7 +12,7 @@ pub async fn get_collection(
     let client = match ChromaDBClient::new(chroma_address.as_str()) {
         Ok(c) => c,
         Err(e) => {
-            println!("Failed to create ChromaDB client: {}", e);
+            warn!("Failed to create ChromaDB client: {}", e);
             return Ok(
                 HttpResponse::ServiceUnavailable().json(ChromaDBResponse::<Collection> {
                     success: false,
@@ -31,7 +32,7 @@ pub async fn get_collection(
             message: None,
         })),
         Err(e) => {
-            println!("Failed to get collection: {}", e);
+            warn!("Failed to get collection: {}", e);
             Ok(
                 HttpResponse::NotFound().json(ChromaDBResponse::<Collection> {
                     success: false,
@@ -1,13 +1,14 @@
 use crate::api::chromadb::client::ChromaDBClient;
 use crate::api::chromadb::types::ChromaDBResponse;
 use actix_web::{get, web, HttpResponse, Result as ActixResult};
+use log::warn;
 
 #[get("/api/chromadb/collections")]
 pub async fn get_collections(chroma_address: web::Data<String>) -> ActixResult<HttpResponse> {
     let client = match ChromaDBClient::new(chroma_address.as_str()) {
         Ok(c) => c,
         Err(e) => {
-            println!("Failed to create ChromaDB client: {}", e);
+            warn!("Failed to create ChromaDB client: {}", e);
             return Ok(
                 HttpResponse::ServiceUnavailable().json(ChromaDBResponse::<Vec<()>> {
                     success: false,
@@ -27,7 +28,7 @@ pub async fn get_collections(chroma_address: web::Data<String>) -> ActixResult<H
             message: None,
         })),
         Err(e) => {
-            println!("Failed to list collections: {}", e);
+            warn!("Failed to list collections: {}", e);
             Ok(
                 HttpResponse::InternalServerError().json(ChromaDBResponse::<Vec<()>> {
                     success: false,
@@ -1,20 +1,22 @@
 use crate::api::chromadb::config::types::{ChromaDBConfig, ConfigResponse};
 use actix_web::{get, web, HttpResponse, Result as ActixResult};
+use log::info;
 use std::sync::{Arc, Mutex};
 
 #[get("/api/chromadb/config")]
 pub async fn get_chromadb_config(
     config: web::Data<Arc<Mutex<ChromaDBConfig>>>,
 ) -> ActixResult<HttpResponse> {
-    println!("📋 Fetching ChromaDB configuration...");
+    info!("📋 Fetching ChromaDB configuration...");
 
     let config_guard = config.lock().unwrap();
     let config_response = ConfigResponse {
         embedding_model: config_guard.embedding_model.clone(),
         query_model: config_guard.query_model.clone(),
+        embedding_backend: config_guard.embedding_backend,
     };
 
-    println!(
+    info!(
         "✅ Current config - Embedding: {}, Query: {}",
         config_response.embedding_model, config_response.query_model
     );
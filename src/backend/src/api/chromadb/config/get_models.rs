@@ -1,41 +1,55 @@
 use crate::api::chromadb::config::types::{ModelInfo, ModelsResponse};
 use actix_web::{get, HttpResponse, Result as ActixResult};
+use log::{info, warn};
 use std::process::Command;
 
-#[get("/api/chromadb/models")]
-pub async fn get_ollama_models() -> ActixResult<HttpResponse> {
-    println!("📋 Fetching Ollama models using 'ollama list' command...");
-
-    // Execute ollama list command
+/// Run `ollama list` and parse the installed models
+pub(crate) async fn list_ollama_models() -> Result<Vec<ModelInfo>, String> {
     let output =
         match tokio::task::spawn_blocking(|| Command::new("ollama").arg("list").output()).await {
             Ok(Ok(output)) => output,
             Ok(Err(e)) => {
-                println!("Failed to execute ollama list: {}", e);
-                return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
-                    "error": format!("Failed to execute ollama list: {}", e)
-                })));
+                warn!("Failed to execute ollama list: {}", e);
+                return Err(format!("Failed to execute ollama list: {}", e));
             }
             Err(e) => {
-                println!("Failed to spawn ollama list task: {}", e);
-                return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
-                    "error": format!("Failed to spawn task: {}", e)
-                })));
+                warn!("Failed to spawn ollama list task: {}", e);
+                return Err(format!("Failed to spawn task: {}", e));
             }
         };
 
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
-        println!("ollama list command failed: {}", stderr);
-        return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
-            "error": format!("ollama list command failed: {}", stderr)
-        })));
+        warn!("ollama list command failed: {}", stderr);
+        return Err(format!("ollama list command failed: {}", stderr));
     }
 
     let stdout = String::from_utf8_lossy(&output.stdout);
-    let models = parse_ollama_list_output(&stdout);
+    Ok(parse_ollama_list_output(&stdout))
+}
+
+/// Whether `model` is in the list, treating a missing tag as `:latest`
+pub(crate) fn is_model_installed(models: &[ModelInfo], model: &str) -> bool {
+    let model = model.trim();
+    models
+        .iter()
+        .any(|m| m.name == model || (!model.contains(':') && m.name == format!("{}:latest", model)))
+}
+
+#[get("/api/chromadb/models")]
+pub async fn get_ollama_models() -> ActixResult<HttpResponse> {
+    info!("📋 Fetching Ollama models using 'ollama list' command...");
+
+    let models = match list_ollama_models().await {
+        Ok(models) => models,
+        Err(e) => {
+            return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": e
+            })));
+        }
+    };
 
-    println!("✅ Found {} Ollama models", models.len());
+    info!("✅ Found {} Ollama models", models.len());
 
     Ok(HttpResponse::Ok().json(ModelsResponse { models }))
 }
@@ -108,4 +122,15 @@ mod tests {
         assert_eq!(models[0].size, Some("4.7 GB".to_string()));
         assert_eq!(models[1].name, "mistral:latest");
     }
+
+    #[test]
+    fn test_is_model_installed_defaults_to_latest_tag() {
+        let output = "NAME                    ID              SIZE    MODIFIED\nnomic-embed-text:latest abc123def456    274 MB  2 hours ago";
+        let models = parse_ollama_list_output(output);
+
+        assert!(is_model_installed(&models, "nomic-embed-text"));
+        assert!(is_model_installed(&models, "nomic-embed-text:latest"));
+        assert!(!is_model_installed(&models, "nomic-embed-text:v1.5"));
+        assert!(!is_model_installed(&models, "mxbai-embed-large"));
+    }
 }
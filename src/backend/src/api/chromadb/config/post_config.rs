@@ -1,6 +1,9 @@
+use crate::api::chromadb::client::EmbeddingBackendKind;
+use crate::api::chromadb::config::get_models::{is_model_installed, list_ollama_models};
 use crate::api::chromadb::config::types::{ChromaDBConfig, ConfigRequest, ConfigUpdateResponse};
 use crate::api::default_configs::{ChromaDBDefaultConfig, DefaultConfigsStorage};
 use actix_web::{post, web, HttpResponse, Result as ActixResult};
+use log::{info, warn};
 use std::sync::{Arc, Mutex};
 
 #[post("/api/chromadb/config")]
@@ -9,7 +12,7 @@ pub async fn post_chromadb_config(
     config: web::Data<Arc<Mutex<ChromaDBConfig>>>,
     default_configs: web::Data<Arc<DefaultConfigsStorage>>,
 ) -> ActixResult<HttpResponse> {
-    println!("💾 Updating ChromaDB configuration...");
+    info!("💾 Updating ChromaDB configuration...");
 
     // Validate that embedding_model is not empty
     if request.embedding_model.trim().is_empty() {
@@ -19,20 +22,48 @@ pub async fn post_chromadb_config(
         }));
     }
 
-    let embedding_model = request.embedding_model.clone();
+    let embedding_model = request.embedding_model.trim().to_string();
     let query_model = request
         .query_model
-        .clone()
+        .as_deref()
+        .map(str::trim)
+        .filter(|m| !m.is_empty())
+        .map(str::to_string)
         .unwrap_or_else(|| embedding_model.clone());
 
+    let embedding_backend = request
+        .embedding_backend
+        .unwrap_or_else(|| config.lock().unwrap().embedding_backend);
+
+    if request.verify_model && embedding_backend == EmbeddingBackendKind::Ollama {
+        let models = match list_ollama_models().await {
+            Ok(models) => models,
+            Err(e) => {
+                return Ok(HttpResponse::BadGateway().json(ConfigUpdateResponse {
+                    success: false,
+                    message: format!("Could not verify model with Ollama: {}", e),
+                }));
+            }
+        };
+        for model in [&embedding_model, &query_model] {
+            if !is_model_installed(&models, model) {
+                return Ok(HttpResponse::BadRequest().json(ConfigUpdateResponse {
+                    success: false,
+                    message: format!("Model '{}' is not installed in Ollama", model),
+                }));
+            }
+        }
+    }
+
     // Update config (drop lock before await)
     {
         let mut config_guard = config.lock().unwrap();
         config_guard.embedding_model = embedding_model.clone();
         config_guard.query_model = query_model.clone();
-        println!(
-            "✅ Updated config - Embedding: {}, Query: {}",
-            config_guard.embedding_model, config_guard.query_model
+        config_guard.embedding_backend = embedding_backend;
+        info!(
+            "✅ Updated config - Embedding: {}, Query: {}, Backend: {:?}",
+            config_guard.embedding_model, config_guard.query_model, config_guard.embedding_backend
         );
     } // Drop lock here
 
@@ -43,9 +74,9 @@ pub async fn post_chromadb_config(
         })
         .await
     {
-        println!("⚠️  Failed to save chromadb default config: {}", e);
+        warn!("⚠️  Failed to save chromadb default config: {}", e);
     } else {
-        println!("✅ Saved chromadb default config");
+        info!("✅ Saved chromadb default config");
     }
 
     Ok(HttpResponse::Ok().json(ConfigUpdateResponse {
@@ -1,9 +1,13 @@
+use crate::api::chromadb::client::EmbeddingBackendKind;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ChromaDBConfig {
     pub embedding_model: String,
     pub query_model: String,
+    /// Service that generates embeddings for uploads and queries
+    #[serde(default = "EmbeddingBackendKind::from_env")]
+    pub embedding_backend: EmbeddingBackendKind,
 }
 
 impl Default for ChromaDBConfig {
@@ -11,6 +15,7 @@ impl Default for ChromaDBConfig {
         Self {
             embedding_model: "nomic-embed-text".to_string(),
             query_model: "nomic-embed-text".to_string(),
+            embedding_backend: EmbeddingBackendKind::from_env(),
         }
     }
 }
@@ -31,12 +36,19 @@ pub struct ModelsResponse {
 pub struct ConfigResponse {
     pub embedding_model: String,
     pub query_model: String,
+    pub embedding_backend: EmbeddingBackendKind,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ConfigRequest {
     pub embedding_model: String,
     pub query_model: Option<String>,
+    /// Switch embedding backend; unchanged when omitted
+    #[serde(default)]
+    pub embedding_backend: Option<EmbeddingBackendKind>,
+    /// Check the models are installed in Ollama before saving (Ollama backend only)
+    #[serde(default)]
+    pub verify_model: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -0,0 +1,213 @@
+use crate::api::error::ApiError;
+use std::fmt;
+
+/// Environment variable overriding the per-file upload cap, in bytes
+pub const UPLOAD_MAX_FILE_BYTES_ENV: &str = "UPLOAD_MAX_FILE_BYTES";
+/// Environment variable overriding the whole-request upload cap, in bytes
+pub const UPLOAD_MAX_TOTAL_BYTES_ENV: &str = "UPLOAD_MAX_TOTAL_BYTES";
+
+/// Largest single file accepted when `UPLOAD_MAX_FILE_BYTES` isn't set (20 MiB)
+pub const DEFAULT_MAX_FILE_BYTES: usize = 20 * 1024 * 1024;
+/// Largest upload request accepted when `UPLOAD_MAX_TOTAL_BYTES` isn't set (100 MiB)
+pub const DEFAULT_MAX_TOTAL_BYTES: usize = 100 * 1024 * 1024;
+
+/// Byte caps for `POST /api/chromadb/documents/upload`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UploadLimits {
+    pub max_file_bytes: usize,
+    pub max_total_bytes: usize,
+}
+
+impl Default for UploadLimits {
+    fn default() -> Self {
+        Self {
+            max_file_bytes: DEFAULT_MAX_FILE_BYTES,
+            max_total_bytes: DEFAULT_MAX_TOTAL_BYTES,
+        }
+    }
+}
+
+impl UploadLimits {
+    /// Limits from `UPLOAD_MAX_FILE_BYTES` / `UPLOAD_MAX_TOTAL_BYTES`, falling
+    /// back to the defaults for unset, invalid or zero values
+    pub fn from_env() -> Self {
+        Self::from_values(
+            std::env::var(UPLOAD_MAX_FILE_BYTES_ENV).ok(),
+            std::env::var(UPLOAD_MAX_TOTAL_BYTES_ENV).ok(),
+        )
+    }
+
+    fn from_values(max_file: Option<String>, max_total: Option<String>) -> Self {
+        let parse = |value: Option<String>, default: usize| {
+            value
+                .and_then(|v| v.trim().parse::<usize>().ok())
+                .filter(|bytes| *bytes > 0)
+                .unwrap_or(default)
+        };
+        let max_total_bytes = parse(max_total, DEFAULT_MAX_TOTAL_BYTES);
+        Self {
+            // A single file can never be larger than the whole request
+            max_file_bytes: parse(max_file, DEFAULT_MAX_FILE_BYTES).min(max_total_bytes),
+            max_total_bytes,
+        }
+    }
+}
+
+/// An upload went over one of the [`UploadLimits`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UploadLimitError {
+    FileTooLarge { filename: String, limit: usize },
+    RequestTooLarge { limit: usize },
+}
+
+impl fmt::Display for UploadLimitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::FileTooLarge { filename, limit } => write!(
+                f,
+                "File '{}' exceeds the maximum upload size of {}",
+                filename,
+                format_bytes(*limit)
+            ),
+            Self::RequestTooLarge { limit } => write!(
+                f,
+                "Upload exceeds the maximum request size of {}",
+                format_bytes(*limit)
+            ),
+        }
+    }
+}
+
+impl std::error::Error for UploadLimitError {}
+
+impl From<UploadLimitError> for ApiError {
+    fn from(err: UploadLimitError) -> Self {
+        ApiError::PayloadTooLarge(err.to_string())
+    }
+}
+
+fn format_bytes(bytes: usize) -> String {
+    const MIB: usize = 1024 * 1024;
+    if bytes >= MIB && bytes.is_multiple_of(MIB) {
+        format!("{} MiB", bytes / MIB)
+    } else {
+        format!("{} bytes", bytes)
+    }
+}
+
+/// Running byte count for one upload request, checked as each chunk arrives
+/// so an oversized upload is rejected before it is fully buffered
+pub struct UploadBudget {
+    limits: UploadLimits,
+    total: usize,
+}
+
+impl UploadBudget {
+    pub fn new(limits: UploadLimits) -> Self {
+        Self { limits, total: 0 }
+    }
+
+    /// Reject a request whose declared `Content-Length` is already over the cap
+    pub fn check_content_length(&self, content_length: usize) -> Result<(), UploadLimitError> {
+        if content_length > self.limits.max_total_bytes {
+            return Err(UploadLimitError::RequestTooLarge {
+                limit: self.limits.max_total_bytes,
+            });
+        }
+        Ok(())
+    }
+
+    /// Count a chunk of a form field. `file` is the name of the file being
+    /// read and `file_len` the bytes of it buffered so far (`None` for plain fields).
+    pub fn consume(
+        &mut self,
+        chunk_len: usize,
+        file: Option<(&str, usize)>,
+    ) -> Result<(), UploadLimitError> {
+        self.total += chunk_len;
+        if let Some((filename, file_len)) = file {
+            if file_len + chunk_len > self.limits.max_file_bytes {
+                return Err(UploadLimitError::FileTooLarge {
+                    filename: filename.to_string(),
+                    limit: self.limits.max_file_bytes,
+                });
+            }
+        }
+        if self.total > self.limits.max_total_bytes {
+            return Err(UploadLimitError::RequestTooLarge {
+                limit: self.limits.max_total_bytes,
+            });
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_upload_limits_from_values() {
+        assert_eq!(
+            UploadLimits::from_values(None, None),
+            UploadLimits::default()
+        );
+        assert_eq!(
+            UploadLimits::from_values(Some("1024".to_string()), Some("4096".to_string())),
+            UploadLimits {
+                max_file_bytes: 1024,
+                max_total_bytes: 4096
+            }
+        );
+        // Invalid values fall back, and the file cap is clamped to the total
+        assert_eq!(
+            UploadLimits::from_values(Some("0".to_string()), Some("2048".to_string())),
+            UploadLimits {
+                max_file_bytes: 2048,
+                max_total_bytes: 2048
+            }
+        );
+    }
+
+    #[test]
+    fn test_upload_budget_enforces_file_and_total_caps() {
+        let limits = UploadLimits {
+            max_file_bytes: 10,
+            max_total_bytes: 25,
+        };
+
+        let mut budget = UploadBudget::new(limits);
+        assert!(budget.consume(6, Some(("a.txt", 0))).is_ok());
+        assert_eq!(
+            budget.consume(6, Some(("a.txt", 6))),
+            Err(UploadLimitError::FileTooLarge {
+                filename: "a.txt".to_string(),
+                limit: 10
+            })
+        );
+
+        let mut budget = UploadBudget::new(limits);
+        assert!(budget.consume(5, None).is_ok());
+        assert!(budget.consume(10, Some(("a.txt", 0))).is_ok());
+        assert!(budget.consume(10, Some(("b.txt", 0))).is_ok());
+        assert_eq!(
+            budget.consume(1, Some(("c.txt", 0))),
+            Err(UploadLimitError::RequestTooLarge { limit: 25 })
+        );
+
+        assert!(budget.check_content_length(25).is_ok());
+        assert!(budget.check_content_length(26).is_err());
+    }
+
+    #[test]
+    fn test_upload_limit_error_message() {
+        let err = UploadLimitError::FileTooLarge {
+            filename: "big.pdf".to_string(),
+            limit: DEFAULT_MAX_FILE_BYTES,
+        };
+        assert_eq!(
+            err.to_string(),
+            "File 'big.pdf' exceeds the maximum upload size of 20 MiB"
+        );
+    }
+}
@@ -1 +1,2 @@
+pub mod limits;
 pub mod upload;
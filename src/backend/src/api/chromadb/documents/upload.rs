@@ -1,9 +1,13 @@
-use crate::api::chromadb::client::ChromaDBClient;
+use crate::api::chromadb::client::{embedding_backend, ChromaDBClient};
 use crate::api::chromadb::config::types::ChromaDBConfig;
+use crate::api::chromadb::documents::limits::{UploadBudget, UploadLimitError, UploadLimits};
+use crate::api::chromadb::schema::{MetadataSchemaError, SchemaViolation};
 use crate::api::chromadb::types::{AddDocumentsRequest, ChromaDBResponse};
-use actix_multipart::Multipart;
-use actix_web::{post, web, HttpResponse, Result as ActixResult};
+use crate::api::error::ApiError;
+use actix_multipart::{Field, Multipart};
+use actix_web::{post, web, HttpRequest, HttpResponse};
 use futures_util::TryStreamExt;
+use log::{error, info, warn};
 use std::sync::{Arc, Mutex, Once};
 use tokenizers::tokenizer::{Result as TokenizerResult, Tokenizer};
 use uuid::Uuid;
@@ -20,11 +24,11 @@ fn get_tokenizer() -> TokenizerResult<&'static Tokenizer> {
             // Try to load GPT-2 tokenizer (compatible with nomic-embed-text style BPE)
             match Tokenizer::from_pretrained("gpt2", None) {
                 Ok(tok) => {
-                    println!("✅ Loaded GPT-2 tokenizer for token-based chunking");
+                    info!("✅ Loaded GPT-2 tokenizer for token-based chunking");
                     TOKENIZER = Some(tok);
                 }
                 Err(e) => {
-                    println!(
+                    warn!(
                         "⚠️ Failed to load GPT-2 tokenizer: {:?}. Will retry on next call.",
                         e
                     );
@@ -49,27 +53,73 @@ fn get_tokenizer() -> TokenizerResult<&'static Tokenizer> {
     }
 }
 
+/// Parse the optional `metadata` form field: a JSON object of scalar values that
+/// is copied onto every chunk (values are stored as strings)
+fn parse_upload_metadata(
+    bytes: &[u8],
+) -> Result<std::collections::HashMap<String, String>, String> {
+    let object: serde_json::Map<String, serde_json::Value> = serde_json::from_slice(bytes)
+        .map_err(|e| format!("metadata must be a JSON object: {}", e))?;
+
+    object
+        .into_iter()
+        .map(|(key, value)| match value {
+            serde_json::Value::String(s) => Ok((key, s)),
+            serde_json::Value::Number(n) => Ok((key, n.to_string())),
+            serde_json::Value::Bool(b) => Ok((key, b.to_string())),
+            _ => Err(format!(
+                "metadata value for '{}' must be a string, number or boolean",
+                key
+            )),
+        })
+        .collect()
+}
+
+/// Buffer a multipart field, counting every chunk against `budget` as it
+/// arrives. `filename` is set for file fields so the per-file cap applies.
+async fn read_field(
+    field: &mut Field,
+    budget: &mut UploadBudget,
+    filename: Option<&str>,
+) -> Result<Vec<u8>, UploadLimitError> {
+    let mut bytes = Vec::new();
+    while let Ok(Some(chunk)) = field.try_next().await {
+        budget.consume(chunk.len(), filename.map(|name| (name, bytes.len())))?;
+        bytes.extend_from_slice(&chunk);
+    }
+    Ok(bytes)
+}
+
+fn rejected_upload(err: UploadLimitError) -> ApiError {
+    warn!("⚠️ Rejected upload: {}", err);
+    err.into()
+}
+
 #[post("/api/chromadb/documents/upload")]
 pub async fn upload_documents(
+    http_req: HttpRequest,
     mut payload: Multipart,
     chroma_address: web::Data<String>,
     chromadb_config: web::Data<Arc<Mutex<ChromaDBConfig>>>,
-) -> ActixResult<HttpResponse> {
-    let client = match ChromaDBClient::new(chroma_address.as_str()) {
-        Ok(c) => c,
-        Err(e) => {
-            return Ok(
-                HttpResponse::InternalServerError().json(ChromaDBResponse::<()> {
-                    success: false,
-                    data: None,
-                    error: Some(format!("Failed to initialize ChromaDB client: {}", e)),
-                    message: None,
-                }),
-            );
+    upload_limits: web::Data<UploadLimits>,
+) -> Result<HttpResponse, ApiError> {
+    let mut budget = UploadBudget::new(**upload_limits);
+    let content_length = http_req
+        .headers()
+        .get(actix_web::http::header::CONTENT_LENGTH)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.parse::<usize>().ok());
+    if let Some(content_length) = content_length {
+        if let Err(e) = budget.check_content_length(content_length) {
+            return Err(rejected_upload(e));
         }
-    };
+    }
+
+    let client = ChromaDBClient::new(chroma_address.as_str())
+        .map_err(|e| ApiError::Internal(format!("Failed to initialize ChromaDB client: {}", e)))?;
 
     let mut collection_name: Option<String> = None;
+    let mut upload_metadata = std::collections::HashMap::new();
     let mut files: Vec<(String, Vec<u8>)> = Vec::new();
 
     // Parse multipart form data
@@ -82,11 +132,17 @@ pub async fn upload_documents(
 
         if field_name == "collection" {
             // Read collection name
-            let mut bytes = Vec::new();
-            while let Ok(Some(chunk)) = field.try_next().await {
-                bytes.extend_from_slice(&chunk);
-            }
+            let bytes = match read_field(&mut field, &mut budget, None).await {
+                Ok(bytes) => bytes,
+                Err(e) => return Err(rejected_upload(e)),
+            };
             collection_name = String::from_utf8(bytes).ok();
+        } else if field_name == "metadata" {
+            let bytes = match read_field(&mut field, &mut budget, None).await {
+                Ok(bytes) => bytes,
+                Err(e) => return Err(rejected_upload(e)),
+            };
+            upload_metadata = parse_upload_metadata(&bytes).map_err(ApiError::BadRequest)?;
         } else if field_name == "files" {
             // Read file data
             let filename = content_disposition
@@ -95,10 +151,10 @@ pub async fn upload_documents(
                 .unwrap_or("unknown")
                 .to_string();
 
-            let mut file_data = Vec::new();
-            while let Ok(Some(chunk)) = field.try_next().await {
-                file_data.extend_from_slice(&chunk);
-            }
+            let file_data = match read_field(&mut field, &mut budget, Some(&filename)).await {
+                Ok(bytes) => bytes,
+                Err(e) => return Err(rejected_upload(e)),
+            };
 
             if !file_data.is_empty() {
                 files.push((filename, file_data));
@@ -110,22 +166,16 @@ pub async fn upload_documents(
     let collection = match collection_name {
         Some(name) if !name.is_empty() => name,
         _ => {
-            return Ok(HttpResponse::BadRequest().json(ChromaDBResponse::<()> {
-                success: false,
-                data: None,
-                error: Some("Collection name is required".to_string()),
-                message: None,
-            }));
+            return Err(ApiError::BadRequest(
+                "Collection name is required".to_string(),
+            ));
         }
     };
 
     if files.is_empty() {
-        return Ok(HttpResponse::BadRequest().json(ChromaDBResponse::<()> {
-            success: false,
-            data: None,
-            error: Some("At least one file is required".to_string()),
-            message: None,
-        }));
+        return Err(ApiError::BadRequest(
+            "At least one file is required".to_string(),
+        ));
     }
 
     // Process files
@@ -134,14 +184,14 @@ pub async fn upload_documents(
     let mut all_metadatas: Vec<std::collections::HashMap<String, String>> = Vec::new();
 
     for (filename, file_data) in files {
-        println!("📄 Processing file: {}", filename);
+        info!("📄 Processing file: {}", filename);
 
         // Determine file type and parse
         let (text, metadata) = if filename.ends_with(".pdf") {
             match parse_pdf(&file_data) {
                 Ok((text, meta)) => (text, meta),
                 Err(e) => {
-                    println!("Error parsing PDF {}: {}", filename, e);
+                    error!("Error parsing PDF {}: {}", filename, e);
                     continue;
                 }
             }
@@ -152,12 +202,12 @@ pub async fn upload_documents(
             match parse_text(&file_data) {
                 Ok((text, meta)) => (text, meta),
                 Err(e) => {
-                    println!("Error parsing text file {}: {}", filename, e);
+                    error!("Error parsing text file {}: {}", filename, e);
                     continue;
                 }
             }
         } else {
-            println!("⚠️ Unsupported file type: {}", filename);
+            warn!("⚠️ Unsupported file type: {}", filename);
             continue;
         };
 
@@ -173,7 +223,7 @@ pub async fn upload_documents(
                 }
             }
             Err(e) => {
-                println!(
+                warn!(
                     "⚠️ Tokenizer error: {:?}. Falling back to character-based chunking.",
                     e
                 );
@@ -193,7 +243,8 @@ pub async fn upload_documents(
             all_documents.push(chunk.clone());
 
             // Create metadata for this chunk
-            let mut chunk_metadata = metadata.clone();
+            let mut chunk_metadata = upload_metadata.clone();
+            chunk_metadata.extend(metadata.clone());
             chunk_metadata.insert("filename".to_string(), filename.clone());
             chunk_metadata.insert("chunk_index".to_string(), chunk_idx.to_string());
             chunk_metadata.insert("total_chunks".to_string(), chunks.len().to_string());
@@ -202,12 +253,9 @@ pub async fn upload_documents(
     }
 
     if all_documents.is_empty() {
-        return Ok(HttpResponse::BadRequest().json(ChromaDBResponse::<()> {
-            success: false,
-            data: None,
-            error: Some("No valid documents were extracted from the files".to_string()),
-            message: None,
-        }));
+        return Err(ApiError::BadRequest(
+            "No valid documents were extracted from the files".to_string(),
+        ));
     }
 
     // Add documents to ChromaDB
@@ -219,15 +267,20 @@ pub async fn upload_documents(
         metadatas: Some(all_metadatas),
     };
 
-    // Get embedding model from config
-    let embedding_model = {
+    // Get embedding model and backend from config
+    let (embedding_model, backend_kind) = {
         let config_guard = chromadb_config.lock().unwrap();
-        config_guard.embedding_model.clone()
+        (
+            config_guard.embedding_model.clone(),
+            config_guard.embedding_backend,
+        )
     };
+    let embedder = embedding_backend(backend_kind, &embedding_model)
+        .map_err(|e| ApiError::Internal(format!("Embedding backend is not configured: {}", e)))?;
 
-    match client.add_documents(request, &embedding_model).await {
+    match client.add_documents(request, embedder.as_ref()).await {
         Ok(_) => {
-            println!(
+            info!(
                 "✅ Successfully added {} documents to collection {}",
                 document_count, collection
             );
@@ -241,17 +294,28 @@ pub async fn upload_documents(
                 )),
             }))
         }
-        Err(e) => {
-            println!("Failed to add documents: {}", e);
-            Ok(
-                HttpResponse::InternalServerError().json(ChromaDBResponse::<()> {
-                    success: false,
-                    data: None,
-                    error: Some(format!("Failed to add documents to ChromaDB: {}", e)),
-                    message: None,
-                }),
-            )
-        }
+        Err(e) => match e.downcast::<MetadataSchemaError>() {
+            // Documents that don't match the collection schema are a client error
+            Ok(schema_error) => {
+                warn!("Rejected upload to {}: {}", collection, schema_error);
+                Ok(
+                    HttpResponse::BadRequest().json(ChromaDBResponse::<Vec<SchemaViolation>> {
+                        success: false,
+                        error: Some(schema_error.to_string()),
+                        data: Some(schema_error.violations),
+                        message: None,
+                    }),
+                )
+            }
+            // Known client errors (dimension mismatch, missing collection) keep their
+            // status; anything else means ChromaDB or the embedding backend failed
+            Err(e) => {
+                warn!("Failed to add documents to {}: {}", collection, e);
+                Err(ApiError::classify(e).unwrap_or_else(|e| {
+                    ApiError::Upstream(format!("Failed to add documents to ChromaDB: {}", e))
+                }))
+            }
+        },
     }
 }
 
@@ -654,3 +718,110 @@ fn chunk_text_fallback(text: &str, chunk_size: usize, overlap: usize) -> Vec<Str
 
     chunks
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::chromadb::schema::MetadataSchema;
+
+    fn schema() -> MetadataSchema {
+        serde_json::from_value(serde_json::json!({
+            "fields": {
+                "author": { "type": "string", "required": true },
+                "chunk_index": { "type": "int", "required": true }
+            }
+        }))
+        .unwrap()
+    }
+
+    /// Metadata as built for each chunk of an uploaded file
+    fn chunk_metadatas(
+        upload: &std::collections::HashMap<String, String>,
+        chunks: usize,
+    ) -> Vec<std::collections::HashMap<String, String>> {
+        (0..chunks)
+            .map(|idx| {
+                let mut meta = upload.clone();
+                meta.insert("filename".to_string(), "notes.md".to_string());
+                meta.insert("chunk_index".to_string(), idx.to_string());
+                meta
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_upload_with_schema_metadata_passes() {
+        let upload = parse_upload_metadata(br#"{"author": "Ada", "year": 1843}"#).unwrap();
+        assert_eq!(upload.get("year"), Some(&"1843".to_string()));
+
+        let metadatas = chunk_metadatas(&upload, 3);
+        assert!(schema().validate(3, Some(metadatas.as_slice())).is_ok());
+    }
+
+    #[test]
+    fn test_upload_missing_required_metadata_fails() {
+        let metadatas = chunk_metadatas(&std::collections::HashMap::new(), 2);
+        let err = schema()
+            .validate(2, Some(metadatas.as_slice()))
+            .unwrap_err();
+
+        let indices: Vec<usize> = err.violations.iter().map(|v| v.index).collect();
+        assert_eq!(indices, vec![0, 1]);
+        assert!(err.violations[0].errors[0].contains("author"));
+    }
+
+    #[test]
+    fn test_parse_upload_metadata_rejects_nested_values() {
+        assert!(parse_upload_metadata(br#"{"tags": ["a", "b"]}"#).is_err());
+        assert!(parse_upload_metadata(b"not json").is_err());
+    }
+
+    /// Multipart body with a collection field and one text file
+    fn multipart_body(boundary: &str, file_contents: &str) -> String {
+        format!(
+            "--{b}\r\nContent-Disposition: form-data; name=\"collection\"\r\n\r\ndocs\r\n\
+             --{b}\r\nContent-Disposition: form-data; name=\"files\"; filename=\"big.txt\"\r\n\
+             Content-Type: text/plain\r\n\r\n{f}\r\n--{b}--\r\n",
+            b = boundary,
+            f = file_contents
+        )
+    }
+
+    #[actix_web::test]
+    async fn test_upload_rejects_oversized_file_with_413() {
+        use actix_web::{test, App};
+
+        let limits = UploadLimits {
+            max_file_bytes: 16,
+            max_total_bytes: 4096,
+        };
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new("http://localhost:8000".to_string()))
+                .app_data(web::Data::new(Arc::new(Mutex::new(
+                    ChromaDBConfig::default(),
+                ))))
+                .app_data(web::Data::new(limits))
+                .service(upload_documents),
+        )
+        .await;
+
+        let boundary = "upload-test-boundary";
+        let req = test::TestRequest::post()
+            .uri("/api/chromadb/documents/upload")
+            .insert_header((
+                "content-type",
+                format!("multipart/form-data; boundary={}", boundary),
+            ))
+            .set_payload(multipart_body(boundary, &"x".repeat(64)))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status().as_u16(), 413);
+
+        let body: serde_json::Value = test::read_body_json(resp).await;
+        assert_eq!(
+            body["error"],
+            "File 'big.txt' exceeds the maximum upload size of 16 bytes"
+        );
+    }
+}
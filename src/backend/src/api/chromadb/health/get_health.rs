@@ -1,10 +1,11 @@
 use crate::api::chromadb::client::ChromaDBClient;
 use crate::api::chromadb::types::{ChromaDBHealthResponse, ChromaDBResponse};
 use actix_web::{get, web, HttpResponse, Result as ActixResult};
+use log::{debug, info, warn};
 
 #[get("/api/chromadb/health")]
 pub async fn get_chromadb_health(chroma_address: web::Data<String>) -> ActixResult<HttpResponse> {
-    println!(
+    debug!(
         "🔍 Checking ChromaDB health at: {}",
         chroma_address.as_str()
     );
@@ -13,7 +14,7 @@ pub async fn get_chromadb_health(chroma_address: web::Data<String>) -> ActixResu
         Ok(client) => match client.health_check().await {
             Ok(connected) => {
                 let status = if connected { "healthy" } else { "unhealthy" };
-                println!("✅ ChromaDB health check result: {}", status);
+                info!("✅ ChromaDB health check result: {}", status);
                 Ok(HttpResponse::Ok().json(ChromaDBResponse {
                     success: true,
                     data: Some(ChromaDBHealthResponse {
@@ -26,7 +27,7 @@ pub async fn get_chromadb_health(chroma_address: web::Data<String>) -> ActixResu
                 }))
             }
             Err(e) => {
-                println!("ChromaDB health check failed: {}", e);
+                warn!("ChromaDB health check failed: {}", e);
                 Ok(HttpResponse::ServiceUnavailable().json(ChromaDBResponse::<
                     ChromaDBHealthResponse,
                 > {
@@ -42,7 +43,7 @@ pub async fn get_chromadb_health(chroma_address: web::Data<String>) -> ActixResu
             }
         },
         Err(e) => {
-            println!("Failed to create ChromaDB client: {}", e);
+            warn!("Failed to create ChromaDB client: {}", e);
             Ok(HttpResponse::ServiceUnavailable().json(
                 ChromaDBResponse::<ChromaDBHealthResponse> {
                     success: false,
@@ -4,4 +4,5 @@ pub mod config;
 pub mod documents;
 pub mod health;
 pub mod query;
+pub mod schema;
 pub mod types;
@@ -1,7 +1,8 @@
-use crate::api::chromadb::client::ChromaDBClient;
+use crate::api::chromadb::client::{embedding_backend, ChromaDBClient};
 use crate::api::chromadb::config::types::ChromaDBConfig;
 use crate::api::chromadb::types::{ChromaDBResponse, QueryRequest, QueryResponse};
 use actix_web::{post, web, HttpResponse, Result as ActixResult};
+use log::{debug, info, warn};
 use std::sync::{Arc, Mutex};
 
 #[post("/api/chromadb/query")]
@@ -13,7 +14,7 @@ pub async fn search_collection(
     let client = match ChromaDBClient::new(chroma_address.as_str()) {
         Ok(c) => c,
         Err(e) => {
-            println!("Failed to create ChromaDB client: {}", e);
+            warn!("Failed to create ChromaDB client: {}", e);
             return Ok(HttpResponse::ServiceUnavailable().json(
                 ChromaDBResponse::<QueryResponse> {
                     success: false,
@@ -31,7 +32,7 @@ pub async fn search_collection(
         let config_guard = chromadb_config.lock().unwrap();
 
         // Log current config state for debugging
-        println!(
+        info!(
             "📋 Current config - Embedding: '{}', Query: '{}'",
             config_guard.embedding_model, config_guard.query_model
         );
@@ -53,20 +54,20 @@ pub async fn search_collection(
         if !config_guard.query_model.trim().is_empty()
             && config_guard.query_model != config_guard.embedding_model
         {
-            println!(
+            warn!(
                 "⚠️  WARNING: Query model '{}' is set but will be ignored. Using embedding model '{}' instead to ensure dimension consistency.",
                 config_guard.query_model, model
             );
         }
 
-        println!(
+        info!(
             "✅ Query will use embedding model '{}' (must match upload model)",
             model
         );
-        println!("🔍 IMPORTANT: If you get dimension mismatch errors, verify that:");
-        println!("   1. Your config has embedding_model set to: '{}'", model);
-        println!("   2. You uploaded documents using this same model");
-        println!("   3. The model name matches exactly (including :latest tag if present)");
+        info!("🔍 IMPORTANT: If you get dimension mismatch errors, verify that:");
+        info!("   1. Your config has embedding_model set to: '{}'", model);
+        info!("   2. You uploaded documents using this same model");
+        info!("   3. The model name matches exactly (including :latest tag if present)");
         model
     };
 
@@ -84,12 +85,27 @@ pub async fn search_collection(
         );
     }
 
-    println!(
+    debug!(
         "🔍 Query will use model '{}' (must match the model used for document uploads)",
         query_model
     );
 
-    match client.query(query_request, &query_model).await {
+    let backend_kind = chromadb_config.lock().unwrap().embedding_backend;
+    let embedder = match embedding_backend(backend_kind, &query_model) {
+        Ok(embedder) => embedder,
+        Err(e) => {
+            return Ok(HttpResponse::InternalServerError().json(
+                ChromaDBResponse::<QueryResponse> {
+                    success: false,
+                    data: None,
+                    error: Some(format!("Embedding backend is not configured: {}", e)),
+                    message: None,
+                },
+            ));
+        }
+    };
+
+    match client.query(query_request, embedder.as_ref()).await {
         Ok(results) => Ok(HttpResponse::Ok().json(ChromaDBResponse {
             success: true,
             data: Some(results),
@@ -99,7 +115,7 @@ pub async fn search_collection(
         Err(e) => {
             // Get the root error message without duplication
             let error_msg = e.to_string();
-            println!("Query failed: {}", error_msg);
+            warn!("Query failed: {}", error_msg);
 
             // If the error message already contains detailed information, use it directly
             // Otherwise, try to get more context from the error chain
@@ -149,6 +165,8 @@ mod tests {
             query_texts: vec!["test query".to_string()],
             n_results: Some(5),
             where_clause: None,
+            normalize_scores: false,
+            keyword: None,
         };
 
         let req = test::TestRequest::post()
@@ -175,6 +193,8 @@ mod tests {
             query_texts: vec![],
             n_results: Some(5),
             where_clause: None,
+            normalize_scores: false,
+            keyword: None,
         };
 
         let req = test::TestRequest::post()
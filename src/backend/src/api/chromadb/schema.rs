@@ -0,0 +1,240 @@
+//! Collection metadata schemas
+//!
+//! A schema is stored as JSON in the collection metadata under
+//! [`SCHEMA_METADATA_KEY`] and checked against document metadata before any
+//! embeddings are generated.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+
+/// Collection metadata key holding the serialized schema
+pub const SCHEMA_METADATA_KEY: &str = "metadata_schema";
+
+/// Type a metadata value must parse as (values are stored as strings)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MetadataFieldType {
+    String,
+    Int,
+    Float,
+    Bool,
+}
+
+impl MetadataFieldType {
+    fn accepts(&self, value: &str) -> bool {
+        match self {
+            Self::String => true,
+            Self::Int => value.trim().parse::<i64>().is_ok(),
+            Self::Float => value.trim().parse::<f64>().is_ok(),
+            Self::Bool => matches!(value.trim(), "true" | "false"),
+        }
+    }
+
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::String => "string",
+            Self::Int => "int",
+            Self::Float => "float",
+            Self::Bool => "bool",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MetadataFieldSpec {
+    #[serde(rename = "type")]
+    pub field_type: MetadataFieldType,
+    #[serde(default)]
+    pub required: bool,
+}
+
+/// Expected metadata keys for documents in a collection. Keys not listed are
+/// allowed and not checked.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MetadataSchema {
+    pub fields: BTreeMap<String, MetadataFieldSpec>,
+}
+
+/// Problems with one document's metadata
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SchemaViolation {
+    /// Position of the document in the request
+    pub index: usize,
+    pub errors: Vec<String>,
+}
+
+/// Returned by `add_documents` when metadata does not match the collection schema
+#[derive(Debug, Clone)]
+pub struct MetadataSchemaError {
+    pub violations: Vec<SchemaViolation>,
+}
+
+impl fmt::Display for MetadataSchemaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let indices: Vec<String> = self
+            .violations
+            .iter()
+            .map(|v| v.index.to_string())
+            .collect();
+        write!(
+            f,
+            "Metadata does not match the collection schema for document(s) {}",
+            indices.join(", ")
+        )?;
+        if let Some(first) = self.violations.first() {
+            write!(
+                f,
+                " (document {}: {})",
+                first.index,
+                first.errors.join("; ")
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for MetadataSchemaError {}
+
+impl MetadataSchema {
+    /// Reject empty or whitespace-only field names
+    pub fn check(&self) -> Result<(), String> {
+        if self.fields.keys().any(|k| k.trim().is_empty()) {
+            return Err("Metadata schema field names cannot be empty".to_string());
+        }
+        Ok(())
+    }
+
+    /// Read the schema stored in collection metadata, if any
+    pub fn from_collection_metadata(
+        metadata: &HashMap<String, String>,
+    ) -> Result<Option<Self>, String> {
+        metadata
+            .get(SCHEMA_METADATA_KEY)
+            .map(|json| {
+                serde_json::from_str(json)
+                    .map_err(|e| format!("Invalid metadata schema on collection: {}", e))
+            })
+            .transpose()
+    }
+
+    /// Serialize for storage in collection metadata
+    pub fn to_metadata_value(&self) -> String {
+        serde_json::to_string(self).unwrap_or_default()
+    }
+
+    /// Check one document's metadata, returning every problem found
+    fn errors_for(&self, metadata: Option<&HashMap<String, String>>) -> Vec<String> {
+        let mut errors = Vec::new();
+        for (key, spec) in &self.fields {
+            match metadata.and_then(|m| m.get(key)) {
+                Some(value) if !spec.field_type.accepts(value) => errors.push(format!(
+                    "'{}' must be {} (got '{}')",
+                    key,
+                    spec.field_type.as_str(),
+                    value
+                )),
+                Some(_) => {}
+                None if spec.required => errors.push(format!("missing required key '{}'", key)),
+                None => {}
+            }
+        }
+        errors
+    }
+
+    /// Validate metadata for `document_count` documents. A missing metadata list
+    /// is treated as every document having no metadata.
+    pub fn validate(
+        &self,
+        document_count: usize,
+        metadatas: Option<&[HashMap<String, String>]>,
+    ) -> Result<(), MetadataSchemaError> {
+        let violations: Vec<SchemaViolation> = (0..document_count)
+            .filter_map(|index| {
+                let errors = self.errors_for(metadatas.and_then(|m| m.get(index)));
+                (!errors.is_empty()).then_some(SchemaViolation { index, errors })
+            })
+            .collect();
+
+        if violations.is_empty() {
+            Ok(())
+        } else {
+            Err(MetadataSchemaError { violations })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn schema() -> MetadataSchema {
+        serde_json::from_value(serde_json::json!({
+            "fields": {
+                "author": { "type": "string", "required": true },
+                "year": { "type": "int", "required": true },
+                "reviewed": { "type": "bool" }
+            }
+        }))
+        .unwrap()
+    }
+
+    fn meta(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_validate_passes_matching_metadata() {
+        let metadatas = vec![
+            meta(&[("author", "Ada"), ("year", "1843"), ("filename", "a.md")]),
+            meta(&[("author", "Alan"), ("year", "1936"), ("reviewed", "true")]),
+        ];
+        assert!(schema().validate(2, Some(metadatas.as_slice())).is_ok());
+    }
+
+    #[test]
+    fn test_validate_lists_offending_documents() {
+        let metadatas = vec![
+            meta(&[("author", "Ada"), ("year", "1843")]),
+            meta(&[("year", "soon")]),
+            meta(&[("author", "Alan"), ("year", "1936"), ("reviewed", "yes")]),
+        ];
+        let err = schema()
+            .validate(3, Some(metadatas.as_slice()))
+            .unwrap_err();
+
+        let indices: Vec<usize> = err.violations.iter().map(|v| v.index).collect();
+        assert_eq!(indices, vec![1, 2]);
+        assert_eq!(err.violations[0].errors.len(), 2);
+        assert!(err.violations[1].errors[0].contains("'reviewed' must be bool"));
+        assert!(err.to_string().contains("document(s) 1, 2"));
+    }
+
+    #[test]
+    fn test_validate_without_metadata_reports_required_keys() {
+        let err = schema().validate(1, None).unwrap_err();
+        assert_eq!(err.violations[0].index, 0);
+        assert_eq!(err.violations[0].errors.len(), 2);
+    }
+
+    #[test]
+    fn test_schema_round_trips_through_collection_metadata() {
+        let mut metadata = HashMap::new();
+        assert_eq!(
+            MetadataSchema::from_collection_metadata(&metadata),
+            Ok(None)
+        );
+
+        metadata.insert(
+            SCHEMA_METADATA_KEY.to_string(),
+            schema().to_metadata_value(),
+        );
+        assert_eq!(
+            MetadataSchema::from_collection_metadata(&metadata),
+            Ok(Some(schema()))
+        );
+    }
+}
@@ -23,6 +23,12 @@ pub struct QueryRequest {
     pub query_texts: Vec<String>,
     pub n_results: Option<usize>,
     pub where_clause: Option<HashMap<String, serde_json::Value>>,
+    /// Also return `scores`: distances converted to 0–1 similarity
+    #[serde(default)]
+    pub normalize_scores: bool,
+    /// Hybrid search: rank results containing this text (case-insensitive) first
+    #[serde(default)]
+    pub keyword: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -31,6 +37,12 @@ pub struct QueryResponse {
     pub distances: Option<Vec<Vec<f64>>>,
     pub documents: Option<Vec<Vec<String>>>,
     pub metadatas: Option<Vec<Vec<HashMap<String, serde_json::Value>>>>,
+    /// Similarity per result (1 = identical), present when `normalize_scores` is set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scores: Option<Vec<Vec<f64>>>,
+    /// Metric the scores were derived from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub distance_metric: Option<DistanceMetric>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -42,7 +54,7 @@ pub struct AddDocumentsRequest {
 }
 
 /// Distance metric options for ChromaDB collections
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum DistanceMetric {
     Cosine,
@@ -50,6 +62,34 @@ pub enum DistanceMetric {
     Ip,
 }
 
+impl DistanceMetric {
+    /// Metric from the `hnsw:space` collection metadata key (ChromaDB defaults to L2)
+    pub fn from_collection_metadata(metadata: &HashMap<String, String>) -> Self {
+        match metadata.get("hnsw:space").map(|s| s.trim()) {
+            Some("cosine") => Self::Cosine,
+            Some("ip") => Self::Ip,
+            _ => Self::L2,
+        }
+    }
+
+    /// Convert a ChromaDB distance into a 0–1 similarity where 1 means identical.
+    ///
+    /// Stored and query embeddings are normalized to unit length, so each
+    /// distance has a fixed range:
+    /// - cosine: `d = 1 - cos` in [0, 2], score `1 - d / 2`
+    /// - l2: `d = |a - b|² = 2 - 2·cos` in [0, 4], score `1 - d / 4`
+    /// - ip: `d = 1 - a·b` in [0, 2], score `1 - d / 2`
+    ///
+    /// Scores are clamped to [0, 1] to absorb floating point drift.
+    pub fn similarity(&self, distance: f64) -> f64 {
+        let score = match self {
+            Self::Cosine | Self::Ip => 1.0 - distance / 2.0,
+            Self::L2 => 1.0 - distance / 4.0,
+        };
+        score.clamp(0.0, 1.0)
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ChromaDBHealthResponse {
     pub status: String,
@@ -69,3 +109,38 @@ pub struct ChromaDBResponse<T> {
     pub error: Option<String>,
     pub message: Option<String>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_similarity_per_metric() {
+        assert_eq!(DistanceMetric::Cosine.similarity(0.0), 1.0);
+        assert_eq!(DistanceMetric::Cosine.similarity(1.0), 0.5);
+        assert_eq!(DistanceMetric::Cosine.similarity(2.0), 0.0);
+
+        assert_eq!(DistanceMetric::L2.similarity(0.0), 1.0);
+        assert_eq!(DistanceMetric::L2.similarity(2.0), 0.5);
+        assert_eq!(DistanceMetric::L2.similarity(4.0), 0.0);
+
+        assert_eq!(DistanceMetric::Ip.similarity(0.5), 0.75);
+        // Rounding can push distances slightly out of range
+        assert_eq!(DistanceMetric::Ip.similarity(-0.0001), 1.0);
+        assert_eq!(DistanceMetric::L2.similarity(4.0001), 0.0);
+    }
+
+    #[test]
+    fn test_distance_metric_from_collection_metadata() {
+        let mut metadata = HashMap::new();
+        assert_eq!(
+            DistanceMetric::from_collection_metadata(&metadata),
+            DistanceMetric::L2
+        );
+        metadata.insert("hnsw:space".to_string(), "cosine".to_string());
+        assert_eq!(
+            DistanceMetric::from_collection_metadata(&metadata),
+            DistanceMetric::Cosine
+        );
+    }
+}
@@ -0,0 +1,161 @@
+use serde::{Deserialize, Serialize};
+
+use crate::api::{
+    csv_to_json, html_to_markdown, image_convert, json_to_csv, json_to_toon, markdown_to_html,
+    parquet_to_txt, pdf_to_markdown, text_to_tokens, url_to_markdown,
+};
+
+/// How a converter expects its input to be sent
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ConverterInput {
+    /// JSON request body
+    Json,
+    /// multipart/form-data file upload
+    Multipart,
+    /// Either a JSON body or a multipart upload
+    JsonOrMultipart,
+}
+
+/// A supported input → output conversion and how to call it
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ConverterInfo {
+    pub id: String,
+    pub name: String,
+    pub method: String,
+    pub endpoint: String,
+    pub input_formats: Vec<String>,
+    pub output_formats: Vec<String>,
+    pub request: ConverterInput,
+    /// Largest accepted input in bytes
+    pub max_input_bytes: Option<usize>,
+    pub notes: String,
+}
+
+fn formats(list: &[&str]) -> Vec<String> {
+    list.iter().map(|f| f.to_string()).collect()
+}
+
+/// Every conversion registered by `configure_converter_services`.
+/// Add an entry here when adding a converter endpoint.
+pub fn converter_catalog() -> Vec<ConverterInfo> {
+    vec![
+        ConverterInfo {
+            id: "url-to-markdown".to_string(),
+            name: "URL to Markdown".to_string(),
+            method: "POST".to_string(),
+            endpoint: "/api/url-to-markdown".to_string(),
+            input_formats: formats(&["url"]),
+            output_formats: formats(&["markdown", "zip"]),
+            request: ConverterInput::Json,
+            max_input_bytes: Some(url_to_markdown::post::MAX_HTML_SIZE),
+            notes: "Fetches the page server-side; follow_links bundles linked pages into a zip."
+                .to_string(),
+        },
+        ConverterInfo {
+            id: "html-to-markdown".to_string(),
+            name: "HTML to Markdown".to_string(),
+            method: "POST".to_string(),
+            endpoint: "/api/html-to-markdown".to_string(),
+            input_formats: formats(&["html"]),
+            output_formats: formats(&["markdown"]),
+            request: ConverterInput::Json,
+            max_input_bytes: Some(html_to_markdown::post::MAX_HTML_SIZE),
+            notes: "Optional preprocessing presets: minimal, standard, aggressive.".to_string(),
+        },
+        ConverterInfo {
+            id: "json-to-toon".to_string(),
+            name: "JSON to TOON".to_string(),
+            method: "POST".to_string(),
+            endpoint: "/api/json-to-toon".to_string(),
+            input_formats: formats(&["json"]),
+            output_formats: formats(&["toon"]),
+            request: ConverterInput::JsonOrMultipart,
+            max_input_bytes: Some(json_to_toon::post::MAX_JSON_SIZE),
+            notes: "Paste JSON or upload a .json file; reports token savings when count_tokens is set.".to_string(),
+        },
+        ConverterInfo {
+            id: "parquet-to-txt".to_string(),
+            name: "Parquet to text".to_string(),
+            method: "POST".to_string(),
+            endpoint: "/api/parquet-to-txt".to_string(),
+            input_formats: formats(&["parquet"]),
+            output_formats: formats(&["txt"]),
+            request: ConverterInput::Multipart,
+            max_input_bytes: Some(parquet_to_txt::post::MAX_TOTAL_SIZE),
+            notes: "Upload one or more files in the `files` field; the limit applies to their combined size.".to_string(),
+        },
+        ConverterInfo {
+            id: "pdf-to-markdown".to_string(),
+            name: "PDF to Markdown".to_string(),
+            method: "POST".to_string(),
+            endpoint: "/api/pdf-to-markdown".to_string(),
+            input_formats: formats(&["pdf"]),
+            output_formats: formats(&["markdown"]),
+            request: ConverterInput::Multipart,
+            max_input_bytes: Some(pdf_to_markdown::post::MAX_FILE_SIZE),
+            notes: "Text is extracted from the PDF; scanned pages without a text layer come out empty.".to_string(),
+        },
+        ConverterInfo {
+            id: "text-to-tokens".to_string(),
+            name: "Text to token count".to_string(),
+            method: "POST".to_string(),
+            endpoint: "/api/text-to-tokens".to_string(),
+            input_formats: formats(&["text"]),
+            output_formats: formats(&["token_count"]),
+            request: ConverterInput::Json,
+            max_input_bytes: Some(text_to_tokens::post::MAX_TEXT_SIZE),
+            notes: "Counts tokens, characters and words.".to_string(),
+        },
+        ConverterInfo {
+            id: "image-convert".to_string(),
+            name: "Image format conversion".to_string(),
+            method: "POST".to_string(),
+            endpoint: "/api/image-convert".to_string(),
+            input_formats: formats(&["png", "jpeg", "webp", "gif", "bmp"]),
+            output_formats: formats(&["png", "jpeg", "webp"]),
+            request: ConverterInput::Multipart,
+            max_input_bytes: Some(image_convert::post::MAX_FILE_SIZE),
+            notes: "Optional max_width/max_height shrink the image keeping its aspect ratio. \
+                    quality (1-100) applies to JPEG; WebP output is lossless."
+                .to_string(),
+        },
+        ConverterInfo {
+            id: "csv-to-json".to_string(),
+            name: "CSV to JSON".to_string(),
+            method: "POST".to_string(),
+            endpoint: "/api/csv-to-json".to_string(),
+            input_formats: formats(&["csv"]),
+            output_formats: formats(&["json"]),
+            request: ConverterInput::Json,
+            max_input_bytes: Some(csv_to_json::post::MAX_CSV_SIZE),
+            notes: "The first row is the header; values stay strings. Ragged rows are rejected."
+                .to_string(),
+        },
+        ConverterInfo {
+            id: "json-to-csv".to_string(),
+            name: "JSON to CSV".to_string(),
+            method: "POST".to_string(),
+            endpoint: "/api/json-to-csv".to_string(),
+            input_formats: formats(&["json"]),
+            output_formats: formats(&["csv"]),
+            request: ConverterInput::Json,
+            max_input_bytes: Some(json_to_csv::post::MAX_JSON_SIZE),
+            notes: "Takes an array of flat objects; columns are the union of their keys."
+                .to_string(),
+        },
+        ConverterInfo {
+            id: "markdown-to-html".to_string(),
+            name: "Markdown to HTML".to_string(),
+            method: "POST".to_string(),
+            endpoint: "/api/converters/markdown-to-html".to_string(),
+            input_formats: formats(&["markdown"]),
+            output_formats: formats(&["html"]),
+            request: ConverterInput::Json,
+            max_input_bytes: Some(markdown_to_html::post::MAX_MARKDOWN_SIZE),
+            notes: "Supports tables, strikethrough and task lists. Output is sanitized unless \
+                    sanitize is false."
+                .to_string(),
+        },
+    ]
+}
@@ -0,0 +1,41 @@
+use actix_web::{get, HttpResponse, Result as ActixResult};
+use serde::{Deserialize, Serialize};
+
+use crate::api::converters::catalog::{converter_catalog, ConverterInfo};
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ConvertersResponse {
+    pub converters: Vec<ConverterInfo>,
+}
+
+/// List the supported conversions so a frontend can build its UI from them
+#[get("/api/converters")]
+pub async fn get_converters() -> ActixResult<HttpResponse> {
+    Ok(HttpResponse::Ok().json(ConvertersResponse {
+        converters: converter_catalog(),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::{test, App};
+
+    #[actix_web::test]
+    async fn test_get_converters() {
+        let app = test::init_service(App::new().service(get_converters)).await;
+
+        let req = test::TestRequest::get().uri("/api/converters").to_request();
+        let resp = test::call_service(&app, req).await;
+        assert!(resp.status().is_success());
+
+        let body: ConvertersResponse = test::read_body_json(resp).await;
+        let pdf = body
+            .converters
+            .iter()
+            .find(|c| c.id == "pdf-to-markdown")
+            .expect("pdf converter listed");
+        assert_eq!(pdf.input_formats, vec!["pdf"]);
+        assert_eq!(pdf.max_input_bytes, Some(50 * 1024 * 1024));
+    }
+}
@@ -0,0 +1,14 @@
+use actix_web::web;
+
+/// Request body size allowed for a converter input of up to `max_input_bytes`.
+/// Twice the input limit leaves room for JSON escaping and the other fields,
+/// so the handler's own size check is the one that rejects oversized input.
+pub fn json_body_limit(max_input_bytes: usize) -> usize {
+    max_input_bytes * 2
+}
+
+/// `JsonConfig` for a converter route whose input may be up to `max_input_bytes`
+/// (actix's default 2 MiB would reject larger inputs before the handler runs)
+pub fn json_config(max_input_bytes: usize) -> web::JsonConfig {
+    web::JsonConfig::default().limit(json_body_limit(max_input_bytes))
+}
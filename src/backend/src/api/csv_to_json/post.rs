@@ -0,0 +1,141 @@
+use crate::api::converters::limits::json_config;
+use crate::api::error::ApiError;
+use actix_web::{web, Error as ActixError, HttpResponse, Resource};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+/// Largest CSV document accepted
+pub const MAX_CSV_SIZE: usize = 10 * 1024 * 1024;
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct CsvToJsonRequest {
+    pub csv: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CsvToJsonResponse {
+    pub json: Vec<Map<String, Value>>,
+    pub columns: Vec<String>,
+    pub rows: usize,
+}
+
+/// Parse CSV (RFC 4180) into one JSON object per row, keyed by the header row.
+/// All values are kept as strings; rows with a different field count are rejected.
+pub fn csv_to_json(input: &str) -> Result<CsvToJsonResponse, String> {
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(true)
+        .flexible(false)
+        .from_reader(input.as_bytes());
+
+    let columns: Vec<String> = reader
+        .headers()
+        .map_err(|e| format!("Invalid CSV header: {}", e))?
+        .iter()
+        .map(|h| h.to_string())
+        .collect();
+
+    if columns.is_empty() || columns.iter().all(|c| c.trim().is_empty()) {
+        return Err("CSV has no header row".to_string());
+    }
+    for (i, column) in columns.iter().enumerate() {
+        if columns[..i].contains(column) {
+            return Err(format!("Duplicate column name '{}'", column));
+        }
+    }
+
+    let mut rows = Vec::new();
+    for record in reader.records() {
+        let record = record.map_err(|e| format!("Invalid CSV: {}", e))?;
+        let row = columns
+            .iter()
+            .zip(record.iter())
+            .map(|(column, value)| (column.clone(), Value::String(value.to_string())))
+            .collect();
+        rows.push(row);
+    }
+
+    Ok(CsvToJsonResponse {
+        rows: rows.len(),
+        columns,
+        json: rows,
+    })
+}
+
+/// `POST /api/csv-to-json`, accepting request bodies big enough for a `MAX_CSV_SIZE` input
+pub fn csv_to_json_service() -> Resource {
+    web::resource("/api/csv-to-json")
+        .app_data(json_config(MAX_CSV_SIZE))
+        .route(web::post().to(convert_csv_to_json))
+}
+
+pub async fn convert_csv_to_json(
+    body: web::Json<CsvToJsonRequest>,
+) -> Result<HttpResponse, ActixError> {
+    if body.csv.trim().is_empty() {
+        return Err(ApiError::BadRequest("CSV cannot be empty".to_string()).into());
+    }
+
+    if body.csv.len() > MAX_CSV_SIZE {
+        return Err(ApiError::PayloadTooLarge(format!(
+            "CSV too large: {} bytes (max {} bytes)",
+            body.csv.len(),
+            MAX_CSV_SIZE
+        ))
+        .into());
+    }
+
+    match csv_to_json(&body.csv) {
+        Ok(response) => Ok(HttpResponse::Ok().json(response)),
+        Err(e) => Err(ApiError::BadRequest(e).into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[actix_web::test]
+    async fn test_convert_csv_to_json_too_large() {
+        use crate::api::error::ErrorEnvelope;
+        use actix_web::{test, App};
+
+        let app = test::init_service(App::new().service(csv_to_json_service())).await;
+
+        // Over actix's default 2 MiB JSON limit, so it must reach the handler's check
+        let req = test::TestRequest::post()
+            .uri("/api/csv-to-json")
+            .set_json(CsvToJsonRequest {
+                csv: format!("a\n{}", "1".repeat(MAX_CSV_SIZE)),
+            })
+            .to_request();
+
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 413);
+        let body: ErrorEnvelope = test::read_body_json(resp).await;
+        assert_eq!(body.code, "payload_too_large");
+        assert!(body.error.starts_with("CSV too large"));
+    }
+
+    #[test]
+    fn test_csv_to_json_with_quoting() {
+        let input = "name,notes\nAda,\"likes \"\"math\"\", engines\"\nLinus,\"multi\nline\"\n";
+        let response = csv_to_json(input).unwrap();
+
+        assert_eq!(response.columns, vec!["name", "notes"]);
+        assert_eq!(response.rows, 2);
+        assert_eq!(response.json[0]["notes"], "likes \"math\", engines");
+        assert_eq!(response.json[1]["notes"], "multi\nline");
+    }
+
+    #[test]
+    fn test_csv_to_json_rejects_ragged_rows() {
+        let err = csv_to_json("a,b\n1,2\n3\n").unwrap_err();
+        assert!(err.starts_with("Invalid CSV"), "{}", err);
+    }
+
+    #[test]
+    fn test_csv_to_json_rejects_duplicate_columns() {
+        let err = csv_to_json("a,a\n1,2\n").unwrap_err();
+        assert!(err.contains("Duplicate column"));
+    }
+}
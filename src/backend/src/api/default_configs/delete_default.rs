@@ -0,0 +1,38 @@
+use crate::api::default_configs::sqlite_storage::DefaultConfigsStorage;
+use crate::api::default_configs::types::DefaultConfigCategory;
+use actix_web::{delete, web, HttpResponse, Result as ActixResult};
+use std::sync::Arc;
+
+/// Forget the saved default for `llama` or `chromadb`. The running config is
+/// untouched; the hardcoded default is used from the next startup.
+#[delete("/api/default-configs/{category}")]
+pub async fn clear_default_config(
+    path: web::Path<String>,
+    storage: web::Data<Arc<DefaultConfigsStorage>>,
+) -> ActixResult<HttpResponse> {
+    let category = match DefaultConfigCategory::parse(&path) {
+        Some(category) => category,
+        None => {
+            return Ok(HttpResponse::BadRequest().json(serde_json::json!({
+                "error": format!("Unknown category: {}. Must be 'llama' or 'chromadb'", path)
+            })));
+        }
+    };
+    println!("🗑️  Clearing {} default config", category.as_str());
+
+    match storage.clear_default(category).await {
+        Ok(true) => Ok(HttpResponse::Ok().json(serde_json::json!({
+            "success": true,
+            "message": format!("Cleared {} default config", category.as_str())
+        }))),
+        Ok(false) => Ok(HttpResponse::NotFound().json(serde_json::json!({
+            "error": format!("No {} default config set", category.as_str())
+        }))),
+        Err(e) => {
+            println!("Failed to clear default config: {}", e);
+            Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": format!("Failed to clear default config: {}", e)
+            })))
+        }
+    }
+}
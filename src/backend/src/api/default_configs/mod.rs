@@ -1,5 +1,7 @@
+pub mod delete_default;
 pub mod sqlite_storage;
 pub mod types;
 
+pub use delete_default::clear_default_config;
 pub use sqlite_storage::DefaultConfigsStorage;
 pub use types::{ChromaDBDefaultConfig, LlamaDefaultConfig};
@@ -1,7 +1,30 @@
-use crate::api::default_configs::types::{ChromaDBDefaultConfig, LlamaDefaultConfig};
+use crate::api::default_configs::types::{
+    ChromaDBDefaultConfig, DefaultConfigCategory, LlamaDefaultConfig,
+};
+use crate::utils::migrations::{run_migrations, Migration};
 use anyhow::{Context, Result};
-use sqlx::{sqlite::SqliteConnectOptions, Row, SqlitePool};
-use std::path::Path;
+use sqlx::{Row, SqlitePool};
+
+/// Default configs schema; append new versions, never edit applied ones
+const MIGRATIONS: &[Migration] = &[Migration {
+    version: 1,
+    name: "create default_configs",
+    statements: &[
+        "CREATE TABLE IF NOT EXISTS default_configs (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            config_type TEXT NOT NULL UNIQUE,
+            hf_model TEXT,
+            embedding_model TEXT,
+            updated_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now'))
+        )",
+        // Older databases may hold several rows per config_type; keep the newest
+        // and enforce one row per category from here on
+        "DELETE FROM default_configs
+         WHERE id NOT IN (SELECT MAX(id) FROM default_configs GROUP BY config_type)",
+        "CREATE UNIQUE INDEX IF NOT EXISTS idx_default_configs_type
+         ON default_configs(config_type)",
+    ],
+}];
 
 /// SQLite-based storage for default configs
 pub struct DefaultConfigsStorage {
@@ -10,70 +33,10 @@ pub struct DefaultConfigsStorage {
 
 impl DefaultConfigsStorage {
     /// Create a new default configs storage
-    pub async fn new(db_path: impl AsRef<Path>) -> Result<Self> {
-        let db_path = db_path.as_ref();
-        let db_path_str = db_path.to_str().unwrap_or("");
-
-        // Handle in-memory database specially (skip file system operations)
-        let (db_path_for_connection, display_path) = if db_path_str == ":memory:" {
-            println!("💾 Connecting to SQLite in-memory database for default configs");
-            (":memory:".to_string(), ":memory:".to_string())
-        } else {
-            // Ensure directory exists
-            if let Some(parent) = db_path.parent() {
-                std::fs::create_dir_all(parent).context("Failed to create database directory")?;
-            }
-
-            // Get absolute path
-            let absolute_path = if db_path.exists() {
-                db_path
-                    .canonicalize()
-                    .context("Failed to canonicalize existing database path")?
-            } else {
-                let parent = db_path.parent().unwrap_or(Path::new("."));
-                let parent_abs = parent
-                    .canonicalize()
-                    .or_else(|_| std::env::current_dir().map(|d| d.join(parent)))
-                    .context("Failed to get absolute path for database directory")?;
-                let filename = db_path
-                    .file_name()
-                    .and_then(|n| n.to_str())
-                    .unwrap_or("conversations.db");
-                parent_abs.join(filename)
-            };
-
-            let display = absolute_path.display().to_string();
-            println!(
-                "💾 Connecting to SQLite database for default configs at: {}",
-                display
-            );
-            (absolute_path.to_str().unwrap().to_string(), display)
-        };
-
-        let options = SqliteConnectOptions::new()
-            .filename(&db_path_for_connection)
-            .create_if_missing(true);
-
-        let pool = SqlitePool::connect_with(options).await.context(format!(
-            "Failed to connect to SQLite database at: {}",
-            display_path
-        ))?;
-
-        // Create default_configs table
-        println!("📋 Creating default_configs table if it doesn't exist...");
-        sqlx::query(
-            "CREATE TABLE IF NOT EXISTS default_configs (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
-                config_type TEXT NOT NULL UNIQUE,
-                hf_model TEXT,
-                embedding_model TEXT,
-                updated_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now'))
-            )",
-        )
-        .execute(&pool)
-        .await
-        .context("Failed to create default_configs table")?;
-        println!("✅ default_configs table created/verified");
+    pub async fn new(pool: SqlitePool) -> Result<Self> {
+        run_migrations(&pool, "default_configs", MIGRATIONS)
+            .await
+            .context("Failed to migrate default_configs table")?;
 
         Ok(Self { pool })
     }
@@ -96,6 +59,9 @@ impl DefaultConfigsStorage {
 
     /// Set llama default config
     pub async fn set_llama_default(&self, config: &LlamaDefaultConfig) -> Result<()> {
+        let hf_model = config.hf_model.trim();
+        anyhow::ensure!(!hf_model.is_empty(), "hf_model cannot be empty");
+
         sqlx::query(
             "INSERT INTO default_configs (config_type, hf_model, updated_at)
              VALUES ('llama', ?1, strftime('%s', 'now'))
@@ -103,7 +69,7 @@ impl DefaultConfigsStorage {
                  hf_model = ?1,
                  updated_at = strftime('%s', 'now')",
         )
-        .bind(&config.hf_model)
+        .bind(hf_model)
         .execute(&self.pool)
         .await
         .context("Failed to set llama default config")?;
@@ -130,6 +96,12 @@ impl DefaultConfigsStorage {
 
     /// Set chromadb default config
     pub async fn set_chromadb_default(&self, config: &ChromaDBDefaultConfig) -> Result<()> {
+        let embedding_model = config.embedding_model.trim();
+        anyhow::ensure!(
+            !embedding_model.is_empty(),
+            "embedding_model cannot be empty"
+        );
+
         sqlx::query(
             "INSERT INTO default_configs (config_type, embedding_model, updated_at)
              VALUES ('chromadb', ?1, strftime('%s', 'now'))
@@ -137,10 +109,113 @@ impl DefaultConfigsStorage {
                  embedding_model = ?1,
                  updated_at = strftime('%s', 'now')",
         )
-        .bind(&config.embedding_model)
+        .bind(embedding_model)
         .execute(&self.pool)
         .await
         .context("Failed to set chromadb default config")?;
         Ok(())
     }
+
+    /// Remove the saved default for a category. Returns false if none was set.
+    pub async fn clear_default(&self, category: DefaultConfigCategory) -> Result<bool> {
+        let rows_affected = sqlx::query("DELETE FROM default_configs WHERE config_type = ?1")
+            .bind(category.as_str())
+            .execute(&self.pool)
+            .await
+            .context(format!(
+                "Failed to clear {} default config",
+                category.as_str()
+            ))?
+            .rows_affected();
+
+        Ok(rows_affected > 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::database::connect_sqlite;
+
+    fn temp_db_path() -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("default_configs_test_{}.db", uuid::Uuid::new_v4()))
+    }
+
+    #[tokio::test]
+    async fn test_set_default_upserts_single_row() {
+        let path = temp_db_path();
+        let storage = DefaultConfigsStorage::new(connect_sqlite(&path).await.unwrap())
+            .await
+            .unwrap();
+
+        for model in ["first/model", "  second/model  "] {
+            storage
+                .set_llama_default(&LlamaDefaultConfig {
+                    hf_model: model.to_string(),
+                })
+                .await
+                .unwrap();
+        }
+
+        let count: i64 =
+            sqlx::query_scalar("SELECT COUNT(*) FROM default_configs WHERE config_type = 'llama'")
+                .fetch_one(&storage.pool)
+                .await
+                .unwrap();
+        assert_eq!(count, 1);
+        let saved = storage.get_llama_default().await.unwrap().unwrap();
+        assert_eq!(saved.hf_model, "second/model");
+
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[tokio::test]
+    async fn test_set_default_rejects_empty_model() {
+        let path = temp_db_path();
+        let storage = DefaultConfigsStorage::new(connect_sqlite(&path).await.unwrap())
+            .await
+            .unwrap();
+
+        assert!(storage
+            .set_llama_default(&LlamaDefaultConfig {
+                hf_model: "   ".to_string(),
+            })
+            .await
+            .is_err());
+        assert!(storage
+            .set_chromadb_default(&ChromaDBDefaultConfig {
+                embedding_model: String::new(),
+            })
+            .await
+            .is_err());
+        assert!(storage.get_llama_default().await.unwrap().is_none());
+
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[tokio::test]
+    async fn test_clear_default() {
+        let path = temp_db_path();
+        let storage = DefaultConfigsStorage::new(connect_sqlite(&path).await.unwrap())
+            .await
+            .unwrap();
+        storage
+            .set_chromadb_default(&ChromaDBDefaultConfig {
+                embedding_model: "nomic-embed-text".to_string(),
+            })
+            .await
+            .unwrap();
+
+        assert!(storage
+            .clear_default(DefaultConfigCategory::ChromaDB)
+            .await
+            .unwrap());
+        assert!(storage.get_chromadb_default().await.unwrap().is_none());
+        assert!(!storage
+            .clear_default(DefaultConfigCategory::ChromaDB)
+            .await
+            .unwrap());
+
+        let _ = std::fs::remove_file(path);
+    }
 }
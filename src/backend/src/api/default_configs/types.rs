@@ -9,3 +9,29 @@ pub struct LlamaDefaultConfig {
 pub struct ChromaDBDefaultConfig {
     pub embedding_model: String,
 }
+
+/// Kind of default config; each category holds at most one row
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum DefaultConfigCategory {
+    Llama,
+    ChromaDB,
+}
+
+impl DefaultConfigCategory {
+    /// Value stored in the `config_type` column
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Llama => "llama",
+            Self::ChromaDB => "chromadb",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "llama" => Some(Self::Llama),
+            "chromadb" => Some(Self::ChromaDB),
+            _ => None,
+        }
+    }
+}
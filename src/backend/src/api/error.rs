@@ -0,0 +1,291 @@
+//! Errors returned by API handlers
+//!
+//! [`ApiError`] maps each kind of failure to its HTTP status so clients can tell
+//! a bad request (400) or a missing resource (404) from an unreachable upstream
+//! service (502) instead of getting a blanket 500.
+//!
+//! Every failure is sent as the same envelope:
+//! `{"success": false, "error": "<message>", "code": "<machine readable code>"}`.
+//! Handlers return [`ApiError`] directly; errors raised elsewhere (extractors,
+//! `actix_web::error::ErrorBadRequest`, unmatched routes) are rewritten into the
+//! envelope by the [`json_error_handlers`] middleware.
+
+use crate::api::chromadb::client::{CollectionNotFoundError, EmbeddingDimensionError};
+use crate::api::chromadb::schema::MetadataSchemaError;
+use actix_web::body::MessageBody;
+use actix_web::dev::ServiceResponse;
+use actix_web::http::{header, StatusCode};
+use actix_web::middleware::{ErrorHandlerResponse, ErrorHandlers};
+use actix_web::{HttpResponse, ResponseError};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Body of every error response
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ErrorEnvelope {
+    pub success: bool,
+    pub error: String,
+    pub code: String,
+}
+
+impl ErrorEnvelope {
+    pub fn new(code: &str, error: impl Into<String>) -> Self {
+        Self {
+            success: false,
+            error: error.into(),
+            code: code.to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ApiError {
+    /// The requested resource doesn't exist (404)
+    NotFound(String),
+    /// The request itself is invalid (400)
+    BadRequest(String),
+    /// Missing or wrong credentials (401)
+    Unauthorized(String),
+    /// The request body is over a size limit (413)
+    PayloadTooLarge(String),
+    /// A service we depend on (ChromaDB, the embedding backend) failed (502)
+    Upstream(String),
+    /// Anything else (500)
+    Internal(String),
+}
+
+impl ApiError {
+    /// Map errors with a known status: invalid input, missing collections or
+    /// rows. Returns the error unchanged when it isn't one of them, so the
+    /// caller can pick the fallback (`Internal` or `Upstream`).
+    pub fn classify(err: anyhow::Error) -> Result<ApiError, anyhow::Error> {
+        if err.downcast_ref::<CollectionNotFoundError>().is_some()
+            || matches!(
+                err.downcast_ref::<sqlx::Error>(),
+                Some(sqlx::Error::RowNotFound)
+            )
+        {
+            return Ok(ApiError::NotFound(err.to_string()));
+        }
+        if err.downcast_ref::<EmbeddingDimensionError>().is_some()
+            || err.downcast_ref::<MetadataSchemaError>().is_some()
+        {
+            return Ok(ApiError::BadRequest(err.to_string()));
+        }
+        Err(err)
+    }
+
+    /// Machine readable code sent in the envelope
+    pub fn code(&self) -> &'static str {
+        match self {
+            ApiError::NotFound(_) => "not_found",
+            ApiError::BadRequest(_) => "bad_request",
+            ApiError::Unauthorized(_) => "unauthorized",
+            ApiError::PayloadTooLarge(_) => "payload_too_large",
+            ApiError::Upstream(_) => "upstream_error",
+            ApiError::Internal(_) => "internal_error",
+        }
+    }
+
+    fn message(&self) -> &str {
+        match self {
+            ApiError::NotFound(message)
+            | ApiError::BadRequest(message)
+            | ApiError::Unauthorized(message)
+            | ApiError::PayloadTooLarge(message)
+            | ApiError::Upstream(message)
+            | ApiError::Internal(message) => message,
+        }
+    }
+}
+
+impl fmt::Display for ApiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.message())
+    }
+}
+
+impl std::error::Error for ApiError {}
+
+impl From<anyhow::Error> for ApiError {
+    fn from(err: anyhow::Error) -> Self {
+        ApiError::classify(err).unwrap_or_else(|err| ApiError::Internal(err.to_string()))
+    }
+}
+
+impl ResponseError for ApiError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            ApiError::NotFound(_) => StatusCode::NOT_FOUND,
+            ApiError::BadRequest(_) => StatusCode::BAD_REQUEST,
+            ApiError::Unauthorized(_) => StatusCode::UNAUTHORIZED,
+            ApiError::PayloadTooLarge(_) => StatusCode::PAYLOAD_TOO_LARGE,
+            ApiError::Upstream(_) => StatusCode::BAD_GATEWAY,
+            ApiError::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        HttpResponse::build(self.status_code())
+            .json(ErrorEnvelope::new(self.code(), self.message()))
+    }
+}
+
+/// Envelope code for a status that didn't come from an [`ApiError`]
+fn code_for_status(status: StatusCode) -> &'static str {
+    match status {
+        StatusCode::BAD_REQUEST => "bad_request",
+        StatusCode::UNAUTHORIZED => "unauthorized",
+        StatusCode::FORBIDDEN => "forbidden",
+        StatusCode::NOT_FOUND => "not_found",
+        StatusCode::METHOD_NOT_ALLOWED => "method_not_allowed",
+        StatusCode::PAYLOAD_TOO_LARGE => "payload_too_large",
+        StatusCode::UNSUPPORTED_MEDIA_TYPE => "unsupported_media_type",
+        StatusCode::TOO_MANY_REQUESTS => "too_many_requests",
+        StatusCode::BAD_GATEWAY => "upstream_error",
+        StatusCode::SERVICE_UNAVAILABLE => "service_unavailable",
+        s if s.is_client_error() => "client_error",
+        _ => "internal_error",
+    }
+}
+
+/// Middleware rewriting error responses that aren't JSON (actix's plain text
+/// bodies from extractors, `ErrorBadRequest` and friends, empty 404s) into
+/// an [`ErrorEnvelope`]. JSON error bodies are left alone.
+pub fn json_error_handlers<B: MessageBody + 'static>() -> ErrorHandlers<B> {
+    ErrorHandlers::new().default_handler(wrap_error_response)
+}
+
+fn wrap_error_response<B>(res: ServiceResponse<B>) -> actix_web::Result<ErrorHandlerResponse<B>> {
+    let is_json = res
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.starts_with("application/json"));
+    if is_json {
+        return Ok(ErrorHandlerResponse::Response(res.map_into_left_body()));
+    }
+
+    let status = res.status();
+    let message = res
+        .response()
+        .error()
+        .map(|e| e.to_string())
+        .filter(|m| !m.is_empty())
+        .unwrap_or_else(|| status.canonical_reason().unwrap_or("Error").to_string());
+
+    let (req, original) = res.into_parts();
+    let mut response =
+        HttpResponse::build(status).json(ErrorEnvelope::new(code_for_status(status), message));
+    // Keep headers set by the handler or inner middleware (Retry-After, CORS, ...)
+    for (name, value) in original.headers() {
+        if name != header::CONTENT_TYPE && name != header::CONTENT_LENGTH {
+            response.headers_mut().insert(name.clone(), value.clone());
+        }
+    }
+
+    Ok(ErrorHandlerResponse::Response(
+        ServiceResponse::new(req, response).map_into_right_body(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::body::to_bytes;
+
+    #[test]
+    fn test_status_codes() {
+        let cases = [
+            (ApiError::NotFound("x".to_string()), 404),
+            (ApiError::BadRequest("x".to_string()), 400),
+            (ApiError::Unauthorized("x".to_string()), 401),
+            (ApiError::PayloadTooLarge("x".to_string()), 413),
+            (ApiError::Upstream("x".to_string()), 502),
+            (ApiError::Internal("x".to_string()), 500),
+        ];
+        for (err, status) in cases {
+            assert_eq!(err.status_code().as_u16(), status, "{:?}", err);
+        }
+    }
+
+    #[actix_web::test]
+    async fn test_error_response_body() {
+        let response = ApiError::Upstream("ChromaDB is unreachable".to_string()).error_response();
+        assert_eq!(response.status(), StatusCode::BAD_GATEWAY);
+        let body = to_bytes(response.into_body()).await.unwrap();
+        let body: ErrorEnvelope = serde_json::from_slice(&body).unwrap();
+        assert_eq!(
+            body,
+            ErrorEnvelope::new("upstream_error", "ChromaDB is unreachable")
+        );
+    }
+
+    #[actix_web::test]
+    async fn test_middleware_wraps_plain_text_errors() {
+        use actix_web::{test, web, App};
+
+        let app = test::init_service(
+            App::new()
+                .wrap(json_error_handlers())
+                .route(
+                    "/plain",
+                    web::get().to(|| async {
+                        Err::<HttpResponse, _>(actix_web::error::ErrorBadRequest("Missing name"))
+                    }),
+                )
+                .route(
+                    "/json",
+                    web::get().to(|| async {
+                        HttpResponse::Conflict().json(serde_json::json!({"error": "taken"}))
+                    }),
+                ),
+        )
+        .await;
+
+        let resp =
+            test::call_service(&app, test::TestRequest::get().uri("/plain").to_request()).await;
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+        let body: ErrorEnvelope = test::read_body_json(resp).await;
+        assert_eq!(body, ErrorEnvelope::new("bad_request", "Missing name"));
+
+        let resp =
+            test::call_service(&app, test::TestRequest::get().uri("/missing").to_request()).await;
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+        let body: ErrorEnvelope = test::read_body_json(resp).await;
+        assert_eq!(body, ErrorEnvelope::new("not_found", "Not Found"));
+
+        // Handlers that already answer with JSON keep their body
+        let resp =
+            test::call_service(&app, test::TestRequest::get().uri("/json").to_request()).await;
+        assert_eq!(resp.status(), StatusCode::CONFLICT);
+        let body: serde_json::Value = test::read_body_json(resp).await;
+        assert_eq!(body, serde_json::json!({"error": "taken"}));
+    }
+
+    #[test]
+    fn test_classify_known_errors() {
+        let missing = anyhow::anyhow!("connection refused").context(CollectionNotFoundError {
+            name: "docs".to_string(),
+        });
+        assert_eq!(
+            ApiError::from(missing),
+            ApiError::NotFound("Collection 'docs' not found".to_string())
+        );
+
+        let mismatch = anyhow::Error::new(EmbeddingDimensionError {
+            expected: 768,
+            actual: 1024,
+        });
+        assert!(matches!(ApiError::from(mismatch), ApiError::BadRequest(_)));
+
+        let row = anyhow::Error::new(sqlx::Error::RowNotFound).context("Failed to load note");
+        assert!(matches!(ApiError::from(row), ApiError::NotFound(_)));
+
+        let unknown = ApiError::classify(anyhow::anyhow!("disk full")).unwrap_err();
+        assert_eq!(
+            ApiError::from(unknown),
+            ApiError::Internal("disk full".to_string())
+        );
+    }
+}
@@ -0,0 +1,159 @@
+use crate::api::chromadb::client::ChromaDBClient;
+use crate::api::health::types::{
+    ChromaHealth, DatabaseHealth, HealthResponse, LlamaServerHealth, ServiceStartTime,
+};
+use crate::api::llama_server::types::ServerStateHandle;
+use actix_web::{get, web, HttpResponse, Result as ActixResult};
+use sqlx::SqlitePool;
+use std::time::Duration;
+
+/// Longest a single dependency check may take, so a hung ChromaDB can't stall
+/// the load balancer's probe
+const CHECK_TIMEOUT: Duration = Duration::from_secs(2);
+
+async fn check_database(pool: &SqlitePool) -> DatabaseHealth {
+    let ping = sqlx::query_scalar::<_, i64>("SELECT 1").fetch_one(pool);
+    match tokio::time::timeout(CHECK_TIMEOUT, ping).await {
+        Ok(Ok(_)) => DatabaseHealth {
+            reachable: true,
+            error: None,
+        },
+        Ok(Err(e)) => DatabaseHealth {
+            reachable: false,
+            error: Some(e.to_string()),
+        },
+        Err(_) => DatabaseHealth {
+            reachable: false,
+            error: Some("Database ping timed out".to_string()),
+        },
+    }
+}
+
+async fn check_chroma(address: &str) -> ChromaHealth {
+    let client = match ChromaDBClient::new(address) {
+        Ok(client) => client,
+        Err(e) => {
+            return ChromaHealth {
+                reachable: false,
+                error: Some(e.to_string()),
+            }
+        }
+    };
+    match tokio::time::timeout(CHECK_TIMEOUT, client.health_check()).await {
+        Ok(Ok(true)) => ChromaHealth {
+            reachable: true,
+            error: None,
+        },
+        Ok(Ok(false)) => ChromaHealth {
+            reachable: false,
+            error: None,
+        },
+        Ok(Err(e)) => ChromaHealth {
+            reachable: false,
+            error: Some(e.to_string()),
+        },
+        Err(_) => ChromaHealth {
+            reachable: false,
+            error: Some("ChromaDB health check timed out".to_string()),
+        },
+    }
+}
+
+/// Overall service health for load balancers
+///
+/// Returns 200 while the database is reachable (status "ok", or "degraded"
+/// when the llama server or ChromaDB is down) and 503 when it isn't. The
+/// detailed body is returned either way.
+#[get("/api/health")]
+pub async fn get_service_health(
+    db_pool: web::Data<SqlitePool>,
+    server_state: web::Data<ServerStateHandle>,
+    chroma_address: web::Data<String>,
+    start_time: web::Data<ServiceStartTime>,
+) -> ActixResult<HttpResponse> {
+    let (database, chromadb) = futures::join!(
+        check_database(&db_pool),
+        check_chroma(chroma_address.as_str())
+    );
+    let llama_server = LlamaServerHealth {
+        ready: server_state.lock().unwrap().is_ready,
+    };
+
+    let status = if !database.reachable {
+        "unavailable"
+    } else if llama_server.ready && chromadb.reachable {
+        "ok"
+    } else {
+        "degraded"
+    };
+    let body = HealthResponse {
+        status: status.to_string(),
+        uptime_secs: start_time.0.elapsed().as_secs(),
+        database,
+        llama_server,
+        chromadb,
+    };
+
+    if body.database.reachable {
+        Ok(HttpResponse::Ok().json(body))
+    } else {
+        Ok(HttpResponse::ServiceUnavailable().json(body))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::llama_server::types::ServerState;
+    use crate::utils::database::connect_sqlite;
+    use actix_web::{test, App};
+    use std::sync::{Arc, Mutex};
+    use std::time::Instant;
+
+    async fn call_health(pool: SqlitePool) -> (u16, serde_json::Value) {
+        let server_state: ServerStateHandle = Arc::new(Mutex::new(ServerState {
+            is_ready: true,
+            generation: 0,
+            port: 8090,
+        }));
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(pool))
+                .app_data(web::Data::new(server_state))
+                // Nothing listens here, so ChromaDB is reported unreachable
+                .app_data(web::Data::new("http://127.0.0.1:1".to_string()))
+                .app_data(web::Data::new(ServiceStartTime(Instant::now())))
+                .service(get_service_health),
+        )
+        .await;
+
+        let req = test::TestRequest::get().uri("/api/health").to_request();
+        let resp = test::call_service(&app, req).await;
+        let status = resp.status().as_u16();
+        (status, test::read_body_json(resp).await)
+    }
+
+    #[actix_web::test]
+    async fn test_health_degraded_when_chroma_is_down() {
+        let pool = connect_sqlite(":memory:").await.unwrap();
+        let (status, body) = call_health(pool).await;
+
+        assert_eq!(status, 200);
+        assert_eq!(body["status"], "degraded");
+        assert_eq!(body["database"]["reachable"], true);
+        assert_eq!(body["llama_server"]["ready"], true);
+        assert_eq!(body["chromadb"]["reachable"], false);
+    }
+
+    #[actix_web::test]
+    async fn test_health_unavailable_when_database_is_down() {
+        let pool = connect_sqlite(":memory:").await.unwrap();
+        pool.close().await;
+        let (status, body) = call_health(pool).await;
+
+        assert_eq!(status, 503);
+        assert_eq!(body["status"], "unavailable");
+        assert_eq!(body["database"]["reachable"], false);
+        assert!(body["database"]["error"].is_string());
+    }
+}
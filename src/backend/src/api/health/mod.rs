@@ -0,0 +1,5 @@
+pub mod get_health;
+pub mod types;
+
+pub use get_health::get_service_health;
+pub use types::ServiceStartTime;
@@ -0,0 +1,37 @@
+use serde::Serialize;
+use std::time::Instant;
+
+/// When the service started, for reporting uptime
+#[derive(Clone, Copy)]
+pub struct ServiceStartTime(pub Instant);
+
+#[derive(Serialize, Debug)]
+pub struct DatabaseHealth {
+    pub reachable: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct LlamaServerHealth {
+    pub ready: bool,
+}
+
+#[derive(Serialize, Debug)]
+pub struct ChromaHealth {
+    pub reachable: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Body of `GET /api/health`
+#[derive(Serialize, Debug)]
+pub struct HealthResponse {
+    /// "ok" when everything is up, "degraded" when only optional
+    /// dependencies are down, "unavailable" when the database is down
+    pub status: String,
+    pub uptime_secs: u64,
+    pub database: DatabaseHealth,
+    pub llama_server: LlamaServerHealth,
+    pub chromadb: ChromaHealth,
+}
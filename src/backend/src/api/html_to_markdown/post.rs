@@ -1,8 +1,13 @@
-use actix_web::{post, web, Error as ActixError, HttpResponse};
+use actix_web::{web, Error as ActixError, HttpResponse, Resource};
 use serde::{Deserialize, Serialize};
 
+use crate::api::converters::limits::json_config;
+use crate::api::error::ApiError;
 use crate::markdown_utils::convert::{convert_html_to_markdown, ConversionConfig};
 
+/// Largest HTML document accepted
+pub const MAX_HTML_SIZE: usize = 10 * 1024 * 1024;
+
 #[derive(Deserialize, Serialize, Debug)]
 pub struct HtmlRequest {
     pub html: String,
@@ -35,7 +40,13 @@ pub struct MarkdownResponse {
     pub token_count: usize,
 }
 
-#[post("/api/html-to-markdown")]
+/// `POST /api/html-to-markdown`, accepting request bodies big enough for a `MAX_HTML_SIZE` input
+pub fn html_to_markdown_service() -> Resource {
+    web::resource("/api/html-to-markdown")
+        .app_data(json_config(MAX_HTML_SIZE))
+        .route(web::post().to(convert_html_to_markdown_endpoint))
+}
+
 pub async fn convert_html_to_markdown_endpoint(
     body: web::Json<HtmlRequest>,
 ) -> Result<HttpResponse, ActixError> {
@@ -43,17 +54,17 @@ pub async fn convert_html_to_markdown_endpoint(
 
     // Validate HTML is not empty
     if html.trim().is_empty() {
-        return Ok(HttpResponse::BadRequest().json(serde_json::json!({
-            "error": "HTML content cannot be empty"
-        })));
+        return Err(ApiError::BadRequest("HTML content cannot be empty".to_string()).into());
     }
 
     // Limit HTML size to prevent stack overflow (10MB max)
-    const MAX_HTML_SIZE: usize = 10 * 1024 * 1024;
     if html.len() > MAX_HTML_SIZE {
-        return Ok(HttpResponse::BadRequest().json(serde_json::json!({
-            "error": format!("HTML content too large: {} bytes (max {} bytes)", html.len(), MAX_HTML_SIZE)
-        })));
+        return Err(ApiError::PayloadTooLarge(format!(
+            "HTML content too large: {} bytes (max {} bytes)",
+            html.len(),
+            MAX_HTML_SIZE
+        ))
+        .into());
     }
 
     println!("📥 Received HTML (length: {})", html.len());
@@ -114,9 +125,7 @@ pub async fn convert_html_to_markdown_endpoint(
         }
         Err(error) => {
             println!("Conversion failed: {}", error);
-            Ok(HttpResponse::InternalServerError().json(serde_json::json!({
-                "error": error
-            })))
+            Err(ApiError::Internal(error.to_string()).into())
         }
     }
 }
@@ -124,12 +133,12 @@ pub async fn convert_html_to_markdown_endpoint(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::api::error::ErrorEnvelope;
     use actix_web::{test, App};
 
     #[actix_rt::test]
     async fn test_convert_html_to_markdown_empty_html() {
-        let mut app =
-            test::init_service(App::new().service(convert_html_to_markdown_endpoint)).await;
+        let mut app = test::init_service(App::new().service(html_to_markdown_service())).await;
 
         let req = test::TestRequest::post()
             .uri("/api/html-to-markdown")
@@ -148,10 +157,34 @@ mod tests {
         assert!(resp.status().is_client_error());
     }
 
+    #[actix_rt::test]
+    async fn test_convert_html_to_markdown_too_large() {
+        let app = test::init_service(App::new().service(html_to_markdown_service())).await;
+
+        // Over actix's default 2 MiB JSON limit, so it must reach the handler's check
+        let req = test::TestRequest::post()
+            .uri("/api/html-to-markdown")
+            .set_json(&HtmlRequest {
+                html: "a".repeat(MAX_HTML_SIZE + 1),
+                extract_body: true,
+                enable_preprocessing: false,
+                remove_navigation: false,
+                remove_forms: false,
+                preprocessing_preset: None,
+                count_tokens: false,
+            })
+            .to_request();
+
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 413);
+        let body: ErrorEnvelope = test::read_body_json(resp).await;
+        assert_eq!(body.code, "payload_too_large");
+        assert!(body.error.starts_with("HTML content too large"));
+    }
+
     #[actix_rt::test]
     async fn test_convert_html_to_markdown_valid_html() {
-        let mut app =
-            test::init_service(App::new().service(convert_html_to_markdown_endpoint)).await;
+        let mut app = test::init_service(App::new().service(html_to_markdown_service())).await;
 
         let req = test::TestRequest::post()
             .uri("/api/html-to-markdown")
@@ -172,8 +205,7 @@ mod tests {
 
     #[actix_rt::test]
     async fn test_convert_html_to_markdown_with_options() {
-        let mut app =
-            test::init_service(App::new().service(convert_html_to_markdown_endpoint)).await;
+        let mut app = test::init_service(App::new().service(html_to_markdown_service())).await;
 
         let req = test::TestRequest::post()
             .uri("/api/html-to-markdown")
@@ -0,0 +1,282 @@
+use crate::api::error::ApiError;
+use actix_multipart::Multipart;
+use actix_web::{post, Error as ActixError, HttpResponse};
+use futures_util::TryStreamExt;
+use image::codecs::jpeg::JpegEncoder;
+use image::imageops::FilterType;
+use image::{DynamicImage, ImageFormat};
+use std::io::Cursor;
+
+/// Largest image accepted
+pub const MAX_FILE_SIZE: usize = 25 * 1024 * 1024;
+
+/// JPEG quality used when the request doesn't set one
+pub const DEFAULT_QUALITY: u8 = 85;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TargetFormat {
+    Png,
+    Jpeg,
+    Webp,
+}
+
+impl TargetFormat {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "png" => Some(TargetFormat::Png),
+            "jpeg" | "jpg" => Some(TargetFormat::Jpeg),
+            "webp" => Some(TargetFormat::Webp),
+            _ => None,
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            TargetFormat::Png => "png",
+            TargetFormat::Jpeg => "jpg",
+            TargetFormat::Webp => "webp",
+        }
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            TargetFormat::Png => "image/png",
+            TargetFormat::Jpeg => "image/jpeg",
+            TargetFormat::Webp => "image/webp",
+        }
+    }
+}
+
+/// Shrink the image to fit within the given bounds, keeping its aspect ratio.
+/// Images that already fit are returned unchanged (never upscaled).
+fn resize_to_fit(
+    img: DynamicImage,
+    max_width: Option<u32>,
+    max_height: Option<u32>,
+) -> DynamicImage {
+    let bound_width = max_width.unwrap_or(u32::MAX);
+    let bound_height = max_height.unwrap_or(u32::MAX);
+    if img.width() <= bound_width && img.height() <= bound_height {
+        return img;
+    }
+    img.resize(bound_width, bound_height, FilterType::Lanczos3)
+}
+
+/// Encode to the target format. `quality` (1-100) only applies to JPEG; the
+/// WebP encoder is lossless.
+fn encode_image(img: &DynamicImage, format: TargetFormat, quality: u8) -> Result<Vec<u8>, String> {
+    let mut buffer = Vec::new();
+    let result = match format {
+        TargetFormat::Png => img.write_to(&mut Cursor::new(&mut buffer), ImageFormat::Png),
+        TargetFormat::Jpeg => {
+            // JPEG has no alpha channel
+            let rgb = DynamicImage::ImageRgb8(img.to_rgb8());
+            rgb.write_with_encoder(JpegEncoder::new_with_quality(&mut buffer, quality))
+        }
+        TargetFormat::Webp => DynamicImage::ImageRgba8(img.to_rgba8())
+            .write_to(&mut Cursor::new(&mut buffer), ImageFormat::WebP),
+    };
+    result.map_err(|e| format!("Failed to encode image: {}", e))?;
+    Ok(buffer)
+}
+
+#[derive(Debug, PartialEq)]
+pub enum ImageConvertError {
+    /// The upload isn't an image we can read (client error)
+    Decode(String),
+    /// Writing the target format failed (server error)
+    Encode(String),
+}
+
+/// Decode, optionally resize, and re-encode an image
+pub fn convert_image(
+    data: &[u8],
+    format: TargetFormat,
+    max_width: Option<u32>,
+    max_height: Option<u32>,
+    quality: u8,
+) -> Result<Vec<u8>, ImageConvertError> {
+    let img = image::load_from_memory(data)
+        .map_err(|e| ImageConvertError::Decode(format!("File is not a decodable image: {}", e)))?;
+    let img = resize_to_fit(img, max_width, max_height);
+    encode_image(&img, format, quality).map_err(ImageConvertError::Encode)
+}
+
+fn parse_dimension(name: &str, value: &str) -> Result<Option<u32>, String> {
+    let value = value.trim();
+    if value.is_empty() {
+        return Ok(None);
+    }
+    match value.parse::<u32>() {
+        Ok(v) if v > 0 => Ok(Some(v)),
+        _ => Err(format!("{} must be a positive integer", name)),
+    }
+}
+
+/// Convert an uploaded image to png, jpeg or webp.
+///
+/// Multipart fields: `file`, `format`, optional `max_width` / `max_height`
+/// (fit within, aspect ratio preserved) and `quality` (1-100, JPEG only).
+#[post("/api/image-convert")]
+pub async fn convert_image_endpoint(mut payload: Multipart) -> Result<HttpResponse, ActixError> {
+    let mut file_data: Option<Vec<u8>> = None;
+    let mut filename: Option<String> = None;
+    let mut fields: Vec<(String, String)> = Vec::new();
+
+    // Parse multipart form data
+    while let Some(mut field) = payload.try_next().await? {
+        let field_name = field.name().map(|name| name.to_string());
+
+        if field_name.as_deref() == Some("file") {
+            if let Some(name) = field
+                .content_disposition()
+                .as_ref()
+                .and_then(|cd| cd.get_filename())
+            {
+                filename = Some(name.to_string());
+            }
+
+            let mut data = Vec::new();
+            while let Some(chunk) = field.try_next().await? {
+                data.extend_from_slice(&chunk);
+                if data.len() > MAX_FILE_SIZE {
+                    return Err(ApiError::BadRequest(format!(
+                        "File too large (max {} bytes)",
+                        MAX_FILE_SIZE
+                    ))
+                    .into());
+                }
+            }
+            file_data = Some(data);
+        } else if let Some(name) = field_name {
+            let mut bytes = Vec::new();
+            while let Some(chunk) = field.try_next().await? {
+                bytes.extend_from_slice(&chunk);
+            }
+            fields.push((name, String::from_utf8_lossy(&bytes).to_string()));
+        }
+    }
+
+    let file_data = match file_data {
+        Some(data) if !data.is_empty() => data,
+        _ => return Err(ApiError::BadRequest("No file data received".to_string()).into()),
+    };
+
+    let field = |name: &str| {
+        fields
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    };
+
+    let format = match field("format").and_then(TargetFormat::parse) {
+        Some(format) => format,
+        None => {
+            return Err(
+                ApiError::BadRequest("format must be one of png, jpeg, webp".to_string()).into(),
+            )
+        }
+    };
+    let max_width = match parse_dimension("max_width", field("max_width").unwrap_or("")) {
+        Ok(v) => v,
+        Err(e) => return Err(ApiError::BadRequest(e).into()),
+    };
+    let max_height = match parse_dimension("max_height", field("max_height").unwrap_or("")) {
+        Ok(v) => v,
+        Err(e) => return Err(ApiError::BadRequest(e).into()),
+    };
+    let quality = match field("quality").map(str::trim).filter(|q| !q.is_empty()) {
+        Some(q) => match q.parse::<u8>() {
+            Ok(q) if (1..=100).contains(&q) => q,
+            _ => {
+                return Err(
+                    ApiError::BadRequest("quality must be between 1 and 100".to_string()).into(),
+                )
+            }
+        },
+        None => DEFAULT_QUALITY,
+    };
+
+    // Decoding and resizing are CPU heavy, keep them off the async workers
+    let result = actix_web::web::block(move || {
+        convert_image(&file_data, format, max_width, max_height, quality)
+    })
+    .await
+    .map_err(actix_web::error::ErrorInternalServerError)?;
+
+    let converted = match result {
+        Ok(bytes) => bytes,
+        Err(ImageConvertError::Decode(e)) => return Err(ApiError::BadRequest(e).into()),
+        Err(ImageConvertError::Encode(e)) => return Err(ApiError::Internal(e.to_string()).into()),
+    };
+
+    let stem = filename
+        .as_deref()
+        .and_then(|name| std::path::Path::new(name).file_stem())
+        .and_then(|stem| stem.to_str())
+        .unwrap_or("image");
+
+    Ok(HttpResponse::Ok()
+        .content_type(format.content_type())
+        .append_header((
+            "Content-Disposition",
+            format!("attachment; filename=\"{}.{}\"", stem, format.extension()),
+        ))
+        .body(converted))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{ImageBuffer, Rgba};
+
+    fn sample_png(width: u32, height: u32) -> Vec<u8> {
+        let img = ImageBuffer::from_pixel(width, height, Rgba([200u8, 30, 30, 128]));
+        let mut buffer = Vec::new();
+        DynamicImage::ImageRgba8(img)
+            .write_to(&mut Cursor::new(&mut buffer), ImageFormat::Png)
+            .unwrap();
+        buffer
+    }
+
+    #[test]
+    fn test_convert_png_to_jpeg_with_resize() {
+        let converted =
+            convert_image(&sample_png(40, 20), TargetFormat::Jpeg, Some(20), None, 80).unwrap();
+
+        assert_eq!(image::guess_format(&converted).unwrap(), ImageFormat::Jpeg);
+        let img = image::load_from_memory(&converted).unwrap();
+        assert_eq!((img.width(), img.height()), (20, 10));
+    }
+
+    #[test]
+    fn test_convert_to_webp_keeps_small_images() {
+        let converted = convert_image(
+            &sample_png(8, 8),
+            TargetFormat::Webp,
+            Some(100),
+            Some(100),
+            80,
+        )
+        .unwrap();
+
+        assert_eq!(image::guess_format(&converted).unwrap(), ImageFormat::WebP);
+        let img = image::load_from_memory(&converted).unwrap();
+        assert_eq!((img.width(), img.height()), (8, 8));
+    }
+
+    #[test]
+    fn test_convert_rejects_non_image() {
+        match convert_image(b"not an image", TargetFormat::Png, None, None, 80) {
+            Err(ImageConvertError::Decode(e)) => assert!(e.contains("not a decodable image")),
+            other => panic!("expected a decode error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_target_format_parse() {
+        assert_eq!(TargetFormat::parse("JPG"), Some(TargetFormat::Jpeg));
+        assert_eq!(TargetFormat::parse("webp"), Some(TargetFormat::Webp));
+        assert_eq!(TargetFormat::parse("gif"), None);
+    }
+}
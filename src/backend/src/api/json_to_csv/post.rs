@@ -0,0 +1,168 @@
+use crate::api::converters::limits::json_config;
+use crate::api::error::ApiError;
+use actix_web::{web, Error as ActixError, HttpResponse, Resource};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Largest JSON document accepted
+pub const MAX_JSON_SIZE: usize = 10 * 1024 * 1024;
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct JsonToCsvRequest {
+    pub json: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct JsonToCsvResponse {
+    pub csv: String,
+    pub columns: Vec<String>,
+    pub rows: usize,
+}
+
+fn cell_value(value: &Value) -> Option<String> {
+    match value {
+        Value::Null => Some(String::new()),
+        Value::String(s) => Some(s.clone()),
+        Value::Bool(_) | Value::Number(_) => Some(value.to_string()),
+        Value::Array(_) | Value::Object(_) => None,
+    }
+}
+
+/// Convert an array of flat JSON objects to CSV (RFC 4180 quoting).
+/// Columns are the union of the keys of all items; missing keys become empty cells.
+pub fn json_to_csv(input: &str) -> Result<JsonToCsvResponse, String> {
+    let value: Value = serde_json::from_str(input).map_err(|e| format!("Invalid JSON: {}", e))?;
+    let items = value
+        .as_array()
+        .ok_or_else(|| "JSON must be an array of objects".to_string())?;
+
+    let mut columns: Vec<String> = Vec::new();
+    for (index, item) in items.iter().enumerate() {
+        let object = item
+            .as_object()
+            .ok_or_else(|| format!("Item {} is not an object", index))?;
+        for key in object.keys() {
+            if !columns.contains(key) {
+                columns.push(key.clone());
+            }
+        }
+    }
+
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    writer
+        .write_record(&columns)
+        .map_err(|e| format!("Failed to write CSV: {}", e))?;
+
+    for (index, item) in items.iter().enumerate() {
+        let object = item.as_object().expect("checked above");
+        let mut record = Vec::with_capacity(columns.len());
+        for column in &columns {
+            let cell = match object.get(column) {
+                Some(value) => cell_value(value).ok_or_else(|| {
+                    format!(
+                        "Item {} field '{}' is nested; only flat objects are supported",
+                        index, column
+                    )
+                })?,
+                None => String::new(),
+            };
+            record.push(cell);
+        }
+        writer
+            .write_record(&record)
+            .map_err(|e| format!("Failed to write CSV: {}", e))?;
+    }
+
+    let bytes = writer
+        .into_inner()
+        .map_err(|e| format!("Failed to write CSV: {}", e))?;
+    let csv = String::from_utf8(bytes).map_err(|e| format!("Failed to write CSV: {}", e))?;
+    Ok(JsonToCsvResponse {
+        csv,
+        columns,
+        rows: items.len(),
+    })
+}
+
+/// `POST /api/json-to-csv`, accepting request bodies big enough for a `MAX_JSON_SIZE` input
+pub fn json_to_csv_service() -> Resource {
+    web::resource("/api/json-to-csv")
+        .app_data(json_config(MAX_JSON_SIZE))
+        .route(web::post().to(convert_json_to_csv))
+}
+
+pub async fn convert_json_to_csv(
+    body: web::Json<JsonToCsvRequest>,
+) -> Result<HttpResponse, ActixError> {
+    if body.json.trim().is_empty() {
+        return Err(ApiError::BadRequest("JSON cannot be empty".to_string()).into());
+    }
+
+    if body.json.len() > MAX_JSON_SIZE {
+        return Err(ApiError::PayloadTooLarge(format!(
+            "JSON too large: {} bytes (max {} bytes)",
+            body.json.len(),
+            MAX_JSON_SIZE
+        ))
+        .into());
+    }
+
+    match json_to_csv(&body.json) {
+        Ok(response) => Ok(HttpResponse::Ok().json(response)),
+        Err(e) => Err(ApiError::BadRequest(e).into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[actix_web::test]
+    async fn test_convert_json_to_csv_too_large() {
+        use crate::api::error::ErrorEnvelope;
+        use actix_web::{test, App};
+
+        let app = test::init_service(App::new().service(json_to_csv_service())).await;
+
+        // Over actix's default 2 MiB JSON limit, so it must reach the handler's check
+        let req = test::TestRequest::post()
+            .uri("/api/json-to-csv")
+            .set_json(JsonToCsvRequest {
+                json: format!("[{}]", " ".repeat(MAX_JSON_SIZE)),
+            })
+            .to_request();
+
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 413);
+        let body: ErrorEnvelope = test::read_body_json(resp).await;
+        assert_eq!(body.code, "payload_too_large");
+        assert!(body.error.starts_with("JSON too large"));
+    }
+
+    #[test]
+    fn test_json_to_csv_unions_keys_and_quotes() {
+        let input = r#"[{"name":"Ada","note":"says \"hi\", twice"},{"name":"Linus","age":54}]"#;
+        let response = json_to_csv(input).unwrap();
+
+        assert_eq!(response.rows, 2);
+        assert_eq!(response.columns, vec!["name", "note", "age"]);
+        assert_eq!(
+            response.csv,
+            "name,note,age\nAda,\"says \"\"hi\"\", twice\",\nLinus,,54\n"
+        );
+    }
+
+    #[test]
+    fn test_json_to_csv_rejects_nested_values() {
+        let err = json_to_csv(r#"[{"a":{"b":1}}]"#).unwrap_err();
+        assert!(err.contains("nested"));
+    }
+
+    #[test]
+    fn test_json_to_csv_rejects_non_array() {
+        assert!(json_to_csv(r#"{"a":1}"#).is_err());
+        assert!(json_to_csv(r#"[1, 2]"#)
+            .unwrap_err()
+            .contains("not an object"));
+    }
+}
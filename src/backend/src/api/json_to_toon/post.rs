@@ -1,9 +1,16 @@
+use crate::api::converters::limits::json_body_limit;
+use crate::api::error::ApiError;
 use actix_multipart::Multipart;
 use actix_web::{post, web, Error as ActixError, HttpRequest, HttpResponse};
 use futures_util::{StreamExt, TryStreamExt};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
+/// Largest uploaded JSON file accepted
+pub const MAX_FILE_SIZE: usize = 10 * 1024 * 1024;
+/// Largest JSON content accepted
+pub const MAX_JSON_SIZE: usize = 10 * 1024 * 1024;
+
 #[derive(Deserialize, Serialize, Debug)]
 pub struct JsonToToonRequest {
     pub json: String,
@@ -49,11 +56,13 @@ pub async fn convert_json_to_toon(
                 }
 
                 // Validate file size (10MB max)
-                const MAX_FILE_SIZE: usize = 10 * 1024 * 1024;
                 if data.len() > MAX_FILE_SIZE {
-                    return Ok(HttpResponse::BadRequest().json(serde_json::json!({
-                        "error": format!("File too large: {} bytes (max {} bytes)", data.len(), MAX_FILE_SIZE)
-                    })));
+                    return Err(ApiError::BadRequest(format!(
+                        "File too large: {} bytes (max {} bytes)",
+                        data.len(),
+                        MAX_FILE_SIZE
+                    ))
+                    .into());
                 }
 
                 // Try to parse as UTF-8 JSON
@@ -62,9 +71,11 @@ pub async fn convert_json_to_toon(
                         json_data = Some(json_str);
                     }
                     Err(e) => {
-                        return Ok(HttpResponse::BadRequest().json(serde_json::json!({
-                            "error": format!("File is not valid UTF-8: {}", e)
-                        })));
+                        return Err(ApiError::BadRequest(format!(
+                            "File is not valid UTF-8: {}",
+                            e
+                        ))
+                        .into());
                     }
                 }
             } else if field_name == Some("json") {
@@ -90,7 +101,7 @@ pub async fn convert_json_to_toon(
         }
 
         let json_string = json_data.ok_or_else(|| {
-            actix_web::error::ErrorBadRequest("No JSON data provided (file or json field)")
+            ApiError::BadRequest("No JSON data provided (file or json field)".to_string())
         })?;
 
         (json_string, count_tokens)
@@ -100,14 +111,21 @@ pub async fn convert_json_to_toon(
         let mut stream = payload;
         while let Some(item) = stream.next().await {
             let chunk = item?;
+            if body.len() + chunk.len() > json_body_limit(MAX_JSON_SIZE) {
+                return Err(ApiError::PayloadTooLarge(format!(
+                    "Request body too large (max {} bytes of JSON content)",
+                    MAX_JSON_SIZE
+                ))
+                .into());
+            }
             body.extend_from_slice(&chunk);
         }
 
         let body_str = String::from_utf8(body.to_vec())
-            .map_err(|_| actix_web::error::ErrorBadRequest("Invalid UTF-8 in request body"))?;
+            .map_err(|_| ApiError::BadRequest("Invalid UTF-8 in request body".to_string()))?;
 
         let body: JsonToToonRequest = serde_json::from_str(&body_str)
-            .map_err(|_| actix_web::error::ErrorBadRequest("Invalid JSON request body"))?;
+            .map_err(|_| ApiError::BadRequest("Invalid JSON request body".to_string()))?;
 
         (body.json, body.count_tokens)
     };
@@ -115,17 +133,17 @@ pub async fn convert_json_to_toon(
     // Validate and parse JSON
     let json_string = json_string.trim();
     if json_string.is_empty() {
-        return Ok(HttpResponse::BadRequest().json(serde_json::json!({
-            "error": "JSON content cannot be empty"
-        })));
+        return Err(ApiError::BadRequest("JSON content cannot be empty".to_string()).into());
     }
 
     // Limit JSON size (10MB max)
-    const MAX_JSON_SIZE: usize = 10 * 1024 * 1024;
     if json_string.len() > MAX_JSON_SIZE {
-        return Ok(HttpResponse::BadRequest().json(serde_json::json!({
-            "error": format!("JSON content too large: {} bytes (max {} bytes)", json_string.len(), MAX_JSON_SIZE)
-        })));
+        return Err(ApiError::PayloadTooLarge(format!(
+            "JSON content too large: {} bytes (max {} bytes)",
+            json_string.len(),
+            MAX_JSON_SIZE
+        ))
+        .into());
     }
 
     println!("📥 Received JSON (length: {} chars)", json_string.len());
@@ -135,9 +153,7 @@ pub async fn convert_json_to_toon(
         Ok(value) => value,
         Err(e) => {
             println!("Invalid JSON: {}", e);
-            return Ok(HttpResponse::BadRequest().json(serde_json::json!({
-                "error": format!("Invalid JSON: {}", e)
-            })));
+            return Err(ApiError::BadRequest(format!("Invalid JSON: {}", e)).into());
         }
     };
 
@@ -20,23 +20,29 @@ pub struct ConfigResponse {
     pub model: Option<String>,
 }
 
+impl From<&Config> for ConfigResponse {
+    fn from(config: &Config) -> Self {
+        Self {
+            hf_model: config.hf_model.clone(),
+            ctx_size: config.ctx_size,
+            threads: config.threads,
+            threads_batch: config.threads_batch,
+            predict: config.predict,
+            batch_size: config.batch_size,
+            ubatch_size: config.ubatch_size,
+            flash_attn: config.flash_attn,
+            mlock: config.mlock,
+            no_mmap: config.no_mmap,
+            gpu_layers: config.gpu_layers,
+            model: config.model.clone(),
+        }
+    }
+}
+
 #[get("/api/llama-server/config")]
 pub async fn get_llama_config(config: web::Data<Arc<Mutex<Config>>>) -> ActixResult<HttpResponse> {
     let config_guard = config.lock().unwrap();
-    Ok(HttpResponse::Ok().json(ConfigResponse {
-        hf_model: config_guard.hf_model.clone(),
-        ctx_size: config_guard.ctx_size,
-        threads: config_guard.threads,
-        threads_batch: config_guard.threads_batch,
-        predict: config_guard.predict,
-        batch_size: config_guard.batch_size,
-        ubatch_size: config_guard.ubatch_size,
-        flash_attn: config_guard.flash_attn,
-        mlock: config_guard.mlock,
-        no_mmap: config_guard.no_mmap,
-        gpu_layers: config_guard.gpu_layers,
-        model: config_guard.model.clone(),
-    }))
+    Ok(HttpResponse::Ok().json(ConfigResponse::from(&*config_guard)))
 }
 
 #[cfg(test)]
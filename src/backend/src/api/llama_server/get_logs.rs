@@ -1,36 +1,149 @@
+use actix_web::http::header::{ContentDisposition, DispositionParam, DispositionType};
 use actix_web::{get, web, HttpResponse, Result as ActixResult};
 use serde::{Deserialize, Serialize};
 
-use crate::api::llama_server::types::{LogBuffer, LogSource};
+use crate::api::llama_server::logs_reader::strip_ansi;
+use crate::api::llama_server::types::{LogBuffer, LogEntry, LogSource};
+
+/// Severity parsed from a llama-server log line, ordered from least to most severe
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum LogLevel {
+    Debug,
+    Info,
+    Warn,
+    Error,
+}
+
+impl LogLevel {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "debug" | "d" => Some(LogLevel::Debug),
+            "info" | "i" => Some(LogLevel::Info),
+            "warn" | "warning" | "w" => Some(LogLevel::Warn),
+            "error" | "e" => Some(LogLevel::Error),
+            _ => None,
+        }
+    }
+}
+
+/// Work out the severity of a log line.
+///
+/// llama.cpp prefixes lines with a single-letter level (`E`, `W`, `I`, `D`) when
+/// timestamps are enabled; otherwise fall back to keywords in the text.
+pub fn parse_log_level(line: &str) -> LogLevel {
+    let clean = strip_ansi(line);
+    for token in clean.split_whitespace().take(2) {
+        if token.len() == 1 {
+            if let Some(level) = LogLevel::parse(token) {
+                return level;
+            }
+        }
+    }
+
+    let lower = clean.to_lowercase();
+    if lower.contains("error") || lower.contains("failed") {
+        LogLevel::Error
+    } else if lower.contains("warn") {
+        LogLevel::Warn
+    } else {
+        LogLevel::Info
+    }
+}
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct LogLine {
     pub timestamp: u64,
     pub line: String,
     pub source: String,
+    pub level: LogLevel,
+}
+
+impl From<&LogEntry> for LogLine {
+    fn from(entry: &LogEntry) -> Self {
+        Self {
+            timestamp: entry.timestamp,
+            line: entry.line.clone(),
+            source: match entry.source {
+                LogSource::Stdout => "stdout".to_string(),
+                LogSource::Stderr => "stderr".to_string(),
+            },
+            level: parse_log_level(&entry.line),
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct LogsResponse {
     pub logs: Vec<LogLine>,
+    /// Offset to pass as `since` to fetch only lines added after this response
+    pub next_since: usize,
 }
 
+#[derive(Deserialize, Debug)]
+pub struct LogsQuery {
+    /// Minimum severity to return (debug, info, warn, error)
+    pub level: Option<String>,
+    /// Skip lines before this offset in the buffer
+    pub since: Option<usize>,
+}
+
+/// Buffered llama-server log lines, optionally filtered by minimum level and
+/// a line offset into the buffer.
 #[get("/api/llama-server/logs")]
-pub async fn get_llama_logs(log_buffer: web::Data<LogBuffer>) -> ActixResult<HttpResponse> {
+pub async fn get_llama_logs(
+    log_buffer: web::Data<LogBuffer>,
+    query: web::Query<LogsQuery>,
+) -> ActixResult<HttpResponse> {
+    let min_level = match query.level.as_deref().filter(|l| !l.trim().is_empty()) {
+        Some(value) => match LogLevel::parse(value) {
+            Some(level) => Some(level),
+            None => {
+                return Ok(HttpResponse::BadRequest().json(serde_json::json!({
+                    "error": format!(
+                        "Unknown log level '{}', expected debug, info, warn or error",
+                        value
+                    )
+                })))
+            }
+        },
+        None => None,
+    };
+
     let buffer = log_buffer.lock().unwrap();
     let logs: Vec<LogLine> = buffer
         .iter()
-        .map(|entry| LogLine {
-            timestamp: entry.timestamp,
-            line: entry.line.clone(),
-            source: match entry.source {
-                LogSource::Stdout => "stdout".to_string(),
-                LogSource::Stderr => "stderr".to_string(),
-            },
-        })
+        .skip(query.since.unwrap_or(0))
+        .map(LogLine::from)
+        .filter(|line| min_level.is_none_or(|min| line.level >= min))
         .collect();
 
-    Ok(HttpResponse::Ok().json(LogsResponse { logs }))
+    Ok(HttpResponse::Ok().json(LogsResponse {
+        logs,
+        next_since: buffer.len(),
+    }))
+}
+
+/// Download the whole log buffer as a plain text file
+#[get("/api/llama-server/logs/download")]
+pub async fn get_llama_logs_download(
+    log_buffer: web::Data<LogBuffer>,
+) -> ActixResult<HttpResponse> {
+    let body = {
+        let buffer = log_buffer.lock().unwrap();
+        buffer
+            .iter()
+            .map(|entry| format!("{}\n", entry.line))
+            .collect::<String>()
+    };
+
+    Ok(HttpResponse::Ok()
+        .content_type("text/plain; charset=utf-8")
+        .insert_header(ContentDisposition {
+            disposition: DispositionType::Attachment,
+            parameters: vec![DispositionParam::Filename("llama-server.log".to_string())],
+        })
+        .body(body))
 }
 
 #[cfg(test)]
@@ -98,4 +211,96 @@ mod tests {
         assert_eq!(body.logs[1].line, "Error log line");
         assert_eq!(body.logs[1].source, "stderr");
     }
+
+    fn test_buffer(lines: &[&str]) -> LogBuffer {
+        let buffer = lines
+            .iter()
+            .enumerate()
+            .map(|(i, line)| LogEntry {
+                timestamp: i as u64,
+                line: line.to_string(),
+                source: LogSource::Stderr,
+            })
+            .collect();
+        Arc::new(Mutex::new(buffer))
+    }
+
+    #[actix_web::test]
+    async fn test_parse_log_level() {
+        assert_eq!(
+            parse_log_level("0.00.120.456 W ctx is too small"),
+            LogLevel::Warn
+        );
+        assert_eq!(
+            parse_log_level("\u{1b}[31mE\u{1b}[0m main: oops"),
+            LogLevel::Error
+        );
+        assert_eq!(parse_log_level("failed to load model"), LogLevel::Error);
+        assert_eq!(parse_log_level("main: server is listening"), LogLevel::Info);
+    }
+
+    #[actix_web::test]
+    async fn test_get_llama_logs_filters_level_and_since() {
+        let log_buffer = test_buffer(&[
+            "warning: old",
+            "loading model",
+            "error: failed to allocate",
+            "warning: low memory",
+        ]);
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(log_buffer))
+                .service(get_llama_logs),
+        )
+        .await;
+
+        let req = test::TestRequest::get()
+            .uri("/api/llama-server/logs?level=warn&since=1")
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert!(resp.status().is_success());
+
+        let body: LogsResponse = test::read_body_json(resp).await;
+        assert_eq!(body.next_since, 4);
+        let lines: Vec<&str> = body.logs.iter().map(|l| l.line.as_str()).collect();
+        assert_eq!(
+            lines,
+            vec!["error: failed to allocate", "warning: low memory"]
+        );
+
+        let req = test::TestRequest::get()
+            .uri("/api/llama-server/logs?level=verbose")
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status().as_u16(), 400);
+    }
+
+    #[actix_web::test]
+    async fn test_get_llama_logs_download() {
+        let log_buffer = test_buffer(&["first", "second"]);
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(log_buffer))
+                .service(get_llama_logs_download),
+        )
+        .await;
+
+        let req = test::TestRequest::get()
+            .uri("/api/llama-server/logs/download")
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert!(resp.status().is_success());
+        assert!(resp
+            .headers()
+            .get("content-disposition")
+            .unwrap()
+            .to_str()
+            .unwrap()
+            .contains("llama-server.log"));
+
+        let body = test::read_body(resp).await;
+        assert_eq!(body, "first\nsecond\n");
+    }
 }
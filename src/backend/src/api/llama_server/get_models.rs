@@ -1,4 +1,5 @@
 use actix_web::{get, HttpResponse, Result as ActixResult};
+use log::{debug, info, warn};
 use serde::Serialize;
 use std::fs;
 use std::path::PathBuf;
@@ -30,17 +31,17 @@ pub async fn get_llama_models() -> ActixResult<HttpResponse> {
     let mut models = Vec::new();
 
     if cache_dir.exists() {
-        println!("📂 Scanning for GGUF models in: {:?}", cache_dir);
+        info!("📂 Scanning for GGUF models in: {:?}", cache_dir);
         match scan_directory_for_gguf(&cache_dir, &mut models) {
             Ok(_) => {
-                println!("✅ Found {} GGUF models", models.len());
+                info!("✅ Found {} GGUF models", models.len());
             }
             Err(e) => {
-                println!("⚠️  Error scanning directory: {}", e);
+                warn!("⚠️  Error scanning directory: {}", e);
             }
         }
     } else {
-        println!("⚠️  Cache directory does not exist: {:?}", cache_dir);
+        warn!("⚠️  Cache directory does not exist: {:?}", cache_dir);
     }
 
     Ok(HttpResponse::Ok().json(ModelsResponse {
@@ -144,7 +145,7 @@ fn parse_gguf_to_hf_format(filename: &str) -> Option<String> {
 
             // Reconstruct as user/model:quant
             let result = format!("{}/{}:{}", user, model_name, quant_str);
-            println!(
+            debug!(
                 "🔍 Parsed GGUF: {} -> {} (quant found: {})",
                 filename, result, quant_str
             );
@@ -158,7 +159,7 @@ fn parse_gguf_to_hf_format(filename: &str) -> Option<String> {
         // Try to use first two parts as user/model
         let model_name = parts[1];
         let result = format!("{}/{}", user, model_name);
-        println!("🔍 Parsed GGUF (no quant): {} -> {}", filename, result);
+        debug!("🔍 Parsed GGUF (no quant): {} -> {}", filename, result);
         return Some(result);
     }
 
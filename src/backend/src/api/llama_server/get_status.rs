@@ -1,12 +1,36 @@
 use actix_web::{get, web, HttpResponse, Result as ActixResult};
 use serde::{Deserialize, Serialize};
+use std::time::Duration;
 
 use crate::api::llama_server::types::{ProcessHandle, ServerStateHandle};
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct LlamaServerStatus {
     pub active: bool,
     pub port: u16,
+    /// The startup logs reported the model as loaded
+    #[serde(default)]
+    pub model_loaded: bool,
+    /// Something accepts connections on the port (the model may still be loading)
+    #[serde(default)]
+    pub port_open: bool,
+}
+
+impl LlamaServerStatus {
+    pub fn stopped(port: u16) -> Self {
+        Self {
+            active: false,
+            port,
+            model_loaded: false,
+            port_open: false,
+        }
+    }
+}
+
+/// Quick TCP probe of the llama-server port on loopback
+pub fn port_is_open(port: u16) -> bool {
+    let addr = std::net::SocketAddr::from(([127, 0, 0, 1], port));
+    std::net::TcpStream::connect_timeout(&addr, Duration::from_millis(250)).is_ok()
 }
 
 #[get("/api/llama-server/status")]
@@ -46,6 +70,8 @@ pub async fn get_llama_server_status(
         state_guard.is_ready
     };
 
+    let port = server_state.lock().unwrap().port;
+
     // Determine final active status
     let active = if is_active {
         // If we are managing the process, explicitly wait for the ready signal
@@ -57,7 +83,12 @@ pub async fn get_llama_server_status(
         false
     };
 
-    Ok(HttpResponse::Ok().json(LlamaServerStatus { active, port: 8080 }))
+    Ok(HttpResponse::Ok().json(LlamaServerStatus {
+        active,
+        port,
+        model_loaded: is_active && is_ready,
+        port_open: is_active && port_is_open(port),
+    }))
 }
 
 #[cfg(test)]
@@ -73,6 +104,7 @@ mod tests {
         let server_state: ServerStateHandle = Arc::new(Mutex::new(ServerState {
             is_ready: false,
             generation: 0,
+            port: 8080,
         }));
 
         let app = test::init_service(
@@ -100,6 +132,7 @@ mod tests {
         let server_state: ServerStateHandle = Arc::new(Mutex::new(ServerState {
             is_ready: true,
             generation: 0,
+            port: 8080,
         }));
 
         let app = test::init_service(
@@ -118,5 +151,8 @@ mod tests {
         assert!(resp.status().is_success());
         let body: LlamaServerStatus = test::read_body_json(resp).await;
         assert_eq!(body.port, 8080);
+        // A stale ready flag without a managed process isn't a loaded model
+        assert!(!body.model_loaded);
+        assert!(!body.port_open);
     }
 }
@@ -1,5 +1,10 @@
-use crate::api::llama_server::types::{LogBuffer, LogEntry, LogSource, ServerStateHandle};
+use crate::api::llama_server::get_status::LlamaServerStatus;
+use crate::api::llama_server::types::{
+    log_buffer_max, push_log_entry, LogBuffer, LogEntry, LogSource, ServerStateHandle,
+    DEFAULT_LLM_PORT,
+};
 use crate::api::llama_server::websocket::WebSocketState;
+use log::{debug, error, info, warn};
 use std::io::{BufRead, BufReader};
 use std::process::{ChildStderr, ChildStdout};
 use std::sync::Arc;
@@ -76,7 +81,7 @@ fn read_stdout_stream(
                 );
             }
             Err(e) => {
-                eprintln!("Error reading stdout line: {}", e);
+                error!("Error reading stdout line: {}", e);
                 break;
             }
         }
@@ -108,13 +113,52 @@ fn read_stderr_stream(
                 );
             }
             Err(e) => {
-                eprintln!("Error reading stderr line: {}", e);
+                error!("Error reading stderr line: {}", e);
                 break;
             }
         }
     }
 }
 
+/// Remove ANSI escape sequences (llama-server colors its output)
+pub(crate) fn strip_ansi(line: &str) -> String {
+    let mut out = String::with_capacity(line.len());
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        if c == '\u{1b}' {
+            // Skip "[...m" style CSI sequences
+            for next in chars.by_ref() {
+                if next.is_ascii_alphabetic() {
+                    break;
+                }
+            }
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+/// Startup progress signalled by a llama-server log line
+#[derive(Debug, PartialEq)]
+enum StartupMarker {
+    /// The HTTP port is bound but the model may still be loading
+    PortOpen,
+    /// The model finished loading and requests can be served
+    ModelLoaded,
+}
+
+fn startup_marker(line: &str) -> Option<StartupMarker> {
+    let line = strip_ansi(line).to_lowercase();
+    if line.contains("model loaded") || line.contains("server is listening on http") {
+        Some(StartupMarker::ModelLoaded)
+    } else if line.contains("http server listening") || line.contains("http server is listening") {
+        Some(StartupMarker::PortOpen)
+    } else {
+        None
+    }
+}
+
 fn process_log_line(
     line: String,
     log_buffer: LogBuffer,
@@ -144,13 +188,10 @@ fn process_log_line(
         source: source.clone(),
     };
 
-    // Add to log buffer (keep last 1000 lines)
+    // Add to log buffer (keep the last `--llama_log_lines` lines)
     {
         let mut buffer = log_buffer.lock().unwrap();
-        buffer.push_back(log_entry.clone());
-        if buffer.len() > 1000 {
-            buffer.pop_front();
-        }
+        push_log_entry(&mut buffer, log_entry.clone(), log_buffer_max());
     }
 
     // Broadcast log via WebSocket
@@ -166,48 +207,64 @@ fn process_log_line(
         };
         state.broadcast_log(log_line);
     } else {
-        println!("⚠️  WebSocket state not available for broadcasting logs");
+        warn!("⚠️  WebSocket state not available for broadcasting logs");
     }
 
-    // Check if server is ready - Generalize check to support any port/host
-    // We check for tokens individually to handle potential ANSI color codes in the output
-    let is_ready_msg =
-        (line.contains("main") && line.contains("listening") && line.contains("http"))
-            || line.contains("HTTP server listening");
-
-    if is_ready_msg {
-        println!("✅ Detected server ready message in line: '{}'", line);
-        let mut state = server_state.lock().unwrap();
-        // Double check generation before setting ready
-        if state.generation == generation {
-            state.is_ready = true;
-            drop(state);
-
-            // Broadcast active status
+    // Only a loaded model counts as ready; an open port alone means "loading model"
+    match startup_marker(&line) {
+        Some(StartupMarker::ModelLoaded) => {
+            info!("✅ Detected model loaded message in line: '{}'", line);
+            let mut state = server_state.lock().unwrap();
+            // Double check generation before setting ready
+            if state.generation == generation {
+                state.is_ready = true;
+                drop(state);
+
+                // Broadcast active status
+                if let Some(ref state) = ws_state {
+                    let actual_port = port.unwrap_or(DEFAULT_LLM_PORT);
+                    info!(
+                        "📡 Broadcasting server ready status on port {}",
+                        actual_port
+                    );
+                    state.broadcast_status(&LlamaServerStatus {
+                        active: true,
+                        port: actual_port,
+                        model_loaded: true,
+                        port_open: true,
+                    });
+                }
+            } else {
+                let msg = format!(
+                    "❌ SYSTEM: Generation mismatch ignoring ready signal: {} != {}",
+                    state.generation, generation
+                );
+                warn!("{}", msg);
+            }
+        }
+        Some(StartupMarker::PortOpen) => {
+            info!("⏳ llama-server port is open, waiting for the model to load");
             if let Some(ref state) = ws_state {
-                let actual_port = port.unwrap_or(8080);
-                println!(
-                    "📡 Broadcasting server ready status on port {}",
-                    actual_port
+                state.broadcast_status(&LlamaServerStatus {
+                    active: false,
+                    port: port.unwrap_or(DEFAULT_LLM_PORT),
+                    model_loaded: false,
+                    port_open: true,
+                });
+            }
+        }
+        None => {
+            if line.contains("listening") {
+                let msg = format!(
+                    "❓ SYSTEM: Line contains 'listening' but failed full check: '{}'",
+                    line
                 );
-                state.broadcast_status(true, actual_port);
+                debug!("{}", msg);
             }
-        } else {
-            let msg = format!(
-                "❌ SYSTEM: Generation mismatch ignoring ready signal: {} != {}",
-                state.generation, generation
-            );
-            println!("{}", msg);
         }
-    } else if line.contains("listening") {
-        let msg = format!(
-            "❓ SYSTEM: Line contains 'listening' but failed full check: '{}'",
-            line
-        );
-        println!("{}", msg);
     }
 
-    println!(
+    debug!(
         "📝 [{}] {}",
         if source == LogSource::Stdout {
             "stdout"
@@ -230,6 +287,7 @@ mod tests {
         let server_state: ServerStateHandle = Arc::new(std::sync::Mutex::new(ServerState {
             is_ready: false,
             generation: 1,
+            port: 8099,
         }));
         let line = "main: server is listening on http://0.0.0.0:8099".to_string();
 
@@ -253,6 +311,7 @@ mod tests {
         let server_state: ServerStateHandle = Arc::new(std::sync::Mutex::new(ServerState {
             is_ready: false,
             generation: 1,
+            port: 8099,
         }));
         // Simulating ANSI color codes
         let line = "\u{1b}[32mmain\u{1b}[0m: server is \u{1b}[1mlistening\u{1b}[0m on \u{1b}[34mhttp\u{1b}[0m://0.0.0.0:8099".to_string();
@@ -277,6 +336,7 @@ mod tests {
         let server_state: ServerStateHandle = Arc::new(std::sync::Mutex::new(ServerState {
             is_ready: false,
             generation: 1,
+            port: 8099,
         }));
         let line = "Some random log line".to_string();
 
@@ -297,12 +357,51 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_process_log_line_port_open_is_not_ready() {
+        let log_buffer: LogBuffer = Arc::new(std::sync::Mutex::new(VecDeque::new()));
+        let server_state: ServerStateHandle = Arc::new(std::sync::Mutex::new(ServerState {
+            is_ready: false,
+            generation: 1,
+            port: 8099,
+        }));
+        let line =
+            "main: HTTP server is listening, hostname: 127.0.0.1, port: 8099, http threads: 7"
+                .to_string();
+
+        process_log_line(
+            line,
+            log_buffer.clone(),
+            server_state.clone(),
+            LogSource::Stdout,
+            None,
+            1,
+            Some(8099),
+        );
+        assert!(
+            !server_state.lock().unwrap().is_ready,
+            "An open port alone should not mark the server ready"
+        );
+
+        process_log_line(
+            "main: model loaded".to_string(),
+            log_buffer,
+            server_state.clone(),
+            LogSource::Stdout,
+            None,
+            1,
+            Some(8099),
+        );
+        assert!(server_state.lock().unwrap().is_ready);
+    }
+
     #[test]
     fn test_process_log_line_generation_mismatch() {
         let log_buffer: LogBuffer = Arc::new(std::sync::Mutex::new(VecDeque::new()));
         let server_state: ServerStateHandle = Arc::new(std::sync::Mutex::new(ServerState {
             is_ready: false,
             generation: 2, // Mismatch
+            port: 8099,
         }));
         let line = "main: server is listening on http://0.0.0.0:8099".to_string();
 
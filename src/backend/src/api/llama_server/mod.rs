@@ -3,7 +3,9 @@ pub mod get_logs;
 pub mod get_models;
 pub mod get_status;
 pub mod logs_reader;
+pub mod patch_config;
 pub mod post_config;
+pub mod post_restart;
 pub mod post_start;
 pub mod post_stop;
 pub mod types;
@@ -0,0 +1,161 @@
+use actix_web::{patch, web, HttpResponse, Result as ActixResult};
+use log::info;
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex};
+
+use crate::api::llama_server::get_config::ConfigResponse;
+use crate::api::llama_server::post_config::{apply_config_fields, ConfigRequest};
+use crate::api::llama_server::types::{Config, ProcessHandle};
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct PatchConfigResponse {
+    pub success: bool,
+    pub message: String,
+    /// True when a llama-server is running with the previous settings
+    pub restart_required: bool,
+    pub config: ConfigResponse,
+}
+
+/// Reject updates that would leave the config unusable
+fn validate_update(update: &ConfigRequest) -> Result<(), String> {
+    if update
+        .hf_model
+        .as_deref()
+        .is_some_and(|m| m.trim().is_empty())
+    {
+        return Err("hf_model must not be empty".to_string());
+    }
+    if update.ctx_size == Some(0) {
+        return Err("ctx_size must be greater than 0".to_string());
+    }
+    if update.batch_size == Some(0) || update.ubatch_size == Some(0) {
+        return Err("batch_size and ubatch_size must be greater than 0".to_string());
+    }
+    Ok(())
+}
+
+/// Update the active llama config at runtime without changing the saved default.
+///
+/// Only the fields present in the body are changed. Settings are read when the
+/// server starts, so a running llama-server has to be restarted to pick them up.
+#[patch("/api/llama-server/config")]
+pub async fn patch_llama_config(
+    body: web::Json<ConfigRequest>,
+    config: web::Data<Arc<Mutex<Config>>>,
+    process: web::Data<ProcessHandle>,
+) -> ActixResult<HttpResponse> {
+    if let Err(message) = validate_update(&body) {
+        return Ok(HttpResponse::BadRequest().json(serde_json::json!({
+            "success": false,
+            "message": message,
+        })));
+    }
+
+    let updated = {
+        let mut config_guard = config.lock().unwrap();
+        if let Some(hf_model) = &body.hf_model {
+            config_guard.hf_model = hf_model.trim().to_string();
+            info!("📝 Updated HF model to: {}", config_guard.hf_model);
+        }
+        apply_config_fields(&mut config_guard, &body);
+        ConfigResponse::from(&*config_guard)
+    };
+
+    let restart_required = process.lock().unwrap().is_some();
+    let message = if restart_required {
+        "Configuration updated; restart the llama server to apply it"
+    } else {
+        "Configuration updated; it will be used on the next server start"
+    };
+
+    Ok(HttpResponse::Ok().json(PatchConfigResponse {
+        success: true,
+        message: message.to_string(),
+        restart_required,
+        config: updated,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::{test, App};
+
+    #[actix_web::test]
+    async fn test_patch_llama_config_updates_fields() {
+        let config: Arc<Mutex<Config>> = Arc::new(Mutex::new(Config::default()));
+        let original_ctx_size = config.lock().unwrap().ctx_size;
+        let process = ProcessHandle(Arc::new(Mutex::new(None)));
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(config.clone()))
+                .app_data(web::Data::new(process))
+                .service(patch_llama_config),
+        )
+        .await;
+
+        let req = test::TestRequest::patch()
+            .uri("/api/llama-server/config")
+            .set_json(&ConfigRequest {
+                hf_model: Some(" test/model:Q4_K_M ".to_string()),
+                gpu_layers: Some(20),
+                ..Default::default()
+            })
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert!(resp.status().is_success());
+
+        let body: PatchConfigResponse = test::read_body_json(resp).await;
+        assert!(body.success);
+        assert!(!body.restart_required);
+        assert_eq!(body.config.hf_model, "test/model:Q4_K_M");
+        assert_eq!(body.config.gpu_layers, Some(20));
+        assert_eq!(body.config.ctx_size, original_ctx_size);
+
+        let config_guard = config.lock().unwrap();
+        assert_eq!(config_guard.hf_model, "test/model:Q4_K_M");
+    }
+
+    #[actix_web::test]
+    async fn test_patch_llama_config_rejects_empty_model() {
+        let config: Arc<Mutex<Config>> = Arc::new(Mutex::new(Config::default()));
+        let original_model = config.lock().unwrap().hf_model.clone();
+        let process = ProcessHandle(Arc::new(Mutex::new(None)));
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(config.clone()))
+                .app_data(web::Data::new(process))
+                .service(patch_llama_config),
+        )
+        .await;
+
+        let req = test::TestRequest::patch()
+            .uri("/api/llama-server/config")
+            .set_json(&ConfigRequest {
+                hf_model: Some("   ".to_string()),
+                ..Default::default()
+            })
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status().as_u16(), 400);
+
+        assert_eq!(config.lock().unwrap().hf_model, original_model);
+    }
+
+    #[actix_web::test]
+    async fn test_validate_update() {
+        assert!(validate_update(&ConfigRequest::default()).is_ok());
+        assert!(validate_update(&ConfigRequest {
+            ctx_size: Some(0),
+            ..Default::default()
+        })
+        .is_err());
+        assert!(validate_update(&ConfigRequest {
+            ubatch_size: Some(0),
+            ..Default::default()
+        })
+        .is_err());
+    }
+}
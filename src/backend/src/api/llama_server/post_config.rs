@@ -1,4 +1,5 @@
 use actix_web::{post, web, HttpResponse, Result as ActixResult};
+use log::{info, warn};
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use std::sync::Mutex;
@@ -6,7 +7,7 @@ use std::sync::Mutex;
 use crate::api::default_configs::{DefaultConfigsStorage, LlamaDefaultConfig};
 use crate::api::llama_server::types::Config;
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, Default)]
 pub struct ConfigRequest {
     pub hf_model: Option<String>,
     pub ctx_size: Option<u32>,
@@ -28,107 +29,112 @@ pub struct ConfigResponse {
     pub message: String,
 }
 
-#[post("/api/llama-server/config")]
-pub async fn post_update_config(
-    body: web::Json<ConfigRequest>,
-    config: web::Data<Arc<Mutex<Config>>>,
-    default_configs: web::Data<Arc<DefaultConfigsStorage>>,
-) -> ActixResult<HttpResponse> {
-    // Handle hf_model update and save to default configs (drop lock before await)
-    if let Some(hf_model) = &body.hf_model {
-        if !hf_model.trim().is_empty() {
-            let hf_model_trimmed = hf_model.trim().to_string();
-            {
-                let mut config_guard = config.lock().unwrap();
-                config_guard.hf_model = hf_model_trimmed.clone();
-                println!("📝 Updated HF model to: {}", config_guard.hf_model);
-            } // Drop lock here
-
-            // Save as default config (hf_model is primary) - lock is dropped
-            if let Err(e) = default_configs
-                .set_llama_default(&LlamaDefaultConfig {
-                    hf_model: hf_model_trimmed.clone(),
-                })
-                .await
-            {
-                println!("⚠️  Failed to save llama default config: {}", e);
-            } else {
-                println!("✅ Saved llama default config");
-            }
-        }
-    }
-
-    // Update other config fields (no await points here)
-    let mut config_guard = config.lock().unwrap();
-
-    if let Some(ctx_size) = body.ctx_size {
+/// Apply every field of a config update except `hf_model`, skipping values
+/// that would be invalid (zero sizes). An empty `model` clears the model path.
+pub(crate) fn apply_config_fields(config_guard: &mut Config, update: &ConfigRequest) {
+    if let Some(ctx_size) = update.ctx_size {
         if ctx_size > 0 {
             config_guard.ctx_size = ctx_size;
-            println!("📝 Updated context size to: {}", config_guard.ctx_size);
+            info!("📝 Updated context size to: {}", config_guard.ctx_size);
         }
     }
 
-    if let Some(threads) = body.threads {
+    if let Some(threads) = update.threads {
         config_guard.threads = Some(threads);
-        println!("📝 Updated threads to: {:?}", config_guard.threads);
+        info!("📝 Updated threads to: {:?}", config_guard.threads);
     }
 
-    if let Some(threads_batch) = body.threads_batch {
+    if let Some(threads_batch) = update.threads_batch {
         config_guard.threads_batch = Some(threads_batch);
-        println!(
+        info!(
             "📝 Updated threads-batch to: {:?}",
             config_guard.threads_batch
         );
     }
 
-    if let Some(predict) = body.predict {
+    if let Some(predict) = update.predict {
         config_guard.predict = Some(predict);
-        println!("📝 Updated predict to: {:?}", config_guard.predict);
+        info!("📝 Updated predict to: {:?}", config_guard.predict);
     }
 
-    if let Some(batch_size) = body.batch_size {
+    if let Some(batch_size) = update.batch_size {
         if batch_size > 0 {
             config_guard.batch_size = Some(batch_size);
-            println!("📝 Updated batch-size to: {:?}", config_guard.batch_size);
+            info!("📝 Updated batch-size to: {:?}", config_guard.batch_size);
         }
     }
 
-    if let Some(ubatch_size) = body.ubatch_size {
+    if let Some(ubatch_size) = update.ubatch_size {
         if ubatch_size > 0 {
             config_guard.ubatch_size = Some(ubatch_size);
-            println!("📝 Updated ubatch-size to: {:?}", config_guard.ubatch_size);
+            info!("📝 Updated ubatch-size to: {:?}", config_guard.ubatch_size);
         }
     }
 
-    if let Some(flash_attn) = body.flash_attn {
+    if let Some(flash_attn) = update.flash_attn {
         config_guard.flash_attn = Some(flash_attn);
-        println!("📝 Updated flash-attn to: {:?}", config_guard.flash_attn);
+        info!("📝 Updated flash-attn to: {:?}", config_guard.flash_attn);
     }
 
-    if let Some(mlock) = body.mlock {
+    if let Some(mlock) = update.mlock {
         config_guard.mlock = Some(mlock);
-        println!("📝 Updated mlock to: {:?}", config_guard.mlock);
+        info!("📝 Updated mlock to: {:?}", config_guard.mlock);
     }
 
-    if let Some(no_mmap) = body.no_mmap {
+    if let Some(no_mmap) = update.no_mmap {
         config_guard.no_mmap = Some(no_mmap);
-        println!("📝 Updated no-mmap to: {:?}", config_guard.no_mmap);
+        info!("📝 Updated no-mmap to: {:?}", config_guard.no_mmap);
     }
 
-    if let Some(gpu_layers) = body.gpu_layers {
+    if let Some(gpu_layers) = update.gpu_layers {
         config_guard.gpu_layers = Some(gpu_layers);
-        println!("📝 Updated gpu-layers to: {:?}", config_guard.gpu_layers);
+        info!("📝 Updated gpu-layers to: {:?}", config_guard.gpu_layers);
     }
 
-    if let Some(model) = &body.model {
+    if let Some(model) = &update.model {
         if model.trim().is_empty() {
             config_guard.model = None;
-            println!("📝 Cleared model path");
+            info!("📝 Cleared model path");
         } else {
             config_guard.model = Some(model.trim().to_string());
-            println!("📝 Updated model to: {:?}", config_guard.model);
+            info!("📝 Updated model to: {:?}", config_guard.model);
         }
     }
+}
+
+#[post("/api/llama-server/config")]
+pub async fn post_update_config(
+    body: web::Json<ConfigRequest>,
+    config: web::Data<Arc<Mutex<Config>>>,
+    default_configs: web::Data<Arc<DefaultConfigsStorage>>,
+) -> ActixResult<HttpResponse> {
+    // Handle hf_model update and save to default configs (drop lock before await)
+    if let Some(hf_model) = &body.hf_model {
+        if !hf_model.trim().is_empty() {
+            let hf_model_trimmed = hf_model.trim().to_string();
+            {
+                let mut config_guard = config.lock().unwrap();
+                config_guard.hf_model = hf_model_trimmed.clone();
+                info!("📝 Updated HF model to: {}", config_guard.hf_model);
+            } // Drop lock here
+
+            // Save as default config (hf_model is primary) - lock is dropped
+            if let Err(e) = default_configs
+                .set_llama_default(&LlamaDefaultConfig {
+                    hf_model: hf_model_trimmed.clone(),
+                })
+                .await
+            {
+                warn!("⚠️  Failed to save llama default config: {}", e);
+            } else {
+                info!("✅ Saved llama default config");
+            }
+        }
+    }
+
+    // Update other config fields (no await points here)
+    let mut config_guard = config.lock().unwrap();
+    apply_config_fields(&mut config_guard, &body);
 
     Ok(HttpResponse::Ok().json(ConfigResponse {
         success: true,
@@ -140,12 +146,14 @@ pub async fn post_update_config(
 mod tests {
     use super::*;
     use crate::api::llama_server::types::Config;
+    use crate::utils::database::connect_sqlite;
     use actix_web::{test, web, App};
     use std::sync::{Arc, Mutex};
 
     // Helper to create a test default_configs storage with in-memory database
     async fn create_test_default_configs() -> Arc<DefaultConfigsStorage> {
-        let storage = DefaultConfigsStorage::new(":memory:")
+        let pool = connect_sqlite(":memory:").await.unwrap();
+        let storage = DefaultConfigsStorage::new(pool)
             .await
             .expect("Failed to create test default configs storage");
         Arc::new(storage)
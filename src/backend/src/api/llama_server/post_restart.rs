@@ -0,0 +1,68 @@
+use actix_web::{post, web, HttpResponse, Result as ActixResult};
+use log::{info, warn};
+use std::sync::{Arc, Mutex};
+
+use crate::api::llama_server::get_status::LlamaServerStatus;
+use crate::api::llama_server::post_start::{spawn_llama_server, LlamaServerResponse};
+use crate::api::llama_server::post_stop::{stop_llama_process, take_llama_process};
+use crate::api::llama_server::types::{Config, LogBuffer, ProcessHandle, ServerStateHandle};
+use crate::api::llama_server::websocket::WebSocketState;
+
+/// Stop the running llama-server (if any) and start it again with the current config
+#[post("/api/llama-server/restart")]
+pub async fn post_restart_llama_server(
+    process: web::Data<ProcessHandle>,
+    config: web::Data<Arc<Mutex<Config>>>,
+    log_buffer: web::Data<LogBuffer>,
+    server_state: web::Data<ServerStateHandle>,
+    ws_state: web::Data<Arc<WebSocketState>>,
+) -> ActixResult<HttpResponse> {
+    if let Some(child) = take_llama_process(&process, &server_state) {
+        let port = server_state.lock().unwrap().port;
+        ws_state.broadcast_status(&LlamaServerStatus::stopped(port));
+        if let Err(e) = stop_llama_process(child).await {
+            warn!("Failed to stop llama server for restart: {}", e);
+            return Ok(
+                HttpResponse::InternalServerError().json(LlamaServerResponse {
+                    success: false,
+                    message: format!("Failed to stop llama server: {}", e),
+                }),
+            );
+        }
+    }
+
+    let config_snapshot = config.lock().unwrap().clone();
+    let mut process_guard = process.lock().unwrap();
+    if process_guard.is_some() {
+        // Another request started the server while the old one was stopping
+        return Ok(HttpResponse::Ok().json(LlamaServerResponse {
+            success: false,
+            message: "Llama server is already running".to_string(),
+        }));
+    }
+
+    match spawn_llama_server(
+        &config_snapshot,
+        log_buffer.get_ref(),
+        server_state.get_ref(),
+        ws_state.get_ref(),
+    ) {
+        Ok(child) => {
+            *process_guard = Some(child);
+            info!("✅ Llama server restarted successfully");
+            Ok(HttpResponse::Ok().json(LlamaServerResponse {
+                success: true,
+                message: "Llama server restarted successfully".to_string(),
+            }))
+        }
+        Err(e) => {
+            warn!("Failed to restart llama server: {}", e);
+            Ok(
+                HttpResponse::InternalServerError().json(LlamaServerResponse {
+                    success: false,
+                    message: format!("Failed to restart llama server: {}", e),
+                }),
+            )
+        }
+    }
+}
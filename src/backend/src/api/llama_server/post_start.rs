@@ -1,6 +1,7 @@
 use actix_web::{post, web, HttpResponse, Result as ActixResult};
+use log::{info, warn};
 use serde::Serialize;
-use std::process::{Command, Stdio};
+use std::process::{Child, Command, Stdio};
 use std::sync::Arc;
 use std::sync::Mutex;
 
@@ -43,26 +44,58 @@ pub async fn post_start_llama_server(
         }
     }
 
-    // Get current config
-    let config_guard = config.lock().unwrap();
-    let hf_model = config_guard.hf_model.clone();
-    let ctx_size = config_guard.ctx_size.to_string();
-    let threads = config_guard.threads;
-    let threads_batch = config_guard.threads_batch;
-    let predict = config_guard.predict;
-    let batch_size = config_guard.batch_size;
-    let ubatch_size = config_guard.ubatch_size;
-    let flash_attn = config_guard.flash_attn;
-    let mlock = config_guard.mlock;
-    let no_mmap = config_guard.no_mmap;
-    let gpu_layers = config_guard.gpu_layers;
-    let model = config_guard.model.clone();
-    let host = config_guard.host.clone();
-    let port = config_guard.port;
-    drop(config_guard);
+    let config_snapshot = config.lock().unwrap().clone();
+    match spawn_llama_server(
+        &config_snapshot,
+        log_buffer.get_ref(),
+        server_state.get_ref(),
+        ws_state.get_ref(),
+    ) {
+        Ok(child) => {
+            *process_guard = Some(child);
+            info!("✅ Llama server started successfully");
+            Ok(HttpResponse::Ok().json(LlamaServerResponse {
+                success: true,
+                message: "Llama server started successfully".to_string(),
+            }))
+        }
+        Err(e) => {
+            warn!("Failed to start llama server: {}", e);
+            Ok(
+                HttpResponse::InternalServerError().json(LlamaServerResponse {
+                    success: false,
+                    message: format!("Failed to start llama server: {}", e),
+                }),
+            )
+        }
+    }
+}
+
+/// Spawn llama-server with the given config, reset the readiness state and
+/// attach log readers. The caller stores the returned child in `ProcessHandle`.
+pub(crate) fn spawn_llama_server(
+    config: &Config,
+    log_buffer: &LogBuffer,
+    server_state: &ServerStateHandle,
+    ws_state: &Arc<WebSocketState>,
+) -> std::io::Result<Child> {
+    let hf_model = config.hf_model.clone();
+    let ctx_size = config.ctx_size.to_string();
+    let threads = config.threads;
+    let threads_batch = config.threads_batch;
+    let predict = config.predict;
+    let batch_size = config.batch_size;
+    let ubatch_size = config.ubatch_size;
+    let flash_attn = config.flash_attn;
+    let mlock = config.mlock;
+    let no_mmap = config.no_mmap;
+    let gpu_layers = config.gpu_layers;
+    let model = config.model.clone();
+    let host = config.host.clone();
+    let port = config.server_port();
 
     // Start the llama-server process
-    println!(
+    info!(
         "🚀 Starting llama-server with model: {}, ctx_size: {}",
         hf_model, ctx_size
     );
@@ -107,56 +140,40 @@ pub async fn post_start_llama_server(
     if let Some(host_val) = &host {
         cmd.arg("--host").arg(host_val);
     }
-    if let Some(port_val) = port {
-        cmd.arg("--port").arg(port_val.to_string());
-    }
+    // Always pass the port so the server matches the URL the agent connects to
+    cmd.arg("--port").arg(port.to_string());
 
-    match cmd.stdout(Stdio::piped()).stderr(Stdio::piped()).spawn() {
-        Ok(mut child) => {
-            // Reset server state
-            {
-                let mut state = server_state.lock().unwrap();
-                state.is_ready = false;
-                state.generation = state.generation.wrapping_add(1);
-            }
+    let mut child = cmd.stdout(Stdio::piped()).stderr(Stdio::piped()).spawn()?;
 
-            // Clear log buffer
-            {
-                let mut buffer = log_buffer.lock().unwrap();
-                buffer.clear();
-            }
+    // Reset server state
+    {
+        let mut state = server_state.lock().unwrap();
+        state.is_ready = false;
+        state.generation = state.generation.wrapping_add(1);
+        state.port = port;
+    }
 
-            // Capture stdout and stderr
-            let stdout = child.stdout.take();
-            let stderr = child.stderr.take();
+    // Clear log buffer
+    {
+        let mut buffer = log_buffer.lock().unwrap();
+        buffer.clear();
+    }
 
-            // Spawn log readers
-            if stdout.is_some() || stderr.is_some() {
-                spawn_log_reader(
-                    stdout,
-                    stderr,
-                    log_buffer.get_ref().clone(),
-                    server_state.get_ref().clone(),
-                    Some(ws_state.get_ref().clone()),
-                    port,
-                );
-            }
+    // Capture stdout and stderr
+    let stdout = child.stdout.take();
+    let stderr = child.stderr.take();
 
-            *process_guard = Some(child);
-            println!("✅ Llama server started successfully");
-            Ok(HttpResponse::Ok().json(LlamaServerResponse {
-                success: true,
-                message: "Llama server started successfully".to_string(),
-            }))
-        }
-        Err(e) => {
-            println!("Failed to start llama server: {}", e);
-            Ok(
-                HttpResponse::InternalServerError().json(LlamaServerResponse {
-                    success: false,
-                    message: format!("Failed to start llama server: {}", e),
-                }),
-            )
-        }
+    // Spawn log readers
+    if stdout.is_some() || stderr.is_some() {
+        spawn_log_reader(
+            stdout,
+            stderr,
+            log_buffer.clone(),
+            server_state.clone(),
+            Some(ws_state.clone()),
+            Some(port),
+        );
     }
+
+    Ok(child)
 }
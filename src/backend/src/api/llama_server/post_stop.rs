@@ -1,9 +1,17 @@
 use actix_web::{post, web, HttpResponse, Result as ActixResult};
+use log::{info, warn};
 use serde::{Deserialize, Serialize};
+use std::process::Child;
+use std::sync::Arc;
+use std::time::Duration;
 
+use crate::api::llama_server::get_status::LlamaServerStatus;
 use crate::api::llama_server::types::{ProcessHandle, ServerStateHandle};
 use crate::api::llama_server::websocket::WebSocketState;
-use std::sync::Arc;
+use crate::utils::process::terminate_child;
+
+/// How long llama-server gets to exit after SIGTERM before it is killed
+pub(crate) const STOP_TIMEOUT: Duration = Duration::from_secs(10);
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct LlamaServerResponse {
@@ -11,59 +19,74 @@ pub struct LlamaServerResponse {
     pub message: String,
 }
 
+/// Take the child out of `ProcessHandle` and mark the server as not ready, so the
+/// status poller sees the stop immediately instead of on its next tick.
+pub(crate) fn take_llama_process(
+    process: &ProcessHandle,
+    server_state: &ServerStateHandle,
+) -> Option<Child> {
+    let child = process.lock().unwrap().take()?;
+    let mut state = server_state.lock().unwrap();
+    state.is_ready = false;
+    // Ignore readiness lines still being read from the old process
+    state.generation = state.generation.wrapping_add(1);
+    Some(child)
+}
+
+/// Gracefully stop a llama-server child (SIGTERM, then SIGKILL after `STOP_TIMEOUT`)
+pub(crate) async fn stop_llama_process(mut child: Child) -> Result<(), String> {
+    info!("🛑 Stopping llama-server (pid {})...", child.id());
+    match web::block(move || terminate_child(&mut child, STOP_TIMEOUT)).await {
+        Ok(Ok(())) => Ok(()),
+        Ok(Err(e)) => Err(e.to_string()),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
 #[post("/api/llama-server/stop")]
 pub async fn post_stop_llama_server(
     process: web::Data<ProcessHandle>,
     server_state: web::Data<ServerStateHandle>,
     ws_state: web::Data<Arc<WebSocketState>>,
 ) -> ActixResult<HttpResponse> {
-    let mut process_guard = process.lock().unwrap();
-
-    if let Some(ref mut child) = *process_guard {
-        println!("🛑 Stopping llama-server...");
-        match child.kill() {
-            Ok(_) => {
-                // Wait for the process to exit
-                let _ = child.wait();
-                *process_guard = None;
-
-                // Reset server state
-                let mut state = server_state.lock().unwrap();
-                state.is_ready = false;
-                drop(state);
-
-                // Broadcast stopped status
-                println!("📡 Broadcasting server stopped status");
-                ws_state.broadcast_status(false, 8080);
-
-                println!("✅ Llama server stopped successfully");
-                Ok(HttpResponse::Ok().json(LlamaServerResponse {
-                    success: true,
-                    message: "Llama server stopped successfully".to_string(),
-                }))
-            }
-            Err(e) => {
-                println!("Failed to stop llama server: {}", e);
-                Ok(
-                    HttpResponse::InternalServerError().json(LlamaServerResponse {
-                        success: false,
-                        message: format!("Failed to stop llama server: {}", e),
-                    }),
-                )
-            }
-        }
-    } else {
-        Ok(HttpResponse::Ok().json(LlamaServerResponse {
+    let port = server_state.lock().unwrap().port;
+    let Some(child) = take_llama_process(&process, &server_state) else {
+        return Ok(HttpResponse::Ok().json(LlamaServerResponse {
             success: false,
             message: "Llama server is not running".to_string(),
-        }))
+        }));
+    };
+
+    // Broadcast stopped status
+    info!("📡 Broadcasting server stopped status");
+    ws_state.broadcast_status(&LlamaServerStatus::stopped(port));
+
+    match stop_llama_process(child).await {
+        Ok(()) => {
+            info!("✅ Llama server stopped successfully");
+            Ok(HttpResponse::Ok().json(LlamaServerResponse {
+                success: true,
+                message: "Llama server stopped successfully".to_string(),
+            }))
+        }
+        Err(e) => {
+            warn!("Failed to stop llama server: {}", e);
+            Ok(
+                HttpResponse::InternalServerError().json(LlamaServerResponse {
+                    success: false,
+                    message: format!("Failed to stop llama server: {}", e),
+                }),
+            )
+        }
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::api::llama_server::types::{ProcessHandle, ServerState, ServerStateHandle};
+    use crate::api::llama_server::types::{
+        ProcessHandle, ServerState, ServerStateHandle, DEFAULT_LLM_PORT,
+    };
     use actix_web::{test, web, App};
     use std::sync::{Arc, Mutex};
 
@@ -73,6 +96,7 @@ mod tests {
         let server_state: ServerStateHandle = Arc::new(Mutex::new(ServerState {
             is_ready: false,
             generation: 0,
+            port: DEFAULT_LLM_PORT,
         }));
         let log_buffer = Arc::new(Mutex::new(std::collections::VecDeque::new()));
         let ws_state = Arc::new(WebSocketState::new(
@@ -101,4 +125,49 @@ mod tests {
         assert!(!body.success);
         assert!(body.message.contains("not running"));
     }
+
+    #[cfg(unix)]
+    #[actix_web::test]
+    async fn test_post_stop_llama_server_running() {
+        let child = std::process::Command::new("sleep")
+            .arg("30")
+            .spawn()
+            .unwrap();
+        let process = ProcessHandle(Arc::new(Mutex::new(Some(child))));
+        let server_state: ServerStateHandle = Arc::new(Mutex::new(ServerState {
+            is_ready: true,
+            generation: 1,
+            port: DEFAULT_LLM_PORT,
+        }));
+        let log_buffer = Arc::new(Mutex::new(std::collections::VecDeque::new()));
+        let ws_state = Arc::new(WebSocketState::new(
+            web::Data::new(log_buffer),
+            web::Data::new(process.clone()),
+            web::Data::new(server_state.clone()),
+        ));
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(process.clone()))
+                .app_data(web::Data::new(server_state.clone()))
+                .app_data(web::Data::new(ws_state))
+                .service(post_stop_llama_server),
+        )
+        .await;
+
+        let req = test::TestRequest::post()
+            .uri("/api/llama-server/stop")
+            .to_request();
+
+        let resp = test::call_service(&app, req).await;
+        assert!(resp.status().is_success());
+
+        let body: LlamaServerResponse = test::read_body_json(resp).await;
+        assert!(body.success);
+        assert!(process.lock().unwrap().is_none());
+
+        let state = server_state.lock().unwrap();
+        assert!(!state.is_ready);
+        assert_eq!(state.generation, 2);
+    }
 }
@@ -1,5 +1,6 @@
 use std::collections::VecDeque;
 use std::process::Child;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::{Arc, Mutex};
 
 #[derive(Clone)]
@@ -52,6 +53,45 @@ impl Default for Config {
     }
 }
 
+/// Environment variable overriding the LLM server base URL (e.g. `http://gpu-box:8080`)
+pub const LLM_BASE_URL_ENV: &str = "LLM_BASE_URL";
+
+/// Port used when the llama config doesn't specify one
+pub const DEFAULT_LLM_PORT: u16 = 8090;
+
+/// Seconds between llama-server status checks when `--llama_poll_secs` isn't given
+pub const DEFAULT_STATUS_POLL_SECS: u64 = 2;
+
+impl Config {
+    /// Port llama-server is started on and reached at.
+    pub fn server_port(&self) -> u16 {
+        self.port.unwrap_or(DEFAULT_LLM_PORT)
+    }
+
+    /// Base URL of the OpenAI-compatible LLM server.
+    /// `LLM_BASE_URL` takes precedence over the configured host and port.
+    pub fn llm_base_url(&self) -> String {
+        self.llm_base_url_with(std::env::var(LLM_BASE_URL_ENV).ok())
+    }
+
+    fn llm_base_url_with(&self, env_override: Option<String>) -> String {
+        if let Some(url) = env_override
+            .map(|url| url.trim().trim_end_matches('/').to_string())
+            .filter(|url| !url.is_empty())
+        {
+            return url;
+        }
+
+        let host = match self.host.as_deref() {
+            // The server listens on all interfaces but must be reached via loopback
+            Some("0.0.0.0") => "127.0.0.1",
+            Some(host) => host,
+            None => "localhost",
+        };
+        format!("http://{}:{}", host, self.server_port())
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct LogEntry {
     pub timestamp: u64,
@@ -67,10 +107,84 @@ pub enum LogSource {
 
 pub type LogBuffer = Arc<Mutex<VecDeque<LogEntry>>>;
 
+/// Number of log lines kept when `--llama_log_lines` isn't given
+pub const DEFAULT_LOG_BUFFER_MAX: usize = 1000;
+
+static LOG_BUFFER_MAX: AtomicUsize = AtomicUsize::new(DEFAULT_LOG_BUFFER_MAX);
+
+/// Set how many llama-server log lines are kept in memory (at least one)
+pub fn set_log_buffer_max(max: usize) {
+    LOG_BUFFER_MAX.store(max.max(1), Ordering::Relaxed);
+}
+
+pub fn log_buffer_max() -> usize {
+    LOG_BUFFER_MAX.load(Ordering::Relaxed)
+}
+
+/// Append a log entry, dropping the oldest lines once `max` is exceeded
+pub fn push_log_entry(buffer: &mut VecDeque<LogEntry>, entry: LogEntry, max: usize) {
+    buffer.push_back(entry);
+    while buffer.len() > max {
+        buffer.pop_front();
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct ServerState {
     pub is_ready: bool,
     pub generation: u32,
+    /// Port the managed llama-server listens on
+    pub port: u16,
 }
 
 pub type ServerStateHandle = Arc<Mutex<ServerState>>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_llm_base_url_from_config() {
+        let config = Config::default();
+        assert_eq!(config.llm_base_url_with(None), "http://localhost:8090");
+
+        let config = Config {
+            host: Some("0.0.0.0".to_string()),
+            port: Some(8080),
+            ..Config::default()
+        };
+        assert_eq!(config.llm_base_url_with(None), "http://127.0.0.1:8080");
+    }
+
+    #[test]
+    fn test_push_log_entry_caps_buffer() {
+        let mut buffer = VecDeque::new();
+        for i in 0..5 {
+            push_log_entry(
+                &mut buffer,
+                LogEntry {
+                    timestamp: i,
+                    line: format!("line {}", i),
+                    source: LogSource::Stdout,
+                },
+                3,
+            );
+        }
+        assert_eq!(buffer.len(), 3);
+        assert_eq!(buffer.front().unwrap().line, "line 2");
+        assert_eq!(buffer.back().unwrap().line, "line 4");
+    }
+
+    #[test]
+    fn test_llm_base_url_env_override() {
+        let config = Config::default();
+        assert_eq!(
+            config.llm_base_url_with(Some("http://gpu-box:8080/".to_string())),
+            "http://gpu-box:8080"
+        );
+        assert_eq!(
+            config.llm_base_url_with(Some("  ".to_string())),
+            "http://localhost:8090"
+        );
+    }
+}
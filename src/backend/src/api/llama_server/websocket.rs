@@ -1,10 +1,12 @@
 use actix_web::{web, web::Payload, Error, HttpRequest, HttpResponse};
 use actix_ws::{Message, Session};
+use log::info;
 use serde::Serialize;
 use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
 use tokio::sync::mpsc;
 
+use crate::api::llama_server::get_status::{port_is_open, LlamaServerStatus};
 use crate::api::llama_server::types::{LogBuffer, LogSource, ProcessHandle, ServerStateHandle};
 
 #[derive(Serialize, Debug, Clone)]
@@ -20,11 +22,27 @@ pub enum WebSocketMessage {
     #[serde(rename = "log")]
     Log { log: LogLine },
     #[serde(rename = "status")]
-    Status { active: bool, port: u16 },
+    Status {
+        active: bool,
+        port: u16,
+        model_loaded: bool,
+        port_open: bool,
+    },
     #[serde(rename = "logs_batch")]
     LogsBatch { logs: Vec<LogLine> },
 }
 
+impl From<&LlamaServerStatus> for WebSocketMessage {
+    fn from(status: &LlamaServerStatus) -> Self {
+        WebSocketMessage::Status {
+            active: status.active,
+            port: status.port,
+            model_loaded: status.model_loaded,
+            port_open: status.port_open,
+        }
+    }
+}
+
 #[derive(Clone)]
 pub struct WebSocketState {
     pub logs_clients: Arc<Mutex<HashMap<String, mpsc::UnboundedSender<String>>>>,
@@ -52,7 +70,7 @@ impl WebSocketState {
     pub fn add_logs_client(&self, id: String, tx: mpsc::UnboundedSender<String>) {
         let mut clients = self.logs_clients.lock().unwrap();
         clients.insert(id.clone(), tx);
-        println!(
+        info!(
             "✅ Logs WebSocket client connected: {} (total: {})",
             id,
             clients.len()
@@ -62,7 +80,7 @@ impl WebSocketState {
     pub fn remove_logs_client(&self, id: &str) {
         let mut clients = self.logs_clients.lock().unwrap();
         clients.remove(id);
-        println!(
+        info!(
             "🔌 Logs WebSocket client disconnected: {} (remaining: {})",
             id,
             clients.len()
@@ -90,16 +108,16 @@ impl WebSocketState {
             }
         }
         if client_count > 0 {
-            println!(
+            info!(
                 "📤 Sent log to {}/{} WebSocket clients",
                 sent_count, client_count
             );
         }
     }
 
-    pub fn broadcast_status(&self, active: bool, port: u16) {
+    pub fn broadcast_status(&self, status: &LlamaServerStatus) {
         let clients = self.status_clients.lock().unwrap();
-        let message = serde_json::to_string(&WebSocketMessage::Status { active, port }).unwrap();
+        let message = serde_json::to_string(&WebSocketMessage::from(status)).unwrap();
         for tx in clients.values() {
             let _ = tx.send(message.clone());
         }
@@ -110,9 +128,7 @@ impl WebSocketState {
 fn get_status(
     process: &web::Data<ProcessHandle>,
     server_state: &web::Data<ServerStateHandle>,
-) -> crate::api::llama_server::get_status::LlamaServerStatus {
-    use crate::api::llama_server::get_status::LlamaServerStatus;
-
+) -> LlamaServerStatus {
     let mut process_guard = process.lock().unwrap();
 
     let is_active = if let Some(ref mut child) = *process_guard {
@@ -132,14 +148,15 @@ fn get_status(
 
     let state_guard = server_state.lock().unwrap();
     let is_ready = state_guard.is_ready;
+    let port = state_guard.port;
     drop(state_guard);
 
-    // Check port synchronously
-    let port_check = std::net::TcpStream::connect("127.0.0.1:8080").is_ok();
-
+    // An open port only means llama-server is up, not that the model is loaded
     LlamaServerStatus {
-        active: is_active && (is_ready || port_check),
-        port: 8080,
+        active: is_active && is_ready,
+        port,
+        model_loaded: is_active && is_ready,
+        port_open: is_active && port_is_open(port),
     }
 }
 
@@ -224,11 +241,7 @@ pub async fn status_ws(
 
     // Send initial status
     let status = get_status(&state.process, &state.server_state);
-    let initial_message = serde_json::to_string(&WebSocketMessage::Status {
-        active: status.active,
-        port: status.port,
-    })
-    .unwrap();
+    let initial_message = serde_json::to_string(&WebSocketMessage::from(&status)).unwrap();
     let _ = session_sender.text(initial_message).await;
     actix_rt::spawn(async move {
         while let Some(msg) = rx.recv().await {
@@ -0,0 +1,93 @@
+use actix_web::{web, Error as ActixError, HttpResponse, Resource};
+use serde::{Deserialize, Serialize};
+
+use crate::api::converters::limits::json_config;
+use crate::api::error::ApiError;
+use crate::markdown_utils::render::render_markdown_to_html;
+
+/// Largest markdown document accepted
+pub const MAX_MARKDOWN_SIZE: usize = 10 * 1024 * 1024;
+
+fn default_sanitize() -> bool {
+    true
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct MarkdownToHtmlRequest {
+    pub markdown: String,
+    /// Strip unsafe HTML (scripts, event handlers) from the output. Defaults to true.
+    #[serde(default = "default_sanitize")]
+    pub sanitize: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct HtmlResponse {
+    pub html: String,
+}
+
+/// `POST /api/converters/markdown-to-html`, accepting request bodies big enough
+/// for a `MAX_MARKDOWN_SIZE` input
+pub fn markdown_to_html_service() -> Resource {
+    web::resource("/api/converters/markdown-to-html")
+        .app_data(json_config(MAX_MARKDOWN_SIZE))
+        .route(web::post().to(convert_markdown_to_html))
+}
+
+pub async fn convert_markdown_to_html(
+    body: web::Json<MarkdownToHtmlRequest>,
+) -> Result<HttpResponse, ActixError> {
+    if body.markdown.len() > MAX_MARKDOWN_SIZE {
+        return Err(ApiError::PayloadTooLarge(format!(
+            "Markdown too large: {} bytes (max {} bytes)",
+            body.markdown.len(),
+            MAX_MARKDOWN_SIZE
+        ))
+        .into());
+    }
+
+    let html = render_markdown_to_html(&body.markdown, body.sanitize);
+    Ok(HttpResponse::Ok().json(HtmlResponse { html }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::error::ErrorEnvelope;
+    use actix_web::{test, App};
+
+    #[actix_web::test]
+    async fn test_convert_markdown_to_html_sanitizes_by_default() {
+        let app = test::init_service(App::new().service(markdown_to_html_service())).await;
+
+        let req = test::TestRequest::post()
+            .uri("/api/converters/markdown-to-html")
+            .set_json(serde_json::json!({ "markdown": "# Title\n<script>x()</script>" }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert!(resp.status().is_success());
+
+        let body: HtmlResponse = test::read_body_json(resp).await;
+        assert!(body.html.contains("<h1>Title</h1>"));
+        assert!(!body.html.contains("<script>"));
+    }
+
+    #[actix_web::test]
+    async fn test_convert_markdown_to_html_too_large() {
+        let app = test::init_service(App::new().service(markdown_to_html_service())).await;
+
+        // Over actix's default 2 MiB JSON limit, so it must reach the handler's check
+        let req = test::TestRequest::post()
+            .uri("/api/converters/markdown-to-html")
+            .set_json(MarkdownToHtmlRequest {
+                markdown: "a".repeat(MAX_MARKDOWN_SIZE + 1),
+                sanitize: true,
+            })
+            .to_request();
+
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 413);
+        let body: ErrorEnvelope = test::read_body_json(resp).await;
+        assert_eq!(body.code, "payload_too_large");
+        assert!(body.error.starts_with("Markdown too large"));
+    }
+}
@@ -0,0 +1,40 @@
+use crate::api::metrics::Metrics;
+use actix_web::{get, web, HttpResponse, Result as ActixResult};
+use std::sync::Arc;
+
+/// Prometheus scrape endpoint. Only registered when `METRICS_ENABLED` is set.
+#[get("/metrics")]
+pub async fn get_prometheus_metrics(metrics: web::Data<Arc<Metrics>>) -> ActixResult<HttpResponse> {
+    let body = metrics
+        .render()
+        .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+    Ok(HttpResponse::Ok()
+        .content_type("text/plain; version=0.0.4; charset=utf-8")
+        .body(body))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::{test, App};
+
+    #[actix_web::test]
+    async fn test_get_metrics_returns_text_format() {
+        let metrics = Arc::new(Metrics::new().unwrap());
+        metrics.embedding_calls.inc();
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(metrics))
+                .service(get_prometheus_metrics),
+        )
+        .await;
+
+        let req = test::TestRequest::get().uri("/metrics").to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 200);
+        let body = test::read_body(resp).await;
+        let body = String::from_utf8(body.to_vec()).unwrap();
+        assert!(body.contains("# TYPE embedding_calls_total counter"));
+        assert!(body.contains("embedding_calls_total 1"));
+    }
+}
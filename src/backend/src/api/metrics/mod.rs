@@ -0,0 +1,5 @@
+pub mod get_metrics;
+pub mod registry;
+
+pub use get_metrics::get_prometheus_metrics;
+pub use registry::Metrics;
@@ -0,0 +1,151 @@
+//! Prometheus counters for agent, tool, embedding and LLM activity
+//!
+//! When `METRICS_ENABLED` is set, `main` creates one [`Metrics`], registers it
+//! as app data for `GET /metrics` and installs it with [`install`]. Code that
+//! has no access to app data (the tool registry, the ChromaDB client, the agent
+//! loop) records through [`record`], which is a no-op while metrics are off.
+
+use anyhow::{Context, Result};
+use prometheus::{
+    Encoder, Histogram, HistogramOpts, IntCounter, IntCounterVec, IntGauge, Opts, Registry,
+    TextEncoder,
+};
+use std::sync::{Arc, OnceLock};
+
+/// Environment variable that turns metrics collection and `GET /metrics` on
+pub const METRICS_ENABLED_ENV: &str = "METRICS_ENABLED";
+
+static METRICS: OnceLock<Arc<Metrics>> = OnceLock::new();
+
+/// Whether `METRICS_ENABLED` is set to a truthy value
+pub fn metrics_enabled_from_env() -> bool {
+    std::env::var(METRICS_ENABLED_ENV)
+        .map(|v| {
+            matches!(
+                v.trim().to_ascii_lowercase().as_str(),
+                "1" | "true" | "yes" | "on"
+            )
+        })
+        .unwrap_or(false)
+}
+
+/// Make `metrics` the target of [`record`]. Only the first call has an effect.
+pub fn install(metrics: Arc<Metrics>) {
+    let _ = METRICS.set(metrics);
+}
+
+/// Run `f` against the installed metrics, if any
+pub fn record(f: impl FnOnce(&Metrics)) {
+    if let Some(metrics) = METRICS.get() {
+        f(metrics);
+    }
+}
+
+pub struct Metrics {
+    registry: Registry,
+    /// Agent requests by endpoint (`chat`, `chat_stream`, `rag`)
+    pub agent_requests: IntCounterVec,
+    /// Tool executions by tool function name
+    pub tool_calls: IntCounterVec,
+    /// Tool executions that errored or timed out, by tool function name
+    pub tool_failures: IntCounterVec,
+    /// Batches sent to the embedding backend
+    pub embedding_calls: IntCounter,
+    /// Time to the LLM server's response headers, in seconds
+    pub llm_request_duration: Histogram,
+    /// Open agent websocket connections
+    pub active_websockets: IntGauge,
+}
+
+impl Metrics {
+    pub fn new() -> Result<Self> {
+        let registry = Registry::new();
+
+        let agent_requests = IntCounterVec::new(
+            Opts::new("agent_requests_total", "Agent requests by endpoint"),
+            &["endpoint"],
+        )?;
+        let tool_calls = IntCounterVec::new(
+            Opts::new("agent_tool_calls_total", "Tool calls by tool name"),
+            &["tool"],
+        )?;
+        let tool_failures = IntCounterVec::new(
+            Opts::new(
+                "agent_tool_failures_total",
+                "Failed or timed out tool calls by tool name",
+            ),
+            &["tool"],
+        )?;
+        let embedding_calls = IntCounter::new(
+            "embedding_calls_total",
+            "Requests sent to the embedding backend",
+        )?;
+        let llm_request_duration = Histogram::with_opts(
+            HistogramOpts::new(
+                "llm_request_duration_seconds",
+                "Latency of chat completion requests to the LLM server",
+            )
+            .buckets(vec![0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0, 60.0, 120.0]),
+        )?;
+        let active_websockets = IntGauge::new(
+            "agent_websocket_connections",
+            "Open agent websocket connections",
+        )?;
+
+        registry.register(Box::new(agent_requests.clone()))?;
+        registry.register(Box::new(tool_calls.clone()))?;
+        registry.register(Box::new(tool_failures.clone()))?;
+        registry.register(Box::new(embedding_calls.clone()))?;
+        registry.register(Box::new(llm_request_duration.clone()))?;
+        registry.register(Box::new(active_websockets.clone()))?;
+
+        Ok(Self {
+            registry,
+            agent_requests,
+            tool_calls,
+            tool_failures,
+            embedding_calls,
+            llm_request_duration,
+            active_websockets,
+        })
+    }
+
+    /// All metrics in the Prometheus text exposition format
+    pub fn render(&self) -> Result<String> {
+        let mut buffer = Vec::new();
+        TextEncoder::new()
+            .encode(&self.registry.gather(), &mut buffer)
+            .context("Failed to encode metrics")?;
+        String::from_utf8(buffer).context("Metrics output is not UTF-8")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_includes_recorded_values() {
+        let metrics = Metrics::new().unwrap();
+        metrics.agent_requests.with_label_values(&["chat"]).inc();
+        metrics
+            .tool_calls
+            .with_label_values(&["weather_current"])
+            .inc_by(2);
+        metrics
+            .tool_failures
+            .with_label_values(&["weather_current"])
+            .inc();
+        metrics.embedding_calls.inc();
+        metrics.llm_request_duration.observe(0.3);
+        metrics.active_websockets.inc();
+
+        let text = metrics.render().unwrap();
+        assert!(text.contains("agent_requests_total{endpoint=\"chat\"} 1"));
+        assert!(text.contains("agent_tool_calls_total{tool=\"weather_current\"} 2"));
+        assert!(text.contains("agent_tool_failures_total{tool=\"weather_current\"} 1"));
+        assert!(text.contains("embedding_calls_total 1"));
+        assert!(text.contains("llm_request_duration_seconds_count 1"));
+        assert!(text.contains("agent_websocket_connections 1"));
+    }
+}
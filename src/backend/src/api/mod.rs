@@ -1,9 +1,17 @@
 pub mod agent;
 pub mod chromadb;
+pub mod converters;
+pub mod csv_to_json;
 pub mod default_configs;
+pub mod error;
+pub mod health;
 pub mod html_to_markdown;
+pub mod image_convert;
+pub mod json_to_csv;
 pub mod json_to_toon;
 pub mod llama_server;
+pub mod markdown_to_html;
+pub mod metrics;
 pub mod model_notes;
 pub mod parquet_to_txt;
 pub mod pdf_to_markdown;
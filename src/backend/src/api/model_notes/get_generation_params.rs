@@ -0,0 +1,32 @@
+use crate::api::model_notes::sqlite_storage::ModelNotesStorage;
+use crate::api::model_notes::types::ModelGenerationParamsResponse;
+use actix_web::{get, web, HttpResponse, Result as ActixResult};
+use serde::Deserialize;
+use std::sync::Arc;
+
+#[derive(Debug, Deserialize)]
+pub struct GenerationParamsQuery {
+    /// Model name as passed to the LLM server (e.g. the llama `hf_model`)
+    pub model_name: String,
+}
+
+#[get("/api/model-notes/generation-params")]
+pub async fn get_model_generation_params(
+    query: web::Query<GenerationParamsQuery>,
+    storage: web::Data<Arc<ModelNotesStorage>>,
+) -> ActixResult<HttpResponse> {
+    println!("🎛️ Fetching generation params for {}", query.model_name);
+
+    match storage.get_generation_params(&query.model_name).await {
+        Ok(Some(params)) => Ok(HttpResponse::Ok().json(ModelGenerationParamsResponse { params })),
+        Ok(None) => Ok(HttpResponse::NotFound().json(serde_json::json!({
+            "error": format!("No generation params saved for {}", query.model_name)
+        }))),
+        Err(e) => {
+            println!("Failed to fetch generation params: {}", e);
+            Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": format!("Failed to fetch generation params: {}", e)
+            })))
+        }
+    }
+}
@@ -1,12 +1,18 @@
 pub mod delete_note;
 pub mod get_default;
+pub mod get_generation_params;
 pub mod get_notes;
+pub mod post_generation_params;
 pub mod post_note;
+pub mod search_notes;
 pub mod sqlite_storage;
 pub mod types;
 
 pub use delete_note::delete_model_note;
 pub use get_default::get_default_model;
+pub use get_generation_params::get_model_generation_params;
 pub use get_notes::get_model_notes;
+pub use post_generation_params::set_generation_params;
 pub use post_note::create_or_update_model_note;
+pub use search_notes::search_model_notes;
 pub use sqlite_storage::ModelNotesStorage;
@@ -0,0 +1,55 @@
+use crate::api::agent::core::agent_loop::AgentLoopConfig;
+use crate::api::model_notes::sqlite_storage::ModelNotesStorage;
+use crate::api::model_notes::types::{
+    ModelGenerationParams, ModelGenerationParamsRequest, ModelGenerationParamsResponse,
+};
+use actix_web::{post, web, HttpResponse, Result as ActixResult};
+use std::sync::Arc;
+
+/// Save the generation parameters the agent chat uses by default for a model.
+/// Unset fields fall back to the agent defaults; request overrides still win.
+#[post("/api/model-notes/generation-params")]
+pub async fn set_generation_params(
+    req: web::Json<ModelGenerationParamsRequest>,
+    storage: web::Data<Arc<ModelNotesStorage>>,
+) -> ActixResult<HttpResponse> {
+    println!(
+        "🎛️ Saving generation params for {} (temperature: {:?}, max_tokens: {:?}, top_p: {:?})",
+        req.model_name, req.temperature, req.max_tokens, req.top_p
+    );
+
+    if req.model_name.trim().is_empty() {
+        return Ok(HttpResponse::BadRequest().json(serde_json::json!({
+            "error": "model_name is required"
+        })));
+    }
+
+    // Same ranges the agent loop enforces for per-request overrides
+    if let Err(e) = AgentLoopConfig::default().with_generation_defaults(
+        req.temperature,
+        req.max_tokens,
+        req.top_p,
+    ) {
+        return Ok(HttpResponse::BadRequest().json(serde_json::json!({
+            "error": e.to_string()
+        })));
+    }
+
+    let params = ModelGenerationParams {
+        model_name: req.model_name.trim().to_string(),
+        temperature: req.temperature,
+        max_tokens: req.max_tokens,
+        top_p: req.top_p,
+        updated_at: None,
+    };
+
+    match storage.set_generation_params(&params).await {
+        Ok(params) => Ok(HttpResponse::Ok().json(ModelGenerationParamsResponse { params })),
+        Err(e) => {
+            println!("Failed to save generation params: {}", e);
+            Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": format!("Failed to save generation params: {}", e)
+            })))
+        }
+    }
+}
@@ -0,0 +1,40 @@
+use crate::api::model_notes::sqlite_storage::ModelNotesStorage;
+use crate::api::model_notes::types::ModelNotesResponse;
+use actix_web::{get, web, HttpResponse, Result as ActixResult};
+use serde::Deserialize;
+use std::sync::Arc;
+
+#[derive(Debug, Deserialize)]
+pub struct SearchNotesQuery {
+    /// Text matched against model name, notes and tags
+    pub q: Option<String>,
+    /// Exact tag (case-insensitive)
+    pub tag: Option<String>,
+}
+
+#[get("/api/model-notes/search")]
+pub async fn search_model_notes(
+    query: web::Query<SearchNotesQuery>,
+    storage: web::Data<Arc<ModelNotesStorage>>,
+) -> ActixResult<HttpResponse> {
+    println!(
+        "🔍 Searching model notes (q: {:?}, tag: {:?})",
+        query.q, query.tag
+    );
+
+    match storage
+        .search_notes(query.q.as_deref(), query.tag.as_deref())
+        .await
+    {
+        Ok(notes) => {
+            println!("✅ Found {} matching model notes", notes.len());
+            Ok(HttpResponse::Ok().json(ModelNotesResponse { notes }))
+        }
+        Err(e) => {
+            println!("Failed to search model notes: {}", e);
+            Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": format!("Failed to search model notes: {}", e)
+            })))
+        }
+    }
+}
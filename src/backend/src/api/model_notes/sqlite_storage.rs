@@ -1,7 +1,70 @@
-use crate::api::model_notes::types::ModelNote;
+use crate::api::model_notes::types::{ModelGenerationParams, ModelNote};
+use crate::utils::migrations::{run_migrations, Migration};
 use anyhow::{Context, Result};
-use sqlx::{sqlite::SqliteConnectOptions, Row, SqlitePool};
-use std::path::Path;
+use sqlx::{sqlite::SqliteRow, Row, SqlitePool};
+
+/// Model notes schema; append new versions, never edit applied ones
+const MIGRATIONS: &[Migration] = &[Migration {
+    version: 1,
+    name: "create model_notes and model_generation_params",
+    statements: &[
+        "CREATE TABLE IF NOT EXISTS model_notes (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            platform TEXT NOT NULL,
+            model_name TEXT NOT NULL,
+            model_path TEXT,
+            is_favorite INTEGER NOT NULL DEFAULT 0,
+            is_default INTEGER NOT NULL DEFAULT 0,
+            tags TEXT,
+            notes TEXT,
+            created_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now')),
+            updated_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now')),
+            UNIQUE(platform, model_name)
+        )",
+        "CREATE TABLE IF NOT EXISTS model_generation_params (
+            model_name TEXT PRIMARY KEY,
+            temperature REAL,
+            max_tokens INTEGER,
+            top_p REAL,
+            updated_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now'))
+        )",
+        "CREATE INDEX IF NOT EXISTS idx_model_notes_platform_name ON model_notes(platform, model_name)",
+        "CREATE INDEX IF NOT EXISTS idx_model_notes_favorite ON model_notes(is_favorite)",
+    ],
+}];
+
+/// Map a `SELECT id, platform, model_name, model_path, is_favorite, is_default,
+/// tags, notes, created_at, updated_at` row to a note
+fn note_from_row(row: &SqliteRow) -> ModelNote {
+    let tags_json: Option<String> = row.get(6);
+    let tags: Vec<String> = if let Some(json) = tags_json {
+        serde_json::from_str(&json).unwrap_or_default()
+    } else {
+        Vec::new()
+    };
+
+    ModelNote {
+        id: Some(row.get(0)),
+        platform: row.get(1),
+        model_name: row.get(2),
+        model_path: row.get(3),
+        is_favorite: row.get::<i64, _>(4) != 0,
+        is_default: row.get::<i64, _>(5) != 0,
+        tags,
+        notes: row.get(7),
+        created_at: Some(row.get(8)),
+        updated_at: Some(row.get(9)),
+    }
+}
+
+/// Escape `%`, `_` and `\` so user input matches literally in a LIKE pattern
+fn like_pattern(query: &str) -> String {
+    let escaped = query
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_");
+    format!("%{}%", escaped)
+}
 
 /// SQLite-based storage for model notes
 pub struct ModelNotesStorage {
@@ -10,83 +73,26 @@ pub struct ModelNotesStorage {
 
 impl ModelNotesStorage {
     /// Create a new model notes storage
-    pub async fn new(db_path: impl AsRef<Path>) -> Result<Self> {
-        let db_path = db_path.as_ref();
-
-        // Ensure directory exists
-        if let Some(parent) = db_path.parent() {
-            std::fs::create_dir_all(parent).context("Failed to create database directory")?;
-        }
-
-        // Get absolute path
-        let absolute_path = if db_path.exists() {
-            db_path
-                .canonicalize()
-                .context("Failed to canonicalize existing database path")?
-        } else {
-            let parent = db_path.parent().unwrap_or(Path::new("."));
-            let parent_abs = parent
-                .canonicalize()
-                .or_else(|_| std::env::current_dir().map(|d| d.join(parent)))
-                .context("Failed to get absolute path for database directory")?;
-            let filename = db_path
-                .file_name()
-                .and_then(|n| n.to_str())
-                .unwrap_or("conversations.db");
-            parent_abs.join(filename)
-        };
-
-        println!(
-            "💾 Connecting to SQLite database for model notes at: {}",
-            absolute_path.display()
-        );
-
-        let options = SqliteConnectOptions::new()
-            .filename(&absolute_path)
-            .create_if_missing(true);
-
-        let pool = SqlitePool::connect_with(options).await.context(format!(
-            "Failed to connect to SQLite database at: {}",
-            absolute_path.display()
-        ))?;
-
-        // Create model_notes table
-        println!("📋 Creating model_notes table if it doesn't exist...");
-        sqlx::query(
-            "CREATE TABLE IF NOT EXISTS model_notes (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
-                platform TEXT NOT NULL,
-                model_name TEXT NOT NULL,
-                model_path TEXT,
-                is_favorite INTEGER NOT NULL DEFAULT 0,
-                is_default INTEGER NOT NULL DEFAULT 0,
-                tags TEXT,
-                notes TEXT,
-                created_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now')),
-                updated_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now')),
-                UNIQUE(platform, model_name)
-            )",
-        )
-        .execute(&pool)
-        .await
-        .context("Failed to create model_notes table")?;
-        println!("✅ model_notes table created/verified");
-
-        sqlx::query(
-            "CREATE INDEX IF NOT EXISTS idx_model_notes_platform_name ON model_notes(platform, model_name)",
-        )
-        .execute(&pool)
-        .await
-        .context("Failed to create index")?;
-        println!("✅ Index idx_model_notes_platform_name created/verified");
+    pub async fn new(pool: SqlitePool) -> Result<Self> {
+        run_migrations(&pool, "model_notes", MIGRATIONS)
+            .await
+            .context("Failed to migrate model_notes tables")?;
 
-        sqlx::query(
-            "CREATE INDEX IF NOT EXISTS idx_model_notes_favorite ON model_notes(is_favorite)",
+        // Databases created before tags existed (and before migrations were
+        // tracked) lack the column; add it once
+        let has_tags: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM pragma_table_info('model_notes') WHERE name = 'tags'",
         )
-        .execute(&pool)
+        .fetch_one(&pool)
         .await
-        .context("Failed to create favorite index")?;
-        println!("✅ Index idx_model_notes_favorite created/verified");
+        .context("Failed to inspect model_notes columns")?;
+        if has_tags == 0 {
+            sqlx::query("ALTER TABLE model_notes ADD COLUMN tags TEXT")
+                .execute(&pool)
+                .await
+                .context("Failed to add tags column to model_notes")?;
+            println!("✅ Added tags column to model_notes");
+        }
 
         // Verify table exists
         let table_exists: Option<i64> = sqlx::query_scalar(
@@ -119,30 +125,46 @@ impl ModelNotesStorage {
 
         let mut notes = Vec::new();
         for row in rows {
-            let tags_json: Option<String> = row.get(6);
-            let tags: Vec<String> = if let Some(json) = tags_json {
-                serde_json::from_str(&json).unwrap_or_default()
-            } else {
-                Vec::new()
-            };
-
-            notes.push(ModelNote {
-                id: Some(row.get(0)),
-                platform: row.get(1),
-                model_name: row.get(2),
-                model_path: row.get(3),
-                is_favorite: row.get::<i64, _>(4) != 0,
-                is_default: row.get::<i64, _>(5) != 0,
-                tags,
-                notes: row.get(7),
-                created_at: Some(row.get(8)),
-                updated_at: Some(row.get(9)),
-            });
+            notes.push(note_from_row(&row));
         }
 
         Ok(notes)
     }
 
+    /// Search notes by text (model name, notes or tags, case-insensitive) and/or an
+    /// exact tag. Empty filters are ignored, so with neither this returns all notes.
+    pub async fn search_notes(
+        &self,
+        query: Option<&str>,
+        tag: Option<&str>,
+    ) -> Result<Vec<ModelNote>> {
+        let pattern = query
+            .map(str::trim)
+            .filter(|q| !q.is_empty())
+            .map(like_pattern);
+        let tag = tag.map(str::trim).filter(|t| !t.is_empty());
+
+        let rows = sqlx::query(
+            "SELECT id, platform, model_name, model_path, is_favorite, is_default, tags, notes, created_at, updated_at
+             FROM model_notes
+             WHERE (?1 IS NULL
+                    OR model_name LIKE ?1 ESCAPE '\\'
+                    OR notes LIKE ?1 ESCAPE '\\'
+                    OR tags LIKE ?1 ESCAPE '\\')
+               AND (?2 IS NULL OR EXISTS (
+                    SELECT 1 FROM json_each(CASE WHEN json_valid(model_notes.tags) THEN model_notes.tags ELSE '[]' END)
+                    WHERE lower(json_each.value) = lower(?2)))
+             ORDER BY is_favorite DESC, updated_at DESC",
+        )
+        .bind(pattern)
+        .bind(tag)
+        .fetch_all(&self.pool)
+        .await
+        .context("Failed to search model notes")?;
+
+        Ok(rows.iter().map(note_from_row).collect())
+    }
+
     /// Get a specific model note by platform and model name
     pub async fn get_note(&self, platform: &str, model_name: &str) -> Result<Option<ModelNote>> {
         let row = sqlx::query(
@@ -156,29 +178,7 @@ impl ModelNotesStorage {
         .await
         .context("Failed to fetch model note")?;
 
-        if let Some(row) = row {
-            let tags_json: Option<String> = row.get(6);
-            let tags: Vec<String> = if let Some(json) = tags_json {
-                serde_json::from_str(&json).unwrap_or_default()
-            } else {
-                Vec::new()
-            };
-
-            Ok(Some(ModelNote {
-                id: Some(row.get(0)),
-                platform: row.get(1),
-                model_name: row.get(2),
-                model_path: row.get(3),
-                is_favorite: row.get::<i64, _>(4) != 0,
-                is_default: row.get::<i64, _>(5) != 0,
-                tags,
-                notes: row.get(7),
-                created_at: Some(row.get(8)),
-                updated_at: Some(row.get(9)),
-            }))
-        } else {
-            Ok(None)
-        }
+        Ok(row.as_ref().map(note_from_row))
     }
 
     /// Create or update a model note
@@ -372,28 +372,203 @@ impl ModelNotesStorage {
         .await
         .context("Failed to fetch default model")?;
 
-        if let Some(row) = row {
-            let tags_json: Option<String> = row.get(6);
-            let tags: Vec<String> = if let Some(json) = tags_json {
-                serde_json::from_str(&json).unwrap_or_default()
-            } else {
-                Vec::new()
-            };
+        Ok(row.as_ref().map(note_from_row))
+    }
 
-            Ok(Some(ModelNote {
-                id: Some(row.get(0)),
-                platform: row.get(1),
-                model_name: row.get(2),
-                model_path: row.get(3),
-                is_favorite: row.get::<i64, _>(4) != 0,
-                is_default: row.get::<i64, _>(5) != 0,
-                tags,
-                notes: row.get(7),
-                created_at: Some(row.get(8)),
-                updated_at: Some(row.get(9)),
-            }))
-        } else {
-            Ok(None)
+    /// Save generation parameters for a model, replacing any previous values
+    pub async fn set_generation_params(
+        &self,
+        params: &ModelGenerationParams,
+    ) -> Result<ModelGenerationParams> {
+        sqlx::query(
+            "INSERT INTO model_generation_params (model_name, temperature, max_tokens, top_p, updated_at)
+             VALUES (?1, ?2, ?3, ?4, strftime('%s', 'now'))
+             ON CONFLICT(model_name) DO UPDATE SET
+                temperature = excluded.temperature,
+                max_tokens = excluded.max_tokens,
+                top_p = excluded.top_p,
+                updated_at = excluded.updated_at",
+        )
+        .bind(&params.model_name)
+        .bind(params.temperature)
+        .bind(params.max_tokens.map(i64::from))
+        .bind(params.top_p)
+        .execute(&self.pool)
+        .await
+        .context("Failed to save generation params")?;
+
+        self.get_generation_params(&params.model_name)
+            .await?
+            .context("Generation params not found after save")
+    }
+
+    /// Get the generation parameters saved for a model, if any
+    pub async fn get_generation_params(
+        &self,
+        model_name: &str,
+    ) -> Result<Option<ModelGenerationParams>> {
+        let row = sqlx::query(
+            "SELECT model_name, temperature, max_tokens, top_p, updated_at
+             FROM model_generation_params
+             WHERE model_name = ?1",
+        )
+        .bind(model_name)
+        .fetch_optional(&self.pool)
+        .await
+        .context("Failed to fetch generation params")?;
+
+        Ok(row.map(|row| ModelGenerationParams {
+            model_name: row.get(0),
+            temperature: row.get::<Option<f64>, _>(1).map(|t| t as f32),
+            max_tokens: row
+                .get::<Option<i64>, _>(2)
+                .and_then(|t| u32::try_from(t).ok()),
+            top_p: row.get::<Option<f64>, _>(3).map(|p| p as f32),
+            updated_at: Some(row.get(4)),
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::database::connect_sqlite;
+
+    fn temp_db_path() -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("model_notes_test_{}.db", uuid::Uuid::new_v4()))
+    }
+
+    fn note(model_name: &str, tags: &[&str], notes: &str) -> ModelNote {
+        ModelNote {
+            id: None,
+            platform: "llama".to_string(),
+            model_name: model_name.to_string(),
+            model_path: None,
+            is_favorite: false,
+            is_default: false,
+            tags: tags.iter().map(|t| t.to_string()).collect(),
+            notes: Some(notes.to_string()),
+            created_at: None,
+            updated_at: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_search_notes_by_text_and_tag() {
+        let path = temp_db_path();
+        let storage = ModelNotesStorage::new(connect_sqlite(&path).await.unwrap())
+            .await
+            .unwrap();
+        storage
+            .upsert_note(&note("qwen-coder", &["good-for-coding"], "Fast at Rust"))
+            .await
+            .unwrap();
+        storage
+            .upsert_note(&note("llama-3-70b", &["slow"], "Great reasoning, 100% GPU"))
+            .await
+            .unwrap();
+
+        let by_text = storage.search_notes(Some("rust"), None).await.unwrap();
+        assert_eq!(by_text.len(), 1);
+        assert_eq!(by_text[0].model_name, "qwen-coder");
+
+        let by_tag = storage.search_notes(None, Some("Slow")).await.unwrap();
+        assert_eq!(by_tag.len(), 1);
+        assert_eq!(by_tag[0].model_name, "llama-3-70b");
+
+        // LIKE wildcards in the query match literally
+        let literal = storage.search_notes(Some("100%"), None).await.unwrap();
+        assert_eq!(literal.len(), 1);
+        let none = storage
+            .search_notes(Some("rust"), Some("slow"))
+            .await
+            .unwrap();
+        assert!(none.is_empty());
+
+        let all = storage.search_notes(None, None).await.unwrap();
+        assert_eq!(all.len(), 2);
+
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[tokio::test]
+    async fn test_new_adds_missing_tags_column() {
+        let path = temp_db_path();
+        {
+            let pool = connect_sqlite(&path).await.unwrap();
+            sqlx::query(
+                "CREATE TABLE model_notes (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    platform TEXT NOT NULL,
+                    model_name TEXT NOT NULL,
+                    model_path TEXT,
+                    is_favorite INTEGER NOT NULL DEFAULT 0,
+                    is_default INTEGER NOT NULL DEFAULT 0,
+                    notes TEXT,
+                    created_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now')),
+                    updated_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now')),
+                    UNIQUE(platform, model_name)
+                )",
+            )
+            .execute(&pool)
+            .await
+            .unwrap();
+            pool.close().await;
         }
+
+        // Opening twice must not try to add the column again
+        ModelNotesStorage::new(connect_sqlite(&path).await.unwrap())
+            .await
+            .unwrap();
+        let storage = ModelNotesStorage::new(connect_sqlite(&path).await.unwrap())
+            .await
+            .unwrap();
+        storage
+            .upsert_note(&note("phi", &["small"], ""))
+            .await
+            .unwrap();
+        let found = storage.search_notes(None, Some("small")).await.unwrap();
+        assert_eq!(found.len(), 1);
+
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[tokio::test]
+    async fn test_generation_params_round_trip() {
+        let path = temp_db_path();
+        let storage = ModelNotesStorage::new(connect_sqlite(&path).await.unwrap())
+            .await
+            .unwrap();
+        assert!(storage
+            .get_generation_params("qwen-coder")
+            .await
+            .unwrap()
+            .is_none());
+
+        let mut params = ModelGenerationParams {
+            model_name: "qwen-coder".to_string(),
+            temperature: Some(0.3),
+            max_tokens: Some(4096),
+            top_p: None,
+            updated_at: None,
+        };
+        let saved = storage.set_generation_params(&params).await.unwrap();
+        assert_eq!(saved.temperature, Some(0.3));
+        assert_eq!(saved.max_tokens, Some(4096));
+        assert!(saved.top_p.is_none());
+
+        // Saving again replaces every field, including clearing ones left unset
+        params.max_tokens = None;
+        params.top_p = Some(0.9);
+        storage.set_generation_params(&params).await.unwrap();
+        let fetched = storage
+            .get_generation_params("qwen-coder")
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(fetched.max_tokens.is_none());
+        assert_eq!(fetched.top_p, Some(0.9));
+
+        let _ = std::fs::remove_file(path);
     }
 }
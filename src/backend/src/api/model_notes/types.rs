@@ -34,3 +34,26 @@ pub struct ModelNotesResponse {
 pub struct ModelNoteResponse {
     pub note: ModelNote,
 }
+
+/// Generation settings saved for a model and applied as agent chat defaults
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct ModelGenerationParams {
+    pub model_name: String,
+    pub temperature: Option<f32>,
+    pub max_tokens: Option<u32>,
+    pub top_p: Option<f32>,
+    pub updated_at: Option<i64>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ModelGenerationParamsRequest {
+    pub model_name: String,
+    pub temperature: Option<f32>,
+    pub max_tokens: Option<u32>,
+    pub top_p: Option<f32>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ModelGenerationParamsResponse {
+    pub params: ModelGenerationParams,
+}
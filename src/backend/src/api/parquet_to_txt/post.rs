@@ -1,3 +1,4 @@
+use crate::api::error::ApiError;
 use actix_multipart::Multipart;
 use actix_web::{post, web, Error as ActixError, HttpResponse};
 use arrow::array::{
@@ -8,6 +9,9 @@ use futures_util::{stream, TryStreamExt};
 use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
 use std::time::{SystemTime, UNIX_EPOCH};
 
+/// Largest combined size of the uploaded parquet files
+pub const MAX_TOTAL_SIZE: usize = 500 * 1024 * 1024;
+
 #[post("/api/parquet-to-txt")]
 pub async fn convert_parquet_to_txt(mut payload: Multipart) -> Result<HttpResponse, ActixError> {
     let mut parquet_files: Vec<(String, Vec<u8>)> = Vec::new();
@@ -39,9 +43,7 @@ pub async fn convert_parquet_to_txt(mut payload: Multipart) -> Result<HttpRespon
 
     // Validate that we have at least one file
     if parquet_files.is_empty() {
-        return Ok(HttpResponse::BadRequest().json(serde_json::json!({
-            "error": "No parquet files provided"
-        })));
+        return Err(ApiError::BadRequest("No parquet files provided".to_string()).into());
     }
 
     println!(
@@ -50,12 +52,13 @@ pub async fn convert_parquet_to_txt(mut payload: Multipart) -> Result<HttpRespon
     );
 
     // Limit total file size to prevent memory issues (500MB max)
-    const MAX_TOTAL_SIZE: usize = 500 * 1024 * 1024;
     let total_size: usize = parquet_files.iter().map(|(_, data)| data.len()).sum();
     if total_size > MAX_TOTAL_SIZE {
-        return Ok(HttpResponse::BadRequest().json(serde_json::json!({
-            "error": format!("Total file size too large: {} bytes (max {} bytes)", total_size, MAX_TOTAL_SIZE)
-        })));
+        return Err(ApiError::BadRequest(format!(
+            "Total file size too large: {} bytes (max {} bytes)",
+            total_size, MAX_TOTAL_SIZE
+        ))
+        .into());
     }
 
     // Create a stream that processes files and yields text chunks
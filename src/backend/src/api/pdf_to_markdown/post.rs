@@ -1,8 +1,12 @@
+use crate::api::error::ApiError;
 use actix_multipart::Multipart;
 use actix_web::{post, Error as ActixError, HttpResponse};
 use futures_util::TryStreamExt;
 use serde::{Deserialize, Serialize};
 
+/// Largest PDF accepted
+pub const MAX_FILE_SIZE: usize = 50 * 1024 * 1024;
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct MarkdownResponse {
     pub markdown: String,
@@ -53,16 +57,12 @@ pub async fn convert_pdf_to_markdown(mut payload: Multipart) -> Result<HttpRespo
     let file_data = match file_data {
         Some(data) => {
             if data.is_empty() {
-                return Ok(HttpResponse::BadRequest().json(serde_json::json!({
-                    "error": "No file data received"
-                })));
+                return Err(ApiError::BadRequest("No file data received".to_string()).into());
             }
             data
         }
         None => {
-            return Ok(HttpResponse::BadRequest().json(serde_json::json!({
-                "error": "No file provided"
-            })));
+            return Err(ApiError::BadRequest("No file provided".to_string()).into());
         }
     };
 
@@ -70,9 +70,7 @@ pub async fn convert_pdf_to_markdown(mut payload: Multipart) -> Result<HttpRespo
 
     // Validate file is PDF
     if !filename.to_lowercase().ends_with(".pdf") {
-        return Ok(HttpResponse::BadRequest().json(serde_json::json!({
-            "error": "File must be a PDF"
-        })));
+        return Err(ApiError::BadRequest("File must be a PDF".to_string()).into());
     }
 
     println!(
@@ -82,11 +80,13 @@ pub async fn convert_pdf_to_markdown(mut payload: Multipart) -> Result<HttpRespo
     );
 
     // Limit file size to prevent memory issues (50MB max)
-    const MAX_FILE_SIZE: usize = 50 * 1024 * 1024;
     if file_data.len() > MAX_FILE_SIZE {
-        return Ok(HttpResponse::BadRequest().json(serde_json::json!({
-            "error": format!("File too large: {} bytes (max {} bytes)", file_data.len(), MAX_FILE_SIZE)
-        })));
+        return Err(ApiError::BadRequest(format!(
+            "File too large: {} bytes (max {} bytes)",
+            file_data.len(),
+            MAX_FILE_SIZE
+        ))
+        .into());
     }
 
     // Extract text from PDF
@@ -94,18 +94,19 @@ pub async fn convert_pdf_to_markdown(mut payload: Multipart) -> Result<HttpRespo
     let text = match extract_text_from_pdf(&file_data) {
         Ok(text) => {
             if text.trim().is_empty() {
-                return Ok(HttpResponse::BadRequest().json(serde_json::json!({
-                    "error": "PDF appears to be empty or contains no extractable text"
-                })));
+                return Err(ApiError::BadRequest(
+                    "PDF appears to be empty or contains no extractable text".to_string(),
+                )
+                .into());
             }
             println!("✅ Extracted {} characters from PDF", text.len());
             text
         }
         Err(e) => {
             println!("Failed to extract text from PDF: {}", e);
-            return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
-                "error": format!("Failed to extract text from PDF: {}", e)
-            })));
+            return Err(
+                ApiError::Internal(format!("Failed to extract text from PDF: {}", e)).into(),
+            );
         }
     };
 
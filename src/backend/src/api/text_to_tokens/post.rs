@@ -1,6 +1,11 @@
-use actix_web::{post, web, Error as ActixError, HttpResponse};
+use crate::api::converters::limits::json_config;
+use crate::api::error::ApiError;
+use actix_web::{web, Error as ActixError, HttpResponse, Resource};
 use serde::{Deserialize, Serialize};
 
+/// Largest text accepted for tokenization
+pub const MAX_TEXT_SIZE: usize = 10 * 1024 * 1024;
+
 #[derive(Deserialize, Serialize, Debug)]
 pub struct TextRequest {
     pub text: String,
@@ -13,24 +18,30 @@ pub struct TokenResponse {
     pub word_count: usize,
 }
 
-#[post("/api/text-to-tokens")]
+/// `POST /api/text-to-tokens`, accepting request bodies big enough for a `MAX_TEXT_SIZE` input
+pub fn text_to_tokens_service() -> Resource {
+    web::resource("/api/text-to-tokens")
+        .app_data(json_config(MAX_TEXT_SIZE))
+        .route(web::post().to(convert_text_to_tokens))
+}
+
 pub async fn convert_text_to_tokens(
     body: web::Json<TextRequest>,
 ) -> Result<HttpResponse, ActixError> {
     let text = body.text.trim();
 
     if text.is_empty() {
-        return Ok(HttpResponse::BadRequest().json(serde_json::json!({
-            "error": "Text cannot be empty"
-        })));
+        return Err(ApiError::BadRequest("Text cannot be empty".to_string()).into());
     }
 
     // Limit text size to prevent memory issues (10MB max)
-    const MAX_TEXT_SIZE: usize = 10 * 1024 * 1024;
     if text.len() > MAX_TEXT_SIZE {
-        return Ok(HttpResponse::BadRequest().json(serde_json::json!({
-            "error": format!("Text too large: {} bytes (max {} bytes)", text.len(), MAX_TEXT_SIZE)
-        })));
+        return Err(ApiError::PayloadTooLarge(format!(
+            "Text too large: {} bytes (max {} bytes)",
+            text.len(),
+            MAX_TEXT_SIZE
+        ))
+        .into());
     }
 
     println!(
@@ -46,9 +57,7 @@ pub async fn convert_text_to_tokens(
         }
         Err(e) => {
             println!("Failed to count tokens: {}", e);
-            return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
-                "error": format!("Failed to count tokens: {}", e)
-            })));
+            return Err(ApiError::Internal(format!("Failed to count tokens: {}", e)).into());
         }
     };
 
@@ -70,7 +79,7 @@ mod tests {
 
     #[actix_web::test]
     async fn test_convert_text_to_tokens_success() {
-        let app = test::init_service(App::new().service(convert_text_to_tokens)).await;
+        let app = test::init_service(App::new().service(text_to_tokens_service())).await;
 
         let req = test::TestRequest::post()
             .uri("/api/text-to-tokens")
@@ -92,7 +101,7 @@ mod tests {
 
     #[actix_web::test]
     async fn test_convert_text_to_tokens_empty() {
-        let app = test::init_service(App::new().service(convert_text_to_tokens)).await;
+        let app = test::init_service(App::new().service(text_to_tokens_service())).await;
 
         let req = test::TestRequest::post()
             .uri("/api/text-to-tokens")
@@ -107,7 +116,7 @@ mod tests {
 
     #[actix_web::test]
     async fn test_convert_text_to_tokens_whitespace_trimmed() {
-        let app = test::init_service(App::new().service(convert_text_to_tokens)).await;
+        let app = test::init_service(App::new().service(text_to_tokens_service())).await;
 
         let req = test::TestRequest::post()
             .uri("/api/text-to-tokens")
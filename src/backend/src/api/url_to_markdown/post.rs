@@ -3,8 +3,12 @@ use reqwest;
 use serde::{Deserialize, Serialize};
 use url::Url;
 
+use crate::api::error::ApiError;
 use crate::markdown_utils::convert::{convert_html_to_markdown, ConversionConfig};
 
+/// Largest fetched HTML page accepted
+pub const MAX_HTML_SIZE: usize = 10 * 1024 * 1024;
+
 #[derive(Deserialize, Serialize, Debug)]
 pub struct UrlRequest {
     pub url: String,
@@ -48,9 +52,7 @@ pub async fn convert_url_to_markdown(
 
     // Validate URL format
     if Url::parse(&url).is_err() {
-        return Ok(HttpResponse::BadRequest().json(serde_json::json!({
-            "error": "Invalid URL format"
-        })));
+        return Err(ApiError::BadRequest("Invalid URL format".to_string()).into());
     }
 
     // Fetch HTML from the URL
@@ -59,9 +61,11 @@ pub async fn convert_url_to_markdown(
     match response {
         Ok(response) => {
             if !response.status().is_success() {
-                return Ok(HttpResponse::BadRequest().json(serde_json::json!({
-                    "error": format!("Failed to fetch URL: HTTP {}", response.status())
-                })));
+                return Err(ApiError::BadRequest(format!(
+                    "Failed to fetch URL: HTTP {}",
+                    response.status()
+                ))
+                .into());
             }
 
             let html = response.text().await;
@@ -70,11 +74,13 @@ pub async fn convert_url_to_markdown(
                 Ok(html_content) => {
                     println!("📥 Received HTML from URL (length: {})", html_content.len());
                     // Limit response size to prevent stack overflow (10MB max)
-                    const MAX_HTML_SIZE: usize = 10 * 1024 * 1024;
                     if html_content.len() > MAX_HTML_SIZE {
-                        return Ok(HttpResponse::BadRequest().json(serde_json::json!({
-                            "error": format!("HTML response too large: {} bytes (max {} bytes)", html_content.len(), MAX_HTML_SIZE)
-                        })));
+                        return Err(ApiError::BadRequest(format!(
+                            "HTML response too large: {} bytes (max {} bytes)",
+                            html_content.len(),
+                            MAX_HTML_SIZE
+                        ))
+                        .into());
                     }
 
                     // Build conversion config from request
@@ -160,20 +166,18 @@ pub async fn convert_url_to_markdown(
                         }
                         Err(error) => {
                             println!("Conversion failed: {}", error);
-                            Ok(HttpResponse::InternalServerError().json(serde_json::json!({
-                                "error": error
-                            })))
+                            Err(ApiError::Internal(error.to_string()).into())
                         }
                     }
                 }
-                Err(error) => Ok(HttpResponse::InternalServerError().json(serde_json::json!({
-                    "error": format!("Failed to read response body: {}", error)
-                }))),
+                Err(error) => Err(ApiError::Internal(format!(
+                    "Failed to read response body: {}",
+                    error
+                ))
+                .into()),
             }
         }
-        Err(error) => Ok(HttpResponse::BadRequest().json(serde_json::json!({
-            "error": format!("Failed to fetch URL: {}", error)
-        }))),
+        Err(error) => Err(ApiError::BadRequest(format!("Failed to fetch URL: {}", error)).into()),
     }
 }
 
@@ -1,3 +1,5 @@
+use crate::api::llama_server::types::DEFAULT_STATUS_POLL_SECS;
+
 /// Get the additional arguments from "cargo run"
 ///
 /// List of arguments
@@ -18,6 +20,21 @@
 ///
 /// Set the ChromaDB address
 /// --chroma_address=http://localhost:8000
+///
+/// Set the llama-server port (also used by the agent to reach the LLM)
+/// --llama_port=8090
+///
+/// Set how often the llama-server status is polled, in seconds
+/// --llama_poll_secs=2
+///
+/// Set how many llama-server log lines are kept in memory
+/// --llama_log_lines=1000
+///
+/// Set the directory holding the SQLite database (overrides `DATA_DIR`)
+/// --data-dir=./data
+///
+/// Rate limit agent and upload requests per client (off unless set)
+/// --rate_limit_rpm=30 --rate_limit_burst=10
 pub struct Args {
     pub host: String,
     pub port: String,
@@ -26,8 +43,16 @@ pub struct Args {
     pub chroma_address: Option<String>,
     pub llama_host: Option<String>,
     pub llama_port: Option<u16>,
+    pub llama_log_lines: Option<usize>,
+    pub llama_poll_secs: u64,
+    pub data_dir: Option<String>,
+    pub rate_limit_rpm: Option<u32>,
+    pub rate_limit_burst: Option<u32>,
 }
 
+/// Upper bound for `--llama_poll_secs`, beyond which the UI status goes stale
+const MAX_STATUS_POLL_SECS: u64 = 60;
+
 pub fn collect_args(args: Vec<String>) -> Args {
     let mut env = "dev";
     let mut host = "127.0.0.1";
@@ -36,6 +61,11 @@ pub fn collect_args(args: Vec<String>) -> Args {
     let mut chroma_address: Option<String> = None;
     let mut llama_host: Option<String> = None;
     let mut llama_port: Option<u16> = None;
+    let mut llama_log_lines: Option<usize> = None;
+    let mut llama_poll_secs = DEFAULT_STATUS_POLL_SECS;
+    let mut data_dir: Option<String> = None;
+    let mut rate_limit_rpm: Option<u32> = None;
+    let mut rate_limit_burst: Option<u32> = None;
 
     for arg in &args {
         if arg.starts_with("--env=") {
@@ -82,8 +112,63 @@ pub fn collect_args(args: Vec<String>) -> Args {
         if arg.starts_with("--llama_port=") {
             let split: Vec<&str> = arg.split('=').collect();
             if split.len() == 2 {
-                if let Ok(p) = split[1].parse::<u16>() {
-                    llama_port = Some(p);
+                match split[1].parse::<u16>() {
+                    Ok(p) if p > 0 => llama_port = Some(p),
+                    _ => println!("⚠️  Ignoring invalid --llama_port value: {}", split[1]),
+                }
+            }
+        }
+
+        if arg.starts_with("--llama_log_lines=") {
+            let split: Vec<&str> = arg.split('=').collect();
+            if split.len() == 2 {
+                if let Ok(lines) = split[1].parse::<usize>() {
+                    llama_log_lines = Some(lines).filter(|lines| *lines > 0);
+                }
+            }
+        }
+
+        if arg.starts_with("--llama_poll_secs=") {
+            let split: Vec<&str> = arg.split('=').collect();
+            if split.len() == 2 {
+                match split[1].parse::<u64>() {
+                    Ok(secs) if (1..=MAX_STATUS_POLL_SECS).contains(&secs) => {
+                        llama_poll_secs = secs
+                    }
+                    _ => println!(
+                        "⚠️  Ignoring invalid --llama_poll_secs value: {} (expected 1-{})",
+                        split[1], MAX_STATUS_POLL_SECS
+                    ),
+                }
+            }
+        }
+
+        if arg.starts_with("--data-dir=") {
+            let split: Vec<&str> = arg.split('=').collect();
+            if split.len() == 2 && !split[1].is_empty() {
+                data_dir = Some(split[1].to_string());
+            }
+        }
+
+        if arg.starts_with("--rate_limit_rpm=") {
+            let split: Vec<&str> = arg.split('=').collect();
+            if split.len() == 2 {
+                match split[1].parse::<u32>() {
+                    Ok(rpm) => rate_limit_rpm = Some(rpm),
+                    _ => println!("⚠️  Ignoring invalid --rate_limit_rpm value: {}", split[1]),
+                }
+            }
+        }
+
+        if arg.starts_with("--rate_limit_burst=") {
+            let split: Vec<&str> = arg.split('=').collect();
+            if split.len() == 2 {
+                match split[1].parse::<u32>() {
+                    Ok(burst) if burst > 0 => rate_limit_burst = Some(burst),
+                    _ => println!(
+                        "⚠️  Ignoring invalid --rate_limit_burst value: {}",
+                        split[1]
+                    ),
                 }
             }
         }
@@ -97,6 +182,11 @@ pub fn collect_args(args: Vec<String>) -> Args {
         chroma_address,
         llama_host,
         llama_port,
+        llama_log_lines,
+        llama_poll_secs,
+        data_dir,
+        rate_limit_rpm,
+        rate_limit_burst,
     }
 }
 #[cfg(test)]
@@ -128,4 +218,53 @@ mod tests {
         assert_eq!(args.env, "prod");
         assert_eq!(args.cors_url, "spaceout.pl");
     }
+
+    #[test]
+    fn test_collect_llama_log_lines_arg() {
+        let args = collect_args(vec!["--llama_log_lines=250".to_string()]);
+        assert_eq!(args.llama_log_lines, Some(250));
+
+        let args = collect_args(vec!["--llama_log_lines=0".to_string()]);
+        assert_eq!(args.llama_log_lines, None);
+    }
+
+    #[test]
+    fn test_collect_llama_port_and_poll_args() {
+        let args = collect_args(vec![
+            "--llama_port=8099".to_string(),
+            "--llama_poll_secs=5".to_string(),
+        ]);
+        assert_eq!(args.llama_port, Some(8099));
+        assert_eq!(args.llama_poll_secs, 5);
+
+        let args = collect_args(vec![
+            "--llama_port=0".to_string(),
+            "--llama_poll_secs=0".to_string(),
+        ]);
+        assert_eq!(args.llama_port, None);
+        assert_eq!(args.llama_poll_secs, DEFAULT_STATUS_POLL_SECS);
+    }
+
+    #[test]
+    fn test_collect_data_dir_arg() {
+        let args = collect_args(vec!["--data-dir=/mnt/volume/ai_tools".to_string()]);
+        assert_eq!(args.data_dir.as_deref(), Some("/mnt/volume/ai_tools"));
+
+        let args = collect_args(vec!["--data-dir=".to_string()]);
+        assert_eq!(args.data_dir, None);
+    }
+
+    #[test]
+    fn test_collect_rate_limit_args() {
+        let args = collect_args(vec![
+            "--rate_limit_rpm=30".to_string(),
+            "--rate_limit_burst=5".to_string(),
+        ]);
+        assert_eq!(args.rate_limit_rpm, Some(30));
+        assert_eq!(args.rate_limit_burst, Some(5));
+
+        let args = collect_args(vec!["--rate_limit_burst=0".to_string()]);
+        assert_eq!(args.rate_limit_rpm, None);
+        assert_eq!(args.rate_limit_burst, None);
+    }
 }
@@ -0,0 +1,207 @@
+use crate::api::error::ErrorEnvelope;
+use actix_web::body::EitherBody;
+use actix_web::dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform};
+use actix_web::{http::header, Error, HttpResponse};
+use futures_util::future::LocalBoxFuture;
+use std::future::{ready, Ready};
+use std::rc::Rc;
+
+/// Environment variable holding the key required on `/api/*` (unset = open API)
+pub const API_KEY_ENV: &str = "API_KEY";
+
+/// Query parameter carrying the key on websocket upgrades, where browsers
+/// can't set an `Authorization` header
+pub const API_KEY_QUERY_PARAM: &str = "api_key";
+
+/// Key from `API_KEY`, if set and non-empty
+pub fn api_key_from_env() -> Option<String> {
+    std::env::var(API_KEY_ENV)
+        .ok()
+        .map(|key| key.trim().to_string())
+        .filter(|key| !key.is_empty())
+}
+
+/// Compare without returning early on the first differing byte
+pub fn keys_match(given: &str, expected: &str) -> bool {
+    let (given, expected) = (given.as_bytes(), expected.as_bytes());
+    given.len() == expected.len()
+        && given
+            .iter()
+            .zip(expected)
+            .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+/// Key sent with the request: `Authorization: Bearer <key>` (or the bare key),
+/// or `?api_key=<key>` on websocket routes
+fn presented_key(req: &ServiceRequest) -> Option<String> {
+    if let Some(value) = req
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+    {
+        let value = value.trim();
+        let key = value
+            .strip_prefix("Bearer ")
+            .or_else(|| value.strip_prefix("bearer "))
+            .unwrap_or(value);
+        return Some(key.trim().to_string());
+    }
+
+    if req.path().trim_end_matches('/').ends_with("/ws") {
+        return url::form_urlencoded::parse(req.query_string().as_bytes())
+            .find(|(name, _)| name == API_KEY_QUERY_PARAM)
+            .map(|(_, value)| value.into_owned());
+    }
+    None
+}
+
+/// Whether `path` needs the key. Static files, the SPA and the health check
+/// (polled by load balancers) stay public.
+pub fn requires_api_key(path: &str) -> bool {
+    (path == "/api" || path.starts_with("/api/")) && path.trim_end_matches('/') != "/api/health"
+}
+
+/// Middleware answering `401 Unauthorized` on `/api/*` requests without the
+/// configured key. With no key configured every request passes through.
+pub struct ApiKeyAuth {
+    api_key: Option<Rc<str>>,
+}
+
+impl ApiKeyAuth {
+    pub fn new(api_key: Option<String>) -> Self {
+        Self {
+            api_key: api_key.map(Rc::from),
+        }
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for ApiKeyAuth
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type InitError = ();
+    type Transform = ApiKeyAuthMiddleware<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(ApiKeyAuthMiddleware {
+            service,
+            api_key: self.api_key.clone(),
+        }))
+    }
+}
+
+pub struct ApiKeyAuthMiddleware<S> {
+    service: S,
+    api_key: Option<Rc<str>>,
+}
+
+impl<S, B> Service<ServiceRequest> for ApiKeyAuthMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        if let Some(expected) = self.api_key.as_deref() {
+            let authorized = presented_key(&req).is_some_and(|key| keys_match(&key, expected));
+            if requires_api_key(req.path()) && !authorized {
+                let response = HttpResponse::Unauthorized()
+                    .insert_header((header::WWW_AUTHENTICATE, "Bearer"))
+                    .json(ErrorEnvelope::new(
+                        "unauthorized",
+                        "Missing or invalid API key",
+                    ));
+                return Box::pin(
+                    async move { Ok(req.into_response(response).map_into_right_body()) },
+                );
+            }
+        }
+
+        let fut = self.service.call(req);
+        Box::pin(async move { fut.await.map(ServiceResponse::map_into_left_body) })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::{test, web, App};
+
+    #[actix_web::test]
+    async fn test_keys_match() {
+        assert!(keys_match("secret", "secret"));
+        assert!(!keys_match("secreT", "secret"));
+        assert!(!keys_match("secret2", "secret"));
+        assert!(!keys_match("", "secret"));
+    }
+
+    #[actix_web::test]
+    async fn test_api_key_required_on_api_routes() {
+        let app = test::init_service(
+            App::new()
+                .wrap(ApiKeyAuth::new(Some("secret".to_string())))
+                .route("/api/agent/config", web::get().to(HttpResponse::Ok))
+                .route("/api/agent/stream/ws", web::get().to(HttpResponse::Ok))
+                .route("/api/health", web::get().to(HttpResponse::Ok))
+                .route("/index.html", web::get().to(HttpResponse::Ok)),
+        )
+        .await;
+
+        let get = |uri: &str, auth: Option<&str>| {
+            let mut req = test::TestRequest::get().uri(uri);
+            if let Some(auth) = auth {
+                req = req.insert_header((header::AUTHORIZATION, auth.to_string()));
+            }
+            req.to_request()
+        };
+
+        let resp = test::call_service(&app, get("/api/agent/config", None)).await;
+        assert_eq!(resp.status(), 401);
+        let resp = test::call_service(&app, get("/api/agent/config", Some("Bearer nope"))).await;
+        assert_eq!(resp.status(), 401);
+        let resp = test::call_service(&app, get("/api/agent/config", Some("Bearer secret"))).await;
+        assert_eq!(resp.status(), 200);
+
+        // Static files and the health check stay public
+        let resp = test::call_service(&app, get("/index.html", None)).await;
+        assert_eq!(resp.status(), 200);
+        let resp = test::call_service(&app, get("/api/health", None)).await;
+        assert_eq!(resp.status(), 200);
+
+        // Websocket upgrades may pass the key as a query param
+        let resp = test::call_service(&app, get("/api/agent/stream/ws?api_key=secret", None)).await;
+        assert_eq!(resp.status(), 200);
+        let resp = test::call_service(&app, get("/api/agent/stream/ws?api_key=nope", None)).await;
+        assert_eq!(resp.status(), 401);
+        // ...but only websocket routes
+        let resp = test::call_service(&app, get("/api/agent/config?api_key=secret", None)).await;
+        assert_eq!(resp.status(), 401);
+    }
+
+    #[actix_web::test]
+    async fn test_no_api_key_configured_allows_everything() {
+        let app = test::init_service(
+            App::new()
+                .wrap(ApiKeyAuth::new(None))
+                .route("/api/agent/config", web::get().to(HttpResponse::Ok)),
+        )
+        .await;
+
+        let req = test::TestRequest::get()
+            .uri("/api/agent/config")
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 200);
+    }
+}
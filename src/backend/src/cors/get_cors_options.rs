@@ -1,6 +1,8 @@
 use actix_cors::Cors;
 use actix_web::http;
 
+use crate::api::agent::service::utils::REQUEST_ID_HEADER;
+
 /// Gets the CORS options based on the environment and allowed origin.
 ///
 /// # Arguments
@@ -23,15 +25,17 @@ pub fn get_cors_options(env: String, allowed_origin: String) -> Cors {
     if env == "prod" {
         Cors::default()
             .allowed_origin(&allowed_origin)
-            .allowed_methods(vec!["GET", "POST"])
+            .allowed_methods(vec!["GET", "POST", "PATCH"])
             .allowed_headers(vec![http::header::AUTHORIZATION, http::header::ACCEPT])
             .allowed_header(http::header::CONTENT_TYPE)
+            .expose_headers(vec![REQUEST_ID_HEADER, "Retry-After"])
             .max_age(3600)
     } else {
         Cors::default()
             .allow_any_origin()
             .allow_any_method()
             .allow_any_header()
+            .expose_headers(vec![REQUEST_ID_HEADER, "Retry-After"])
             .max_age(3600)
     }
 }
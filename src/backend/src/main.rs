@@ -8,8 +8,10 @@ use actix_web::{middleware, web, App, HttpServer};
 
 mod api;
 mod args;
+mod auth;
 mod cors;
 mod markdown_utils;
+mod rate_limit;
 mod services;
 mod utils;
 
@@ -20,31 +22,51 @@ use crate::api::agent::memory::sqlite_memory::SqliteConversationMemory;
 use crate::api::agent::service::config::AgentConfigHandle;
 use crate::api::agent::service::websocket::{agent_websocket, AgentWebSocketState};
 use crate::api::agent::testing::storage::TestingStorage;
+use crate::api::agent::tools::framework::cache::{ToolResultCache, DEFAULT_CACHE_CAPACITY};
+use crate::api::chromadb::client::OllamaManager;
 use crate::api::chromadb::config::types::ChromaDBConfig;
+use crate::api::chromadb::documents::limits::UploadLimits;
 use crate::api::default_configs::DefaultConfigsStorage;
+use crate::api::error::json_error_handlers;
+use crate::api::health::ServiceStartTime;
+use crate::api::llama_server::get_status::LlamaServerStatus;
 use crate::api::llama_server::types::{
-    Config, LogBuffer, ProcessHandle, ServerState, ServerStateHandle,
+    set_log_buffer_max, Config, LogBuffer, ProcessHandle, ServerState, ServerStateHandle,
 };
 use crate::api::llama_server::websocket::{logs_websocket, status_websocket, WebSocketState};
+use crate::api::metrics::registry::{install as install_metrics, metrics_enabled_from_env};
+use crate::api::metrics::Metrics;
 use crate::api::model_notes::ModelNotesStorage;
 use crate::api::sd_server::types::{
     LogBuffer as SDLogBuffer, SDConfig, SDConfigHandle, SDProcessHandle, SDState as SDServerState,
     SDStateHandle as SDServerStateHandle,
 };
 use crate::args::collect_args::collect_args;
+use crate::auth::api_key::{api_key_from_env, ApiKeyAuth};
 use crate::cors::get_cors_options::get_cors_options;
+use crate::rate_limit::limiter::{RateLimitConfig, RateLimiter};
+use crate::rate_limit::middleware::RateLimit;
 use crate::services::agent::configure_agent_services;
 use crate::services::chromadb::configure_chromadb_services;
 use crate::services::converters::configure_converter_services;
+use crate::services::default_configs::configure_default_configs_services;
+use crate::services::health::configure_health_services;
 use crate::services::llama_server::configure_llama_server_services;
+use crate::services::metrics::configure_metrics_services;
 use crate::services::model_notes::configure_model_notes_services;
 use crate::services::sd_server::configure_sd_server_services;
+use crate::utils::database::{connect_sqlite, database_path, resolve_data_dir};
+use crate::utils::process::kill_child;
 
 use std::sync::{Arc, Mutex};
 
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
     dotenv().ok();
+    let start_time = ServiceStartTime(std::time::Instant::now());
+
+    // Verbosity is controlled via RUST_LOG (e.g. RUST_LOG=debug or RUST_LOG=backend=warn)
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info")).init();
     let args = collect_args(env::args().collect());
     let host = args.host;
     let port = args.port.parse::<u16>().unwrap();
@@ -56,30 +78,32 @@ async fn main() -> std::io::Result<()> {
         .unwrap_or_else(|| "http://localhost:8000".to_string());
     println!("🔗 ChromaDB address: {}", chroma_address);
 
+    // One pool for every storage in <data dir>/conversations.db
+    let data_dir = resolve_data_dir(args.data_dir.clone());
+    println!("📁 Data directory: {}", data_dir.display());
+    let db_path = database_path(&data_dir).expect("Failed to create data directory");
+    let db_pool = connect_sqlite(&db_path)
+        .await
+        .expect("Failed to connect to database");
+
     // SQLite-based conversation storage (persists user/assistant messages)
     let sqlite_memory: Arc<SqliteConversationMemory> = Arc::new(
-        SqliteConversationMemory::new("./data/conversations.db")
+        SqliteConversationMemory::new(db_pool.clone())
             .await
             .expect("Failed to initialize SQLite conversation memory"),
     );
 
     let model_notes_storage: Arc<ModelNotesStorage> = Arc::new(
-        ModelNotesStorage::new("./data/conversations.db")
+        ModelNotesStorage::new(db_pool.clone())
             .await
             .expect("Failed to initialize model notes storage"),
     );
     let default_configs_storage: Arc<DefaultConfigsStorage> = Arc::new(
-        DefaultConfigsStorage::new("./data/conversations.db")
+        DefaultConfigsStorage::new(db_pool.clone())
             .await
             .expect("Failed to initialize default configs storage"),
     );
 
-    use sqlx::sqlite::SqlitePoolOptions;
-    let db_pool = SqlitePoolOptions::new()
-        .connect("./data/conversations.db")
-        .await
-        .expect("Failed to connect to database");
-
     let testing_storage = TestingStorage::new(db_pool.clone())
         .await
         .expect("Failed to initialize testing storage");
@@ -105,9 +129,13 @@ async fn main() -> std::io::Result<()> {
     // Shared state for llama server process
     let llama_process = ProcessHandle(Arc::new(Mutex::new(None)));
     let llama_logs: LogBuffer = Arc::new(Mutex::new(std::collections::VecDeque::new()));
+    if let Some(max_lines) = args.llama_log_lines {
+        set_log_buffer_max(max_lines);
+    }
     let llama_server_state: ServerStateHandle = Arc::new(Mutex::new(ServerState {
         is_ready: false,
         generation: 0,
+        port: llama_config.lock().unwrap().server_port(),
     }));
 
     // Initialize ChromaDB config with default from storage or fallback to hardcoded
@@ -130,6 +158,9 @@ async fn main() -> std::io::Result<()> {
     // Shared state for agent config
     let agent_config: AgentConfigHandle = Arc::new(Mutex::new(AgentConfig::default()));
 
+    // Shared cache for tool results (enabled via AgentConfig::cache_ttl_secs)
+    let tool_cache = Arc::new(ToolResultCache::new(DEFAULT_CACHE_CAPACITY));
+
     // Shared state for Active Generations (cancellation tokens)
     let active_generations: ActiveGenerations =
         Arc::new(Mutex::new(std::collections::HashMap::new()));
@@ -153,6 +184,15 @@ async fn main() -> std::io::Result<()> {
         }
     });
 
+    // Load the token-counting tokenizer off the request path; agent history
+    // trimming estimates by characters until it is ready
+    actix_rt::spawn(async {
+        let _ = tokio::task::spawn_blocking(|| {
+            let _ = crate::utils::tokenizer::get_tokenizer();
+        })
+        .await;
+    });
+
     use crate::api::sd_server::model_sets::SDModelSetsStorage;
     let sd_model_sets_storage = Arc::new(SDModelSetsStorage::new(db_pool.clone()));
 
@@ -206,9 +246,10 @@ async fn main() -> std::io::Result<()> {
     let llama_process_status = llama_process.clone();
     let llama_server_state_status = llama_server_state.clone();
     let llama_config_status = llama_config.clone();
+    let llama_poll_secs = args.llama_poll_secs;
     actix_rt::spawn(async move {
         use tokio::time::{interval, Duration};
-        let mut interval = interval(Duration::from_secs(2));
+        let mut interval = interval(Duration::from_secs(llama_poll_secs));
 
         loop {
             interval.tick().await;
@@ -275,12 +316,26 @@ async fn main() -> std::io::Result<()> {
 
             let port = {
                 let config = config_handle.lock().unwrap();
-                config.port.unwrap_or(8080)
+                config.server_port()
             };
 
             let active = if is_active { is_ready } else { false };
 
-            ws_state_status.broadcast_status(active, port);
+            // Probe the port separately so the UI can tell "loading model" from "stopped"
+            let port_open = is_active
+                && tokio::time::timeout(
+                    Duration::from_millis(250),
+                    tokio::net::TcpStream::connect(("127.0.0.1", port)),
+                )
+                .await
+                .is_ok_and(|conn| conn.is_ok());
+
+            ws_state_status.broadcast_status(&LlamaServerStatus {
+                active,
+                port,
+                model_loaded: is_active && is_ready,
+                port_open,
+            });
         }
     });
 
@@ -292,12 +347,39 @@ async fn main() -> std::io::Result<()> {
     let ws_state_data = ws_state.clone();
     let agent_ws_state_data = agent_ws_state.clone();
     let chroma_address_data = web::Data::new(chroma_address.clone());
+    let db_pool_data = web::Data::new(db_pool.clone());
+    let rate_limit_config = RateLimitConfig::resolve(args.rate_limit_rpm, args.rate_limit_burst);
+    if rate_limit_config.is_enabled() {
+        println!(
+            "⏳ Rate limiting agent and upload requests: {}/min per client, burst {}",
+            rate_limit_config.requests_per_minute, rate_limit_config.burst
+        );
+    }
+    let rate_limiter = Arc::new(RateLimiter::new(rate_limit_config));
+    let api_key = api_key_from_env();
+    if api_key.is_some() {
+        println!("🔐 API key required on /api/* routes");
+    }
+    let metrics: Option<Arc<Metrics>> = if metrics_enabled_from_env() {
+        let metrics = Arc::new(Metrics::new().expect("Failed to create metrics registry"));
+        install_metrics(Arc::clone(&metrics));
+        println!("📈 Prometheus metrics enabled at /metrics");
+        Some(metrics)
+    } else {
+        None
+    };
+    let upload_limits = UploadLimits::from_env();
+    println!(
+        "📦 Upload limits: {} bytes per file, {} bytes per request",
+        upload_limits.max_file_bytes, upload_limits.max_total_bytes
+    );
     let chromadb_config_data = chromadb_config.clone();
     let agent_config_data = agent_config.clone();
     let sqlite_memory_data = web::Data::new(sqlite_memory.clone());
     let model_notes_storage_data = web::Data::new(model_notes_storage.clone());
     let default_configs_storage_data = web::Data::new(default_configs_storage.clone());
     let active_generations_data = web::Data::new(active_generations.clone());
+    let tool_cache_data = web::Data::new(tool_cache.clone());
     let sd_config_data = sd_config.clone();
     let sd_process_data = sd_process.clone();
     let sd_logs_data = sd_logs.clone();
@@ -326,12 +408,23 @@ async fn main() -> std::io::Result<()> {
             .app_data(web::Data::new(ws_state_data.clone()))
             .app_data(web::Data::new(agent_ws_state_data.clone()))
             .app_data(chroma_address_data.clone())
+            .app_data(db_pool_data.clone())
+            .app_data(web::Data::new(start_time))
+            .app_data(web::Data::new(upload_limits))
+            // Typed multipart forms get the same cap; the raw document upload
+            // stream is checked chunk by chunk in the handler
+            .app_data(
+                actix_multipart::form::MultipartFormConfig::default()
+                    .total_limit(upload_limits.max_total_bytes)
+                    .memory_limit(upload_limits.max_total_bytes),
+            )
             .app_data(web::Data::new(chromadb_config_data.clone()))
             .app_data(web::Data::new(agent_config_data.clone()))
             .app_data(sqlite_memory_data.clone())
             .app_data(model_notes_storage_data.clone())
             .app_data(default_configs_storage_data.clone())
             .app_data(active_generations_data.clone())
+            .app_data(tool_cache_data.clone())
             .app_data(web::Data::new(testing_storage.clone()))
             .app_data(web::Data::new(sd_config_data.clone()))
             .app_data(web::Data::new(sd_process_data.clone()))
@@ -340,6 +433,12 @@ async fn main() -> std::io::Result<()> {
             .app_data(web::Data::new(sd_ws_state_data.clone()))
             .app_data(web::Data::new(sd_images_storage.clone()))
             .app_data(web::Data::new(sd_model_sets_storage.clone()))
+            .wrap(json_error_handlers())
+            .wrap(middleware::Condition::new(
+                rate_limit_config.is_enabled(),
+                RateLimit::new(Arc::clone(&rate_limiter)),
+            ))
+            .wrap(ApiKeyAuth::new(api_key.clone()))
             .wrap(cors)
             .route("/api/llama-server/logs/ws", web::get().to(logs_websocket))
             .route(
@@ -351,11 +450,19 @@ async fn main() -> std::io::Result<()> {
                 web::get().to(crate::api::sd_server::websocket::sd_logs_ws),
             )
             .route("/api/agent/stream/ws", web::get().to(agent_websocket))
+            .configure(configure_health_services)
+            .configure(|cfg| {
+                if let Some(metrics) = &metrics {
+                    cfg.app_data(web::Data::new(Arc::clone(metrics)));
+                    configure_metrics_services(cfg);
+                }
+            })
             .configure(configure_converter_services)
             .configure(configure_llama_server_services)
             .configure(configure_chromadb_services)
             .configure(configure_agent_services)
             .configure(configure_model_notes_services)
+            .configure(configure_default_configs_services)
             .configure(configure_sd_server_services)
             .service(Files::new("/public", &images_path_str).show_files_listing())
             .service(
@@ -381,5 +488,13 @@ async fn main() -> std::io::Result<()> {
         println!("Actix server has started 🚀");
     });
 
-    server.await
+    let result = server.await;
+
+    // The server has stopped (e.g. Ctrl-C); don't leave child servers orphaned
+    println!("🛑 Shutting down child processes...");
+    kill_child(&llama_process, "llama-server");
+    kill_child(&sd_process, "sd-server");
+    OllamaManager::kill_spawned_server();
+
+    result
 }
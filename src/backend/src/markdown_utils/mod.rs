@@ -2,3 +2,4 @@ pub mod clean;
 pub mod convert;
 pub mod extract;
 pub mod links;
+pub mod render;
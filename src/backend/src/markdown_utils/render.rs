@@ -0,0 +1,73 @@
+use pulldown_cmark::{html, Options, Parser};
+
+/// GitHub-flavoured extensions enabled when rendering markdown
+fn gfm_options() -> Options {
+    Options::ENABLE_TABLES
+        | Options::ENABLE_STRIKETHROUGH
+        | Options::ENABLE_TASKLISTS
+        | Options::ENABLE_FOOTNOTES
+}
+
+/// Strip scripts, event handlers and other unsafe HTML while keeping what the
+/// renderer produces (task list checkboxes, code block language classes)
+pub fn sanitize_html(html: &str) -> String {
+    ammonia::Builder::default()
+        .add_tags(&["input"])
+        .add_tag_attributes("input", &["type", "checked", "disabled"])
+        .add_tag_attributes("code", &["class"])
+        .clean(html)
+        .to_string()
+}
+
+/// Renders markdown (with tables, strikethrough and task lists) to HTML,
+/// sanitizing the output unless `sanitize` is false
+pub fn render_markdown_to_html(markdown: &str, sanitize: bool) -> String {
+    let parser = Parser::new_ext(markdown, gfm_options());
+    let mut output = String::with_capacity(markdown.len() * 3 / 2);
+    html::push_html(&mut output, parser);
+
+    if sanitize {
+        sanitize_html(&output)
+    } else {
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_code_block() {
+        let html = render_markdown_to_html("```rust\nlet x = 1 < 2;\n```", true);
+        assert!(html.contains("<pre><code class=\"language-rust\">"));
+        assert!(html.contains("let x = 1 &lt; 2;"));
+    }
+
+    #[test]
+    fn test_render_table() {
+        let html = render_markdown_to_html("| a | b |\n|---|---|\n| 1 | 2 |", true);
+        assert!(html.contains("<table>"));
+        assert!(html.contains("<th>a</th>"));
+        assert!(html.contains("<td>2</td>"));
+    }
+
+    #[test]
+    fn test_render_strikethrough_and_tasks() {
+        let html = render_markdown_to_html("- [x] ~~done~~\n- [ ] todo", true);
+        assert!(html.contains("<del>done</del>"));
+        assert!(html.contains("type=\"checkbox\""));
+        assert!(html.contains("checked"));
+    }
+
+    #[test]
+    fn test_sanitize_removes_scripts() {
+        let markdown = "hi <script>alert(1)</script> <a href=\"#\" onclick=\"x()\">link</a>";
+        let html = render_markdown_to_html(markdown, true);
+        assert!(!html.contains("<script>"));
+        assert!(!html.contains("onclick"));
+
+        let raw = render_markdown_to_html(markdown, false);
+        assert!(raw.contains("<script>"));
+    }
+}
@@ -0,0 +1,203 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Environment variable setting requests per minute per client when
+/// `--rate_limit_rpm` isn't given
+pub const RATE_LIMIT_RPM_ENV: &str = "RATE_LIMIT_RPM";
+/// Environment variable setting the burst size when `--rate_limit_burst` isn't given
+pub const RATE_LIMIT_BURST_ENV: &str = "RATE_LIMIT_BURST";
+
+/// Buckets kept before idle (full) ones are pruned
+const MAX_TRACKED_CLIENTS: usize = 10_000;
+
+/// Token bucket settings shared by every client
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimitConfig {
+    /// Sustained requests allowed per minute (0 = rate limiting off)
+    pub requests_per_minute: u32,
+    /// Requests a client may make back to back before being throttled
+    pub burst: u32,
+}
+
+impl RateLimitConfig {
+    /// Config from the `--rate_limit_rpm` / `--rate_limit_burst` args, falling
+    /// back to `RATE_LIMIT_RPM` / `RATE_LIMIT_BURST`. Off unless a rate is set;
+    /// the burst defaults to one minute's worth of requests.
+    pub fn resolve(rpm_arg: Option<u32>, burst_arg: Option<u32>) -> Self {
+        let from_env = |name: &str| {
+            std::env::var(name)
+                .ok()
+                .and_then(|v| v.trim().parse::<u32>().ok())
+        };
+        Self::from_values(
+            rpm_arg.or_else(|| from_env(RATE_LIMIT_RPM_ENV)),
+            burst_arg.or_else(|| from_env(RATE_LIMIT_BURST_ENV)),
+        )
+    }
+
+    fn from_values(rpm: Option<u32>, burst: Option<u32>) -> Self {
+        let requests_per_minute = rpm.unwrap_or(0);
+        Self {
+            requests_per_minute,
+            burst: burst.filter(|b| *b > 0).unwrap_or(requests_per_minute),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.requests_per_minute > 0
+    }
+}
+
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+/// Per-client token buckets. Each client starts with `burst` tokens, every
+/// request takes one, and tokens refill at `requests_per_minute / 60` per second.
+pub struct RateLimiter {
+    config: RateLimitConfig,
+    buckets: Mutex<Buckets>,
+}
+
+#[derive(Default)]
+struct Buckets {
+    by_client: HashMap<String, Bucket>,
+    /// Earliest time the next prune may run, so a map full of busy clients
+    /// isn't scanned on every request
+    next_prune: Option<Instant>,
+}
+
+impl RateLimiter {
+    pub fn new(config: RateLimitConfig) -> Self {
+        Self {
+            config,
+            buckets: Mutex::new(Buckets::default()),
+        }
+    }
+
+    fn refill_per_sec(&self) -> f64 {
+        self.config.requests_per_minute as f64 / 60.0
+    }
+
+    /// Take a token for `client` at `now`. When the bucket is empty, returns
+    /// how long until the next token is available.
+    pub fn check(&self, client: &str, now: Instant) -> Result<(), Duration> {
+        if !self.config.is_enabled() {
+            return Ok(());
+        }
+        let capacity = self.config.burst as f64;
+        let rate = self.refill_per_sec();
+        let mut buckets = self.buckets.lock().unwrap();
+
+        if buckets.by_client.len() >= MAX_TRACKED_CLIENTS
+            && buckets.next_prune.is_none_or(|at| now >= at)
+        {
+            // A bucket that has refilled completely is the same as a new one
+            buckets.by_client.retain(|_, b| {
+                b.tokens + now.saturating_duration_since(b.updated).as_secs_f64() * rate < capacity
+            });
+            // Nothing kept now can be full before an empty bucket refills
+            buckets.next_prune = Some(now + Duration::from_secs_f64(capacity / rate));
+        }
+
+        let bucket = buckets
+            .by_client
+            .entry(client.to_string())
+            .or_insert(Bucket {
+                tokens: capacity,
+                updated: now,
+            });
+        let elapsed = now.saturating_duration_since(bucket.updated).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * rate).min(capacity);
+        bucket.updated = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64((1.0 - bucket.tokens) / rate))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rate_limit_config_defaults_off() {
+        let config = RateLimitConfig::from_values(None, None);
+        assert!(!config.is_enabled());
+
+        let config = RateLimitConfig::from_values(Some(30), None);
+        assert!(config.is_enabled());
+        assert_eq!(config.burst, 30);
+
+        let config = RateLimitConfig::from_values(Some(30), Some(5));
+        assert_eq!(config.burst, 5);
+    }
+
+    #[test]
+    fn test_rate_limiter_throttles_after_burst_and_refills() {
+        let limiter = RateLimiter::new(RateLimitConfig {
+            requests_per_minute: 60,
+            burst: 2,
+        });
+        let start = Instant::now();
+
+        assert!(limiter.check("10.0.0.1", start).is_ok());
+        assert!(limiter.check("10.0.0.1", start).is_ok());
+        let retry_after = limiter.check("10.0.0.1", start).unwrap_err();
+        assert!(retry_after > Duration::ZERO && retry_after <= Duration::from_secs(1));
+
+        // Other clients have their own bucket
+        assert!(limiter.check("10.0.0.2", start).is_ok());
+
+        // One token per second at 60 rpm
+        assert!(limiter
+            .check("10.0.0.1", start + Duration::from_secs(1))
+            .is_ok());
+        assert!(limiter
+            .check("10.0.0.1", start + Duration::from_secs(1))
+            .is_err());
+    }
+
+    #[test]
+    fn test_prune_runs_at_most_once_per_refill_period() {
+        let limiter = RateLimiter::new(RateLimitConfig {
+            requests_per_minute: 60,
+            burst: 2,
+        });
+        let start = Instant::now();
+        for i in 0..MAX_TRACKED_CLIENTS {
+            assert!(limiter.check(&format!("client-{}", i), start).is_ok());
+        }
+
+        // Every bucket is in use, so pruning keeps them all and waits
+        assert!(limiter.check("late-1", start).is_ok());
+        let next_prune = limiter.buckets.lock().unwrap().next_prune;
+        assert_eq!(next_prune, Some(start + Duration::from_secs(2)));
+        assert!(limiter.check("late-2", start).is_ok());
+        assert_eq!(
+            limiter.buckets.lock().unwrap().by_client.len(),
+            MAX_TRACKED_CLIENTS + 2
+        );
+
+        // Once buckets have had time to refill they are dropped
+        assert!(limiter
+            .check("late-3", start + Duration::from_secs(2))
+            .is_ok());
+        assert_eq!(limiter.buckets.lock().unwrap().by_client.len(), 1);
+    }
+
+    #[test]
+    fn test_disabled_rate_limiter_allows_everything() {
+        let limiter = RateLimiter::new(RateLimitConfig::from_values(None, None));
+        let now = Instant::now();
+        for _ in 0..100 {
+            assert!(limiter.check("10.0.0.1", now).is_ok());
+        }
+    }
+}
@@ -0,0 +1,173 @@
+use super::limiter::RateLimiter;
+use crate::api::error::ErrorEnvelope;
+use actix_web::body::EitherBody;
+use actix_web::dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform};
+use actix_web::{http::header, Error, HttpResponse};
+use futures_util::future::LocalBoxFuture;
+use std::future::{ready, Ready};
+use std::sync::Arc;
+use std::time::Instant;
+
+/// Whether `path` is one of the expensive endpoints that get rate limited
+/// (agent chat/RAG and document upload)
+pub fn is_rate_limited_path(path: &str) -> bool {
+    let path = path.trim_end_matches('/');
+    path.starts_with("/api/agent/") || path == "/api/chromadb/documents/upload"
+}
+
+/// Bucket key for a request: the peer IP. Not a client-supplied header such
+/// as X-Forwarded-For or an API key, which a client could vary per request
+/// to get a fresh bucket each time.
+fn client_key(req: &ServiceRequest) -> String {
+    req.connection_info()
+        .peer_addr()
+        .unwrap_or("unknown")
+        .to_string()
+}
+
+/// Middleware answering `429 Too Many Requests` with `Retry-After` once a
+/// client has used up its token bucket on a rate-limited path
+pub struct RateLimit {
+    limiter: Arc<RateLimiter>,
+}
+
+impl RateLimit {
+    pub fn new(limiter: Arc<RateLimiter>) -> Self {
+        Self { limiter }
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for RateLimit
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type InitError = ();
+    type Transform = RateLimitMiddleware<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(RateLimitMiddleware {
+            service,
+            limiter: Arc::clone(&self.limiter),
+        }))
+    }
+}
+
+pub struct RateLimitMiddleware<S> {
+    service: S,
+    limiter: Arc<RateLimiter>,
+}
+
+impl<S, B> Service<ServiceRequest> for RateLimitMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        if is_rate_limited_path(req.path()) {
+            let key = client_key(&req);
+            if let Err(retry_after) = self.limiter.check(&key, Instant::now()) {
+                let secs = retry_after.as_secs_f64().ceil().max(1.0) as u64;
+                println!("⏳ Rate limited {} on {}", key, req.path());
+                let response = HttpResponse::TooManyRequests()
+                    .insert_header((header::RETRY_AFTER, secs.to_string()))
+                    .json(ErrorEnvelope::new(
+                        "too_many_requests",
+                        format!("Too many requests, retry in {} second(s)", secs),
+                    ));
+                return Box::pin(
+                    async move { Ok(req.into_response(response).map_into_right_body()) },
+                );
+            }
+        }
+
+        let fut = self.service.call(req);
+        Box::pin(async move { fut.await.map(ServiceResponse::map_into_left_body) })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rate_limit::limiter::RateLimitConfig;
+    use actix_web::{test, web, App};
+
+    #[actix_web::test]
+    async fn test_is_rate_limited_path() {
+        assert!(is_rate_limited_path("/api/agent/chat"));
+        assert!(is_rate_limited_path("/api/agent/rag"));
+        assert!(is_rate_limited_path("/api/chromadb/documents/upload/"));
+        assert!(!is_rate_limited_path("/api/chromadb/collections"));
+        assert!(!is_rate_limited_path("/api/agent"));
+    }
+
+    #[actix_web::test]
+    async fn test_rate_limit_returns_429_with_retry_after() {
+        let limiter = Arc::new(RateLimiter::new(RateLimitConfig {
+            requests_per_minute: 1,
+            burst: 1,
+        }));
+        let app = test::init_service(
+            App::new()
+                .wrap(RateLimit::new(limiter))
+                .route("/api/agent/chat", web::post().to(HttpResponse::Ok))
+                .route("/api/chromadb/collections", web::get().to(HttpResponse::Ok)),
+        )
+        .await;
+
+        let chat = || {
+            test::TestRequest::post()
+                .uri("/api/agent/chat")
+                .peer_addr("10.0.0.1:5000".parse().unwrap())
+                .to_request()
+        };
+        assert_eq!(test::call_service(&app, chat()).await.status(), 200);
+
+        let resp = test::call_service(&app, chat()).await;
+        assert_eq!(resp.status(), 429);
+        let retry_after: u64 = resp
+            .headers()
+            .get(header::RETRY_AFTER)
+            .unwrap()
+            .to_str()
+            .unwrap()
+            .parse()
+            .unwrap();
+        assert!((1..=60).contains(&retry_after));
+
+        // Paths outside the limited set are unaffected
+        for _ in 0..3 {
+            let req = test::TestRequest::get()
+                .uri("/api/chromadb/collections")
+                .peer_addr("10.0.0.1:5000".parse().unwrap())
+                .to_request();
+            assert_eq!(test::call_service(&app, req).await.status(), 200);
+        }
+
+        // Sending a made-up key doesn't buy a fresh bucket
+        let req = test::TestRequest::post()
+            .uri("/api/agent/chat")
+            .peer_addr("10.0.0.1:5000".parse().unwrap())
+            .insert_header(("X-API-Key", "team-a"))
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 429);
+
+        // Another address has its own bucket
+        let req = test::TestRequest::post()
+            .uri("/api/agent/chat")
+            .peer_addr("10.0.0.2:5000".parse().unwrap())
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 200);
+    }
+}
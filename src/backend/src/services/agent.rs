@@ -1,16 +1,22 @@
 use actix_web::web::ServiceConfig;
 
-use crate::api::agent::service::chat::{agent_chat, agent_chat_stream, cancel_agent_generation};
+use crate::api::agent::service::chat::{
+    agent_chat, agent_chat_stream, cancel_agent_generation, cancel_generation,
+};
 use crate::api::agent::service::config::{
     get_agent_config, get_agent_status, get_available_tools, get_model_capabilities,
-    post_agent_config,
+    post_agent_config, post_enabled_tools,
 };
 use crate::api::agent::service::conversations::{
-    delete_conversation, get_conversation_history, get_conversations, update_conversation_title,
+    clear_all_conversations, delete_conversation, export_conversation, get_conversation_history,
+    get_conversations, search_conversations, update_conversation_title,
 };
+use crate::api::agent::service::eval::agent_eval;
+use crate::api::agent::service::rag::agent_rag;
+use crate::api::agent::service::validate::validate_tools;
 use crate::api::agent::testing::routes::{
-    add_question, create_suite, delete_question, delete_suite, get_questions, get_suites,
-    update_question, update_suite,
+    add_question, create_suite, delete_question, delete_suite, get_questions, get_runs, get_suites,
+    run_question, run_suite, update_question, update_suite,
 };
 
 /// Configures all agent related endpoints
@@ -18,15 +24,23 @@ pub fn configure_agent_services(cfg: &mut ServiceConfig) {
     cfg.service(get_agent_status)
         .service(get_agent_config)
         .service(post_agent_config)
+        .service(post_enabled_tools)
         .service(get_available_tools)
+        .service(validate_tools)
         .service(get_model_capabilities)
         .service(agent_chat)
         .service(agent_chat_stream)
+        .service(agent_rag)
+        .service(agent_eval)
+        .service(cancel_generation)
         .service(cancel_agent_generation)
         .service(get_conversations)
+        .service(clear_all_conversations)
         .service(delete_conversation)
         .service(update_conversation_title)
         .service(get_conversation_history)
+        .service(search_conversations)
+        .service(export_conversation)
         .service(
             actix_web::web::scope("/api/agent/testing")
                 .service(get_suites)
@@ -36,7 +50,10 @@ pub fn configure_agent_services(cfg: &mut ServiceConfig) {
                 .service(get_questions)
                 .service(add_question)
                 .service(update_question)
-                .service(delete_question),
+                .service(delete_question)
+                .service(run_question)
+                .service(run_suite)
+                .service(get_runs),
         );
 }
 
@@ -64,7 +81,14 @@ mod tests {
             ("/api/agent/status", "GET"),
             ("/api/agent/config", "GET"),
             ("/api/agent/config", "POST"),
+            ("/api/agent/config/tools", "POST"),
+            ("/api/agent/tools/validate", "GET"),
             ("/api/agent/chat", "POST"),
+            ("/api/agent/rag", "POST"),
+            ("/api/agent/eval", "POST"),
+            ("/api/agent/testing/questions/1/run", "POST"),
+            ("/api/agent/testing/suites/1/run", "POST"),
+            ("/api/agent/testing/questions/1/runs", "GET"),
         ];
 
         for (path, method) in endpoints {
@@ -1,20 +1,30 @@
 use actix_web::web::ServiceConfig;
 
-use crate::api::html_to_markdown::post::convert_html_to_markdown_endpoint;
+use crate::api::converters::get::get_converters;
+use crate::api::csv_to_json::post::csv_to_json_service;
+use crate::api::html_to_markdown::post::html_to_markdown_service;
+use crate::api::image_convert::post::convert_image_endpoint;
+use crate::api::json_to_csv::post::json_to_csv_service;
 use crate::api::json_to_toon::post::convert_json_to_toon;
+use crate::api::markdown_to_html::post::markdown_to_html_service;
 use crate::api::parquet_to_txt::post::convert_parquet_to_txt;
 use crate::api::pdf_to_markdown::post::convert_pdf_to_markdown;
-use crate::api::text_to_tokens::post::convert_text_to_tokens;
+use crate::api::text_to_tokens::post::text_to_tokens_service;
 use crate::api::url_to_markdown::post::convert_url_to_markdown;
 
 /// Configures all converter related endpoints
 pub fn configure_converter_services(cfg: &mut ServiceConfig) {
-    cfg.service(convert_url_to_markdown)
-        .service(convert_html_to_markdown_endpoint)
+    cfg.service(get_converters)
+        .service(convert_url_to_markdown)
+        .service(html_to_markdown_service())
         .service(convert_json_to_toon)
         .service(convert_parquet_to_txt)
         .service(convert_pdf_to_markdown)
-        .service(convert_text_to_tokens);
+        .service(text_to_tokens_service())
+        .service(convert_image_endpoint)
+        .service(csv_to_json_service())
+        .service(json_to_csv_service())
+        .service(markdown_to_html_service());
 }
 
 #[cfg(test)]
@@ -34,10 +44,19 @@ mod tests {
             ("/api/parquet-to-txt", "POST"),
             ("/api/pdf-to-markdown", "POST"),
             ("/api/text-to-tokens", "POST"),
+            ("/api/image-convert", "POST"),
+            ("/api/csv-to-json", "POST"),
+            ("/api/json-to-csv", "POST"),
+            ("/api/converters/markdown-to-html", "POST"),
+            ("/api/converters", "GET"),
         ];
 
         for (path, method) in endpoints {
-            let req = test::TestRequest::post().uri(path).to_request();
+            let req = match method {
+                "GET" => test::TestRequest::get().uri(path),
+                _ => test::TestRequest::post().uri(path),
+            };
+            let req = req.to_request();
             let resp = test::call_service(&app, req).await;
             // Endpoints should be registered (not 404)
             assert_ne!(
@@ -49,4 +68,24 @@ mod tests {
             );
         }
     }
+
+    #[actix_web::test]
+    async fn test_converter_catalog_endpoints_are_registered() {
+        use crate::api::converters::catalog::converter_catalog;
+
+        let app = test::init_service(App::new().configure(configure_converter_services)).await;
+
+        for converter in converter_catalog() {
+            let req = test::TestRequest::post()
+                .uri(&converter.endpoint)
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_ne!(
+                resp.status().as_u16(),
+                404,
+                "Catalog entry {} points at an unregistered endpoint",
+                converter.id
+            );
+        }
+    }
 }
@@ -0,0 +1,30 @@
+use actix_web::web::ServiceConfig;
+
+use crate::api::default_configs::clear_default_config;
+
+/// Configures default config endpoints
+pub fn configure_default_configs_services(cfg: &mut ServiceConfig) {
+    cfg.service(clear_default_config);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::{test, App};
+
+    #[actix_web::test]
+    async fn test_configure_default_configs_services_registers_all_endpoints() {
+        let app =
+            test::init_service(App::new().configure(configure_default_configs_services)).await;
+
+        let req = test::TestRequest::delete()
+            .uri("/api/default-configs/llama")
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_ne!(
+            resp.status().as_u16(),
+            404,
+            "Endpoint DELETE /api/default-configs/llama should be registered"
+        );
+    }
+}
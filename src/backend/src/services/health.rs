@@ -0,0 +1,28 @@
+use actix_web::web::ServiceConfig;
+
+use crate::api::health::get_service_health;
+
+/// Configures the service-wide health endpoint
+pub fn configure_health_services(cfg: &mut ServiceConfig) {
+    cfg.service(get_service_health);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::{test, App};
+
+    #[actix_web::test]
+    async fn test_configure_health_services_registers_all_endpoints() {
+        let app = test::init_service(App::new().configure(configure_health_services)).await;
+
+        let req = test::TestRequest::get().uri("/api/health").to_request();
+        let resp = test::call_service(&app, req).await;
+        // Endpoint should be registered (not 404)
+        assert_ne!(
+            resp.status().as_u16(),
+            404,
+            "Endpoint GET /api/health should be registered"
+        );
+    }
+}
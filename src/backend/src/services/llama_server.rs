@@ -1,10 +1,12 @@
 use actix_web::web::ServiceConfig;
 
 use crate::api::llama_server::get_config::get_llama_config;
-use crate::api::llama_server::get_logs::get_llama_logs;
+use crate::api::llama_server::get_logs::{get_llama_logs, get_llama_logs_download};
 use crate::api::llama_server::get_models::get_llama_models;
 use crate::api::llama_server::get_status::get_llama_server_status;
+use crate::api::llama_server::patch_config::patch_llama_config;
 use crate::api::llama_server::post_config::post_update_config;
+use crate::api::llama_server::post_restart::post_restart_llama_server;
 use crate::api::llama_server::post_start::post_start_llama_server;
 use crate::api::llama_server::post_stop::post_stop_llama_server;
 
@@ -14,9 +16,12 @@ pub fn configure_llama_server_services(cfg: &mut ServiceConfig) {
         .service(get_llama_models)
         .service(get_llama_config)
         .service(get_llama_logs)
+        .service(get_llama_logs_download)
         .service(post_start_llama_server)
         .service(post_stop_llama_server)
-        .service(post_update_config);
+        .service(post_restart_llama_server)
+        .service(post_update_config)
+        .service(patch_llama_config);
 }
 
 #[cfg(test)]
@@ -34,15 +39,19 @@ mod tests {
             ("/api/llama-server/models", "GET"),
             ("/api/llama-server/config", "GET"),
             ("/api/llama-server/logs", "GET"),
+            ("/api/llama-server/logs/download", "GET"),
             ("/api/llama-server/start", "POST"),
             ("/api/llama-server/stop", "POST"),
+            ("/api/llama-server/restart", "POST"),
             ("/api/llama-server/config", "POST"),
+            ("/api/llama-server/config", "PATCH"),
         ];
 
         for (path, method) in endpoints {
             let req = match method {
                 "GET" => test::TestRequest::get().uri(path),
                 "POST" => test::TestRequest::post().uri(path),
+                "PATCH" => test::TestRequest::patch().uri(path),
                 _ => continue,
             };
             let req = req.to_request();
@@ -0,0 +1,28 @@
+use actix_web::web::ServiceConfig;
+
+use crate::api::metrics::get_prometheus_metrics;
+
+/// Configures the Prometheus scrape endpoint
+pub fn configure_metrics_services(cfg: &mut ServiceConfig) {
+    cfg.service(get_prometheus_metrics);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::{test, App};
+
+    #[actix_web::test]
+    async fn test_configure_metrics_services_registers_all_endpoints() {
+        let app = test::init_service(App::new().configure(configure_metrics_services)).await;
+
+        let req = test::TestRequest::get().uri("/metrics").to_request();
+        let resp = test::call_service(&app, req).await;
+        // Endpoint should be registered (not 404)
+        assert_ne!(
+            resp.status().as_u16(),
+            404,
+            "Endpoint GET /metrics should be registered"
+        );
+    }
+}
@@ -1,6 +1,9 @@
 pub mod agent;
 pub mod chromadb;
 pub mod converters;
+pub mod default_configs;
+pub mod health;
 pub mod llama_server;
+pub mod metrics;
 pub mod model_notes;
 pub mod sd_server;
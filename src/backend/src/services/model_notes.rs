@@ -1,12 +1,16 @@
 use actix_web::web::ServiceConfig;
 
 use crate::api::model_notes::{
-    create_or_update_model_note, delete_model_note, get_default_model, get_model_notes,
+    create_or_update_model_note, delete_model_note, get_default_model, get_model_generation_params,
+    get_model_notes, search_model_notes, set_generation_params,
 };
 
 /// Configures all model notes related endpoints
 pub fn configure_model_notes_services(cfg: &mut ServiceConfig) {
     cfg.service(get_model_notes)
+        .service(search_model_notes)
+        .service(get_model_generation_params)
+        .service(set_generation_params)
         .service(get_default_model)
         .service(create_or_update_model_note)
         .service(delete_model_note);
@@ -25,6 +29,9 @@ mod tests {
         let endpoints = vec![
             ("/api/model-notes", "GET"),
             ("/api/model-notes", "POST"),
+            ("/api/model-notes/search", "GET"),
+            ("/api/model-notes/generation-params", "GET"),
+            ("/api/model-notes/generation-params", "POST"),
             ("/api/model-notes/llama/test-model", "DELETE"),
         ];
 
@@ -0,0 +1,220 @@
+//! Shared SQLite connection pool
+//!
+//! Conversations, model notes, default configs and test suites all live in one
+//! database file. They share a single pool so writes are serialized by one set
+//! of connections instead of several pools competing for the file lock.
+
+use anyhow::{Context, Result};
+use sqlx::sqlite::{SqliteConnectOptions, SqliteJournalMode, SqlitePoolOptions};
+use sqlx::SqlitePool;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Environment variable naming the data directory when `--data-dir` isn't given
+pub const DATA_DIR_ENV: &str = "DATA_DIR";
+
+/// Data directory used when neither `--data-dir` nor `DATA_DIR` is set
+pub const DEFAULT_DATA_DIR: &str = "./data";
+
+/// File name of the shared database inside the data directory
+pub const DATABASE_FILE: &str = "conversations.db";
+
+/// How long a connection waits for another writer's lock before failing with
+/// "database is locked" (`PRAGMA busy_timeout`)
+pub const BUSY_TIMEOUT: Duration = Duration::from_millis(5000);
+
+/// Data directory from the `--data-dir` arg, then `DATA_DIR`, then [`DEFAULT_DATA_DIR`]
+pub fn resolve_data_dir(arg: Option<String>) -> PathBuf {
+    data_dir_with(arg, std::env::var(DATA_DIR_ENV).ok())
+}
+
+fn data_dir_with(arg: Option<String>, env_value: Option<String>) -> PathBuf {
+    arg.into_iter()
+        .chain(env_value)
+        .map(|dir| dir.trim().to_string())
+        .find(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from(DEFAULT_DATA_DIR))
+}
+
+/// Create `data_dir` if missing and return the path of the shared database in it
+pub fn database_path(data_dir: &Path) -> Result<PathBuf> {
+    std::fs::create_dir_all(data_dir)
+        .with_context(|| format!("Failed to create data directory: {}", data_dir.display()))?;
+    Ok(data_dir.join(DATABASE_FILE))
+}
+
+/// Resolve `db_path` to an absolute path, creating its directory if needed
+fn absolute_db_path(db_path: &Path) -> Result<PathBuf> {
+    if let Some(parent) = db_path.parent() {
+        std::fs::create_dir_all(parent).context("Failed to create database directory")?;
+    }
+
+    // If the file doesn't exist yet, canonicalize the parent and join the filename
+    if db_path.exists() {
+        return db_path
+            .canonicalize()
+            .context("Failed to canonicalize existing database path");
+    }
+    let parent = db_path.parent().unwrap_or(Path::new("."));
+    let parent_abs = parent
+        .canonicalize()
+        .or_else(|_| std::env::current_dir().map(|d| d.join(parent)))
+        .context("Failed to get absolute path for database directory")?;
+    let filename = db_path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or(DATABASE_FILE);
+    Ok(parent_abs.join(filename))
+}
+
+/// Open the pool every storage is constructed with. The file is created if
+/// missing and runs in WAL mode so readers don't block the writer; concurrent
+/// writers wait up to [`BUSY_TIMEOUT`] for each other instead of erroring.
+/// `:memory:` opens a single-connection in-memory database (for tests).
+pub async fn connect_sqlite(db_path: impl AsRef<Path>) -> Result<SqlitePool> {
+    let db_path = db_path.as_ref();
+
+    if db_path.to_str() == Some(":memory:") {
+        // Every connection to :memory: is a separate database, so keep just one
+        return SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .context("Failed to open in-memory SQLite database");
+    }
+
+    let absolute_path = absolute_db_path(db_path)?;
+    println!(
+        "💾 Connecting to SQLite database at: {}",
+        absolute_path.display()
+    );
+
+    let options = SqliteConnectOptions::new()
+        .filename(&absolute_path)
+        .create_if_missing(true)
+        .journal_mode(SqliteJournalMode::Wal)
+        .busy_timeout(BUSY_TIMEOUT);
+
+    SqlitePool::connect_with(options).await.with_context(|| {
+        format!(
+            "Failed to connect to SQLite database at: {}",
+            absolute_path.display()
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::agent::memory::sqlite_memory::SqliteConversationMemory;
+    use crate::api::agent::testing::storage::TestingStorage;
+    use crate::api::default_configs::DefaultConfigsStorage;
+    use crate::api::model_notes::ModelNotesStorage;
+
+    #[test]
+    fn test_data_dir_precedence() {
+        assert_eq!(
+            data_dir_with(Some("/mnt/arg".to_string()), Some("/mnt/env".to_string())),
+            PathBuf::from("/mnt/arg")
+        );
+        assert_eq!(
+            data_dir_with(None, Some("/mnt/env".to_string())),
+            PathBuf::from("/mnt/env")
+        );
+        assert_eq!(
+            data_dir_with(None, Some("  ".to_string())),
+            PathBuf::from(DEFAULT_DATA_DIR)
+        );
+    }
+
+    #[test]
+    fn test_database_path_creates_data_dir() {
+        let dir = std::env::temp_dir()
+            .join(format!("data_dir_test_{}", uuid::Uuid::new_v4()))
+            .join("nested");
+
+        let path = database_path(&dir).unwrap();
+        assert!(dir.is_dir());
+        assert_eq!(path, dir.join(DATABASE_FILE));
+
+        let _ = std::fs::remove_dir_all(dir.parent().unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_storages_share_one_pool() {
+        let path =
+            std::env::temp_dir().join(format!("shared_pool_test_{}.db", uuid::Uuid::new_v4()));
+        let pool = connect_sqlite(&path).await.unwrap();
+
+        let journal_mode: String = sqlx::query_scalar("PRAGMA journal_mode")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(journal_mode.to_lowercase(), "wal");
+
+        SqliteConversationMemory::new(pool.clone()).await.unwrap();
+        ModelNotesStorage::new(pool.clone()).await.unwrap();
+        DefaultConfigsStorage::new(pool.clone()).await.unwrap();
+        TestingStorage::new(pool.clone()).await.unwrap();
+
+        let components: Vec<String> = sqlx::query_scalar(
+            "SELECT DISTINCT component FROM schema_migrations ORDER BY component",
+        )
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+        assert_eq!(
+            components,
+            vec!["conversations", "default_configs", "model_notes", "testing"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_writes_do_not_fail() {
+        let path =
+            std::env::temp_dir().join(format!("concurrent_write_test_{}.db", uuid::Uuid::new_v4()));
+        // Two pools on one file, like separate processes or an old per-storage pool
+        let first = connect_sqlite(&path).await.unwrap();
+        let second = connect_sqlite(&path).await.unwrap();
+
+        let busy_timeout: i64 = sqlx::query_scalar("PRAGMA busy_timeout")
+            .fetch_one(&first)
+            .await
+            .unwrap();
+        assert_eq!(busy_timeout, BUSY_TIMEOUT.as_millis() as i64);
+
+        let memory = SqliteConversationMemory::new(first.clone()).await.unwrap();
+        let notes = ModelNotesStorage::new(second.clone()).await.unwrap();
+
+        let writes = (0..20).map(|i| {
+            let pool = if i % 2 == 0 { first.clone() } else { second.clone() };
+            async move {
+                let mut tx = pool.begin().await?;
+                sqlx::query("INSERT INTO conversations (id, title) VALUES (?, ?)")
+                    .bind(format!("conv-{}", i))
+                    .bind("Concurrent")
+                    .execute(&mut *tx)
+                    .await?;
+                sqlx::query(
+                    "INSERT INTO model_notes (platform, model_name, notes) VALUES ('llama', ?, 'x')",
+                )
+                .bind(format!("model-{}", i))
+                .execute(&mut *tx)
+                .await?;
+                tx.commit().await
+            }
+        });
+        let results = futures::future::join_all(writes).await;
+        for result in results {
+            result.expect("concurrent write failed");
+        }
+
+        assert_eq!(memory.list_conversations().await.unwrap().len(), 20);
+        assert_eq!(notes.get_all_notes().await.unwrap().len(), 20);
+
+        first.close().await;
+        second.close().await;
+        let _ = std::fs::remove_file(path);
+    }
+}
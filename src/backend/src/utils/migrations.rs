@@ -0,0 +1,168 @@
+//! Numbered SQLite schema migrations
+//!
+//! Each storage declares its own list of [`Migration`]s under a component name.
+//! [`run_migrations`] applies the ones not yet recorded in `schema_migrations`
+//! for that component, each in its own transaction, so every migration runs
+//! exactly once per database. New schema changes are added as a new version at
+//! the end of the list; applied migrations must never be edited.
+
+use anyhow::{Context, Result};
+use sqlx::SqlitePool;
+
+/// One schema change, applied atomically
+pub struct Migration {
+    /// Position in the component's sequence, starting at 1
+    pub version: i64,
+    /// Short description stored alongside the version
+    pub name: &'static str,
+    pub statements: &'static [&'static str],
+}
+
+/// Apply pending `migrations` for `component` in version order.
+/// Returns how many were applied.
+pub async fn run_migrations(
+    pool: &SqlitePool,
+    component: &str,
+    migrations: &[Migration],
+) -> Result<usize> {
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS schema_migrations (
+            component TEXT NOT NULL,
+            version INTEGER NOT NULL,
+            name TEXT NOT NULL,
+            applied_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now')),
+            PRIMARY KEY (component, version)
+        )",
+    )
+    .execute(pool)
+    .await
+    .context("Failed to create schema_migrations table")?;
+
+    let current: i64 = sqlx::query_scalar(
+        "SELECT COALESCE(MAX(version), 0) FROM schema_migrations WHERE component = ?",
+    )
+    .bind(component)
+    .fetch_one(pool)
+    .await
+    .context("Failed to read schema version")?;
+
+    let mut applied = 0;
+    for migration in migrations.iter().filter(|m| m.version > current) {
+        let mut tx = pool
+            .begin()
+            .await
+            .context("Failed to start migration transaction")?;
+
+        for statement in migration.statements {
+            sqlx::query(statement)
+                .execute(&mut *tx)
+                .await
+                .with_context(|| {
+                    format!(
+                        "Migration {} {} ({}) failed",
+                        component, migration.version, migration.name
+                    )
+                })?;
+        }
+
+        sqlx::query("INSERT INTO schema_migrations (component, version, name) VALUES (?, ?, ?)")
+            .bind(component)
+            .bind(migration.version)
+            .bind(migration.name)
+            .execute(&mut *tx)
+            .await
+            .context("Failed to record migration")?;
+
+        tx.commit()
+            .await
+            .context("Failed to commit migration transaction")?;
+
+        println!(
+            "✅ Applied migration {} {}: {}",
+            component, migration.version, migration.name
+        );
+        applied += 1;
+    }
+
+    Ok(applied)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::sqlite::SqliteConnectOptions;
+
+    async fn temp_pool() -> SqlitePool {
+        let path =
+            std::env::temp_dir().join(format!("migrations_test_{}.db", uuid::Uuid::new_v4()));
+        SqlitePool::connect_with(
+            SqliteConnectOptions::new()
+                .filename(path)
+                .create_if_missing(true),
+        )
+        .await
+        .unwrap()
+    }
+
+    const MIGRATIONS: &[Migration] = &[
+        Migration {
+            version: 1,
+            name: "create items",
+            statements: &["CREATE TABLE items (id INTEGER PRIMARY KEY, name TEXT NOT NULL)"],
+        },
+        Migration {
+            version: 2,
+            name: "add items.tags",
+            statements: &["ALTER TABLE items ADD COLUMN tags TEXT"],
+        },
+    ];
+
+    #[tokio::test]
+    async fn test_run_migrations_applies_each_once() {
+        let pool = temp_pool().await;
+
+        assert_eq!(run_migrations(&pool, "items", MIGRATIONS).await.unwrap(), 2);
+        // A second run (e.g. next startup) would fail on the ALTER if it re-ran
+        assert_eq!(run_migrations(&pool, "items", MIGRATIONS).await.unwrap(), 0);
+
+        let versions: Vec<i64> = sqlx::query_scalar(
+            "SELECT version FROM schema_migrations WHERE component = 'items' ORDER BY version",
+        )
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+        assert_eq!(versions, vec![1, 2]);
+
+        // Components are versioned independently
+        let other = [Migration {
+            version: 1,
+            name: "create other",
+            statements: &["CREATE TABLE other (id INTEGER PRIMARY KEY)"],
+        }];
+        assert_eq!(run_migrations(&pool, "other", &other).await.unwrap(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_failed_migration_is_rolled_back() {
+        let pool = temp_pool().await;
+        let broken = [Migration {
+            version: 1,
+            name: "half broken",
+            statements: &["CREATE TABLE kept (id INTEGER)", "NOT VALID SQL"],
+        }];
+
+        assert!(run_migrations(&pool, "broken", &broken).await.is_err());
+
+        let tables: i64 =
+            sqlx::query_scalar("SELECT COUNT(*) FROM sqlite_master WHERE name = 'kept'")
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert_eq!(tables, 0);
+        let recorded: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM schema_migrations")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(recorded, 0);
+    }
+}
@@ -1 +1,4 @@
+pub mod database;
+pub mod migrations;
+pub mod process;
 pub mod tokenizer;
@@ -0,0 +1,106 @@
+use log::{info, warn};
+use std::process::Child;
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// How often `terminate_child` checks whether the child has exited
+#[cfg(unix)]
+const EXIT_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Kill a spawned child process (if any) and wait for it to exit so it isn't
+/// left running or as a zombie. Used on shutdown, where a poisoned lock still
+/// shouldn't keep the child alive.
+pub fn kill_child(process: &Mutex<Option<Child>>, name: &str) {
+    let mut guard = process.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(mut child) = guard.take() {
+        info!("🛑 Stopping {} (pid {})...", name, child.id());
+        if let Err(e) = child.kill() {
+            warn!("⚠️ Failed to kill {}: {}", name, e);
+        }
+        let _ = child.wait();
+    }
+}
+
+/// Ask a child to exit with SIGTERM, falling back to SIGKILL when it is still
+/// running after `timeout`. Blocks until the child has exited, so call it from
+/// a blocking context.
+pub fn terminate_child(child: &mut Child, timeout: Duration) -> std::io::Result<()> {
+    if child.try_wait()?.is_some() {
+        return Ok(());
+    }
+
+    #[cfg(unix)]
+    if send_sigterm(child.id()) {
+        let deadline = std::time::Instant::now() + timeout;
+        while std::time::Instant::now() < deadline {
+            if child.try_wait()?.is_some() {
+                return Ok(());
+            }
+            std::thread::sleep(EXIT_POLL_INTERVAL);
+        }
+        warn!(
+            "⚠️ Process {} ignored SIGTERM for {:?}, killing it",
+            child.id(),
+            timeout
+        );
+    }
+    #[cfg(not(unix))]
+    let _ = timeout;
+
+    child.kill()?;
+    child.wait()?;
+    Ok(())
+}
+
+#[cfg(unix)]
+fn send_sigterm(pid: u32) -> bool {
+    let Ok(pid) = libc::pid_t::try_from(pid) else {
+        return false;
+    };
+    // SAFETY: kill(2) only sends a signal; `pid` is our own child's id
+    unsafe { libc::kill(pid, libc::SIGTERM) == 0 }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::process::Command;
+
+    #[test]
+    fn test_kill_child_none_is_noop() {
+        let process = Mutex::new(None);
+        kill_child(&process, "nothing");
+        assert!(process.lock().unwrap().is_none());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_kill_child_stops_process() {
+        let child = Command::new("sleep").arg("30").spawn().unwrap();
+        let process = Mutex::new(Some(child));
+        kill_child(&process, "sleep");
+        assert!(process.lock().unwrap().is_none());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_terminate_child_uses_sigterm() {
+        let mut child = Command::new("sleep").arg("30").spawn().unwrap();
+        terminate_child(&mut child, Duration::from_secs(5)).unwrap();
+        assert!(child.try_wait().unwrap().is_some());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_terminate_child_kills_after_timeout() {
+        let mut child = Command::new("sh")
+            .arg("-c")
+            .arg("trap '' TERM; sleep 30")
+            .spawn()
+            .unwrap();
+        // Give the shell time to install the trap
+        std::thread::sleep(Duration::from_millis(200));
+        terminate_child(&mut child, Duration::from_millis(300)).unwrap();
+        assert!(child.try_wait().unwrap().is_some());
+    }
+}
@@ -41,6 +41,19 @@ pub fn get_tokenizer() -> TokenizerResult<&'static Tokenizer> {
     }
 }
 
+/// The tokenizer if it has already been loaded. Unlike `get_tokenizer` this
+/// never starts the download, so it is safe to call on a request path.
+pub fn loaded_tokenizer() -> Option<&'static Tokenizer> {
+    if !TOKENIZER_INIT.is_completed() {
+        return None;
+    }
+    // SAFETY: initialization has finished, so TOKENIZER is only read from here on
+    #[allow(static_mut_refs)]
+    unsafe {
+        TOKENIZER.as_ref()
+    }
+}
+
 /// Counts the number of tokens in a text string
 pub fn count_tokens(text: &str) -> Result<usize, String> {
     match get_tokenizer() {
@@ -2,7 +2,14 @@ import { useWebSocket } from './useWebSocket'
 import type { WebSocketOptions } from './useWebSocket'
 
 export interface AgentStreamEvent {
-  type: 'status' | 'tool_call' | 'tool_result' | 'text_chunk' | 'done' | 'error'
+  type:
+    | 'status'
+    | 'tool_call'
+    | 'tool_result'
+    | 'text_chunk'
+    | 'reasoning_chunk'
+    | 'done'
+    | 'error'
   status?: string
   message?: string
   tool_name?: string
@@ -46,5 +53,12 @@ export function useAgentWebSocket(
     reconnectInterval: 2000
   }
 
-  return useWebSocket(options)
+  const ws = useWebSocket(options)
+
+  // Ask the server to replay events of an in-flight generation after reconnecting
+  const resume = (conversationId: string) =>
+    ws.send(JSON.stringify({ type: 'resume', conversation_id: conversationId }))
+
+  // Assign onto the socket object so its getters (isConnected, socket) stay live
+  return Object.assign(ws, { resume })
 }
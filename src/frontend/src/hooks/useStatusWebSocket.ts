@@ -3,12 +3,17 @@ import { useWebSocket, type WebSocketOptions } from './useWebSocket'
 export interface LlamaServerStatus {
   active: boolean
   port: number
+  // Port accepts connections; with model_loaded false the model is still loading
+  port_open?: boolean
+  model_loaded?: boolean
 }
 
 export interface StatusWebSocketMessage {
   type: 'status'
   active: boolean
   port: number
+  port_open?: boolean
+  model_loaded?: boolean
 }
 
 export function useStatusWebSocket(
@@ -34,14 +39,17 @@ export function useStatusWebSocket(
         if (message.type === 'status') {
           const newStatus: LlamaServerStatus = {
             active: message.active,
-            port: message.port
+            port: message.port,
+            port_open: message.port_open,
+            model_loaded: message.model_loaded
           }
 
           // Only call onStatusChange if status actually changed
           if (
             !previousStatus ||
             previousStatus.active !== newStatus.active ||
-            previousStatus.port !== newStatus.port
+            previousStatus.port !== newStatus.port ||
+            previousStatus.port_open !== newStatus.port_open
           ) {
             onStatusChange(newStatus)
 
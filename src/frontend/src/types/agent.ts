@@ -1,6 +1,17 @@
 export interface AgentChatRequest {
   message: string | any
   conversation_id?: string
+  max_iterations?: number
+  temperature?: number
+  max_tokens?: number
+  top_p?: number
+  frequency_penalty?: number
+  presence_penalty?: number
+  stop?: string[]
+  system_prompt_prefix?: string
+  system_prompt_override?: string
+  enabled_tools?: string[]
+  plan_only?: boolean
 }
 
 export interface AgentStreamEvent {
@@ -9,6 +20,7 @@ export interface AgentStreamEvent {
     | 'tool_call'
     | 'tool_result'
     | 'text_chunk'
+    | 'reasoning_chunk'
     | 'done'
     | 'error'
     | 'conversation_created'
@@ -24,7 +36,9 @@ export interface AgentStreamEvent {
   tool_calls?: Array<{
     tool_name: string
     result: string
+    tool_call_id?: string
   }>
+  // Summed over every LLM request in the agent loop (one per iteration)
   usage?: {
     prompt_tokens: number
     completion_tokens: number
@@ -66,6 +80,13 @@ export interface AgentConfig {
     embedding_model: string
   }
   debug_logging?: boolean
+  tool_max_retries?: number
+  tool_timeout_secs?: number
+  cache_ttl_secs?: number
+  persist_tool_messages?: boolean
+  llm_supports_tool_role?: boolean
+  llm_supports_vision?: boolean
+  agent_timeout_secs?: number
 }
 
 export interface AgentConfigResponse {
@@ -109,6 +130,7 @@ export interface ToolInfo {
   description: string
   category: ToolCategory
   icon: string // Material Icon name
+  function_definition?: Record<string, unknown> // OpenAI-compatible function schema
 }
 
 export interface ModelCapabilities {
@@ -122,6 +144,15 @@ export interface Conversation {
   title: string | null
   model?: string
   created_at: number
+  last_updated: number
+  message_count: number
+}
+
+export interface MessageSearchResult {
+  conversation_id: string
+  role: string
+  snippet: string
+  created_at: number
 }
 
 export interface UpdateConversationRequest {
@@ -131,6 +162,7 @@ export interface UpdateConversationRequest {
 export interface ToolCallResult {
   tool_name: string
   result: string
+  tool_call_id?: string
 }
 
 export interface AgentChatResponse {
@@ -138,6 +170,21 @@ export interface AgentChatResponse {
   message: string
   conversation_id?: string
   tool_calls?: ToolCallResult[]
+  tool_traces?: ToolCallTrace[]
+  planned_tool_calls?: Array<{
+    id: string
+    type: string
+    function: { name: string; arguments: string }
+  }>
+}
+
+export interface ToolCallTrace {
+  tool_call_id: string
+  tool_name: string
+  arguments: string
+  result: string
+  success: boolean
+  duration_ms: number
 }
 
 export interface TestSuite {
@@ -151,5 +198,28 @@ export interface TestQuestion {
   id: number
   suite_id: string
   content: string
+  /** The answer must contain this text (case-insensitive) */
+  expected_substring?: string | null
+  /** Tool function names the agent must call */
+  expected_tools: string[]
+  created_at: number
+}
+
+export interface TestRun {
+  id: number
+  question_id: number
+  passed: boolean
+  output: string
+  tool_calls: string[]
+  failures: string[]
+  duration_ms: number
   created_at: number
 }
+
+export interface SuiteRunSummary {
+  suite_id: string
+  total: number
+  passed: number
+  failed: number
+  runs: TestRun[]
+}
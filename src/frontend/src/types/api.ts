@@ -0,0 +1,7 @@
+/** Body of every failed API response */
+export interface ApiErrorResponse {
+  success: false
+  error: string
+  /** Machine readable code, e.g. `bad_request`, `not_found`, `upstream_error` */
+  code: string
+}
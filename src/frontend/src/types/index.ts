@@ -1,3 +1,4 @@
 export * from './chromadb'
 export * from './modelNotes'
 export * from './agent'
+export * from './api'